@@ -0,0 +1,192 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::dates::{self, TaskDate};
+use crate::mcp_client::Task;
+use crate::table_formatter::priority_level;
+
+/// When a task is due relative to today, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DueWindow {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl DueWindow {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overdue => "overdue",
+            Self::Today => "today",
+            Self::Tomorrow => "tomorrow",
+            Self::ThisWeek => "this_week",
+            Self::Later => "later",
+            Self::NoDate => "no_date",
+        }
+    }
+
+    pub fn heading(self) -> &'static str {
+        match self {
+            Self::Overdue => "🚨 Overdue",
+            Self::Today => "📅 Today",
+            Self::Tomorrow => "🌅 Tomorrow",
+            Self::ThisWeek => "🗓️  This Week",
+            Self::Later => "🔭 Later",
+            Self::NoDate => "❔ No Due Date",
+        }
+    }
+}
+
+/// Which due windows `agenda` shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgendaScope {
+    All,
+    Overdue,
+    /// Overdue and due today
+    Today,
+    /// Overdue through the end of this week (Sunday)
+    Week,
+}
+
+impl AgendaScope {
+    fn includes(self, window: DueWindow) -> bool {
+        match self {
+            Self::All => true,
+            Self::Overdue => window == DueWindow::Overdue,
+            Self::Today => window <= DueWindow::Today,
+            Self::Week => window <= DueWindow::ThisWeek,
+        }
+    }
+}
+
+/// An unfinished task placed in its due window
+#[derive(Debug, Serialize)]
+pub struct AgendaEntry {
+    pub window: DueWindow,
+    /// Due date in the agenda's time zone, e.g. `2025-01-10` or `2025-01-10 17:00`
+    pub due: Option<String>,
+    /// Relative description such as "due in 3 days" or "overdue by 2 days"
+    pub relative: Option<String>,
+    pub task: Task,
+}
+
+/// Tasks grouped by due window as seen from one moment in one time zone
+#[derive(Debug, Serialize)]
+pub struct Agenda {
+    pub today: NaiveDate,
+    pub timezone: String,
+    pub entries: Vec<AgendaEntry>,
+}
+
+impl Agenda {
+    /// Place `tasks` in due windows relative to `now` in the display time zone.
+    /// Entries are sorted by window, due date, then priority.
+    pub fn build(tasks: Vec<Task>, now: DateTime<Utc>, scope: AgendaScope) -> Self {
+        let today = dates::local_date(now);
+
+        let mut entries = tasks
+            .into_iter()
+            .map(|task| entry(task, now, today))
+            .filter(|(entry, _)| scope.includes(entry.window))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(entry, due_key)| {
+            (
+                entry.window,
+                *due_key,
+                priority_rank(entry.task.priority.as_deref()),
+            )
+        });
+
+        Self {
+            today,
+            timezone: dates::timezone_name(),
+            entries: entries.into_iter().map(|(entry, _)| entry).collect(),
+        }
+    }
+
+    /// Entries of each non-empty window, in window order
+    pub fn windows(&self) -> Vec<(DueWindow, Vec<&AgendaEntry>)> {
+        let mut windows: Vec<(DueWindow, Vec<&AgendaEntry>)> = Vec::new();
+        for entry in &self.entries {
+            match windows.last_mut() {
+                Some((window, entries)) if *window == entry.window => entries.push(entry),
+                _ => windows.push((entry.window, vec![entry])),
+            }
+        }
+        windows
+    }
+}
+
+/// The task's entry and a key for sorting within its window
+fn entry(task: Task, now: DateTime<Utc>, today: NaiveDate) -> (AgendaEntry, Option<DateTime<Utc>>) {
+    let Some(due) = TaskDate::parse_opt(task.due_date.as_deref()) else {
+        let entry = AgendaEntry {
+            window: DueWindow::NoDate,
+            due: task.due_date.clone(),
+            relative: None,
+            task,
+        };
+        return (entry, None);
+    };
+
+    let date = due.date();
+    let end_of_week = today + Days::new(6 - u64::from(today.weekday().num_days_from_monday()));
+
+    let window = if due.is_overdue(now) {
+        DueWindow::Overdue
+    } else if date == today {
+        DueWindow::Today
+    } else if date == today + Days::new(1) {
+        DueWindow::Tomorrow
+    } else if date <= end_of_week {
+        DueWindow::ThisWeek
+    } else {
+        DueWindow::Later
+    };
+
+    let entry = AgendaEntry {
+        window,
+        due: Some(due.format()),
+        relative: Some(relative(due, now, today)),
+        task,
+    };
+    (entry, Some(due.sort_key()))
+}
+
+/// Relative description of a due date, such as "due in 3 days" or "overdue by 2 days"
+pub fn describe_due(due: TaskDate, now: DateTime<Utc>) -> String {
+    relative(due, now, dates::local_date(now))
+}
+
+fn relative(due: TaskDate, now: DateTime<Utc>, today: NaiveDate) -> String {
+    let days = (due.date() - today).num_days();
+
+    match (days, due) {
+        (0, TaskDate::Instant(at)) => {
+            let hours = (at - now).num_hours();
+            match hours {
+                h if h < 0 => format!("overdue by {}", dates::count(-h, "hour")),
+                0 if at < now => "overdue by less than an hour".to_string(),
+                0 => "due within the hour".to_string(),
+                h => format!("due in {}", dates::count(h, "hour")),
+            }
+        }
+        (0, TaskDate::Day(_)) => "due today".to_string(),
+        (d, _) if d < 0 => format!("overdue by {}", dates::count(-d, "day")),
+        (1, _) => "due tomorrow".to_string(),
+        (d, _) => format!("due in {}", dates::count(d, "day")),
+    }
+}
+
+fn priority_rank(priority: Option<&str>) -> u8 {
+    match priority_level(priority) {
+        "high" => 0,
+        "medium" => 1,
+        "low" => 2,
+        _ => 3,
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::report;
+use crate::watch::{self, TaskChange};
+
+/// Directory under the data directory snapshots are saved to by default
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Every task on a server at one moment, as written by `snapshot save`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Alias of the MCP server the tasks came from
+    pub server: String,
+    pub task_count: usize,
+    pub tasks: Vec<Task>,
+}
+
+impl TaskSnapshot {
+    pub fn new(server: &str, tasks: Vec<Task>, taken_at: DateTime<Utc>) -> Self {
+        Self {
+            taken_at,
+            server: server.to_string(),
+            task_count: tasks.len(),
+            tasks,
+        }
+    }
+
+    /// `<data_dir>/snapshots/tasks-20250601-093000.json`
+    pub fn default_path(&self, data_dir: &Path) -> PathBuf {
+        data_dir.join(SNAPSHOT_DIR).join(format!(
+            "tasks-{}.json",
+            self.taken_at.format("%Y%m%d-%H%M%S")
+        ))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).context(McpTasksError::Report(
+            "Failed to serialize the task snapshot".to_string(),
+        ))?;
+        report::write_file(path, content.as_bytes())?;
+        info!("Saved {} tasks to {}", self.task_count, path.display());
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| {
+            McpTasksError::Report(format!("Failed to read snapshot {}", path.display()))
+        })?;
+        serde_json::from_str(&content).with_context(|| {
+            McpTasksError::Report(format!(
+                "{} is not a task snapshot written by `snapshot save`",
+                path.display()
+            ))
+        })
+    }
+}
+
+/// `spec` as a path, or else as the name of a snapshot in the default
+/// directory, with or without `.json`
+pub fn resolve_path(data_dir: &Path, spec: &Path) -> PathBuf {
+    if spec.exists() {
+        return spec.to_path_buf();
+    }
+
+    let in_dir = data_dir.join(SNAPSHOT_DIR).join(spec);
+    if in_dir.extension().is_none() {
+        in_dir.with_extension("json")
+    } else {
+        in_dir
+    }
+}
+
+/// Task changes between two snapshots
+#[derive(Debug, Serialize)]
+pub struct SnapshotDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Created, updated and completed tasks in the order of the newer
+    /// snapshot, then deleted tasks
+    pub changes: Vec<TaskChange>,
+}
+
+impl SnapshotDiff {
+    pub fn between(old: &TaskSnapshot, new: &TaskSnapshot) -> Self {
+        Self {
+            from: old.taken_at,
+            to: new.taken_at,
+            changes: watch::diff_snapshots(&old.tasks, &new.tasks),
+        }
+    }
+
+    /// `2 added, 1 removed, 3 changed`
+    pub fn counts(&self) -> String {
+        let added = self
+            .changes
+            .iter()
+            .filter(|change| matches!(change, TaskChange::Created { .. }))
+            .count();
+        let removed = self
+            .changes
+            .iter()
+            .filter(|change| matches!(change, TaskChange::Deleted { .. }))
+            .count();
+        format!(
+            "{} added, {} removed, {} changed",
+            added,
+            removed,
+            self.changes.len() - added - removed
+        )
+    }
+}
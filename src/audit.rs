@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::report::ToolCallRecord;
+
+const AUDIT_FILE: &str = "tool_calls.jsonl";
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    command: &'a str,
+    #[serde(flatten)]
+    record: &'a ToolCallRecord,
+}
+
+/// Append-only JSON-lines log of every tool call the AI made
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(AUDIT_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one line per tool call, tagged with the command that made it
+    pub fn append(&self, command: &str, records: &[ToolCallRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(
+                &serde_json::to_string(&AuditEntry { command, record })
+                    .context("Failed to serialize tool call for the audit log")?,
+            );
+            lines.push('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        file.write_all(lines.as_bytes())
+            .with_context(|| format!("Failed to write audit log {}", self.path.display()))?;
+
+        info!(
+            "Logged {} tool calls to {}",
+            records.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+//! Fixture-backed MCP todo server speaking JSON-RPC over stdio.
+//!
+//! Implements the tools and resources of `mcp_todo_task` closely enough for
+//! end-to-end runs of the CLI without the external server:
+//!
+//! ```bash
+//! MCP_SERVER_COMMAND=target/debug/mock-mcp-server cargo run -- list
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Parser;
+use rmcp::model::{
+    AnnotateAble, CallToolRequestParam, CallToolResult, Content, ListResourcesResult,
+    ListToolsResult, PaginatedRequestParam, RawResource, ReadResourceRequestParam,
+    ReadResourceResult, ResourceContents, ServerCapabilities, ServerInfo, Tool,
+};
+use rmcp::service::RequestContext;
+use rmcp::{ErrorData, RoleServer, ServerHandler, ServiceExt, transport::stdio};
+use serde_json::{Map, Value, json};
+
+/// Tasks served when no fixture file is given
+const DEFAULT_FIXTURE: &str = include_str!("../../fixtures/tasks.json");
+
+const ROADMAP_URI: &str = "notes://roadmap";
+const ROADMAP: &str =
+    "# Roadmap\n\n- v1.2: release notes and login fixes\n- v1.3: dependency updates\n";
+const TASKS_URI: &str = "tasks://all";
+
+#[derive(Parser)]
+#[command(name = "mock-mcp-server")]
+#[command(about = "Fixture-backed MCP todo server for tests and demos")]
+struct Cli {
+    /// JSON file with the initial task list (defaults to the bundled fixture)
+    #[arg(long)]
+    fixture: Option<PathBuf>,
+
+    /// Write changes back to the fixture file so they survive restarts
+    #[arg(long, requires = "fixture")]
+    persist: bool,
+}
+
+struct MockServer {
+    tasks: Mutex<Vec<Value>>,
+    /// Fixture file to save changes to
+    persist_to: Option<PathBuf>,
+}
+
+impl MockServer {
+    fn load(cli: Cli) -> Result<Self> {
+        let json = match &cli.fixture {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read fixture {}", path.display()))?,
+            None => DEFAULT_FIXTURE.to_string(),
+        };
+        let tasks = serde_json::from_str(&json).context("Fixture must be a JSON array of tasks")?;
+
+        Ok(Self {
+            tasks: Mutex::new(tasks),
+            persist_to: cli.persist.then_some(cli.fixture).flatten(),
+        })
+    }
+
+    fn save(&self, tasks: &[Value]) {
+        if let Some(path) = &self.persist_to {
+            let json = serde_json::to_string_pretty(tasks).unwrap_or_default();
+            if let Err(e) = std::fs::write(path, json) {
+                eprintln!("Failed to save fixture {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Run a tool against the task list; returns the result and whether the list changed
+    fn execute(&self, name: &str, args: &Map<String, Value>) -> (CallToolResult, bool) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        let id = args.get("id").and_then(Value::as_str);
+
+        let result = match name {
+            "list_tasks" => {
+                let matching = tasks
+                    .iter()
+                    .filter(|task| matches_filters(task, args))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let total = matching.len();
+
+                // Without `page` the whole list is returned, like servers without pagination
+                let page_size = args.get("page_size").and_then(Value::as_u64).unwrap_or(50);
+                let matching = match args.get("page").and_then(Value::as_u64) {
+                    Some(page) => matching
+                        .into_iter()
+                        .skip((page.saturating_sub(1) * page_size) as usize)
+                        .take(page_size as usize)
+                        .collect(),
+                    None => matching,
+                };
+
+                Ok(json!({
+                    "tasks": matching,
+                    "count": matching.len(),
+                    "total": total,
+                    "filters_applied": args,
+                }))
+            }
+            "task_stats" => Ok(stats(&tasks)),
+            "create_task" => match args.get("title").and_then(Value::as_str) {
+                Some(title) if !title.trim().is_empty() => {
+                    let task = json!({
+                        "id": next_id(&tasks),
+                        "title": title,
+                        "description": args.get("description").cloned().unwrap_or(Value::Null),
+                        "status": "pending",
+                        "priority": args.get("priority").cloned().unwrap_or(Value::Null),
+                        "assignee": args.get("assignee").cloned().unwrap_or(Value::Null),
+                        "due_date": args.get("due_date").cloned().unwrap_or(Value::Null),
+                        "created_at": now,
+                        "updated_at": Value::Null,
+                        "completed_at": Value::Null,
+                        "tags": args.get("tags").cloned().unwrap_or(Value::Null),
+                    });
+                    tasks.push(task.clone());
+                    Ok(task)
+                }
+                _ => Err("title is required".to_string()),
+            },
+            "get_task" | "update_task" | "complete_task" | "delete_task" => {
+                match tasks.iter().position(|task| task["id"].as_str() == id) {
+                    None => Err(format!("Task not found: {}", id.unwrap_or_default())),
+                    Some(idx) => match name {
+                        "get_task" => Ok(tasks[idx].clone()),
+                        "delete_task" => Ok(tasks.remove(idx)),
+                        _ => {
+                            let task = &mut tasks[idx];
+                            if name == "complete_task" {
+                                task["status"] = json!("completed");
+                                task["completed_at"] = json!(now);
+                            } else {
+                                for (key, value) in args.iter().filter(|(key, _)| *key != "id") {
+                                    task[key] = value.clone();
+                                }
+                            }
+                            task["updated_at"] = json!(now);
+                            Ok(task.clone())
+                        }
+                    },
+                }
+            }
+            _ => Err(format!("Unknown tool: {}", name)),
+        };
+
+        let changed = result.is_ok() && !matches!(name, "list_tasks" | "get_task" | "task_stats");
+        if changed {
+            self.save(&tasks);
+        }
+
+        let result = match result {
+            Ok(value) => CallToolResult::success(vec![Content::text(value.to_string())]),
+            Err(message) => {
+                CallToolResult::error(vec![Content::text(json!({ "error": message }).to_string())])
+            }
+        };
+        (result, changed)
+    }
+}
+
+impl ServerHandler for MockServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            instructions: Some("Fixture-backed todo server for tests and demos".to_string()),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult {
+            tools: tools(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let args = request.arguments.unwrap_or_default();
+        let (result, changed) = self.execute(&request.name, &args);
+
+        if changed {
+            // Lets `watch` pick up changes without waiting for the next poll
+            let _ = context.peer.notify_resource_list_changed().await;
+        }
+        Ok(result)
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let resource = |uri: &str, name: &str, description: &str, mime_type: &str| {
+            RawResource {
+                description: Some(description.to_string()),
+                mime_type: Some(mime_type.to_string()),
+                ..RawResource::new(uri, name)
+            }
+            .no_annotation()
+        };
+
+        Ok(ListResourcesResult {
+            resources: vec![
+                resource(
+                    TASKS_URI,
+                    "All tasks",
+                    "Every task as JSON",
+                    "application/json",
+                ),
+                resource(
+                    ROADMAP_URI,
+                    "Roadmap",
+                    "Release roadmap notes",
+                    "text/markdown",
+                ),
+            ],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let text = match request.uri.as_str() {
+            TASKS_URI => serde_json::to_string_pretty(&*self.tasks.lock().unwrap())
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?,
+            ROADMAP_URI => ROADMAP.to_string(),
+            uri => {
+                return Err(ErrorData::resource_not_found(
+                    format!("Unknown resource: {}", uri),
+                    None,
+                ));
+            }
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(text, request.uri)],
+        })
+    }
+}
+
+fn matches_filters(task: &Value, args: &Map<String, Value>) -> bool {
+    let field_matches = |field: &str| match args.get(field).and_then(Value::as_str) {
+        Some(wanted) => task[field]
+            .as_str()
+            .is_some_and(|value| value.eq_ignore_ascii_case(wanted)),
+        None => true,
+    };
+    let tag_matches = match args.get("tag").and_then(Value::as_str) {
+        Some(tag) => task["tags"]
+            .as_array()
+            .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag))),
+        None => true,
+    };
+
+    field_matches("status") && field_matches("priority") && field_matches("assignee") && tag_matches
+}
+
+/// Ids follow the fixture's `task-N` scheme so they stay predictable in tests
+fn next_id(tasks: &[Value]) -> String {
+    let max = tasks
+        .iter()
+        .filter_map(|task| {
+            task["id"]
+                .as_str()?
+                .strip_prefix("task-")?
+                .parse::<u64>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0);
+    format!("task-{}", max + 1)
+}
+
+fn stats(tasks: &[Value]) -> Value {
+    let count_by = |field: &str| {
+        let mut counts = Map::new();
+        for task in tasks {
+            let key = task[field].as_str().unwrap_or("none").to_string();
+            let count = counts.get(&key).and_then(Value::as_u64).unwrap_or(0);
+            counts.insert(key, json!(count + 1));
+        }
+        counts
+    };
+
+    json!({
+        "total": tasks.len(),
+        "by_status": count_by("status"),
+        "by_priority": count_by("priority"),
+    })
+}
+
+fn tools() -> Vec<Tool> {
+    let schema = |value: Value| match value {
+        Value::Object(map) => Arc::new(map),
+        _ => Arc::new(Map::new()),
+    };
+    let id_only = || {
+        schema(json!({
+            "type": "object",
+            "properties": { "id": { "type": "string", "description": "Task ID" } },
+            "required": ["id"]
+        }))
+    };
+
+    vec![
+        Tool::new(
+            "list_tasks",
+            "List tasks, optionally filtered by status, priority, assignee or tag and paginated",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string" },
+                    "priority": { "type": "string" },
+                    "assignee": { "type": "string" },
+                    "tag": { "type": "string" },
+                    "page": { "type": "integer", "minimum": 1 },
+                    "page_size": { "type": "integer", "minimum": 1 }
+                }
+            })),
+        ),
+        Tool::new("get_task", "Get a task by ID", id_only()),
+        Tool::new(
+            "create_task",
+            "Create a new task",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "priority": { "type": "string", "enum": ["low", "medium", "high"] },
+                    "assignee": { "type": "string" },
+                    "due_date": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["title"]
+            })),
+        ),
+        Tool::new(
+            "update_task",
+            "Update fields of a task",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "title": { "type": "string" },
+                    "status": { "type": "string" },
+                    "priority": { "type": "string" },
+                    "assignee": { "type": "string" },
+                    "due_date": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["id"]
+            })),
+        ),
+        Tool::new("complete_task", "Mark a task as completed", id_only()),
+        Tool::new("delete_task", "Delete a task", id_only()),
+        Tool::new(
+            "task_stats",
+            "Task counts by status and priority",
+            schema(json!({ "type": "object", "properties": {} })),
+        ),
+    ]
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let server = MockServer::load(Cli::parse())?;
+
+    // stdout carries the protocol; diagnostics go to stderr
+    let service = server
+        .serve(stdio())
+        .await
+        .context("Failed to start MCP server")?;
+    service.waiting().await?;
+
+    Ok(())
+}
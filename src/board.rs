@@ -0,0 +1,376 @@
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::dates::TaskDate;
+use crate::mcp_client::{McpClient, Task, TaskUpdate};
+use crate::status::TaskStatus;
+use crate::table_formatter::priority_level;
+
+/// One board column per status
+const COLUMNS: [TaskStatus; 4] = TaskStatus::ALL;
+
+/// Index of the column a task is shown in; unknown statuses go to To Do or Done
+fn column_of(task: &Task) -> usize {
+    let status = task.normalized_status().unwrap_or(if task.is_unfinished() {
+        TaskStatus::Pending
+    } else {
+        TaskStatus::Completed
+    });
+    COLUMNS
+        .iter()
+        .position(|column| *column == status)
+        .unwrap_or(0)
+}
+
+const HELP: &str = "←/→ column  ↑/↓ card  </> or 1-4 move  Enter details  r refresh  q quit";
+
+/// What a key press asks the event loop to do
+enum Action {
+    Quit,
+    /// Reload tasks; `manual` refreshes report it in the status bar
+    Refresh {
+        manual: bool,
+    },
+    Move {
+        id: String,
+        to: usize,
+    },
+}
+
+/// Tasks and cursor of the board
+struct Board {
+    tasks: Vec<Task>,
+    column: usize,
+    /// Selected card per column
+    selected: [usize; COLUMNS.len()],
+    show_detail: bool,
+    message: String,
+}
+
+impl Board {
+    fn new(tasks: Vec<Task>) -> Self {
+        Self {
+            message: format!("Loaded {} tasks", tasks.len()),
+            tasks,
+            column: 0,
+            selected: [0; COLUMNS.len()],
+            show_detail: false,
+        }
+    }
+
+    fn cards(&self, column: usize) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| column_of(task) == column)
+            .collect()
+    }
+
+    fn selected_task(&self) -> Option<&Task> {
+        self.cards(self.column)
+            .get(self.selected[self.column])
+            .copied()
+    }
+
+    /// Replace the tasks, keeping the cursor on the card with `follow` if given
+    fn set_tasks(&mut self, tasks: Vec<Task>, follow: Option<&str>) {
+        self.tasks = tasks;
+
+        if let Some(id) = follow
+            && let Some(task) = self.tasks.iter().find(|task| task.id == id)
+        {
+            self.column = column_of(task);
+            self.selected[self.column] = self
+                .cards(self.column)
+                .iter()
+                .position(|card| card.id == id)
+                .unwrap_or(0);
+        }
+
+        for column in 0..COLUMNS.len() {
+            let count = self.cards(column).len();
+            self.selected[column] = self.selected[column].min(count.saturating_sub(1));
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let last_column = COLUMNS.len() - 1;
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Action::Quit)
+            }
+            KeyCode::Esc if self.show_detail => {
+                self.show_detail = false;
+                None
+            }
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char('r') => Some(Action::Refresh { manual: true }),
+            KeyCode::Enter => {
+                self.show_detail = !self.show_detail;
+                None
+            }
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.move_selected(self.column.checked_sub(1)?)
+            }
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.move_selected((self.column + 1).min(last_column))
+            }
+            KeyCode::Char('<') | KeyCode::Char('H') => {
+                self.move_selected(self.column.checked_sub(1)?)
+            }
+            KeyCode::Char('>') | KeyCode::Char('L') => {
+                self.move_selected((self.column + 1).min(last_column))
+            }
+            KeyCode::Char(digit @ '1'..='4') => self.move_selected(digit as usize - '1' as usize),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.column = self.column.saturating_sub(1);
+                None
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.column = (self.column + 1).min(last_column);
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected[self.column] = self.selected[self.column].saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let count = self.cards(self.column).len();
+                self.selected[self.column] =
+                    (self.selected[self.column] + 1).min(count.saturating_sub(1));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn move_selected(&self, to: usize) -> Option<Action> {
+        let task = self.selected_task()?;
+        (column_of(task) != to).then(|| Action::Move {
+            id: task.id.clone(),
+            to,
+        })
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status_bar] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let columns_area = if self.show_detail {
+            let [columns_area, detail_area] =
+                Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)])
+                    .areas(main);
+            self.draw_detail(frame, detail_area);
+            columns_area
+        } else {
+            main
+        };
+
+        let areas = Layout::horizontal([Constraint::Ratio(1, COLUMNS.len() as u32); COLUMNS.len()])
+            .split(columns_area);
+        for (column, area) in areas.iter().enumerate() {
+            self.draw_column(frame, column, *area);
+        }
+
+        let status = Line::from(vec![
+            Span::styled(&self.message, Style::new().add_modifier(Modifier::BOLD)),
+            Span::raw("  │  "),
+            Span::styled(HELP, Style::new().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(status), status_bar);
+    }
+
+    fn draw_column(&self, frame: &mut Frame, column: usize, area: Rect) {
+        let cards = self.cards(column);
+        let active = column == self.column;
+
+        let items = cards.iter().map(|task| {
+            let marker = match priority_level(task.priority.as_deref()) {
+                "high" => "🔴",
+                "medium" => "🟡",
+                "low" => "🟢",
+                _ => "⚪",
+            };
+            let mut meta = task.id.clone();
+            if let Some(due) = &task.due_date {
+                meta.push_str(&format!(" · due {}", short_date(due)));
+            }
+
+            ListItem::new(vec![
+                Line::from(format!("{} {}", marker, task.title)),
+                Line::styled(format!("   {}", meta), Style::new().fg(Color::DarkGray)),
+            ])
+        });
+
+        let border_style = if active {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new()
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(format!(" {} ({}) ", COLUMNS[column].label(), cards.len())),
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        if active && !cards.is_empty() {
+            state.select(Some(self.selected[column]));
+        }
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Details ");
+        let Some(task) = self.selected_task() else {
+            frame.render_widget(Paragraph::new("No task selected").block(block), area);
+            return;
+        };
+
+        let field = |label: &str, value: Option<&str>| {
+            Line::from(vec![
+                Span::styled(format!("{}: ", label), Style::new().fg(Color::DarkGray)),
+                Span::raw(value.map_or_else(|| "N/A".to_string(), short_date)),
+            ])
+        };
+        let tags = task.tags.as_ref().map(|tags| tags.join(", "));
+
+        let mut lines = vec![
+            Line::styled(
+                task.title.clone(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+            field("ID", Some(&task.id)),
+            field("Status", Some(&task.status)),
+            field("Priority", task.priority.as_deref()),
+            field("Due", task.due_date.as_deref()),
+            field("Created", Some(&task.created_at)),
+            field("Completed", task.completed_at.as_deref()),
+            field("Tags", tags.as_deref()),
+        ];
+        if let Some(description) = &task.description {
+            lines.push(Line::default());
+            lines.extend(description.lines().map(|line| Line::from(line.to_string())));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
+
+/// Day of a task date in the display time zone; other values are returned unchanged
+fn short_date(date: &str) -> String {
+    TaskDate::parse(date).map_or_else(|| date.to_string(), TaskDate::format_date)
+}
+
+/// Forward terminal events from a blocking reader thread
+fn spawn_input_reader() -> mpsc::UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Show the interactive board until the user quits. Tasks are reloaded on `r`,
+/// every `refresh` interval if given, and when the server reports a change.
+pub async fn run_board(mcp_client: &McpClient, refresh: Option<Duration>) -> Result<()> {
+    let mut board = Board::new(mcp_client.get_all_tasks().await?);
+
+    let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+    let result = event_loop(&mut terminal, &mut board, mcp_client, refresh).await;
+    ratatui::restore();
+
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    board: &mut Board,
+    mcp_client: &McpClient,
+    refresh: Option<Duration>,
+) -> Result<()> {
+    let mut input = spawn_input_reader();
+    let mut ticker = tokio::time::interval(refresh.unwrap_or(Duration::from_secs(1)));
+    ticker.reset();
+
+    loop {
+        terminal.draw(|frame| board.draw(frame))?;
+
+        let action = tokio::select! {
+            event = input.recv() => match event {
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => board.handle_key(key),
+                Some(_) => None,
+                None => Some(Action::Quit),
+            },
+            _ = ticker.tick(), if refresh.is_some() => Some(Action::Refresh { manual: false }),
+            _ = mcp_client.changed() => {
+                debug!("Server reported a change, refreshing the board");
+                Some(Action::Refresh { manual: false })
+            }
+        };
+
+        let manual = matches!(action, Some(Action::Refresh { manual: true }));
+        let follow = match action {
+            None => continue,
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Refresh { .. }) => board.selected_task().map(|task| task.id.clone()),
+            Some(Action::Move { id, to }) => {
+                let task_title = board.selected_task().map(|task| task.title.clone());
+                let moved = if COLUMNS[to] == TaskStatus::Completed {
+                    mcp_client.complete_task(&id).await
+                } else {
+                    let update = TaskUpdate {
+                        status: Some(COLUMNS[to].as_str().to_string()),
+                        ..Default::default()
+                    };
+                    mcp_client.update_task(&id, &update).await
+                };
+                board.message = match moved {
+                    Ok(_) => format!(
+                        "Moved '{}' to {}",
+                        task_title.unwrap_or_default(),
+                        COLUMNS[to].label()
+                    ),
+                    Err(e) => format!("Failed to move task {}: {:#}", id, e),
+                };
+                Some(id)
+            }
+        };
+
+        mcp_client.invalidate_cache();
+        match mcp_client.get_all_tasks().await {
+            Ok(tasks) => {
+                if manual {
+                    board.message = format!(
+                        "Refreshed {} tasks at {}",
+                        tasks.len(),
+                        chrono::Local::now().format("%H:%M:%S")
+                    );
+                }
+                board.set_tasks(tasks, follow.as_deref());
+            }
+            Err(e) => board.message = format!("Refresh failed: {:#}", e),
+        }
+    }
+}
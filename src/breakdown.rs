@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::bulk::BulkResult;
+use crate::error::McpTasksError;
+use crate::mcp_client::{McpClient, NewTask, Task};
+use crate::structured;
+
+/// A subtask proposed by the AI for a larger task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedSubtask {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Rough effort estimate, e.g. "2h" or "1d"
+    #[serde(default)]
+    pub effort: Option<String>,
+}
+
+/// AI deep-dive of a single task: summary, effort, subtasks and blockers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskBreakdown {
+    #[serde(default)]
+    pub summary: String,
+    /// Total effort estimate for the task
+    #[serde(default)]
+    pub effort: Option<String>,
+    #[serde(default)]
+    pub subtasks: Vec<ProposedSubtask>,
+    #[serde(default)]
+    pub blockers: Vec<String>,
+}
+
+/// Prompt asking for a JSON breakdown of `task`
+pub fn breakdown_prompt(task: &Task) -> String {
+    format!(
+        r#"Break down the following task into concrete subtasks.
+
+Task:
+{task}
+
+You can use the available tools to look at related tasks or resources first, but do not create, update or complete any tasks.
+
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "summary": "short assessment of the task and how to approach it",
+  "effort": "total effort estimate, e.g. 3d",
+  "subtasks": [
+    {{"title": "...", "description": "...", "priority": "high|medium|low", "effort": "e.g. 4h"}}
+  ],
+  "blockers": ["anything that could block or delay the task"]
+}}"#,
+        task = serde_json::to_string_pretty(task).unwrap_or_else(|_| task.title.clone())
+    )
+}
+
+/// Parse the model's answer, tolerating Markdown code fences or text around the JSON
+pub fn parse_breakdown(response: &str) -> Result<TaskBreakdown> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain a JSON breakdown".to_string())
+    })?;
+
+    serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI task breakdown".to_string(),
+    ))
+}
+
+/// Tag linking a subtask to its parent task
+pub fn parent_tag(parent_id: &str) -> String {
+    format!("parent:{}", parent_id)
+}
+
+impl ProposedSubtask {
+    /// Task to create for this subtask; priority, due date and assignee default to the parent's
+    pub fn to_new_task(&self, parent: &Task) -> NewTask {
+        let mut tags = vec!["subtask".to_string(), parent_tag(&parent.id)];
+        if let Some(effort) = &self.effort {
+            tags.push(format!("effort:{}", effort));
+        }
+
+        NewTask {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            priority: self.priority.clone().or_else(|| parent.priority.clone()),
+            due_date: parent.due_date.clone(),
+            assignee: parent.assignee.clone(),
+            tags: Some(tags),
+        }
+    }
+}
+
+/// Create `subtasks` on the server, linked to `parent` by tag. A failure does
+/// not stop the rest; each subtask gets a result, in the order of `subtasks`.
+pub async fn create_subtasks(
+    mcp_client: &McpClient,
+    parent: &Task,
+    subtasks: &[ProposedSubtask],
+) -> Vec<BulkResult> {
+    let mut results = Vec::with_capacity(subtasks.len());
+
+    for subtask in subtasks {
+        let result = match mcp_client.create_task(&subtask.to_new_task(parent)).await {
+            Ok(mut task) => {
+                task.server = parent.server.clone();
+                BulkResult {
+                    id: task.task_ref().to_string(),
+                    title: task.title.clone(),
+                    ok: true,
+                    error: None,
+                    task: Some(task),
+                }
+            }
+            Err(e) => BulkResult {
+                id: String::new(),
+                title: subtask.title.clone(),
+                ok: false,
+                error: Some(format!("{:#}", e)),
+                task: None,
+            },
+        };
+        results.push(result);
+    }
+
+    let created = results.iter().filter(|result| result.ok).count();
+    info!(
+        "Created {} of {} subtasks for task {}",
+        created,
+        subtasks.len(),
+        parent.id
+    );
+    results
+}
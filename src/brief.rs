@@ -0,0 +1,229 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+use crate::agenda::{Agenda, AgendaEntry, AgendaScope, DueWindow};
+use crate::dates;
+use crate::history::HistoryEntry;
+use crate::mcp_client::Task;
+use crate::status::TaskStatus;
+
+/// Marker the briefing prompt starts with; the mock provider recognizes it
+pub const BRIEF_PROMPT_HEADER: &str = "Morning briefing";
+
+pub const BRIEF_SYSTEM_PROMPT: &str = "You are a focused chief of staff writing a short morning briefing. Be brief, concrete and name the tasks that matter most today.";
+
+/// Characters of the latest analysis passed on to the briefing
+const MAX_HIGHLIGHT_CHARS: usize = 1500;
+
+/// What the latest stored analysis said
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisHighlights {
+    pub id: usize,
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+}
+
+impl AnalysisHighlights {
+    /// The structured summary and order, or the start of the analysis text
+    pub fn from_entry(entry: &HistoryEntry) -> Self {
+        let report = &entry.report;
+        let text = match &report.structured {
+            Some(structured) => {
+                let mut text = structured.summary.trim().to_string();
+                let order = structured
+                    .ordered()
+                    .iter()
+                    .take(5)
+                    .map(|task| format!("{} ({})", task.title, task.id))
+                    .collect::<Vec<_>>();
+                if !order.is_empty() {
+                    let _ = write!(text, "\nRecommended order: {}", order.join(", "));
+                }
+                text
+            }
+            None => {
+                let analysis = report.analysis.trim();
+                match analysis.char_indices().nth(MAX_HIGHLIGHT_CHARS) {
+                    Some((end, _)) => format!("{}...", &analysis[..end]),
+                    None => analysis.to_string(),
+                }
+            }
+        };
+
+        Self {
+            id: entry.id,
+            timestamp: report.timestamp,
+            text,
+        }
+    }
+}
+
+/// Overdue, due-today and in-progress tasks with the latest analysis and an
+/// optional AI-written summary
+#[derive(Debug, Serialize)]
+pub struct Brief {
+    pub generated_at: DateTime<Utc>,
+    pub today: NaiveDate,
+    pub overdue: Vec<AgendaEntry>,
+    pub due_today: Vec<AgendaEntry>,
+    /// In-progress tasks that are neither overdue nor due today
+    pub in_progress: Vec<Task>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<AnalysisHighlights>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl Brief {
+    /// Collect the briefing from the unfinished `tasks` as of `now`
+    pub fn build(tasks: Vec<Task>, latest: Option<&HistoryEntry>, now: DateTime<Utc>) -> Self {
+        let in_progress = tasks
+            .iter()
+            .filter(|task| task.normalized_status() == Some(TaskStatus::InProgress))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let agenda = Agenda::build(tasks, now, AgendaScope::Today);
+        let (overdue, due_today): (Vec<_>, Vec<_>) = agenda
+            .entries
+            .into_iter()
+            .partition(|entry| entry.window == DueWindow::Overdue);
+        let in_progress = in_progress
+            .into_iter()
+            .filter(|task| {
+                !overdue
+                    .iter()
+                    .chain(&due_today)
+                    .any(|entry| entry.task.id == task.id)
+            })
+            .collect();
+
+        Self {
+            generated_at: now,
+            today: agenda.today,
+            overdue,
+            due_today,
+            in_progress,
+            highlights: latest.map(AnalysisHighlights::from_entry),
+            summary: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overdue.is_empty() && self.due_today.is_empty() && self.in_progress.is_empty()
+    }
+
+    /// `2 overdue, 1 due today, 3 in progress`
+    pub fn counts(&self) -> String {
+        format!(
+            "{} overdue, {} due today, {} in progress",
+            self.overdue.len(),
+            self.due_today.len(),
+            self.in_progress.len()
+        )
+    }
+
+    /// Prompt asking the model for the briefing
+    pub fn summary_prompt(&self) -> String {
+        let mut prompt = format!(
+            "{} for {} ({}).\n\n",
+            BRIEF_PROMPT_HEADER,
+            self.today.format("%A, %Y-%m-%d"),
+            self.counts()
+        );
+
+        for (heading, entries) in [("Overdue", &self.overdue), ("Due today", &self.due_today)] {
+            let _ = writeln!(prompt, "{}:", heading);
+            if entries.is_empty() {
+                prompt.push_str("  (none)\n");
+            }
+            for entry in entries.iter() {
+                let _ = write!(prompt, "  - {} ({})", entry.task.title, entry.task.id);
+                if let Some(priority) = &entry.task.priority {
+                    let _ = write!(prompt, ", priority {}", priority);
+                }
+                if let Some(relative) = &entry.relative {
+                    let _ = write!(prompt, ", {}", relative);
+                }
+                prompt.push('\n');
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str("In progress:\n");
+        if self.in_progress.is_empty() {
+            prompt.push_str("  (none)\n");
+        }
+        for task in &self.in_progress {
+            let _ = writeln!(prompt, "  - {} ({})", task.title, task.id);
+        }
+        prompt.push('\n');
+
+        if let Some(highlights) = &self.highlights {
+            let _ = writeln!(
+                prompt,
+                "Latest analysis (#{}, {}):\n{}\n",
+                highlights.id,
+                dates::format_instant(highlights.timestamp, "%Y-%m-%d"),
+                highlights.text
+            );
+        }
+
+        prompt.push_str(
+            "Write a morning briefing of at most five short sentences: what needs attention first, what is due today and what to keep moving. Mention the latest analysis only where it still applies. Plain text, no headings.",
+        );
+        prompt
+    }
+
+    /// The briefing as plain text for the terminal
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "☀️  Briefing for {}: {}\n",
+            self.today.format("%A, %Y-%m-%d"),
+            self.counts()
+        );
+
+        if let Some(summary) = &self.summary {
+            let _ = writeln!(out, "\n🤖 {}", summary.trim());
+        }
+
+        for (heading, entries) in [
+            (DueWindow::Overdue.heading(), &self.overdue),
+            (DueWindow::Today.heading(), &self.due_today),
+        ] {
+            if entries.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n{}", heading);
+            for entry in entries.iter() {
+                let _ = write!(out, "  - {} ({})", entry.task.title, entry.task.id);
+                if let Some(relative) = &entry.relative {
+                    let _ = write!(out, ", {}", relative);
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.in_progress.is_empty() {
+            out.push_str("\n🔨 In Progress\n");
+            for task in &self.in_progress {
+                let _ = writeln!(out, "  - {} ({})", task.title, task.id);
+            }
+        }
+
+        if self.summary.is_none()
+            && let Some(highlights) = &self.highlights
+        {
+            let _ = writeln!(
+                out,
+                "\n📊 Latest analysis (#{}, {})\n{}",
+                highlights.id,
+                dates::format_instant(highlights.timestamp, "%Y-%m-%d %H:%M"),
+                highlights.text
+            );
+        }
+
+        out
+    }
+}
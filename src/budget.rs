@@ -0,0 +1,344 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use futures::future::BoxFuture;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::McpTasksError;
+use crate::history::{HistoryStore, SpendEntry};
+use crate::llm::{LlmProvider, TokenSink};
+use crate::tooling::{ChatRequest, ChatResponse};
+use crate::usage::{Pricing, TokenUsage};
+
+/// Fewest tokens a response is limited to before a request is refused instead
+const MIN_MAX_TOKENS: u32 = 256;
+
+/// Budget shared by every provider of the process, so `--max-cost` covers the
+/// whole run
+static SHARED: OnceLock<Arc<Budget>> = OnceLock::new();
+
+/// Spending limits for AI requests: `--max-cost` for one run and a monthly
+/// budget over the spend ledger. Every request's cost is added to the ledger,
+/// with or without limits.
+pub struct Budget {
+    max_cost: Option<f64>,
+    monthly: Option<f64>,
+    /// Spent this month before this run, from the ledger
+    spent_this_month: f64,
+    /// Spent by this run
+    spent: Mutex<f64>,
+    pricing: Pricing,
+    history: HistoryStore,
+}
+
+impl Budget {
+    pub fn new(config: &Config) -> Result<Self> {
+        let history = HistoryStore::new(&config.data_dir);
+        let spent_this_month = match config.monthly_budget {
+            Some(_) => history.spend_since(start_of_month(Utc::now()))?,
+            None => 0.0,
+        };
+
+        Ok(Self {
+            max_cost: config.max_cost,
+            monthly: config.monthly_budget,
+            spent_this_month,
+            spent: Mutex::new(0.0),
+            pricing: config.pricing,
+            history,
+        })
+    }
+
+    /// The process-wide budget, created from `config` on first use
+    pub fn shared(config: &Config) -> Result<Arc<Self>> {
+        if let Some(budget) = SHARED.get() {
+            return Ok(budget.clone());
+        }
+        let budget = Arc::new(Self::new(config)?);
+        Ok(SHARED.get_or_init(|| budget).clone())
+    }
+
+    /// The process-wide budget, once a provider has been created
+    pub fn active() -> Option<Arc<Self>> {
+        SHARED.get().cloned()
+    }
+
+    /// Whether a run or monthly limit is set
+    pub fn is_limited(&self) -> bool {
+        self.max_cost.is_some() || self.monthly.is_some()
+    }
+
+    /// USD left under the tighter of the two limits, and which limit that is;
+    /// `None` without limits
+    fn remaining(&self) -> Option<(f64, String)> {
+        let spent = *self.spent.lock().unwrap();
+        let run = self.max_cost.map(|max_cost| {
+            (
+                max_cost - spent,
+                format!("the --max-cost of ${} for this run", max_cost),
+            )
+        });
+        let month = self.monthly.map(|monthly| {
+            (
+                monthly - self.spent_this_month - spent,
+                format!(
+                    "the monthly budget of ${} (${:.4} spent this month)",
+                    monthly,
+                    self.spent_this_month + spent
+                ),
+            )
+        });
+
+        [run, month]
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    /// Cost of `request` if the response used all of `max_tokens`; nothing is
+    /// counted as cached
+    fn estimate(&self, request: &ChatRequest, max_tokens: u32) -> f64 {
+        self.pricing.cost(&TokenUsage {
+            prompt_tokens: request.estimated_prompt_tokens(),
+            completion_tokens: max_tokens as u64,
+            ..Default::default()
+        })
+    }
+
+    /// Whether `request` fits into the budget without being cut down
+    pub fn affords(&self, request: &ChatRequest) -> bool {
+        self.remaining()
+            .is_none_or(|(remaining, _)| self.estimate(request, request.max_tokens) <= remaining)
+    }
+
+    /// Make `request` fit into the budget: lower its `max_tokens` when a full
+    /// response would not fit, or fail when even a short one would go over
+    pub fn fit(&self, request: &mut ChatRequest) -> Result<()> {
+        let Some((remaining, limit)) = self.remaining() else {
+            return Ok(());
+        };
+        let cost = self.estimate(request, request.max_tokens);
+        debug!(
+            "AI request estimated at ${:.4}, ${:.4} left",
+            cost, remaining
+        );
+        if cost <= remaining {
+            return Ok(());
+        }
+
+        let prompt_cost = self.estimate(request, 0);
+        let affordable = ((remaining - prompt_cost) * 1_000_000.0 / self.pricing.output).floor();
+        if affordable >= MIN_MAX_TOKENS as f64 {
+            let max_tokens = request.max_tokens.min(affordable as u32);
+            warn!(
+                "Lowering max_tokens from {} to {} to stay within {}",
+                request.max_tokens, max_tokens, limit
+            );
+            request.max_tokens = max_tokens;
+            return Ok(());
+        }
+
+        anyhow::bail!(McpTasksError::Budget(format!(
+            "The next AI request would cost about ${:.4}, more than the ${:.4} left of {}",
+            self.estimate(request, MIN_MAX_TOKENS),
+            remaining.max(0.0),
+            limit
+        )))
+    }
+
+    /// Count what a request cost against the budget and in the spend ledger
+    pub fn record(&self, model: &str, usage: TokenUsage) {
+        let cost_usd = self.pricing.cost(&usage);
+        *self.spent.lock().unwrap() += cost_usd;
+
+        let entry = SpendEntry {
+            timestamp: Utc::now(),
+            model: model.to_string(),
+            usage,
+            cost_usd,
+        };
+        if let Err(e) = self.history.record_spend(&entry) {
+            warn!("Failed to record AI spend: {:#}", e);
+        }
+    }
+
+    /// Estimated spend of this run and, with a monthly budget, of this month
+    pub fn describe(&self) -> String {
+        let spent = *self.spent.lock().unwrap();
+        match self.monthly {
+            Some(monthly) => format!(
+                "${:.4} this run, ${:.4} of ${} this month",
+                spent,
+                self.spent_this_month + spent,
+                monthly
+            ),
+            None => format!("${:.4} this run", spent),
+        }
+    }
+}
+
+/// Midnight UTC on the first day of `now`'s month
+pub fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
+
+/// Provider that keeps requests within the budget and records what they cost
+pub struct BudgetedProvider {
+    inner: Box<dyn LlmProvider>,
+    budget: Arc<Budget>,
+}
+
+impl BudgetedProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, budget: Arc<Budget>) -> Self {
+        Self { inner, budget }
+    }
+
+    fn record(&self, model: &str, response: &Result<ChatResponse>) {
+        if let Ok(ChatResponse {
+            usage: Some(usage), ..
+        }) = response
+        {
+            self.budget.record(model, TokenUsage::from(usage));
+        }
+    }
+}
+
+impl LlmProvider for BudgetedProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn chat(&self, mut request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            self.budget.fit(&mut request)?;
+            let model = request.model.clone();
+            let response = self.inner.chat(request).await;
+            self.record(&model, &response);
+            response
+        })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        mut request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            self.budget.fit(&mut request)?;
+            let model = request.model.clone();
+            let response = self.inner.chat_streaming(request, on_token).await;
+            self.record(&model, &response);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// A budget where every response token costs $1 and the prompt is free
+    fn budget(max_cost: Option<f64>, monthly: Option<f64>, spent_this_month: f64) -> Budget {
+        Budget {
+            max_cost,
+            monthly,
+            spent_this_month,
+            spent: Mutex::new(0.0),
+            pricing: Pricing {
+                input: 0.0,
+                cached_input: 0.0,
+                output: 1_000_000.0,
+            },
+            history: HistoryStore::new(Path::new("unused")),
+        }
+    }
+
+    fn request(max_tokens: u32) -> ChatRequest {
+        ChatRequest {
+            model: "deepseek-chat".to_string(),
+            messages: Vec::new(),
+            tools: None,
+            tool_choice: None,
+            temperature: 0.0,
+            max_tokens,
+            stream: None,
+            stream_options: None,
+            response_format: None,
+        }
+    }
+
+    /// `max_tokens` after fitting a request that asks for `max_tokens`
+    fn fitted(budget: &Budget, max_tokens: u32) -> Result<u32> {
+        let mut request = request(max_tokens);
+        budget.fit(&mut request)?;
+        Ok(request.max_tokens)
+    }
+
+    #[test]
+    fn requests_under_or_at_the_limit_are_unchanged() {
+        let budget = budget(Some(1000.0), None, 0.0);
+        assert!(budget.affords(&request(500)));
+        assert_eq!(fitted(&budget, 500).unwrap(), 500);
+        assert!(budget.affords(&request(1000)));
+        assert_eq!(fitted(&budget, 1000).unwrap(), 1000);
+    }
+
+    #[test]
+    fn requests_over_the_limit_are_cut_down() {
+        let budget = budget(Some(1000.0), None, 0.0);
+        assert!(!budget.affords(&request(4000)));
+        assert_eq!(fitted(&budget, 4000).unwrap(), 1000);
+
+        *budget.spent.lock().unwrap() = 700.0;
+        assert_eq!(fitted(&budget, 4000).unwrap(), 300);
+    }
+
+    #[test]
+    fn requests_are_refused_below_the_smallest_response() {
+        let budget = budget(Some(1000.0), None, 0.0);
+        *budget.spent.lock().unwrap() = 1000.0 - MIN_MAX_TOKENS as f64;
+        assert_eq!(fitted(&budget, 4000).unwrap(), MIN_MAX_TOKENS);
+
+        *budget.spent.lock().unwrap() += 1.0;
+        let error = fitted(&budget, 4000).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<McpTasksError>(),
+            Some(McpTasksError::Budget(_))
+        ));
+        assert!(
+            error
+                .to_string()
+                .contains("more than the $255.0000 left of the --max-cost of $1000"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn the_tighter_limit_applies() {
+        // $600 of the monthly budget is already spent, leaving less than --max-cost
+        let capped = budget(Some(1000.0), Some(1000.0), 600.0);
+        assert_eq!(fitted(&capped, 4000).unwrap(), 400);
+        assert_eq!(
+            capped.remaining().unwrap().1,
+            "the monthly budget of $1000 ($600.0000 spent this month)"
+        );
+
+        let unlimited = budget(None, None, 600.0);
+        assert!(!unlimited.is_limited());
+        assert_eq!(fitted(&unlimited, 4000).unwrap(), 4000);
+    }
+
+    #[test]
+    fn start_of_month_is_midnight_on_the_first() {
+        let now = Utc.with_ymd_and_hms(2025, 7, 17, 15, 30, 0).unwrap();
+        assert_eq!(
+            start_of_month(now),
+            Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
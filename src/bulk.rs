@@ -0,0 +1,221 @@
+use futures::future::join_all;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use crate::capabilities::Capability;
+use crate::mcp_client::{McpClient, Task, TaskQuery, TaskUpdate};
+use crate::progress;
+
+/// Mutations running at the same time unless `--concurrency` says otherwise
+pub const DEFAULT_CONCURRENCY: u16 = 4;
+
+/// Split `key=value,key=value` into trimmed pairs
+fn parse_pairs(input: &str) -> Result<Vec<(String, String)>, String> {
+    input
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() && !value.trim().is_empty() => {
+                Ok((key.trim().to_lowercase(), value.trim().to_string()))
+            }
+            _ => Err(format!("expected key=value, got '{}'", pair.trim())),
+        })
+        .collect()
+}
+
+/// Parse a filter such as `status=pending,tag=infra`; keys are status,
+/// priority, tag and assignee, each at most once
+pub fn parse_filter(input: &str) -> Result<TaskQuery, String> {
+    let mut query = TaskQuery::default();
+    let pairs = parse_pairs(input)?;
+    if pairs.is_empty() {
+        return Err("the filter needs at least one key=value condition".to_string());
+    }
+
+    for (key, value) in pairs {
+        let slot = match key.as_str() {
+            "status" => &mut query.status,
+            "priority" => &mut query.priority,
+            "tag" => &mut query.tag,
+            "assignee" => &mut query.assignee,
+            other => {
+                return Err(format!(
+                    "unknown filter key '{}': use status, priority, tag or assignee",
+                    other
+                ));
+            }
+        };
+        if slot.replace(value).is_some() {
+            return Err(format!("filter key '{}' is given twice", key));
+        }
+    }
+    Ok(query)
+}
+
+/// `field=value` pairs of `--set`, in the order given
+#[derive(Debug, Clone)]
+pub struct Assignments(pub Vec<(String, String)>);
+
+/// Parse the `--set` assignments such as `priority=high,due=2025-07-01`;
+/// values are validated by the caller
+pub fn parse_assignments(input: &str) -> Result<Assignments, String> {
+    let pairs = parse_pairs(input)?;
+    if pairs.is_empty() {
+        return Err("--set needs at least one field=value".to_string());
+    }
+    Ok(Assignments(pairs))
+}
+
+/// The change `bulk` applies to every matching task
+#[derive(Debug, Clone)]
+pub enum BulkAction {
+    Set(TaskUpdate),
+    Complete,
+    AddTag(String),
+    Delete,
+}
+
+impl BulkAction {
+    /// `set priority=high`, `add tag #infra`, ...
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Set(update) => {
+                let fields = serde_json::to_value(update)
+                    .ok()
+                    .and_then(|value| value.as_object().cloned())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(field, value)| match value {
+                        serde_json::Value::String(value) => format!("{}={}", field, value),
+                        value => format!("{}={}", field, value),
+                    })
+                    .collect::<Vec<_>>();
+                format!("set {}", fields.join(", "))
+            }
+            Self::Complete => "complete".to_string(),
+            Self::AddTag(tag) => format!("add tag #{}", tag),
+            Self::Delete => "delete".to_string(),
+        }
+    }
+
+    /// Server operation the action needs
+    pub fn capability(&self) -> Capability {
+        match self {
+            Self::Set(_) | Self::AddTag(_) => Capability::Update,
+            Self::Complete => Capability::Complete,
+            Self::Delete => Capability::Delete,
+        }
+    }
+
+    /// Whether applying the action to `task` would change nothing
+    pub fn is_noop(&self, task: &Task) -> bool {
+        match self {
+            Self::AddTag(tag) => task
+                .tags
+                .iter()
+                .flatten()
+                .any(|existing| existing.eq_ignore_ascii_case(tag)),
+            _ => false,
+        }
+    }
+}
+
+/// What happened to one task
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkResult {
+    pub id: String,
+    pub title: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The task as the server returned it; none for deletions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+}
+
+impl BulkResult {
+    /// Failure for a task ID the AI named that is not among the tasks it was given
+    pub fn unknown_task(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: String::new(),
+            ok: false,
+            error: Some(format!(
+                "the AI named task '{}', which is not one of the tasks it was given",
+                id
+            )),
+            task: None,
+        }
+    }
+}
+
+/// Apply `action` to each of `tasks`, at most `concurrency` at a time.
+/// Failures are recorded per task instead of stopping the run; results are
+/// in the order of `tasks`.
+pub async fn apply(
+    client: &McpClient,
+    tasks: &[Task],
+    action: &BulkAction,
+    concurrency: usize,
+) -> Vec<BulkResult> {
+    let changes = tasks
+        .iter()
+        .map(|task| (task, action.clone()))
+        .collect::<Vec<_>>();
+    apply_each(client, &changes, concurrency).await
+}
+
+/// Like [`apply`] with an action of its own for each task, e.g. a different
+/// priority per task
+pub async fn apply_each(
+    client: &McpClient,
+    changes: &[(&Task, BulkAction)],
+    concurrency: usize,
+) -> Vec<BulkResult> {
+    let permits = Semaphore::new(concurrency.max(1));
+    let permits = &permits;
+    let total = changes.len();
+
+    join_all(
+        changes
+            .iter()
+            .enumerate()
+            .map(|(idx, (task, action))| async move {
+                let _permit = permits.acquire().await;
+                progress::set_phase(format!("task {} of {}: {}", idx + 1, total, task.id));
+
+                let result = match action {
+                    BulkAction::Set(update) => client.update_task(&task.id, update).await.map(Some),
+                    BulkAction::Complete => client.complete_task(&task.id).await.map(Some),
+                    BulkAction::AddTag(tag) => {
+                        let mut tags = task.tags.clone().unwrap_or_default();
+                        tags.push(tag.clone());
+                        let update = TaskUpdate {
+                            tags: Some(tags),
+                            ..Default::default()
+                        };
+                        client.update_task(&task.id, &update).await.map(Some)
+                    }
+                    BulkAction::Delete => client.delete_task(&task.id).await.map(|()| None),
+                };
+
+                match result {
+                    Ok(updated) => BulkResult {
+                        id: task.task_ref().to_string(),
+                        title: task.title.clone(),
+                        ok: true,
+                        error: None,
+                        task: updated,
+                    },
+                    Err(e) => BulkResult {
+                        id: task.task_ref().to_string(),
+                        title: task.title.clone(),
+                        ok: false,
+                        error: Some(format!("{:#}", e)),
+                        task: None,
+                    },
+                }
+            }),
+    )
+    .await
+}
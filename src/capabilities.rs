@@ -0,0 +1,138 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::McpTasksError;
+
+/// Task operations the CLI relies on, each provided by one tool of the MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    List,
+    Get,
+    Create,
+    Update,
+    Complete,
+    Delete,
+    Stats,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 7] = [
+        Capability::List,
+        Capability::Get,
+        Capability::Create,
+        Capability::Update,
+        Capability::Complete,
+        Capability::Delete,
+        Capability::Stats,
+    ];
+
+    /// Name of the MCP tool that provides it
+    pub fn tool(self) -> &'static str {
+        match self {
+            Capability::List => "list_tasks",
+            Capability::Get => "get_task",
+            Capability::Create => "create_task",
+            Capability::Update => "update_task",
+            Capability::Complete => "complete_task",
+            Capability::Delete => "delete_task",
+            Capability::Stats => "task_stats",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::List => "list",
+            Capability::Get => "get",
+            Capability::Create => "create",
+            Capability::Update => "update",
+            Capability::Complete => "complete",
+            Capability::Delete => "delete",
+            Capability::Stats => "stats",
+        }
+    }
+}
+
+/// Error for a command that needs a tool the server does not offer
+pub fn missing_tool(server: &str, tool: &str) -> McpTasksError {
+    McpTasksError::McpProtocol(format!(
+        "MCP server '{}' does not provide tool '{}'",
+        server, tool
+    ))
+}
+
+/// What an MCP server can do, from the tool list cached when it connected
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCapabilities {
+    pub server: String,
+    /// Names of the server's tools; empty when they could not be listed
+    pub tools: Vec<String>,
+    /// Whether the server exposes resources
+    pub resources: bool,
+}
+
+impl ServerCapabilities {
+    /// Whether the tool list is known; when listing failed nothing is ruled out
+    pub fn known(&self) -> bool {
+        !self.tools.is_empty()
+    }
+
+    pub fn has_tool(&self, name: &str) -> bool {
+        self.tools.iter().any(|tool| tool == name)
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.has_tool(capability.tool())
+    }
+
+    /// Which of the CLI's operations the server supports, by [`Capability::as_str`]
+    pub fn matrix(&self) -> BTreeMap<&'static str, bool> {
+        Capability::ALL
+            .into_iter()
+            .map(|capability| (capability.as_str(), self.supports(capability)))
+            .collect()
+    }
+
+    /// Fail with [`missing_tool`] unless the server offers `capability`'s tool
+    pub fn require(&self, capability: Capability) -> Result<()> {
+        if self.known() && !self.supports(capability) {
+            anyhow::bail!(missing_tool(&self.server, capability.tool()));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of connecting to one configured server, as `doctor` reports it
+#[derive(Debug, Serialize)]
+pub struct ServerCheck {
+    pub server: String,
+    pub connected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ServerCapabilities>,
+    /// Supported operations by name; empty when the server could not be reached
+    pub supports: BTreeMap<&'static str, bool>,
+}
+
+impl ServerCheck {
+    pub fn connected(capabilities: ServerCapabilities) -> Self {
+        Self {
+            server: capabilities.server.clone(),
+            connected: true,
+            error: None,
+            supports: capabilities.matrix(),
+            capabilities: Some(capabilities),
+        }
+    }
+
+    pub fn failed(server: &str, error: &anyhow::Error) -> Self {
+        Self {
+            server: server.to_string(),
+            connected: false,
+            error: Some(format!("{:#}", error)),
+            capabilities: None,
+            supports: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::io::Write;
+use std::path::Path;
+use tracing::{error, info};
+
+use crate::deepseek_client::DeepSeekClient;
+use crate::error::McpTasksError;
+use crate::mcp_client::McpClientPool;
+use crate::output;
+use crate::tooling::{Message, ToolObject, create_mcp_tool_definitions};
+
+/// Interactive conversation with DeepSeek that keeps history across turns
+pub struct ChatSession<'a> {
+    deepseek_client: &'a DeepSeekClient,
+    pool: &'a McpClientPool,
+    tools: Vec<ToolObject>,
+    messages: Vec<Message>,
+}
+
+impl<'a> ChatSession<'a> {
+    pub async fn new(deepseek_client: &'a DeepSeekClient, pool: &'a McpClientPool) -> Result<Self> {
+        // Changes are confirmed on the terminal by the client's mutation policy
+        let tools = create_mcp_tool_definitions(pool).await?;
+
+        Ok(Self {
+            deepseek_client,
+            pool,
+            tools,
+            messages: vec![deepseek_client.tool_system_message()],
+        })
+    }
+
+    /// Send one user message and return the assistant's answer
+    pub async fn ask(&mut self, question: &str) -> Result<String> {
+        // Roll back to this point on failure so a half-finished tool exchange
+        // doesn't poison later turns
+        let checkpoint = self.messages.len();
+
+        // The reasoner only needs its reasoning within the turn that produced it
+        for message in &mut self.messages {
+            message.reasoning_content = None;
+        }
+
+        self.messages.push(Message {
+            role: "user".to_string(),
+            content: question.to_string(),
+            tool_call_id: None,
+            tool_calls: None,
+            reasoning_content: None,
+        });
+
+        let (answer, tool_calls) = match self
+            .deepseek_client
+            .run_tool_loop(&mut self.messages, &self.tools, self.pool)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                self.messages.truncate(checkpoint);
+                return Err(e);
+            }
+        };
+
+        // Reasoning was already shown live if asked for; chat turns are not reported
+        self.deepseek_client.take_reasoning();
+
+        info!("Chat turn completed with {} tool calls", tool_calls.len());
+        Ok(answer)
+    }
+
+    /// Drop the conversation history, keeping only the system prompt
+    pub fn reset(&mut self) {
+        self.messages.truncate(1);
+    }
+
+    /// Save the transcript as JSON (`.json`) or Markdown (any other extension)
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        let path = Path::new(file_path);
+
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::to_string_pretty(&self.messages)
+                .context("Failed to serialize chat transcript")?,
+            _ => self.format_transcript_as_markdown(),
+        };
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        std::fs::write(path, content).with_context(|| {
+            McpTasksError::Report(format!("Failed to write chat transcript to {}", file_path))
+        })?;
+
+        info!("Chat transcript saved to {}", file_path);
+        Ok(())
+    }
+
+    fn format_transcript_as_markdown(&self) -> String {
+        let mut transcript = format!(
+            "# Chat Transcript\n\n**Saved:** {}\n\n---\n\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        );
+
+        for message in &self.messages {
+            match message.role.as_str() {
+                "user" => transcript.push_str(&format!("**You:** {}\n\n", message.content)),
+                "assistant" => {
+                    if let Some(tool_calls) = &message.tool_calls {
+                        for tool_call in tool_calls {
+                            transcript.push_str(&format!(
+                                "> 🔧 Tool call: `{}` {}\n\n",
+                                tool_call.function.name, tool_call.function.arguments
+                            ));
+                        }
+                    }
+                    if !message.content.is_empty() {
+                        transcript.push_str(&format!("**Assistant:** {}\n\n", message.content));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        transcript
+    }
+}
+
+/// Read one line from stdin without blocking the runtime. Goes through the same
+/// std stdin buffer as tool-call confirmations, so input is consumed in order.
+async fn read_line() -> Result<Option<String>> {
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        let bytes = std::io::stdin()
+            .read_line(&mut line)
+            .context("Failed to read from stdin")?;
+        Ok((bytes > 0).then_some(line))
+    })
+    .await?
+}
+
+/// Run the interactive read-eval-print loop until `/exit` or end of input
+pub async fn run_repl(deepseek_client: &DeepSeekClient, pool: &McpClientPool) -> Result<()> {
+    let mut session = ChatSession::new(deepseek_client, pool).await?;
+    let streaming = deepseek_client.is_streaming();
+
+    crate::notice!(
+        "💬 Chat with DeepSeek about your tasks. The AI can query the MCP server directly."
+    );
+    crate::notice!(
+        "Commands: /reset (clear history), /save [path] (save transcript), /exit (quit)\n"
+    );
+
+    loop {
+        print!("you> ");
+        std::io::stdout().flush()?;
+
+        let Some(line) = read_line().await? else {
+            println!();
+            break;
+        };
+        let input = line.trim();
+
+        if input.is_empty() {
+            continue;
+        }
+
+        match input
+            .split_once(' ')
+            .map_or((input, ""), |(cmd, rest)| (cmd, rest.trim()))
+        {
+            ("/exit" | "/quit", _) => break,
+            ("/reset", _) => {
+                session.reset();
+                crate::say!("🧹 Conversation history cleared.\n");
+            }
+            ("/save", path) => {
+                let path = if path.is_empty() {
+                    format!("chat-{}.md", Utc::now().format("%Y%m%d-%H%M%S"))
+                } else {
+                    path.to_string()
+                };
+                match session.save(&path) {
+                    Ok(()) => crate::say!("💾 Transcript saved to: {}\n", path),
+                    Err(e) => crate::esay!("⚠️  Failed to save transcript: {}\n", e),
+                }
+            }
+            (cmd, _) if cmd.starts_with('/') => {
+                println!(
+                    "Unknown command '{}'. Available: /reset, /save [path], /exit\n",
+                    cmd
+                );
+            }
+            _ => {
+                print!("\n{}", output::decorate("🤖 "));
+                std::io::stdout().flush()?;
+                match session.ask(input).await {
+                    Ok(answer) => {
+                        if !streaming {
+                            print!("{}", answer);
+                        }
+                        println!("\n");
+                    }
+                    Err(e) => {
+                        error!("Chat turn failed: {}", e);
+                        crate::esay!("❌ DeepSeek request failed: {}\n", e);
+                    }
+                }
+            }
+        }
+    }
+
+    crate::notice!("👋 Bye!");
+    Ok(())
+}
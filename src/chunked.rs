@@ -0,0 +1,53 @@
+/// Heading each part's findings get in the synthesis prompt; the mock provider
+/// recognizes it
+pub const PART_FINDINGS_MARKER: &str = "## Findings for part ";
+
+pub const CHUNK_SYSTEM_PROMPT: &str = "You are a task analysis expert reviewing one part of a large backlog. Your notes are merged with the notes on the other parts into one analysis, so be concise and always name tasks by their ID.";
+
+/// Most tasks analyzed in one prompt before the backlog is split into parts
+pub const DEFAULT_CHUNK_SIZE: usize = 100;
+
+/// Parts analyzed at the same time
+pub const DEFAULT_CHUNK_CONCURRENCY: usize = 4;
+
+/// Prompt asking for the findings on one part of the backlog
+pub fn chunk_prompt(task_summary: &str, part: usize, parts: usize, task_count: usize) -> String {
+    format!(
+        r#"This is part {part} of {parts} of a backlog too large to analyze at once; it holds {task_count} of the pending tasks.
+
+{task_summary}
+Write short notes on this part for a later synthesis:
+- the most urgent and important tasks, with why
+- dependencies and conflicts between tasks
+- tasks that look complex or at risk of slipping
+- quick wins
+
+Refer to every task by its ID and title. Use bullet points, no introduction or conclusion."#
+    )
+}
+
+/// Stand-in for the task list in the synthesis prompt: the findings of every
+/// part, in order
+pub fn merged_findings(findings: &[String], chunk_sizes: &[usize], task_count: usize) -> String {
+    let mut merged = format!(
+        "The {} tasks were too many for one prompt, so they were reviewed in {} parts. Base the analysis on the notes for each part below and treat them as one backlog.\n\n",
+        task_count,
+        findings.len()
+    );
+
+    let mut first = 1;
+    for (idx, (notes, size)) in findings.iter().zip(chunk_sizes).enumerate() {
+        merged.push_str(&format!(
+            "{}{} of {} (tasks {}-{})\n\n{}\n\n",
+            PART_FINDINGS_MARKER,
+            idx + 1,
+            findings.len(),
+            first,
+            first + size - 1,
+            notes.trim()
+        ));
+        first += size;
+    }
+
+    merged
+}
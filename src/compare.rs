@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::history::HistoryEntry;
+use crate::mcp_client::Task;
+
+/// Marker the comparison prompt contains; the mock provider recognizes it
+pub const COMPARE_PROMPT_MARKER: &str = "Compare this analysis with the previous one";
+
+/// Heading the model is asked to put the comparison under
+pub const DELTA_HEADING: &str = "## Changes Since Last Analysis";
+
+/// Longer previous analyses are cut to keep the prompt small
+const MAX_PREVIOUS_ANALYSIS_CHARS: usize = 6000;
+
+/// What changed since an earlier analysis, as described by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisDelta {
+    /// History id of the analysis compared with
+    pub previous_id: usize,
+    pub previous_timestamp: DateTime<Utc>,
+    pub changes: String,
+}
+
+/// Prompt section with the previous analysis and instructions for the comparison
+pub fn comparison_prompt(previous: &HistoryEntry, tasks: &[Task]) -> String {
+    let report = &previous.report;
+    let previous_ids = report
+        .tasks
+        .iter()
+        .map(|task| task.id.as_str())
+        .collect::<HashSet<_>>();
+    let current_ids = tasks
+        .iter()
+        .map(|task| task.id.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut prompt = format!(
+        "\n\n{} (#{}, {}, {} tasks).\n\n",
+        COMPARE_PROMPT_MARKER,
+        previous.id,
+        report.timestamp.format("%Y-%m-%d %H:%M UTC"),
+        report.task_count
+    );
+
+    let list = |tasks: Vec<&Task>| {
+        if tasks.is_empty() {
+            "none".to_string()
+        } else {
+            tasks
+                .iter()
+                .map(|task| format!("{} ({})", task.title, task.id))
+                .collect::<Vec<_>>()
+                .join("; ")
+        }
+    };
+    prompt.push_str(&format!(
+        "New since then: {}\n",
+        list(
+            tasks
+                .iter()
+                .filter(|task| !previous_ids.contains(task.id.as_str()))
+                .collect()
+        )
+    ));
+    prompt.push_str(&format!(
+        "No longer pending: {}\n\n",
+        list(
+            report
+                .tasks
+                .iter()
+                .filter(|task| !current_ids.contains(task.id.as_str()))
+                .collect()
+        )
+    ));
+
+    let analysis = report.analysis.trim();
+    let analysis = match analysis.char_indices().nth(MAX_PREVIOUS_ANALYSIS_CHARS) {
+        Some((cut, _)) => format!("{}\n[...]", &analysis[..cut]),
+        None => analysis.to_string(),
+    };
+    prompt.push_str(&format!("Previous analysis:\n\n<<<\n{}\n>>>\n\n", analysis));
+
+    prompt.push_str(&format!(
+        "After your analysis, add a final section headed exactly \"{}\" that covers:\n\
+         - Newly risky tasks: tasks that became urgent, overdue or blocked since the previous analysis\n\
+         - Resolved blockers: risks or blockers from the previous analysis that no longer apply\n\
+         - Progress on prior recommendations: which were followed, which are still open\n",
+        DELTA_HEADING
+    ));
+    prompt
+}
+
+/// Split the model's answer into the analysis and the comparison section
+pub fn split_delta(answer: &str) -> (String, Option<String>) {
+    match answer.find(DELTA_HEADING) {
+        Some(start) => {
+            let changes = answer[start + DELTA_HEADING.len()..].trim();
+            (
+                answer[..start].trim_end().to_string(),
+                (!changes.is_empty()).then(|| changes.to_string()),
+            )
+        }
+        None => (answer.to_string(), None),
+    }
+}
@@ -1,75 +1,1056 @@
 use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use crate::chunked;
+use crate::dates;
+use crate::error::McpTasksError;
+use crate::fields::TaskField;
+use crate::llm::ProviderKind;
+use crate::logger::LogFormat;
+use crate::output::DisplayFormat;
+use crate::prompts::Persona;
+use crate::report::OutputFormat;
+use crate::status::TaskStatus;
+use crate::template::TaskTemplate;
+use crate::todoist;
+use crate::tooling::MutationPolicy;
+use crate::usage::Pricing;
+
+/// Models served by the DeepSeek API
+pub const DEEPSEEK_MODELS: &[&str] = &["deepseek-chat", "deepseek-reasoner"];
+
+/// Alias used for the server configured through `MCP_SERVER_COMMAND`/`MCP_SERVER_ARGS`
+pub const DEFAULT_SERVER_ALIAS: &str = "todo";
+
+/// How the client reaches an MCP server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransportKind {
+    /// Spawn `command` and talk over its stdin/stdout
+    #[default]
+    Stdio,
+    /// Connect to `url` over WebSocket
+    Ws,
+}
+
+/// A named MCP server, spawned over stdio or reached over WebSocket
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpServerConfig {
+    pub name: String,
+    #[serde(default)]
+    pub transport: McpTransportKind,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Variables added to the spawned server's environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Working directory of the spawned server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Seconds a spawned server gets to exit after its stdin is closed before it
+    /// is killed; 0 kills it right away
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+    /// `ws://` or `wss://` endpoint of a WebSocket server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+fn default_shutdown_timeout() -> u64 {
+    3
+}
+
+impl McpServerConfig {
+    /// A server spawned over stdio
+    pub fn stdio(name: &str, command: String, args: Vec<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            transport: McpTransportKind::Stdio,
+            command,
+            args,
+            env: BTreeMap::new(),
+            cwd: None,
+            shutdown_timeout: default_shutdown_timeout(),
+            url: None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
-    pub mcp_server_command: String,
-    pub mcp_server_args: Vec<String>,
+    pub mcp_servers: Vec<McpServerConfig>,
+    pub default_server: String,
     pub request_timeout: u64,
     pub max_retries: u32,
     pub retry_delay: u64,
+    /// Seconds read-only MCP tool results are reused within a run; 0 disables the cache
+    pub cache_ttl: u64,
+    /// Start an MCP server again when it dies mid-session and replay the failed request
+    pub auto_restart: bool,
+    /// Restarts allowed per server and run with `auto_restart`
+    pub max_restarts: u32,
+    /// Chat API used for AI commands
+    pub provider: ProviderKind,
+    /// API root of the provider, overriding its default
+    pub llm_base_url: Option<String>,
+    /// API key for the openai-compatible provider
+    pub llm_api_key: Option<String>,
+    /// Chat requests sent per minute at most; `None` sends them right away
+    pub requests_per_minute: Option<u32>,
+    /// Tokens (prompt and completion) used per minute at most
+    pub tokens_per_minute: Option<u64>,
     pub deepseek_api_key: Option<String>,
+    pub deepseek_model: String,
+    /// API used for task embeddings; `None` uses the chat provider
+    pub embedding_provider: Option<ProviderKind>,
+    /// API root for embeddings, overriding the chat provider's
+    pub embedding_base_url: Option<String>,
+    /// API key for embeddings, overriding the chat provider's
+    pub embedding_api_key: Option<String>,
+    /// Embedding model; `None` uses the provider's default
+    pub embedding_model: Option<String>,
+    /// Sampling temperature (0.0-2.0); `deepseek-reasoner` ignores it
+    pub temperature: f32,
+    /// Maximum number of tokens the model may generate per response
+    pub max_tokens: u32,
+    /// Print the reasoning of `deepseek-reasoner` while it thinks
+    pub show_reasoning: bool,
+    /// Directory every chat request and response is written to as JSON, for prompt debugging
+    pub dump_prompts: Option<PathBuf>,
+    pub stream: bool,
+    pub display_format: DisplayFormat,
+    /// Show dates in tables and reports as "in 2 days" or "3 weeks ago"
+    pub relative_dates: bool,
+    pub data_dir: PathBuf,
+    /// Analysis prompt template: a template name or a path to a template file
+    pub prompt_template: Option<String>,
+    /// User-defined analysis prompt templates by name
+    pub prompt_templates: BTreeMap<String, String>,
+    /// Replaces the built-in system prompt of analyses
+    pub system_prompt: Option<String>,
+    /// Audience analyses are written for
+    pub analysis_persona: Option<Persona>,
+    /// Cron expression used by `daemon` when `--schedule` is not given
+    pub daemon_schedule: Option<String>,
+    /// Directory for reports written by `daemon` (defaults to `<data_dir>/reports`)
+    pub report_dir: Option<PathBuf>,
+    pub report_format: OutputFormat,
+    /// Address `daemon` serves Prometheus metrics on; `None` disables the endpoint
+    pub metrics_addr: Option<SocketAddr>,
+    /// How AI tool calls that change data are handled; `None` uses each command's default
+    pub tool_writes: Option<MutationPolicy>,
+    /// MCP tools the AI may always call, without confirmation
+    pub tool_allow: Vec<String>,
+    /// MCP tools the AI may never call
+    pub tool_deny: Vec<String>,
+    /// Model turns with tool calls before the AI must answer without tools
+    pub max_tool_iterations: u32,
+    /// Seconds an AI-triggered MCP tool call may take before it is cancelled; 0 waits
+    /// as long as it takes
+    pub tool_timeout: u64,
+    /// `tool_timeout` overrides per MCP tool; a trailing `*` matches a prefix
+    pub tool_timeouts: BTreeMap<String, u64>,
+    /// Most tasks in one analysis prompt; larger backlogs are analyzed in parts
+    pub chunk_size: usize,
+    /// Parts of a large backlog analyzed at the same time
+    pub chunk_concurrency: usize,
+    /// Token prices used for cost estimates
+    pub pricing: Pricing,
+    /// Most USD one run may spend on AI requests
+    pub max_cost: Option<f64>,
+    /// Most USD AI requests may cost per calendar month (UTC)
+    pub monthly_budget: Option<f64>,
+    /// Server status values mapped to known statuses, e.g. `doing` to in_progress
+    pub status_aliases: BTreeMap<String, TaskStatus>,
+    /// Server keys for task fields, e.g. `title` read from `name`
+    pub field_keys: BTreeMap<TaskField, String>,
+    /// Time zone for showing dates and for due-date calculations; `None` uses the system's local time zone
+    pub timezone: Option<Tz>,
+    /// Directory for daily rotated log files; `None` logs to the terminal only
+    pub log_dir: Option<PathBuf>,
+    pub log_format: LogFormat,
+    /// Field names whose values are masked in logs and error output, besides the built-in ones
+    pub redact_fields: Vec<String>,
+    /// API token used by `import todoist` when `--token` is not given
+    pub todoist_token: Option<String>,
+    /// Todoist REST API root
+    pub todoist_api_url: String,
+    /// Task templates for recurring work by name, from `[templates.<name>]`
+    pub task_templates: BTreeMap<String, TaskTemplate>,
+    /// Config file profile in effect, if any
+    pub profile: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            mcp_server_command: "./mcp_todo_task".to_string(),
-            mcp_server_args: vec![],
+            mcp_servers: vec![McpServerConfig::stdio(
+                DEFAULT_SERVER_ALIAS,
+                "./mcp_todo_task".to_string(),
+                vec![],
+            )],
+            default_server: DEFAULT_SERVER_ALIAS.to_string(),
             request_timeout: 30,
             max_retries: 3,
             retry_delay: 1000,
+            cache_ttl: 10,
+            auto_restart: false,
+            max_restarts: 3,
+            provider: ProviderKind::Deepseek,
+            llm_base_url: None,
+            llm_api_key: None,
+            requests_per_minute: None,
+            tokens_per_minute: None,
             deepseek_api_key: None,
+            deepseek_model: "deepseek-chat".to_string(),
+            embedding_provider: None,
+            embedding_base_url: None,
+            embedding_api_key: None,
+            embedding_model: None,
+            temperature: 0.7,
+            max_tokens: 4000,
+            show_reasoning: false,
+            dump_prompts: None,
+            stream: true,
+            display_format: DisplayFormat::Table,
+            relative_dates: false,
+            data_dir: default_data_dir(),
+            prompt_template: None,
+            prompt_templates: BTreeMap::new(),
+            system_prompt: None,
+            analysis_persona: None,
+            daemon_schedule: None,
+            report_dir: None,
+            report_format: OutputFormat::Markdown,
+            metrics_addr: None,
+            tool_writes: None,
+            tool_allow: Vec::new(),
+            tool_deny: Vec::new(),
+            max_tool_iterations: 5,
+            tool_timeout: 60,
+            tool_timeouts: BTreeMap::new(),
+            chunk_size: chunked::DEFAULT_CHUNK_SIZE,
+            chunk_concurrency: chunked::DEFAULT_CHUNK_CONCURRENCY,
+            pricing: Pricing::default(),
+            max_cost: None,
+            monthly_budget: None,
+            status_aliases: BTreeMap::new(),
+            field_keys: BTreeMap::new(),
+            timezone: None,
+            log_dir: None,
+            log_format: LogFormat::Text,
+            redact_fields: Vec::new(),
+            todoist_token: None,
+            todoist_api_url: todoist::TODOIST_API_URL.to_string(),
+            task_templates: BTreeMap::new(),
+            profile: None,
         }
     }
 }
 
+/// On-disk configuration file layout (`config.toml`); every field is optional so
+/// files only need to mention what they override
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub default_server: Option<String>,
+    pub data_dir: Option<PathBuf>,
+    pub request_timeout: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub retry_delay: Option<u64>,
+    pub cache_ttl: Option<u64>,
+    pub auto_restart: Option<bool>,
+    pub max_restarts: Option<u32>,
+    /// IANA time zone name, e.g. `Europe/Berlin`
+    pub timezone: Option<Tz>,
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+    #[serde(default)]
+    pub llm: LlmFileConfig,
+    #[serde(default)]
+    pub deepseek: DeepSeekFileConfig,
+    #[serde(default)]
+    pub embeddings: EmbeddingsFileConfig,
+    #[serde(default)]
+    pub output: OutputFileConfig,
+    #[serde(default)]
+    pub prompts: PromptsFileConfig,
+    #[serde(default)]
+    pub daemon: DaemonFileConfig,
+    #[serde(default)]
+    pub tools: ToolsFileConfig,
+    #[serde(default)]
+    pub pricing: PricingFileConfig,
+    #[serde(default)]
+    pub budget: BudgetFileConfig,
+    #[serde(default)]
+    pub logging: LoggingFileConfig,
+    #[serde(default)]
+    pub todoist: TodoistFileConfig,
+    /// Status aliases, e.g. `doing = "in_progress"`
+    #[serde(default)]
+    pub statuses: BTreeMap<String, TaskStatus>,
+    /// Server keys for task fields, e.g. `due_date = "deadline"`
+    #[serde(default)]
+    pub fields: BTreeMap<TaskField, String>,
+    /// Task templates, `[templates.<name>]` with `[[templates.<name>.tasks]]`
+    #[serde(default)]
+    pub templates: BTreeMap<String, TaskTemplate>,
+    /// Named `[profile.<name>]` sections
+    #[serde(default)]
+    pub profile: BTreeMap<String, ProfileFileConfig>,
+}
+
+/// A `[profile.<name>]` section, applied over the rest of the configuration
+/// files when the profile is selected
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileFileConfig {
+    pub default_server: Option<String>,
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+    #[serde(default)]
+    pub llm: LlmFileConfig,
+    #[serde(default)]
+    pub deepseek: DeepSeekFileConfig,
+    #[serde(default)]
+    pub output: OutputFileConfig,
+}
+
+impl From<ProfileFileConfig> for FileConfig {
+    fn from(profile: ProfileFileConfig) -> Self {
+        Self {
+            default_server: profile.default_server,
+            servers: profile.servers,
+            llm: profile.llm,
+            deepseek: profile.deepseek,
+            output: profile.output,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LlmFileConfig {
+    pub provider: Option<ProviderKind>,
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    /// Model name; overrides `[deepseek] model`
+    pub model: Option<String>,
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeepSeekFileConfig {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub show_reasoning: Option<bool>,
+    /// Most tasks in one analysis prompt
+    pub chunk_size: Option<usize>,
+    pub chunk_concurrency: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddingsFileConfig {
+    pub provider: Option<ProviderKind>,
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputFileConfig {
+    pub stream: Option<bool>,
+    pub format: Option<DisplayFormat>,
+    pub relative_dates: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptsFileConfig {
+    pub template: Option<String>,
+    pub system_prompt: Option<String>,
+    pub analysis_persona: Option<Persona>,
+    #[serde(default)]
+    pub templates: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DaemonFileConfig {
+    pub schedule: Option<String>,
+    pub report_dir: Option<PathBuf>,
+    pub report_format: Option<OutputFormat>,
+    /// `host:port` for the `/metrics` endpoint, e.g. `127.0.0.1:9898`
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolsFileConfig {
+    pub writes: Option<MutationPolicy>,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    pub max_iterations: Option<u32>,
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub timeouts: BTreeMap<String, u64>,
+}
+
+/// Token prices in USD per million tokens
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PricingFileConfig {
+    pub input: Option<f64>,
+    pub cached_input: Option<f64>,
+    pub output: Option<f64>,
+}
+
+/// Spending limits in USD
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetFileConfig {
+    pub max_cost: Option<f64>,
+    pub monthly: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingFileConfig {
+    pub dir: Option<PathBuf>,
+    pub format: Option<LogFormat>,
+    #[serde(default)]
+    pub redact: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TodoistFileConfig {
+    pub token: Option<String>,
+    pub api_url: Option<String>,
+}
+
 impl Config {
-    pub fn from_env() -> Result<Self> {
+    /// Load configuration from all layers, lowest precedence first:
+    /// built-in defaults, `~/.config/mcp-tasks/config.toml`, `./mcp-tasks.toml`
+    /// (or only `config_path` when given), the profile named by `MCP_TASKS_PROFILE`,
+    /// then environment variables. CLI flags are applied by the caller on top of the result.
+    pub fn load(config_path: Option<&Path>) -> Result<Self> {
+        Self::load_profile(config_path, None)
+    }
+
+    /// Like [`Config::load`], selecting the `[profile.<name>]` section `profile`
+    /// instead of the one named by `MCP_TASKS_PROFILE`
+    pub fn load_profile(config_path: Option<&Path>, profile: Option<&str>) -> Result<Self> {
         dotenv::dotenv().ok(); // Load .env file if it exists
 
-        let mcp_server_command =
-            env::var("MCP_SERVER_COMMAND").unwrap_or_else(|_| "./mcp_todo_task".to_string());
-
-        let mcp_server_args = env::var("MCP_SERVER_ARGS")
-            .unwrap_or_else(|_| "".to_string())
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
-
-        let request_timeout = env::var("REQUEST_TIMEOUT")
-            .unwrap_or_else(|_| "30".to_string())
-            .parse::<u64>()
-            .context("REQUEST_TIMEOUT must be a valid number")?;
-
-        let max_retries = env::var("MAX_RETRIES")
-            .unwrap_or_else(|_| "3".to_string())
-            .parse::<u32>()
-            .context("MAX_RETRIES must be a valid number")?;
-
-        let retry_delay = env::var("RETRY_DELAY")
-            .unwrap_or_else(|_| "1000".to_string())
-            .parse::<u64>()
-            .context("RETRY_DELAY must be a valid number")?;
-
-        let deepseek_api_key = env::var("DEEPSEEK_API_KEY").ok();
-
-        Ok(Self {
-            mcp_server_command,
-            mcp_server_args,
-            request_timeout,
-            max_retries,
-            retry_delay,
-            deepseek_api_key,
-        })
+        let mut config = Self::default();
+        let mut profiles = BTreeMap::new();
+
+        let paths = match config_path {
+            Some(path) => vec![path.to_path_buf()],
+            None => Self::default_config_paths()
+                .into_iter()
+                .filter(|path| path.exists())
+                .collect(),
+        };
+        for path in paths {
+            let mut file_config = FileConfig::read(&path)?;
+            profiles.append(&mut file_config.profile);
+            config.apply_file(file_config);
+        }
+
+        let profile = profile
+            .map(str::to_string)
+            .or_else(|| env::var("MCP_TASKS_PROFILE").ok())
+            .filter(|name| !name.is_empty());
+        if let Some(name) = profile {
+            let Some(section) = profiles.remove(&name) else {
+                let known = profiles.keys().cloned().collect::<Vec<_>>();
+                anyhow::bail!(McpTasksError::Config(if known.is_empty() {
+                    format!(
+                        "Unknown profile '{}': no [profile.<name>] sections are configured",
+                        name
+                    )
+                } else {
+                    format!(
+                        "Unknown profile '{}'. Configured profiles: {}",
+                        name,
+                        known.join(", ")
+                    )
+                }));
+            };
+            config.apply_file(section.into());
+            config.profile = Some(name);
+        }
+
+        config.apply_env()?;
+        TaskStatus::set_aliases(&config.status_aliases);
+        TaskField::set_custom_keys(&config.field_keys);
+        dates::set_timezone(config.timezone);
+        Ok(config)
+    }
+
+    /// Config file locations searched when `--config` is not given
+    fn default_config_paths() -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        if let Some(config_home) = config_home {
+            paths.push(config_home.join("mcp-tasks").join("config.toml"));
+        }
+
+        paths.push(PathBuf::from("mcp-tasks.toml"));
+        paths
+    }
+
+    fn apply_file(&mut self, file_config: FileConfig) {
+        if !file_config.servers.is_empty() {
+            self.default_server = file_config.servers[0].name.clone();
+            self.mcp_servers = file_config.servers;
+        }
+        if let Some(default_server) = file_config.default_server {
+            self.default_server = default_server;
+        }
+        if let Some(data_dir) = file_config.data_dir {
+            self.data_dir = data_dir;
+        }
+        if let Some(request_timeout) = file_config.request_timeout {
+            self.request_timeout = request_timeout;
+        }
+        if let Some(cache_ttl) = file_config.cache_ttl {
+            self.cache_ttl = cache_ttl;
+        }
+        if let Some(auto_restart) = file_config.auto_restart {
+            self.auto_restart = auto_restart;
+        }
+        if let Some(max_restarts) = file_config.max_restarts {
+            self.max_restarts = max_restarts;
+        }
+        if let Some(max_retries) = file_config.max_retries {
+            self.max_retries = max_retries;
+        }
+        if let Some(retry_delay) = file_config.retry_delay {
+            self.retry_delay = retry_delay;
+        }
+        if let Some(timezone) = file_config.timezone {
+            self.timezone = Some(timezone);
+        }
+        if let Some(api_key) = file_config.deepseek.api_key {
+            self.deepseek_api_key = Some(api_key);
+        }
+        if let Some(model) = file_config.deepseek.model {
+            self.deepseek_model = model;
+        }
+        if let Some(provider) = file_config.llm.provider {
+            self.provider = provider;
+        }
+        if let Some(base_url) = file_config.llm.base_url {
+            self.llm_base_url = Some(base_url);
+        }
+        if let Some(api_key) = file_config.llm.api_key {
+            self.llm_api_key = Some(api_key);
+        }
+        if let Some(model) = file_config.llm.model {
+            self.deepseek_model = model;
+        }
+        if let Some(requests_per_minute) = file_config.llm.requests_per_minute {
+            self.requests_per_minute = Some(requests_per_minute);
+        }
+        if let Some(tokens_per_minute) = file_config.llm.tokens_per_minute {
+            self.tokens_per_minute = Some(tokens_per_minute);
+        }
+        if let Some(provider) = file_config.embeddings.provider {
+            self.embedding_provider = Some(provider);
+        }
+        if let Some(base_url) = file_config.embeddings.base_url {
+            self.embedding_base_url = Some(base_url);
+        }
+        if let Some(api_key) = file_config.embeddings.api_key {
+            self.embedding_api_key = Some(api_key);
+        }
+        if let Some(model) = file_config.embeddings.model {
+            self.embedding_model = Some(model);
+        }
+        if let Some(temperature) = file_config.deepseek.temperature {
+            self.temperature = temperature;
+        }
+        if let Some(max_tokens) = file_config.deepseek.max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        if let Some(show_reasoning) = file_config.deepseek.show_reasoning {
+            self.show_reasoning = show_reasoning;
+        }
+        if let Some(chunk_size) = file_config.deepseek.chunk_size {
+            self.chunk_size = chunk_size;
+        }
+        if let Some(chunk_concurrency) = file_config.deepseek.chunk_concurrency {
+            self.chunk_concurrency = chunk_concurrency;
+        }
+        if let Some(stream) = file_config.output.stream {
+            self.stream = stream;
+        }
+        if let Some(format) = file_config.output.format {
+            self.display_format = format;
+        }
+        if let Some(relative_dates) = file_config.output.relative_dates {
+            self.relative_dates = relative_dates;
+        }
+        if let Some(template) = file_config.prompts.template {
+            self.prompt_template = Some(template);
+        }
+        self.prompt_templates.extend(file_config.prompts.templates);
+        if let Some(system_prompt) = file_config.prompts.system_prompt {
+            self.system_prompt = Some(system_prompt);
+        }
+        if let Some(persona) = file_config.prompts.analysis_persona {
+            self.analysis_persona = Some(persona);
+        }
+        if let Some(schedule) = file_config.daemon.schedule {
+            self.daemon_schedule = Some(schedule);
+        }
+        if let Some(report_dir) = file_config.daemon.report_dir {
+            self.report_dir = Some(report_dir);
+        }
+        if let Some(report_format) = file_config.daemon.report_format {
+            self.report_format = report_format;
+        }
+        if let Some(metrics_addr) = file_config.daemon.metrics_addr {
+            self.metrics_addr = Some(metrics_addr);
+        }
+        if let Some(writes) = file_config.tools.writes {
+            self.tool_writes = Some(writes);
+        }
+        self.tool_allow.extend(file_config.tools.allow);
+        self.tool_deny.extend(file_config.tools.deny);
+        if let Some(max_iterations) = file_config.tools.max_iterations {
+            self.max_tool_iterations = max_iterations;
+        }
+        if let Some(timeout) = file_config.tools.timeout {
+            self.tool_timeout = timeout;
+        }
+        self.tool_timeouts.extend(file_config.tools.timeouts);
+        if let Some(input) = file_config.pricing.input {
+            self.pricing.input = input;
+        }
+        if let Some(cached_input) = file_config.pricing.cached_input {
+            self.pricing.cached_input = cached_input;
+        }
+        if let Some(output) = file_config.pricing.output {
+            self.pricing.output = output;
+        }
+        if let Some(max_cost) = file_config.budget.max_cost {
+            self.max_cost = Some(max_cost);
+        }
+        if let Some(monthly) = file_config.budget.monthly {
+            self.monthly_budget = Some(monthly);
+        }
+        if let Some(dir) = file_config.logging.dir {
+            self.log_dir = Some(dir);
+        }
+        if let Some(format) = file_config.logging.format {
+            self.log_format = format;
+        }
+        self.redact_fields.extend(file_config.logging.redact);
+        if let Some(token) = file_config.todoist.token {
+            self.todoist_token = Some(token);
+        }
+        if let Some(api_url) = file_config.todoist.api_url {
+            self.todoist_api_url = api_url;
+        }
+        self.status_aliases.extend(file_config.statuses);
+        self.field_keys.extend(file_config.fields);
+        self.task_templates.extend(file_config.templates);
+    }
+
+    fn apply_env(&mut self) -> Result<()> {
+        if let Ok(spec) = env::var("MCP_SERVERS")
+            && !spec.trim().is_empty()
+        {
+            self.mcp_servers = Self::parse_mcp_servers(&spec)?;
+            self.default_server = self.mcp_servers[0].name.clone();
+        } else if let Ok(command) = env::var("MCP_SERVER_COMMAND") {
+            // The single-server variables describe the default "todo" server
+            let args = env::var("MCP_SERVER_ARGS")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect();
+            let server = McpServerConfig::stdio(DEFAULT_SERVER_ALIAS, command, args);
+            match self
+                .mcp_servers
+                .iter_mut()
+                .find(|existing| existing.name == DEFAULT_SERVER_ALIAS)
+            {
+                Some(existing) => *existing = server,
+                None => self.mcp_servers.insert(0, server),
+            }
+        }
+
+        if let Ok(default_server) = env::var("MCP_DEFAULT_SERVER") {
+            self.default_server = default_server;
+        }
+
+        if let Ok(data_dir) = env::var("MCP_TASKS_DATA_DIR") {
+            self.data_dir = PathBuf::from(data_dir);
+        }
+
+        if let Ok(timezone) = env::var("MCP_TASKS_TIMEZONE") {
+            self.timezone = Some(
+                timezone.parse::<Tz>().map_err(anyhow::Error::msg).context(
+                    McpTasksError::Config(
+                        "MCP_TASKS_TIMEZONE must be an IANA time zone name such as Europe/Berlin"
+                            .to_string(),
+                    ),
+                )?,
+            );
+        }
+
+        if let Ok(request_timeout) = env::var("REQUEST_TIMEOUT") {
+            self.request_timeout =
+                request_timeout
+                    .parse::<u64>()
+                    .context(McpTasksError::Config(
+                        "REQUEST_TIMEOUT must be a valid number".to_string(),
+                    ))?;
+        }
+
+        if let Ok(max_retries) = env::var("MAX_RETRIES") {
+            self.max_retries = max_retries.parse::<u32>().context(McpTasksError::Config(
+                "MAX_RETRIES must be a valid number".to_string(),
+            ))?;
+        }
+
+        if let Ok(retry_delay) = env::var("RETRY_DELAY") {
+            self.retry_delay = retry_delay.parse::<u64>().context(McpTasksError::Config(
+                "RETRY_DELAY must be a valid number".to_string(),
+            ))?;
+        }
+
+        if let Ok(cache_ttl) = env::var("MCP_CACHE_TTL") {
+            self.cache_ttl = cache_ttl.parse::<u64>().context(McpTasksError::Config(
+                "MCP_CACHE_TTL must be a valid number".to_string(),
+            ))?;
+        }
+
+        if let Ok(tool_timeout) = env::var("MCP_TOOL_TIMEOUT") {
+            self.tool_timeout = tool_timeout.parse::<u64>().context(McpTasksError::Config(
+                "MCP_TOOL_TIMEOUT must be a valid number".to_string(),
+            ))?;
+        }
+
+        if let Ok(auto_restart) = env::var("MCP_AUTO_RESTART") {
+            self.auto_restart = matches!(auto_restart.as_str(), "1" | "true");
+        }
+
+        if let Ok(max_restarts) = env::var("MCP_MAX_RESTARTS") {
+            self.max_restarts = max_restarts.parse::<u32>().context(McpTasksError::Config(
+                "MCP_MAX_RESTARTS must be a valid number".to_string(),
+            ))?;
+        }
+
+        if let Ok(api_key) = env::var("DEEPSEEK_API_KEY") {
+            self.deepseek_api_key = Some(api_key);
+        }
+
+        if let Ok(model) = env::var("DEEPSEEK_MODEL") {
+            self.deepseek_model = model;
+        }
+
+        if let Ok(provider) = env::var("LLM_PROVIDER") {
+            self.provider = ProviderKind::from_str(&provider, true).map_err(|_| {
+                anyhow::anyhow!(McpTasksError::Config(format!("LLM_PROVIDER must be one of: deepseek, openai-compatible, ollama, mock (got '{}')",
+                    provider)))
+            })?;
+        }
+
+        if let Ok(base_url) = env::var("LLM_BASE_URL") {
+            self.llm_base_url = Some(base_url);
+        }
+
+        if let Ok(api_key) = env::var("LLM_API_KEY") {
+            self.llm_api_key = Some(api_key);
+        }
+
+        if let Ok(model) = env::var("LLM_MODEL") {
+            self.deepseek_model = model;
+        }
+
+        if let Ok(requests_per_minute) = env::var("LLM_REQUESTS_PER_MINUTE") {
+            self.requests_per_minute = Some(requests_per_minute.parse::<u32>().context(
+                McpTasksError::Config("LLM_REQUESTS_PER_MINUTE must be a valid number".to_string()),
+            )?);
+        }
+
+        if let Ok(tokens_per_minute) = env::var("LLM_TOKENS_PER_MINUTE") {
+            self.tokens_per_minute = Some(tokens_per_minute.parse::<u64>().context(
+                McpTasksError::Config("LLM_TOKENS_PER_MINUTE must be a valid number".to_string()),
+            )?);
+        }
+        if let Ok(max_cost) = env::var("LLM_MAX_COST") {
+            self.max_cost = Some(max_cost.parse::<f64>().context(McpTasksError::Config(
+                "LLM_MAX_COST must be a valid number".to_string(),
+            ))?);
+        }
+        if let Ok(monthly) = env::var("LLM_MONTHLY_BUDGET") {
+            self.monthly_budget = Some(monthly.parse::<f64>().context(McpTasksError::Config(
+                "LLM_MONTHLY_BUDGET must be a valid number".to_string(),
+            ))?);
+        }
+
+        if let Ok(base_url) = env::var("EMBEDDING_BASE_URL") {
+            self.embedding_base_url = Some(base_url);
+        }
+
+        if let Ok(api_key) = env::var("EMBEDDING_API_KEY") {
+            self.embedding_api_key = Some(api_key);
+        }
+
+        if let Ok(model) = env::var("EMBEDDING_MODEL") {
+            self.embedding_model = Some(model);
+        }
+
+        if let Ok(log_dir) = env::var("MCP_TASKS_LOG_DIR") {
+            self.log_dir = Some(PathBuf::from(log_dir));
+        }
+
+        if let Ok(token) = env::var(todoist::TODOIST_TOKEN_ENV) {
+            self.todoist_token = Some(token);
+        }
+
+        if let Ok(api_url) = env::var("TODOIST_API_URL") {
+            self.todoist_api_url = api_url;
+        }
+
+        if env::var("MCP_TASKS_MOCK").is_ok_and(|value| matches!(value.as_str(), "1" | "true")) {
+            self.provider = ProviderKind::Mock;
+        }
+
+        Ok(())
+    }
+
+    /// Parse `MCP_SERVERS` in the form `alias=command arg1 arg2;other=command2`
+    fn parse_mcp_servers(spec: &str) -> Result<Vec<McpServerConfig>> {
+        spec.split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, command_line) = entry.split_once('=').with_context(|| {
+                    McpTasksError::Config(format!(
+                        "Invalid MCP_SERVERS entry '{}': expected alias=command",
+                        entry
+                    ))
+                })?;
+
+                let command_line = command_line.trim();
+                if command_line.starts_with("ws://") || command_line.starts_with("wss://") {
+                    return Ok(McpServerConfig {
+                        name: name.trim().to_string(),
+                        transport: McpTransportKind::Ws,
+                        url: Some(command_line.to_string()),
+                        ..McpServerConfig::stdio(name.trim(), String::new(), vec![])
+                    });
+                }
+
+                let mut parts = command_line.split_whitespace().map(|s| s.to_string());
+                let command = parts.next().unwrap_or_default();
+                Ok(McpServerConfig::stdio(
+                    name.trim(),
+                    command,
+                    parts.collect(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Look up a server definition by alias
+    pub fn server(&self, alias: &str) -> Option<&McpServerConfig> {
+        self.mcp_servers.iter().find(|server| server.name == alias)
     }
 
     pub fn validate(&self) -> Result<()> {
-        if self.mcp_server_command.is_empty() {
-            anyhow::bail!("MCP server command cannot be empty");
+        if self.deepseek_model.is_empty() {
+            anyhow::bail!(McpTasksError::Config(
+                "DeepSeek model cannot be empty".to_string()
+            ));
+        }
+
+        if self.provider == ProviderKind::Deepseek
+            && !DEEPSEEK_MODELS.contains(&self.deepseek_model.as_str())
+        {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "Unknown DeepSeek model '{}'. Supported models: {}",
+                self.deepseek_model,
+                DEEPSEEK_MODELS.join(", ")
+            )));
+        }
+
+        if !(0.0..=2.0).contains(&self.temperature) {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "Temperature must be between 0.0 and 2.0 (got {})",
+                self.temperature
+            )));
+        }
+
+        if self.max_tokens == 0 {
+            anyhow::bail!(McpTasksError::Config(
+                "max_tokens must be greater than zero".to_string()
+            ));
+        }
+
+        if self.requests_per_minute == Some(0) || self.tokens_per_minute == Some(0) {
+            anyhow::bail!(McpTasksError::Config(
+                "requests_per_minute and tokens_per_minute must be greater than zero".to_string()
+            ));
+        }
+
+        if [self.max_cost, self.monthly_budget]
+            .into_iter()
+            .flatten()
+            .any(|limit| !limit.is_finite() || limit <= 0.0)
+        {
+            anyhow::bail!(McpTasksError::Config(
+                "max_cost and the monthly budget must be greater than zero".to_string()
+            ));
+        }
+
+        if self.max_tool_iterations == 0 {
+            anyhow::bail!(McpTasksError::Config(
+                "max_tool_iterations must be greater than zero".to_string()
+            ));
+        }
+
+        if self.chunk_size == 0 || self.chunk_concurrency == 0 {
+            anyhow::bail!(McpTasksError::Config(
+                "chunk_size and chunk_concurrency must be greater than zero".to_string()
+            ));
+        }
+
+        if self.provider == ProviderKind::OpenaiCompatible && self.llm_base_url.is_none() {
+            anyhow::bail!(McpTasksError::Config("The openai-compatible provider needs a base URL (LLM_BASE_URL or [llm] base_url in config)".to_string()));
+        }
+
+        if self.mcp_servers.is_empty() {
+            anyhow::bail!(McpTasksError::Config(
+                "At least one MCP server must be configured".to_string()
+            ));
+        }
+
+        for (idx, server) in self.mcp_servers.iter().enumerate() {
+            if server.name.is_empty() {
+                anyhow::bail!(McpTasksError::Config(
+                    "MCP server alias cannot be empty".to_string()
+                ));
+            }
+            match server.transport {
+                McpTransportKind::Stdio if server.command.is_empty() => {
+                    anyhow::bail!(McpTasksError::Config(format!(
+                        "MCP server command cannot be empty (server '{}')",
+                        server.name
+                    )));
+                }
+                McpTransportKind::Ws
+                    if !server.url.as_deref().is_some_and(|url| {
+                        url.starts_with("ws://") || url.starts_with("wss://")
+                    }) =>
+                {
+                    anyhow::bail!(McpTasksError::Config(format!(
+                        "MCP server '{}' uses transport \"ws\" and needs a ws:// or wss:// url",
+                        server.name
+                    )));
+                }
+                _ => {}
+            }
+            if let Some(cwd) = &server.cwd
+                && server.transport == McpTransportKind::Stdio
+                && !cwd.is_dir()
+            {
+                anyhow::bail!(McpTasksError::Config(format!(
+                    "Working directory {} of MCP server '{}' does not exist",
+                    cwd.display(),
+                    server.name
+                )));
+            }
+            if self.mcp_servers[..idx]
+                .iter()
+                .any(|other| other.name == server.name)
+            {
+                anyhow::bail!(McpTasksError::Config(format!(
+                    "Duplicate MCP server alias '{}'",
+                    server.name
+                )));
+            }
+        }
+
+        if self.server(&self.default_server).is_none() {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "Unknown MCP server '{}'. Configured servers: {}",
+                self.default_server,
+                self.server_aliases().join(", ")
+            )));
         }
 
         Ok(())
     }
+
+    /// Directory where scheduled reports are written
+    pub fn report_dir(&self) -> PathBuf {
+        self.report_dir
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("reports"))
+    }
+
+    /// Policy for data-changing AI tool calls, falling back to the command's default
+    pub fn mutation_policy(&self, default: MutationPolicy) -> MutationPolicy {
+        self.tool_writes.unwrap_or(default)
+    }
+
+    /// Aliases of all configured MCP servers
+    pub fn server_aliases(&self) -> Vec<String> {
+        self.mcp_servers
+            .iter()
+            .map(|server| server.name.clone())
+            .collect()
+    }
+}
+
+/// Directory for locally persisted data (analysis history, snapshots, ...):
+/// `$XDG_DATA_HOME/mcp-tasks`, falling back to `~/.local/share/mcp-tasks`
+fn default_data_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("mcp-tasks")
+}
+
+impl FileConfig {
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| {
+            McpTasksError::Config(format!("Failed to read config file {}", path.display()))
+        })?;
+
+        toml::from_str(&content).with_context(|| {
+            McpTasksError::Config(format!("Failed to parse config file {}", path.display()))
+        })
+    }
 }
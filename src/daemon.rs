@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use croner::Cron;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+
+use crate::audit::AuditLog;
+use crate::deepseek_client::DeepSeekClient;
+use crate::history::HistoryStore;
+use crate::mcp_client::McpClientPool;
+use crate::metrics;
+use crate::notify::DesktopNotifier;
+use crate::output::{self, TaskStats};
+use crate::report::{self, OutputFormat};
+use crate::status::TaskStatus;
+
+/// Settings for `daemon` runs
+pub struct DaemonOptions {
+    pub tools: bool,
+    pub report_dir: PathBuf,
+    pub report_format: OutputFormat,
+    pub notify: bool,
+    pub run_now: bool,
+    /// Serve Prometheus metrics on this address while the daemon runs
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+/// Parse a standard 5-field cron expression such as `0 9 * * 1-5`
+pub fn parse_schedule(input: &str) -> Result<Cron, String> {
+    input
+        .trim()
+        .parse::<Cron>()
+        .map_err(|e| format!("invalid cron schedule '{}': {}", input, e))
+}
+
+/// Next time the schedule fires after `after`, in the same time zone
+fn next_run<Tz: TimeZone>(schedule: &Cron, after: &DateTime<Tz>) -> Result<DateTime<Tz>> {
+    schedule
+        .find_next_occurrence(after, false)
+        .context("Cron schedule has no upcoming run")
+}
+
+/// Run analyses and stats on `schedule` until interrupted. Failed runs are
+/// reported and the daemon waits for the next slot.
+pub async fn run_daemon(
+    deepseek_client: &DeepSeekClient,
+    pool: &McpClientPool,
+    data_dir: &Path,
+    schedule: Cron,
+    options: DaemonOptions,
+) -> Result<()> {
+    let mut notifier = DesktopNotifier::new(options.notify);
+
+    if let Some(addr) = options.metrics_addr {
+        let listener = metrics::bind(addr).await?;
+        crate::notice!("📈 Serving metrics on http://{}/metrics", addr);
+        tokio::spawn(metrics::serve(listener));
+    }
+
+    crate::notice!(
+        "⏰ Daemon started with schedule '{}', writing reports to {} (Ctrl-C to stop)",
+        schedule.pattern,
+        options.report_dir.display()
+    );
+
+    if options.run_now {
+        run_and_report(deepseek_client, pool, data_dir, &options, &mut notifier).await;
+    }
+
+    loop {
+        let next = next_run(&schedule, &Local::now())?;
+        crate::notice!("🕒 Next run at {}", next.format("%Y-%m-%d %H:%M"));
+
+        let wait = (next - Local::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        run_and_report(deepseek_client, pool, data_dir, &options, &mut notifier).await;
+    }
+}
+
+async fn run_and_report(
+    deepseek_client: &DeepSeekClient,
+    pool: &McpClientPool,
+    data_dir: &Path,
+    options: &DaemonOptions,
+    notifier: &mut DesktopNotifier,
+) {
+    let started = Local::now();
+    crate::notice!(
+        "\n▶️  [{}] Running scheduled analysis",
+        started.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    match run_once(deepseek_client, pool, data_dir, options, started).await {
+        Ok(summary) => {
+            metrics::record_run(true);
+            crate::say!("✅ {}", summary);
+            notifier.send("Task analysis ready", &summary);
+        }
+        Err(e) => {
+            metrics::record_run(false);
+            error!("Scheduled analysis failed: {:#}", e);
+            crate::esay!("❌ Scheduled analysis failed: {:#}", e);
+            notifier.send("Task analysis failed", &e.to_string());
+        }
+    }
+}
+
+/// One scheduled run: save stats, analyze pending tasks and save the report.
+/// Returns a one-line summary for the console and notification.
+async fn run_once(
+    deepseek_client: &DeepSeekClient,
+    pool: &McpClientPool,
+    data_dir: &Path,
+    options: &DaemonOptions,
+    started: DateTime<Local>,
+) -> Result<String> {
+    let mcp_client = pool.default_client().await?;
+    let stamp = started.format("%Y%m%d-%H%M");
+
+    // One fresh fetch per run; the filtered lists are derived from it
+    mcp_client.invalidate_cache();
+    let all_tasks = mcp_client.get_all_tasks().await?;
+    let unfinished_tasks = all_tasks
+        .iter()
+        .filter(|task| task.is_unfinished())
+        .cloned()
+        .collect::<Vec<_>>();
+    let pending_tasks = all_tasks
+        .iter()
+        .filter(|task| task.normalized_status() == Some(TaskStatus::Pending))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let stats = TaskStats::compute(&all_tasks, &unfinished_tasks);
+    metrics::record_task_stats(&stats);
+    let stats_path = options.report_dir.join(format!("stats-{}.json", stamp));
+    report::write_file(&stats_path, output::to_json(&stats)?.as_bytes())?;
+    info!("Saved task statistics to {}", stats_path.display());
+
+    let stats_summary = format!(
+        "{} open tasks, {} overdue, {:.0}% complete",
+        stats.unfinished, stats.overdue, stats.completion_rate
+    );
+
+    if pending_tasks.is_empty() {
+        return Ok(format!("{}; no pending tasks to analyze", stats_summary));
+    }
+
+    let report = if options.tools {
+        deepseek_client
+            .analyze_tasks_with_tools_report(pending_tasks, pool)
+            .await?
+    } else {
+        deepseek_client.analyze_tasks_report(pending_tasks).await?
+    };
+
+    metrics::record_analysis(&report);
+
+    match HistoryStore::new(data_dir).append(&report) {
+        Ok(id) => info!("Analysis stored in history as #{}", id),
+        Err(e) => error!("Failed to store analysis in history: {}", e),
+    }
+    if let Err(e) = AuditLog::new(data_dir).append("daemon", &report.tool_calls) {
+        error!("Failed to write tool call audit log: {}", e);
+    }
+
+    let report_path = options.report_dir.join(format!(
+        "analysis-{}.{}",
+        stamp,
+        options.report_format.extension()
+    ));
+    report::save_analysis_report(&report, &report_path.to_string_lossy())?;
+
+    Ok(format!(
+        "{}; report saved to {} (≈ ${:.4})",
+        stats_summary,
+        report_path.display(),
+        report.metadata.estimated_cost_usd.unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn next(schedule: &str, after: &str) -> DateTime<Utc> {
+        next_run(&parse_schedule(schedule).unwrap(), &at(after)).unwrap()
+    }
+
+    #[test]
+    fn parse_schedule_accepts_standard_expressions() {
+        for input in [
+            "0 9 * * 1-5",
+            "*/15 * * * *",
+            " 30 18 1 * * ",
+            "0 0 * * sun",
+        ] {
+            assert!(parse_schedule(input).is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_schedule_rejects_invalid_expressions() {
+        for input in [
+            "",
+            "every day",
+            "0 9 * *",
+            "61 * * * *",
+            "0 25 * * *",
+            "0 9 * 13 *",
+        ] {
+            let error = parse_schedule(input).unwrap_err();
+            assert!(
+                error.starts_with(&format!("invalid cron schedule '{}'", input)),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn next_run_finds_the_following_slot() {
+        // Weekdays at 9:00; 2025-07-04 is a Friday
+        assert_eq!(
+            next("0 9 * * 1-5", "2025-07-03T08:00:00Z"),
+            at("2025-07-03T09:00:00Z")
+        );
+        assert_eq!(
+            next("0 9 * * 1-5", "2025-07-04T09:30:00Z"),
+            at("2025-07-07T09:00:00Z")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2025-07-03T08:50:10Z"),
+            at("2025-07-03T09:00:00Z")
+        );
+    }
+
+    #[test]
+    fn next_run_skips_the_current_slot() {
+        // The run that just finished must not fire again
+        assert_eq!(
+            next("0 9 * * *", "2025-07-03T09:00:00Z"),
+            at("2025-07-04T09:00:00Z")
+        );
+    }
+}
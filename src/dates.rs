@@ -0,0 +1,277 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::RwLock;
+
+/// Time zone dates are shown in; `None` means the system's local time zone
+static DISPLAY_TIMEZONE: RwLock<Option<Tz>> = RwLock::new(None);
+
+/// Whether tables and reports show dates as "in 2 days" or "3 weeks ago"
+static RELATIVE_DATES: RwLock<bool> = RwLock::new(false);
+
+/// Epoch values at or above this are taken as milliseconds (year 5138 in seconds)
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Use `timezone` for displaying dates and deciding what "today" is; done by `Config::load`
+pub fn set_timezone(timezone: Option<Tz>) {
+    if let Ok(mut display) = DISPLAY_TIMEZONE.write() {
+        *display = timezone;
+    }
+}
+
+/// The configured display time zone, if any
+pub fn timezone() -> Option<Tz> {
+    DISPLAY_TIMEZONE.read().ok().and_then(|timezone| *timezone)
+}
+
+/// Show dates relative to now in tables and reports; done by `main` from
+/// `--relative-dates` or `[output] relative_dates`
+pub fn set_relative_dates(relative: bool) {
+    if let Ok(mut current) = RELATIVE_DATES.write() {
+        *current = relative;
+    }
+}
+
+/// Whether dates are shown relative to now
+pub fn relative_dates() -> bool {
+    RELATIVE_DATES.read().is_ok_and(|relative| *relative)
+}
+
+/// `date` relative to the day of `now` in the display time zone: "today",
+/// "tomorrow", "in 5 days", "3 weeks ago", "in 2 months" or "1 year ago"
+pub fn humanize(date: TaskDate, now: DateTime<Utc>) -> String {
+    let days = (date.date() - local_date(now)).num_days();
+    let span = match days.abs() {
+        0 => return "today".to_string(),
+        1 if days > 0 => return "tomorrow".to_string(),
+        1 => return "yesterday".to_string(),
+        d @ 2..14 => count(d, "day"),
+        d @ 14..60 => count(d / 7, "week"),
+        d @ 60..365 => count(d / 30, "month"),
+        d => count(d / 365, "year"),
+    };
+
+    if days > 0 {
+        format!("in {}", span)
+    } else {
+        format!("{} ago", span)
+    }
+}
+
+/// `n` with `unit`, pluralized: "1 day", "3 days"
+pub fn count(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", n, unit)
+    }
+}
+
+/// Name of the display time zone, e.g. `Europe/Berlin` or `local time`
+pub fn timezone_name() -> String {
+    timezone().map_or_else(|| "local time".to_string(), |tz| tz.name().to_string())
+}
+
+/// Calendar day of `instant` in the display time zone
+pub fn local_date(instant: DateTime<Utc>) -> NaiveDate {
+    match timezone() {
+        Some(tz) => instant.with_timezone(&tz).date_naive(),
+        None => instant.with_timezone(&Local).date_naive(),
+    }
+}
+
+/// `instant` in the display time zone as a naive date and time
+pub fn local_date_time(instant: DateTime<Utc>) -> NaiveDateTime {
+    match timezone() {
+        Some(tz) => instant.with_timezone(&tz).naive_local(),
+        None => instant.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Format `instant` in the display time zone
+pub fn format_instant(instant: DateTime<Utc>, format: &str) -> String {
+    local_date_time(instant).format(format).to_string()
+}
+
+/// The last second of `date` in the display time zone
+pub fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    let end = date
+        .and_hms_opt(23, 59, 59)
+        .expect("23:59:59 is a valid time");
+    match timezone() {
+        Some(tz) => to_utc(tz.from_local_datetime(&end).latest(), end),
+        None => to_utc(Local.from_local_datetime(&end).latest(), end),
+    }
+}
+
+/// Falls back to reading `naive` as UTC when it does not exist in the zone
+fn to_utc<Z: TimeZone>(local: Option<DateTime<Z>>, naive: NaiveDateTime) -> DateTime<Utc> {
+    local.map_or_else(
+        || naive.and_utc(),
+        |date_time| date_time.with_timezone(&Utc),
+    )
+}
+
+/// A date as sent by a server: a calendar day without a time, or an exact instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskDate {
+    Day(NaiveDate),
+    Instant(DateTime<Utc>),
+}
+
+impl TaskDate {
+    /// Parse RFC3339, `YYYY-MM-DD HH:MM:SS` or `YYYY-MM-DDTHH:MM:SS` (read as UTC),
+    /// `YYYY-MM-DD`, or a Unix timestamp in seconds or milliseconds
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+            return Some(Self::Instant(date_time.with_timezone(&Utc)));
+        }
+        for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+            if let Ok(date_time) = NaiveDateTime::parse_from_str(value, format) {
+                return Some(Self::Instant(date_time.and_utc()));
+            }
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(Self::Day(date));
+        }
+
+        let epoch = value.parse::<i64>().ok()?;
+        let instant = if epoch.abs() >= EPOCH_MILLIS_THRESHOLD {
+            DateTime::from_timestamp_millis(epoch)
+        } else {
+            DateTime::from_timestamp(epoch, 0)
+        };
+        instant.map(Self::Instant)
+    }
+
+    /// Parse an optional field, ignoring values in unknown formats
+    pub fn parse_opt(value: Option<&str>) -> Option<Self> {
+        value.and_then(Self::parse)
+    }
+
+    /// Calendar day in the display time zone
+    pub fn date(self) -> NaiveDate {
+        match self {
+            Self::Day(date) => date,
+            Self::Instant(instant) => local_date(instant),
+        }
+    }
+
+    /// Point in time for ordering; a day sorts at its start in the display time zone
+    pub fn sort_key(self) -> DateTime<Utc> {
+        match self {
+            Self::Day(date) => {
+                let start = date.and_hms_opt(0, 0, 0).expect("00:00:00 is a valid time");
+                match timezone() {
+                    Some(tz) => to_utc(tz.from_local_datetime(&start).earliest(), start),
+                    None => to_utc(Local.from_local_datetime(&start).earliest(), start),
+                }
+            }
+            Self::Instant(instant) => instant,
+        }
+    }
+
+    /// A day is overdue once it has fully passed in the display time zone,
+    /// an instant as soon as it is in the past
+    pub fn is_overdue(self, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Day(date) => date < local_date(now),
+            Self::Instant(instant) => instant < now,
+        }
+    }
+
+    /// `YYYY-MM-DD` in the display time zone
+    pub fn format_date(self) -> String {
+        self.date().format("%Y-%m-%d").to_string()
+    }
+
+    /// `YYYY-MM-DD`, plus `HH:MM` for instants, in the display time zone
+    pub fn format(self) -> String {
+        match self {
+            Self::Day(date) => date.format("%Y-%m-%d").to_string(),
+            Self::Instant(instant) => format_instant(instant, "%Y-%m-%d %H:%M"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parse_reads_iso_dates_and_instants() {
+        assert_eq!(
+            TaskDate::parse("2025-07-01"),
+            Some(TaskDate::Day(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()))
+        );
+        // Offsets are converted to UTC
+        assert_eq!(
+            TaskDate::parse("2025-07-01T09:30:00+02:00"),
+            Some(TaskDate::Instant(instant("2025-07-01T07:30:00Z")))
+        );
+        // Without an offset the time is read as UTC
+        for value in [
+            "2025-07-01 07:30:00",
+            "2025-07-01T07:30:00.000",
+            " 2025-07-01T07:30:00 ",
+        ] {
+            assert_eq!(
+                TaskDate::parse(value),
+                Some(TaskDate::Instant(instant("2025-07-01T07:30:00Z"))),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn parse_reads_epoch_seconds_and_milliseconds() {
+        let expected = Some(TaskDate::Instant(instant("2025-07-01T00:00:00Z")));
+        assert_eq!(TaskDate::parse("1751328000"), expected);
+        assert_eq!(TaskDate::parse("1751328000000"), expected);
+    }
+
+    #[test]
+    fn parse_rejects_other_input() {
+        for value in [
+            "",
+            "soon",
+            "tomorrow",
+            "next friday",
+            "in 3 days",
+            "2025-02-30",
+            "01/07/2025",
+            "2025-07-01T25:00:00Z",
+        ] {
+            assert_eq!(TaskDate::parse(value), None, "{}", value);
+        }
+        assert_eq!(TaskDate::parse_opt(None), None);
+    }
+
+    #[test]
+    fn days_follow_the_display_time_zone() {
+        set_timezone(Some(chrono_tz::Asia::Tokyo));
+
+        // 20:00 UTC is already the next morning in Tokyo
+        let evening = TaskDate::Instant(instant("2025-07-01T20:00:00Z"));
+        assert_eq!(evening.format_date(), "2025-07-02");
+        assert_eq!(evening.format(), "2025-07-02 05:00");
+
+        // A due day is overdue only once it has fully passed there
+        let due = TaskDate::Day(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap());
+        assert!(!due.is_overdue(instant("2025-07-01T14:59:59Z")));
+        assert!(due.is_overdue(instant("2025-07-01T15:00:00Z")));
+        assert_eq!(
+            due.sort_key(),
+            instant("2025-06-30T15:00:00Z"),
+            "a day sorts at its start"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::structured;
+
+/// Marker the dedupe prompt contains; the mock provider recognizes it
+pub const DEDUPE_PROMPT_MARKER: &str = "\"clusters\"";
+
+pub const DEDUPE_SYSTEM_PROMPT: &str = "You are a backlog grooming expert. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Tasks the AI considers duplicates of each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    pub ids: Vec<String>,
+    /// The task to keep; the others are closed by `--merge`
+    #[serde(default)]
+    pub keep: String,
+    /// How alike the tasks are, 0-100
+    #[serde(default)]
+    pub similarity: Option<u8>,
+    #[serde(default)]
+    pub rationale: String,
+}
+
+impl DuplicateCluster {
+    /// IDs of the tasks to close
+    pub fn duplicates(&self) -> impl Iterator<Item = &String> {
+        self.ids.iter().filter(move |id| **id != self.keep)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClusterList {
+    clusters: Vec<DuplicateCluster>,
+}
+
+/// Prompt asking for clusters of near-duplicate tasks
+pub fn dedupe_prompt(task_summary: &str, task_count: usize) -> String {
+    format!(
+        r#"Find near-duplicates among these {task_count} tasks: tasks that describe the same piece of work, even when worded differently.
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "clusters": [
+    {{
+      "ids": ["IDs of tasks that duplicate each other"],
+      "keep": "ID of the task to keep, usually the most complete one",
+      "similarity": 0-100,
+      "rationale": "one sentence explaining why these are duplicates"
+    }}
+  ]
+}}
+
+Only report tasks that really are the same work; return an empty "clusters" array if there are none."#
+    )
+}
+
+/// Parse the model's answer. Unknown IDs are dropped, clusters left with fewer than
+/// two tasks are skipped, and a `keep` outside the cluster falls back to its first task.
+pub fn parse_clusters(response: &str, tasks: &[Task]) -> Result<Vec<DuplicateCluster>> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain JSON duplicates".to_string())
+    })?;
+
+    let list: ClusterList = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI duplicate clusters".to_string(),
+    ))?;
+
+    let clusters = list
+        .clusters
+        .into_iter()
+        .filter_map(|mut cluster| {
+            let mut ids: Vec<String> = Vec::new();
+            for id in cluster.ids {
+                if tasks.iter().any(|task| task.id == id) && !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            if ids.len() < 2 {
+                return None;
+            }
+            if !ids.contains(&cluster.keep) {
+                cluster.keep = ids[0].clone();
+            }
+            cluster.ids = ids;
+            cluster.similarity = cluster.similarity.map(|similarity| similarity.min(100));
+            Some(cluster)
+        })
+        .collect();
+
+    Ok(clusters)
+}
@@ -1,453 +1,759 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use genai::Client;
-use genai::chat::{ChatMessage, ChatRequest};
-use serde::{Deserialize, Serialize};
+use chrono::{NaiveDate, Utc};
+use futures::future::join_all;
 use serde_json::Value;
-use std::env;
-use std::fs::File;
+use std::collections::{BTreeMap, HashSet};
 use std::io::Write;
-use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Semaphore;
 use tracing::{debug, info, warn};
 
+use crate::breakdown::{self, TaskBreakdown};
+use crate::budget::Budget;
+use crate::chunked;
+use crate::compare::{self, AnalysisDelta};
+use crate::config::Config;
+use crate::dedupe::{self, DuplicateCluster};
+use crate::error::McpTasksError;
+use crate::estimate::{self, EstimateUnit, TaskEstimates};
+use crate::graph::{self, InferredDependency};
+use crate::history::HistoryEntry;
+use crate::llm::{self, LlmProvider};
+use crate::output;
+use crate::plan::{self, Plan, PlanWindow};
+use crate::progress;
+use crate::prompts::{self, Persona};
+use crate::report::{AnalysisMetadata, AnalysisReport, ToolCallRecord};
+use crate::reprioritize::{self, PrioritySuggestions};
+use crate::session::{PendingResult, SessionRecorder};
+use crate::sprint::{self, SprintCapacity, SprintProposal};
+use crate::structured::{self, StructuredAnalysis};
+use crate::themes::{self, ThemeLabel};
+use crate::timetrack;
 use crate::tooling::{
-    ChatRequest as ToolChatRequest, DeepSeekApiClient, Message, ToolObject,
-    create_mcp_tool_definitions, create_task_tools, execute_mcp_tool_call, execute_task_tool,
+    ChatRequest as ToolChatRequest, ChatResponse, Message, MutationPolicy, ResponseFormat,
+    StreamToken, TASK_TOOLS, ToolDecision, ToolObject, ToolPolicy, ToolTimeouts, confirm,
+    create_mcp_tool_definitions, execute_mcp_tool_call, execute_task_tool, tool_annotations,
+    tool_timeout_result,
 };
+use crate::usage::{Pricing, TokenUsage};
 
-const DEEPSEEK_MODEL: &str = "deepseek-chat";
-
-/// Analysis report structure for JSON serialization
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AnalysisReport {
-    /// Timestamp when the analysis was generated
-    pub timestamp: DateTime<Utc>,
-    /// Model used for analysis
-    pub model: String,
-    /// Number of tasks analyzed
-    pub task_count: usize,
-    /// List of tasks that were analyzed
-    pub tasks: Vec<crate::mcp_client::Task>,
-    /// The actual analysis content from DeepSeek
-    pub analysis: String,
-    /// Analysis metadata
-    pub metadata: AnalysisMetadata,
-}
-
-/// Metadata about the analysis process
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AnalysisMetadata {
-    /// Whether tools were used during analysis
-    pub tools_enabled: bool,
-    /// Number of tool calls made during analysis
-    pub tool_calls_count: Option<usize>,
-    /// Duration of analysis in seconds
-    pub analysis_duration_seconds: Option<f64>,
-}
+/// Tool calls of one model turn that run at the same time
+const MAX_PARALLEL_TOOL_CALLS: usize = 4;
 
-/// Output format for saving analysis reports
-#[derive(Debug, Clone, PartialEq)]
-pub enum OutputFormat {
-    Json,
-    Markdown,
-    PlainText,
-}
-
-impl OutputFormat {
-    /// Determine output format from file extension
-    pub fn from_path(file_path: &str) -> Self {
-        let path = Path::new(file_path);
-        match path.extension().and_then(|ext| ext.to_str()) {
-            Some("json") => OutputFormat::Json,
-            Some("md") | Some("markdown") => OutputFormat::Markdown,
-            Some("txt") | Some("text") => OutputFormat::PlainText,
-            _ => OutputFormat::Markdown, // Default to Markdown for email convenience
-        }
-    }
-}
+/// Sent when the model has to stop calling tools and answer
+const FINAL_ANSWER_PROMPT: &str = "Stop calling tools now. Give your final answer based on the tool results you already have; say so if something could not be checked.";
 
+/// Task analysis and tool-calling conversations on top of the configured LLM provider
 pub struct DeepSeekClient {
-    client: Client,
-    deepseek_api: DeepSeekApiClient,
+    provider: Box<dyn LlmProvider>,
     model: String,
+    temperature: f32,
+    max_tokens: u32,
+    stream: bool,
+    prompt_template: Option<String>,
+    /// Replaces the built-in analysis and tool-calling system prompts
+    system_prompt: Option<String>,
+    persona: Option<Persona>,
+    tool_policy: ToolPolicy,
+    tool_timeouts: ToolTimeouts,
+    pricing: Pricing,
+    /// Tokens used since the last `take_usage`
+    usage: Mutex<TokenUsage>,
+    /// Print reasoning-model thoughts to stderr as they arrive
+    show_reasoning: bool,
+    /// Reasoning received since the last `take_reasoning`
+    reasoning: Mutex<String>,
+    /// Model turns with tool calls before the model must answer without tools
+    max_tool_iterations: u32,
+    /// Most tasks in one analysis prompt; larger backlogs are analyzed in parts
+    chunk_size: usize,
+    /// Parts analyzed at the same time
+    chunk_concurrency: usize,
+    /// Ask for a JSON analysis in JSON mode and parse it into `AnalysisReport::structured`
+    structured: bool,
+    /// Earlier analysis `analyze_tasks` compares with (`analyze --compare-last`)
+    compare_with: Option<HistoryEntry>,
+    /// Hours tracked per task ID, shown next to the estimates in analyses
+    time_tracked: BTreeMap<String, f64>,
+    /// Completed tasks and the day from which on they count as recent, given
+    /// to analyses as context
+    recently_completed: Option<(Vec<crate::mcp_client::Task>, NaiveDate)>,
+    /// Where the tool loop saves its progress, so the analysis can be resumed
+    session: Option<SessionRecorder>,
 }
 
 impl DeepSeekClient {
-    pub fn new() -> Result<Self> {
-        info!("Building DeepSeek API client...");
-
-        // Verify API key is set
-        let api_key = env::var("DEEPSEEK_API_KEY")
-            .map_err(|_| anyhow::anyhow!("DEEPSEEK_API_KEY environment variable is not set"))?;
+    pub fn new(config: &Config) -> Result<Self> {
+        info!("Building LLM client...");
 
-        let client = Client::default();
-        let deepseek_api = DeepSeekApiClient::new(api_key);
+        let provider = llm::create_provider(config)?;
 
-        info!("DeepSeek client created successfully");
+        info!("LLM client created successfully");
         Ok(Self {
-            client,
-            deepseek_api,
-            model: DEEPSEEK_MODEL.to_string(),
+            provider,
+            model: config.deepseek_model.clone(),
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            stream: config.stream,
+            prompt_template: None,
+            system_prompt: config.system_prompt.clone(),
+            persona: config.analysis_persona,
+            tool_policy: ToolPolicy {
+                mutations: MutationPolicy::Allow,
+                allow: config.tool_allow.clone(),
+                deny: config.tool_deny.clone(),
+            },
+            tool_timeouts: ToolTimeouts {
+                default: config.tool_timeout,
+                per_tool: config.tool_timeouts.clone(),
+            },
+            pricing: config.pricing,
+            usage: Mutex::new(TokenUsage::default()),
+            show_reasoning: config.show_reasoning,
+            reasoning: Mutex::new(String::new()),
+            max_tool_iterations: config.max_tool_iterations,
+            chunk_size: config.chunk_size,
+            chunk_concurrency: config.chunk_concurrency,
+            structured: false,
+            compare_with: None,
+            time_tracked: BTreeMap::new(),
+            recently_completed: None,
+            session: None,
         })
     }
 
-    pub async fn analyze_tasks(&self, tasks: Vec<crate::mcp_client::Task>) -> Result<String> {
-        info!("Sending tasks to DeepSeek for analysis...");
+    /// Use a custom analysis prompt template instead of the built-in prompts
+    pub fn with_prompt_template(mut self, template: Option<String>) -> Self {
+        self.prompt_template = template;
+        self
+    }
 
-        let task_summary = self.format_tasks_for_analysis(&tasks);
-        let analysis_prompt = self.create_analysis_prompt(&task_summary, tasks.len());
+    /// Set how data-changing tool calls outside the allow/deny lists are handled
+    /// (default: allowed)
+    pub fn with_mutation_policy(mut self, mutation_policy: MutationPolicy) -> Self {
+        self.tool_policy.mutations = mutation_policy;
+        self
+    }
 
-        let chat_req = ChatRequest::new(vec![
-            ChatMessage::system(
-                "You are a task analysis expert. Analyze the provided pending tasks and provide insights about priorities, dependencies, complexity, and actionable recommendations.",
-            ),
-            ChatMessage::user(analysis_prompt),
-        ]);
+    /// Request machine-readable analyses (see [`StructuredAnalysis`])
+    pub fn with_structured_output(mut self, structured: bool) -> Self {
+        self.structured = structured;
+        self
+    }
 
-        let chat_res = self.client.exec_chat(&self.model, chat_req, None).await?;
+    /// The configured system prompt, or `default`, with the persona's instructions
+    fn analysis_system_prompt(&self, default: &str) -> String {
+        prompts::system_prompt(
+            self.system_prompt.as_deref().unwrap_or(default),
+            self.persona,
+        )
+    }
 
-        let response_text = chat_res
-            .content_text_as_str()
-            .ok_or_else(|| anyhow::anyhow!("No response text received from DeepSeek"))?;
+    /// Ask the model what changed since `previous` and keep that in `AnalysisReport::delta`
+    pub fn with_comparison(mut self, previous: Option<HistoryEntry>) -> Self {
+        self.compare_with = previous;
+        self
+    }
 
-        info!("Task analysis completed successfully");
-        Ok(response_text.to_string())
+    /// Show the hours tracked per task ID, and the estimate they compare with, in analyses
+    pub fn with_time_tracked(mut self, time_tracked: BTreeMap<String, f64>) -> Self {
+        self.time_tracked = time_tracked;
+        self
     }
 
-    fn format_tasks_for_analysis(&self, tasks: &[crate::mcp_client::Task]) -> String {
-        let mut formatted = String::new();
+    /// List the tasks of `completed` finished on or after `since` in analyses,
+    /// as context on what just shipped
+    pub fn with_recently_completed(
+        mut self,
+        completed: Vec<crate::mcp_client::Task>,
+        since: NaiveDate,
+    ) -> Self {
+        self.recently_completed = Some((completed, since));
+        self
+    }
 
-        for (idx, task) in tasks.iter().enumerate() {
-            formatted.push_str(&format!("Task {}: {}\n", idx + 1, task.title));
+    /// Save the progress of tool-enabled analyses to `session`; a session with
+    /// messages is resumed where it stopped
+    pub fn with_session(self, session: SessionRecorder) -> Self {
+        *self.usage.lock().unwrap() = session.snapshot().token_usage;
+        Self {
+            session: Some(session),
+            ..self
+        }
+    }
 
-            if let Some(description) = &task.description {
-                formatted.push_str(&format!("  Description: {}\n", description));
-            }
+    /// Whether responses are streamed to stdout
+    pub fn is_streaming(&self) -> bool {
+        self.stream
+    }
 
-            formatted.push_str(&format!("  Status: {}\n", task.status));
+    /// Tokens used since the last call, resetting the counter
+    pub fn take_usage(&self) -> TokenUsage {
+        std::mem::take(&mut *self.usage.lock().unwrap())
+    }
 
-            if let Some(priority) = &task.priority {
-                formatted.push_str(&format!("  Priority: {}\n", priority));
-            }
+    /// Reasoning received since the last call, resetting it
+    pub fn take_reasoning(&self) -> Option<String> {
+        let reasoning = std::mem::take(&mut *self.reasoning.lock().unwrap());
+        (!reasoning.is_empty()).then_some(reasoning)
+    }
 
-            if let Some(due_date) = &task.due_date {
-                formatted.push_str(&format!("  Due Date: {}\n", due_date));
-            }
+    /// Estimated cost of `usage` in USD with the configured prices
+    pub fn estimate_cost(&self, usage: &TokenUsage) -> f64 {
+        self.pricing.cost(usage)
+    }
 
-            if let Some(tags) = &task.tags {
-                formatted.push_str(&format!("  Tags: {}\n", tags.join(", ")));
-            }
+    /// Estimated USD saved by prompt tokens served from the context cache
+    pub fn estimate_cache_savings(&self, usage: &TokenUsage) -> f64 {
+        self.pricing.cache_savings(usage)
+    }
 
-            formatted.push_str(&format!("  Created: {}\n", task.created_at));
-            formatted.push('\n');
+    /// Request with the configured model settings; `tools` may be empty
+    fn tool_request(&self, messages: &[Message], tools: &[ToolObject]) -> ToolChatRequest {
+        ToolChatRequest {
+            model: self.model.clone(),
+            messages: messages.to_vec(),
+            tools: (!tools.is_empty()).then(|| tools.to_vec()),
+            tool_choice: (!tools.is_empty()).then(|| "auto".to_string()),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            stream: None,
+            stream_options: None,
+            response_format: self.structured.then(ResponseFormat::json_object),
         }
+    }
 
-        formatted
+    /// Send a request to the provider, streaming tokens to stdout when enabled,
+    /// and record its token usage and reasoning
+    async fn send(&self, request: ToolChatRequest) -> Result<ChatResponse> {
+        self.send_with(request, self.stream).await
     }
 
-    fn create_analysis_prompt(&self, task_summary: &str, task_count: usize) -> String {
-        format!(
-            "Please analyze the following {} pending tasks and provide:
+    async fn send_with(&self, request: ToolChatRequest, stream: bool) -> Result<ChatResponse> {
+        let response = if stream {
+            // Streamed tokens take the place of the progress spinner
+            progress::clear();
+            let mut printer = TokenPrinter::new(self.show_reasoning);
+            let response = self
+                .provider
+                .chat_streaming(request, &mut |token: StreamToken<'_>| printer.print(token))
+                .await?;
+            printer.finish();
+            response
+        } else {
+            progress::set_phase(format!("waiting on {}", self.provider.name()));
+            self.provider.chat(request).await?
+        };
+
+        if let Some(usage) = &response.usage {
+            let usage = TokenUsage::from(usage);
+            debug!(
+                "Request used {} prompt tokens, {} from the context cache",
+                usage.prompt_tokens, usage.cached_prompt_tokens
+            );
+            self.record_usage(usage);
+        }
+        if let Some(reasoning) = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.reasoning_content.as_deref())
+        {
+            self.record_reasoning(reasoning, stream);
+        }
 
-1. **Priority Assessment**: Identify high-priority tasks based on due dates, dependencies, and business impact
-2. **Complexity Analysis**: Categorize tasks by estimated complexity (simple, moderate, complex)
-3. **Dependency Mapping**: Identify any potential task dependencies or conflicts
-4. **Actionable Recommendations**: Suggest an optimal execution order and resource allocation
-5. **Risk Assessment**: Highlight any tasks that might be at risk of delays or conflicts
+        Ok(response)
+    }
 
-Here are the pending tasks:
+    fn record_usage(&self, usage: TokenUsage) {
+        self.usage.lock().unwrap().add(&usage);
+    }
 
-{}
+    /// Keep a response's reasoning for the report; `streamed` reasoning was
+    /// already shown while it arrived
+    fn record_reasoning(&self, reasoning: &str, streamed: bool) {
+        let reasoning = reasoning.trim();
+        if reasoning.is_empty() {
+            return;
+        }
 
-Please provide a structured analysis that will help prioritize and organize the work effectively.",
-            task_count,
-            task_summary
-        )
+        if self.show_reasoning && !streamed {
+            crate::esay!("💭 {}\n", reasoning);
+        }
+
+        let mut all = self.reasoning.lock().unwrap();
+        if !all.is_empty() {
+            all.push_str("\n\n");
+        }
+        all.push_str(reasoning);
+    }
+
+    pub async fn analyze_tasks(&self, tasks: Vec<crate::mcp_client::Task>) -> Result<String> {
+        info!("Sending tasks to DeepSeek for analysis...");
+
+        let task_summary = self.analysis_summary(&tasks).await?;
+        let response_text = if self.structured {
+            self.complete(
+                &prompts::system_prompt(structured::STRUCTURED_SYSTEM_PROMPT, self.persona),
+                structured::structured_prompt(&task_summary, tasks.len(), false),
+            )
+            .await?
+        } else {
+            let mut prompt = self.create_analysis_prompt(&task_summary, tasks.len());
+            if let Some(previous) = &self.compare_with {
+                prompt.push_str(&compare::comparison_prompt(previous, &tasks));
+            }
+            self.complete(
+                &self.analysis_system_prompt(prompts::ANALYSIS_SYSTEM_PROMPT),
+                prompt,
+            )
+            .await?
+        };
+
+        info!("Task analysis completed successfully");
+        Ok(response_text)
     }
 
-    /// Format analysis report as Markdown (email-friendly)
-    pub fn format_report_as_markdown(&self, report: &AnalysisReport) -> String {
-        let duration = report
-            .metadata
-            .analysis_duration_seconds
-            .map(|d| format!("{:.1}s", d))
-            .unwrap_or_else(|| "N/A".to_string());
+    /// Send a single prompt without tools and return the answer text
+    pub async fn complete(&self, system_prompt: &str, prompt: String) -> Result<String> {
+        self.complete_request(system_prompt, prompt, self.structured, self.stream)
+            .await
+    }
 
-        let tool_calls = report
-            .metadata
-            .tool_calls_count
-            .map(|c| c.to_string())
-            .unwrap_or_else(|| "N/A".to_string());
+    /// Like [`complete`](Self::complete), but always in JSON mode
+    pub async fn complete_json(&self, system_prompt: &str, prompt: String) -> Result<String> {
+        self.complete_request(system_prompt, prompt, true, self.stream)
+            .await
+    }
 
-        format!(
-            r#"# Task Analysis Report
+    async fn complete_request(
+        &self,
+        system_prompt: &str,
+        prompt: String,
+        json_mode: bool,
+        stream: bool,
+    ) -> Result<String> {
+        let messages = [
+            Message {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+                tool_call_id: None,
+                tool_calls: None,
+                reasoning_content: None,
+            },
+            Message {
+                role: "user".to_string(),
+                content: prompt,
+                tool_call_id: None,
+                tool_calls: None,
+                reasoning_content: None,
+            },
+        ];
 
-**Generated:** {timestamp}  
-**Model:** {model}  
-**Tasks Analyzed:** {task_count}  
-**Analysis Duration:** {duration}  
-**Tool Calls:** {tool_calls}  
+        let mut request = self.tool_request(&messages, &[]);
+        request.response_format = json_mode.then(ResponseFormat::json_object);
+        let response = self.send_with(request, stream).await?;
+
+        let response_text = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .filter(|content| !content.is_empty())
+            .ok_or_else(|| {
+                anyhow::anyhow!(McpTasksError::DeepSeekApi(format!(
+                    "No response text received from {}",
+                    self.provider.name()
+                )))
+            })?;
 
----
+        Ok(response_text)
+    }
 
-## 📋 Tasks Summary
+    /// The tasks as listed in analysis prompts, followed by the recently
+    /// completed ones when requested
+    async fn analysis_summary(&self, tasks: &[crate::mcp_client::Task]) -> Result<String> {
+        let mut summary = if tasks.len() <= self.chunk_size {
+            self.format_tasks_for_analysis(tasks)
+        } else {
+            self.chunked_summary(tasks).await?
+        };
 
-{tasks_summary}
+        if let Some((completed, since)) = &self.recently_completed {
+            summary.push_str(&prompts::recently_completed_section(completed, *since));
+        }
+        Ok(summary)
+    }
 
----
+    /// Backlogs larger than the chunk size are split into parts that are
+    /// analyzed concurrently; the findings of all parts then take the place of
+    /// the task list, for a synthesis pass
+    async fn chunked_summary(&self, tasks: &[crate::mcp_client::Task]) -> Result<String> {
+        let chunks = tasks.chunks(self.chunk_size).collect::<Vec<_>>();
+        let parts = chunks.len();
+        info!(
+            "Analyzing {} tasks in {} parts of up to {}",
+            tasks.len(),
+            parts,
+            self.chunk_size
+        );
+        progress::set_phase(format!("analyzing {} parts", parts));
+
+        let permits = Semaphore::new(self.chunk_concurrency.max(1));
+        let permits = &permits;
+        let done = AtomicUsize::new(0);
+        let done = &done;
+        let system_prompt = prompts::system_prompt(chunked::CHUNK_SYSTEM_PROMPT, self.persona);
+        let system_prompt = &system_prompt;
+
+        // Parts are sent without streaming; only the synthesis is shown as it arrives
+        let findings = join_all(chunks.iter().enumerate().map(|(idx, chunk)| async move {
+            let _permit = permits.acquire().await;
+            let prompt = chunked::chunk_prompt(
+                &self.format_tasks_for_analysis(chunk),
+                idx + 1,
+                parts,
+                chunk.len(),
+            );
+            let notes = self
+                .complete_request(system_prompt, prompt, false, false)
+                .await;
+            let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+            info!("Analyzed part {} of {}", idx + 1, parts);
+            progress::set_phase(format!("analyzed {} of {} parts", done, parts));
+            notes
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        progress::set_phase("merging findings");
+        let sizes = chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>();
+        Ok(chunked::merged_findings(&findings, &sizes, tasks.len()))
+    }
 
-## 🤖 AI Analysis
+    /// Ask for a suggested priority, with a justification, for each of `tasks`
+    pub async fn suggest_priorities(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+    ) -> Result<PrioritySuggestions> {
+        info!("Asking for priority suggestions for {} tasks", tasks.len());
+
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                reprioritize::REPRIORITIZE_SYSTEM_PROMPT,
+                reprioritize::reprioritize_prompt(&task_summary, tasks.len()),
+            )
+            .await?;
 
-{analysis}
+        reprioritize::parse_suggestions(&response, tasks)
+    }
 
----
+    /// Ask for an effort estimate of every task in `unit`; `field` is where
+    /// tasks keep an earlier estimate
+    pub async fn estimate_tasks(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+        unit: EstimateUnit,
+        field: &str,
+    ) -> Result<TaskEstimates> {
+        info!("Asking for effort estimates for {} tasks", tasks.len());
+
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                estimate::ESTIMATE_SYSTEM_PROMPT,
+                estimate::estimate_prompt(&task_summary, tasks.len(), unit),
+            )
+            .await?;
 
-## 📊 Report Metadata
+        estimate::parse_estimates(&response, tasks, unit, field)
+    }
 
-- **Tools Enabled:** {tools_enabled}
-- **Generation Time:** {timestamp}
-- **Processing Duration:** {duration}
-- **MCP Tool Interactions:** {tool_calls}
+    /// Ask for a schedule of `tasks` that fits the capacity of `window`; `field`
+    /// is where tasks keep their estimates
+    pub async fn plan_schedule(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+        field: &str,
+        window: &PlanWindow,
+    ) -> Result<Plan> {
+        info!(
+            "Asking for a plan of {} tasks over {} {}s",
+            tasks.len(),
+            window.starts.len(),
+            window.period.as_str()
+        );
 
----
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                plan::PLAN_SYSTEM_PROMPT,
+                plan::plan_prompt(&task_summary, tasks, field, window),
+            )
+            .await?;
 
-*This report was generated automatically by DeepSeek MCP Tasks analyzer.*
-"#,
-            timestamp = report.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
-            model = report.model,
-            task_count = report.task_count,
-            duration = duration,
-            tool_calls = tool_calls,
-            tasks_summary = self.format_tasks_summary(&report.tasks),
-            analysis = report.analysis,
-            tools_enabled = if report.metadata.tools_enabled {
-                "Yes"
-            } else {
-                "No"
-            },
-        )
+        plan::parse_plan(&response, tasks, window)
     }
 
-    /// Format analysis report as plain text (maximum compatibility)
-    pub fn format_report_as_text(&self, report: &AnalysisReport) -> String {
-        let duration = report
-            .metadata
-            .analysis_duration_seconds
-            .map(|d| format!("{:.1}s", d))
-            .unwrap_or_else(|| "N/A".to_string());
-
-        let tool_calls = report
-            .metadata
-            .tool_calls_count
-            .map(|c| c.to_string())
-            .unwrap_or_else(|| "N/A".to_string());
-
-        format!(
-            r#"===============================================
-            TASK ANALYSIS REPORT
-===============================================
-
-Generated: {timestamp}
-Model: {model}
-Tasks Analyzed: {task_count}
-Analysis Duration: {duration}
-Tool Calls: {tool_calls}
-
-===============================================
-                TASKS SUMMARY
-===============================================
-
-{tasks_summary}
-
-===============================================
-               AI ANALYSIS
-===============================================
-
-{analysis}
-
-===============================================
-              REPORT METADATA
-===============================================
-
-Tools Enabled: {tools_enabled}
-Generation Time: {timestamp}
-Processing Duration: {duration}
-MCP Tool Interactions: {tool_calls}
-
-===============================================
-
-This report was generated automatically by DeepSeek MCP Tasks analyzer.
-"#,
-            timestamp = report.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
-            model = report.model,
-            task_count = report.task_count,
-            duration = duration,
-            tool_calls = tool_calls,
-            tasks_summary = self.format_tasks_summary_text(&report.tasks),
-            analysis = self.strip_markdown(&report.analysis),
-            tools_enabled = if report.metadata.tools_enabled {
-                "Yes"
-            } else {
-                "No"
-            },
-        )
+    /// Ask for a backlog of `days` days from `tasks` that fits `capacity`;
+    /// `field` is where tasks keep their estimates
+    pub async fn propose_sprint(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+        field: &str,
+        name: String,
+        days: u64,
+        capacity: SprintCapacity,
+    ) -> Result<SprintProposal> {
+        info!(
+            "Asking for a {}-day sprint backlog from {} tasks",
+            days,
+            tasks.len()
+        );
+
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                sprint::SPRINT_SYSTEM_PROMPT,
+                sprint::sprint_prompt(&task_summary, tasks, field, capacity, days),
+            )
+            .await?;
+
+        let start = crate::dates::local_date(chrono::Utc::now());
+        sprint::parse_proposal(&response, tasks, name, start, days, capacity)
     }
 
-    /// Format tasks as a summary for Markdown
-    fn format_tasks_summary(&self, tasks: &[crate::mcp_client::Task]) -> String {
-        let mut summary = String::new();
+    /// Ask for clusters of near-duplicate tasks among `tasks`
+    pub async fn find_duplicates(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+    ) -> Result<Vec<DuplicateCluster>> {
+        info!("Looking for duplicates among {} tasks", tasks.len());
+
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                dedupe::DEDUPE_SYSTEM_PROMPT,
+                dedupe::dedupe_prompt(&task_summary, tasks.len()),
+            )
+            .await?;
 
-        for (idx, task) in tasks.iter().enumerate() {
-            summary.push_str(&format!("### {}. {}\n\n", idx + 1, task.title));
+        dedupe::parse_clusters(&response, tasks)
+    }
 
-            if let Some(description) = &task.description {
-                summary.push_str(&format!("**Description:** {}\n\n", description));
-            }
+    /// Ask for a name and summary of each group of similar tasks
+    pub async fn label_themes(
+        &self,
+        groups: &[Vec<crate::mcp_client::Task>],
+    ) -> Result<Vec<ThemeLabel>> {
+        info!("Asking for labels of {} themes", groups.len());
 
-            summary.push_str(&format!("**Status:** {}\n", task.status));
+        let response = self
+            .complete_json(themes::THEMES_SYSTEM_PROMPT, themes::themes_prompt(groups))
+            .await?;
 
-            if let Some(priority) = &task.priority {
-                summary.push_str(&format!("**Priority:** {}\n", priority));
-            }
+        themes::parse_labels(&response, groups)
+    }
 
-            if let Some(due_date) = &task.due_date {
-                summary.push_str(&format!("**Due Date:** {}\n", due_date));
-            }
+    /// Ask which of `tasks` have to be done before which
+    pub async fn infer_dependencies(
+        &self,
+        tasks: &[crate::mcp_client::Task],
+    ) -> Result<Vec<InferredDependency>> {
+        info!("Asking for dependencies between {} tasks", tasks.len());
+
+        let task_summary = self.format_tasks_for_analysis(tasks);
+        let response = self
+            .complete_json(
+                graph::GRAPH_SYSTEM_PROMPT,
+                graph::dependency_prompt(&task_summary, tasks.len()),
+            )
+            .await?;
 
-            if let Some(tags) = &task.tags
-                && !tags.is_empty()
-            {
-                summary.push_str(&format!("**Tags:** {}\n", tags.join(", ")));
-            }
+        graph::parse_dependencies(&response)
+    }
 
-            summary.push_str(&format!("**Created:** {}\n\n", task.created_at));
-            summary.push_str("---\n\n");
-        }
+    /// Analyze tasks without tools, returning a structured report
+    pub async fn analyze_tasks_report(
+        &self,
+        tasks: Vec<crate::mcp_client::Task>,
+    ) -> Result<AnalysisReport> {
+        let start_time = std::time::Instant::now();
+        let analysis = self.analyze_tasks(tasks.clone()).await?;
+        let token_usage = self.take_usage();
+        let reasoning = self.take_reasoning();
+        let structured = self.parse_structured(&analysis)?;
+        let (analysis, delta) = match &self.compare_with {
+            Some(previous) => {
+                let (analysis, changes) = compare::split_delta(&analysis);
+                if changes.is_none() {
+                    warn!(
+                        "The model's answer has no \"{}\" section",
+                        compare::DELTA_HEADING
+                    );
+                }
+                let delta = changes.map(|changes| AnalysisDelta {
+                    previous_id: previous.id,
+                    previous_timestamp: previous.report.timestamp,
+                    changes,
+                });
+                (analysis, delta)
+            }
+            None => (analysis, None),
+        };
 
-        summary
+        Ok(AnalysisReport {
+            timestamp: Utc::now(),
+            model: self.model.clone(),
+            task_count: tasks.len(),
+            tasks,
+            analysis,
+            metadata: AnalysisMetadata {
+                tools_enabled: false,
+                tool_calls_count: None,
+                analysis_duration_seconds: Some(start_time.elapsed().as_secs_f64()),
+                estimated_cost_usd: Some(self.estimate_cost(&token_usage)),
+                estimated_cache_savings_usd: Some(self.estimate_cache_savings(&token_usage)),
+                token_usage: Some(token_usage),
+            },
+            tool_calls: Vec::new(),
+            reasoning,
+            structured,
+            delta,
+        })
     }
 
-    /// Format tasks as a summary for plain text
-    fn format_tasks_summary_text(&self, tasks: &[crate::mcp_client::Task]) -> String {
-        let mut summary = String::new();
+    fn format_tasks_for_analysis(&self, tasks: &[crate::mcp_client::Task]) -> String {
+        let mut formatted = String::new();
 
         for (idx, task) in tasks.iter().enumerate() {
-            summary.push_str(&format!("{}. {}\n", idx + 1, task.title));
+            formatted.push_str(&format!("Task {}: {}\n", idx + 1, task.title));
+            formatted.push_str(&format!("  ID: {}\n", task.task_ref()));
 
             if let Some(description) = &task.description {
-                summary.push_str(&format!("   Description: {}\n", description));
+                formatted.push_str(&format!("  Description: {}\n", description));
             }
 
-            summary.push_str(&format!("   Status: {}\n", task.status));
+            formatted.push_str(&format!("  Status: {}\n", task.status));
 
             if let Some(priority) = &task.priority {
-                summary.push_str(&format!("   Priority: {}\n", priority));
+                formatted.push_str(&format!("  Priority: {}\n", priority));
             }
 
             if let Some(due_date) = &task.due_date {
-                summary.push_str(&format!("   Due Date: {}\n", due_date));
+                formatted.push_str(&format!("  Due Date: {}\n", due_date));
             }
 
-            if let Some(tags) = &task.tags
-                && !tags.is_empty()
-            {
-                summary.push_str(&format!("   Tags: {}\n", tags.join(", ")));
+            if let Some(tags) = &task.tags {
+                formatted.push_str(&format!("  Tags: {}\n", tags.join(", ")));
             }
 
-            summary.push_str(&format!("   Created: {}\n", task.created_at));
-            summary.push('\n');
-        }
-
-        summary
-    }
-
-    /// Strip Markdown formatting for plain text output
-    fn strip_markdown(&self, markdown: &str) -> String {
-        markdown
-            .replace("### ", "")
-            .replace("## ", "")
-            .replace("# ", "")
-            .replace("**", "")
-            .replace("*", "")
-            .replace("`", "")
-            .replace("|", "")
-            .replace("---", "-----------------------------------------------")
-    }
-
-    /// Save analysis report to a file in the specified format
-    pub async fn save_analysis_report(
-        &self,
-        report: &AnalysisReport,
-        file_path: &str,
-    ) -> Result<()> {
-        info!("Saving analysis report to {}", file_path);
-
-        let format = OutputFormat::from_path(file_path);
-
-        let content = match format {
-            OutputFormat::Json => serde_json::to_string_pretty(report)
-                .map_err(|e| anyhow::anyhow!("Failed to serialize analysis report: {}", e))?,
-            OutputFormat::Markdown => self.format_report_as_markdown(report),
-            OutputFormat::PlainText => self.format_report_as_text(report),
-        };
+            if let Some(hours) = self.time_tracked.get(&task.id) {
+                let estimate = estimate::current_estimate(task, estimate::DEFAULT_FIELD)
+                    .unwrap_or_else(|| "none".to_string());
+                formatted.push_str(&format!(
+                    "  Time Tracked: {} (estimate: {})\n",
+                    plan::format_hours(*hours),
+                    estimate
+                ));
+            }
 
-        let path = Path::new(file_path);
+            formatted.push_str(&format!("  Created: {}\n", task.created_at));
+            formatted.push('\n');
+        }
 
-        // Create parent directories if they don't exist
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                anyhow::anyhow!("Failed to create directory {}: {}", parent.display(), e)
-            })?;
+        if tasks
+            .iter()
+            .any(|task| self.time_tracked.contains_key(&task.id))
+        {
+            formatted.push_str(timetrack::ANALYSIS_NOTE);
         }
 
-        let mut file = File::create(path)
-            .map_err(|e| anyhow::anyhow!("Failed to create file {}: {}", file_path, e))?;
+        formatted
+    }
 
-        file.write_all(content.as_bytes())
-            .map_err(|e| anyhow::anyhow!("Failed to write to file {}: {}", file_path, e))?;
+    /// The parsed analysis when structured output was requested
+    fn parse_structured(&self, analysis: &str) -> Result<Option<StructuredAnalysis>> {
+        self.structured
+            .then(|| structured::parse_structured_analysis(analysis))
+            .transpose()
+    }
 
-        info!(
-            "Analysis report saved successfully to {} in {:?} format",
-            file_path, format
-        );
-        Ok(())
+    fn create_analysis_prompt(&self, task_summary: &str, task_count: usize) -> String {
+        let template = self
+            .prompt_template
+            .as_deref()
+            .unwrap_or(prompts::DEFAULT_PROMPT);
+        prompts::render_template(template, task_summary, task_count)
     }
 
     /// Analyze tasks using DeepSeek with MCP tools available, returning structured report
     pub async fn analyze_tasks_with_tools_report(
         &self,
         tasks: Vec<crate::mcp_client::Task>,
-        mcp_client: &crate::mcp_client::McpClient,
+        pool: &crate::mcp_client::McpClientPool,
     ) -> Result<AnalysisReport> {
         let start_time = std::time::Instant::now();
         info!("Analyzing tasks with DeepSeek using MCP tools");
 
         // Get available MCP tools
-        let tools = create_mcp_tool_definitions(mcp_client).await?;
-        let task_tools = create_task_tools();
-
-        let mut all_tools = tools;
-        all_tools.extend(task_tools);
+        let all_tools = create_mcp_tool_definitions(pool).await?;
+
+        // A resumed session already has the prompt and the conversation so far
+        if let Some(mut messages) = self
+            .session
+            .as_ref()
+            .map(|session| session.snapshot().messages)
+            .filter(|messages| !messages.is_empty())
+        {
+            info!("Resuming the analysis after {} messages", messages.len());
+            let result = self.run_tool_loop(&mut messages, &all_tools, pool).await?;
+            return self.tools_report(tasks, result, start_time);
+        }
 
-        let task_summary = self.format_tasks_for_analysis(&tasks);
-        let analysis_prompt = format!(
-            "Please analyze these {} tasks. You have access to MCP tools to get more detailed information about tasks, create task breakdowns, or perform analysis. Feel free to use any available tools to provide a comprehensive analysis.
+        let task_summary = self.analysis_summary(&tasks).await?;
+        let analysis_prompt = match &self.prompt_template {
+            _ if self.structured => {
+                structured::structured_prompt(&task_summary, tasks.len(), true)
+            }
+            Some(template) => format!(
+                "{}\n\nYou can use the available MCP tools to get more data about the tasks before answering.",
+                prompts::render_template(template, &task_summary, tasks.len())
+            ),
+            None => format!(
+                "Please analyze these {} tasks. You have access to MCP tools to get more detailed information about tasks, create task breakdowns, or perform analysis. Feel free to use any available tools to provide a comprehensive analysis.
 
 Here are the initial tasks for reference:
 
 {}
 
 Provide insights about priorities, dependencies, complexity, and actionable recommendations. You can use the available tools to get more data or perform specific analysis operations.",
-            tasks.len(),
-            task_summary
-        );
+                tasks.len(),
+                task_summary
+            ),
+        };
 
         // Start the conversation with tools available
-        let (analysis_content, tool_calls_count) = self
-            .chat_with_tools_detailed(&analysis_prompt, &all_tools, mcp_client)
+        let result = self
+            .chat_with_tools_detailed(&analysis_prompt, &all_tools, pool)
             .await?;
+        self.tools_report(tasks, result, start_time)
+    }
 
+    /// Report of a finished tool-enabled analysis; its session is no longer needed
+    fn tools_report(
+        &self,
+        tasks: Vec<crate::mcp_client::Task>,
+        (analysis_content, tool_calls): (String, Vec<ToolCallRecord>),
+        start_time: std::time::Instant,
+    ) -> Result<AnalysisReport> {
         let duration = start_time.elapsed();
+        let token_usage = self.take_usage();
+        let reasoning = self.take_reasoning();
+        let structured = self.parse_structured(&analysis_content)?;
 
         let report = AnalysisReport {
             timestamp: Utc::now(),
@@ -457,73 +763,176 @@ Provide insights about priorities, dependencies, complexity, and actionable reco
             analysis: analysis_content,
             metadata: AnalysisMetadata {
                 tools_enabled: true,
-                tool_calls_count: Some(tool_calls_count),
+                tool_calls_count: Some(tool_calls.len()),
                 analysis_duration_seconds: Some(duration.as_secs_f64()),
+                estimated_cost_usd: Some(self.estimate_cost(&token_usage)),
+                estimated_cache_savings_usd: Some(self.estimate_cache_savings(&token_usage)),
+                token_usage: Some(token_usage),
             },
+            tool_calls,
+            reasoning,
+            structured,
+            delta: None,
         };
 
+        if let Some(session) = &self.session {
+            session.finish();
+        }
         Ok(report)
     }
 
+    /// Ask DeepSeek (with MCP tools) for a subtask breakdown of a single task,
+    /// returning the breakdown and the number of tool calls made
+    pub async fn analyze_task_breakdown(
+        &self,
+        task: &crate::mcp_client::Task,
+        pool: &crate::mcp_client::McpClientPool,
+    ) -> Result<(TaskBreakdown, usize)> {
+        info!("Analyzing task {} with DeepSeek using MCP tools", task.id);
+
+        let tools = create_mcp_tool_definitions(pool).await?;
+
+        let (response, tool_calls) = self
+            .chat_with_tools_detailed(&breakdown::breakdown_prompt(task), &tools, pool)
+            .await?;
+
+        let breakdown = breakdown::parse_breakdown(&response)?;
+        Ok((breakdown, tool_calls.len()))
+    }
+
     /// Analyze tasks using DeepSeek with MCP tools available
     #[allow(dead_code)]
     pub async fn analyze_tasks_with_tools(
         &self,
         tasks: Vec<crate::mcp_client::Task>,
-        mcp_client: &crate::mcp_client::McpClient,
+        pool: &crate::mcp_client::McpClientPool,
     ) -> Result<String> {
         info!("Analyzing tasks with DeepSeek using MCP tools");
 
         // Use the detailed method for backward compatibility
-        let report = self
-            .analyze_tasks_with_tools_report(tasks, mcp_client)
-            .await?;
+        let report = self.analyze_tasks_with_tools_report(tasks, pool).await?;
         Ok(report.analysis)
     }
 
     /// Chat with DeepSeek using available tools
-    #[allow(dead_code)]
     pub async fn chat_with_tools(
         &self,
         user_message: &str,
         tools: &[ToolObject],
-        mcp_client: &crate::mcp_client::McpClient,
+        pool: &crate::mcp_client::McpClientPool,
     ) -> Result<String> {
+        let (content, _) = self
+            .chat_with_tools_detailed(user_message, tools, pool)
+            .await?;
+        Ok(content)
+    }
+
+    /// Chat with DeepSeek using available tools, returning content and the tool calls made
+    pub async fn chat_with_tools_detailed(
+        &self,
+        user_message: &str,
+        tools: &[ToolObject],
+        pool: &crate::mcp_client::McpClientPool,
+    ) -> Result<(String, Vec<ToolCallRecord>)> {
         debug!("Starting chat with {} tools available", tools.len());
 
         let mut messages = vec![
-            Message {
-                role: "system".to_string(),
-                content: "You are an AI assistant that can analyze tasks and manage todo lists. You have access to various tools to help you provide detailed, accurate information. Use tools when they can help provide better answers.".to_string(),
-                tool_call_id: None,
-                tool_calls: None,
-            },
+            self.tool_system_message(),
             Message {
                 role: "user".to_string(),
                 content: user_message.to_string(),
                 tool_call_id: None,
                 tool_calls: None,
+                reasoning_content: None,
             },
         ];
 
-        // Try up to 5 tool call iterations to avoid infinite loops
-        for iteration in 0..5 {
+        self.run_tool_loop(&mut messages, tools, pool).await
+    }
+
+    /// System message used for tool-enabled conversations
+    pub fn tool_system_message(&self) -> Message {
+        Message {
+            role: "system".to_string(),
+            content: self.analysis_system_prompt(prompts::TOOL_SYSTEM_PROMPT),
+            tool_call_id: None,
+            tool_calls: None,
+            reasoning_content: None,
+        }
+    }
+
+    /// Run the tool-call loop over an existing conversation until the model gives a final
+    /// answer. All assistant and tool messages are appended to `messages`, so callers can
+    /// keep the history for follow-up turns. Every tool call is recorded.
+    pub async fn run_tool_loop(
+        &self,
+        messages: &mut Vec<Message>,
+        tools: &[ToolObject],
+        pool: &crate::mcp_client::McpClientPool,
+    ) -> Result<(String, Vec<ToolCallRecord>)> {
+        let mut records = Vec::new();
+        // Tool calls made so far as (name, arguments), to notice a model going in circles
+        let mut seen_calls: HashSet<(String, String)> = HashSet::new();
+        let mut first_iteration = 0;
+
+        // A resumed session picks up with its tool calls so far and answers the
+        // calls of an interrupted turn first
+        if let Some(session) = &self.session {
+            let saved = session.snapshot();
+            records = saved.tool_calls;
+            first_iteration = saved.iterations;
+            seen_calls.extend(
+                messages
+                    .iter()
+                    .flat_map(|message| message.tool_calls.iter().flatten())
+                    .map(|tool_call| call_signature(&tool_call.function)),
+            );
+            session.update(|session| session.messages = messages.clone());
+
+            if let Some(last) = messages.last()
+                && last.role == "assistant"
+                && let Some(tool_calls) = last.tool_calls.clone()
+            {
+                info!("Resuming with the unanswered tool calls of the last turn");
+                self.answer_tool_calls(messages, &tool_calls, pool, &mut records)
+                    .await?;
+            }
+        }
+
+        for iteration in first_iteration..self.max_tool_iterations {
             debug!("Chat iteration {} starting", iteration + 1);
 
-            let request = ToolChatRequest {
-                model: self.model.clone(),
-                messages: messages.clone(),
-                tools: Some(tools.to_vec()),
-                tool_choice: Some("auto".to_string()),
-                temperature: 0.7,
-                max_tokens: 4000,
-            };
+            let request = self.tool_request(messages, tools);
 
-            let response = self.deepseek_api.chat_with_tools(request).await?;
+            // Spend what is left of the budget on an answer rather than more tool calls
+            if iteration > 0 && Budget::active().is_some_and(|budget| !budget.affords(&request)) {
+                warn!("The budget is nearly used up; asking for an answer");
+                let content = self.final_answer(messages, tools).await?;
+                return Ok((content, records));
+            }
+
+            let response = self.send(request).await?;
 
             if let Some(choice) = response.choices.first() {
                 // Check if there are tool calls to handle
                 if let Some(tool_calls) = &choice.message.tool_calls {
+                    // Repeating only calls whose results it already has gets the model nowhere
+                    let signatures = tool_calls
+                        .iter()
+                        .map(|tool_call| call_signature(&tool_call.function))
+                        .collect::<Vec<_>>();
+                    if signatures
+                        .iter()
+                        .all(|signature| seen_calls.contains(signature))
+                    {
+                        warn!(
+                            "The model repeated tool calls it already made; asking for an answer"
+                        );
+                        let content = self.final_answer(messages, tools).await?;
+                        return Ok((content, records));
+                    }
+                    seen_calls.extend(signatures);
+
                     // Convert response tool calls to message tool calls
                     let message_tool_calls: Vec<crate::tooling::ToolCall> = tool_calls
                         .iter()
@@ -543,24 +952,17 @@ Provide insights about priorities, dependencies, complexity, and actionable reco
                         content: choice.message.content.clone().unwrap_or_default(),
                         tool_call_id: None,
                         tool_calls: Some(message_tool_calls),
+                        reasoning_content: choice.message.reasoning_content.clone(),
                     });
-                    info!("Processing {} tool calls", tool_calls.len());
-
-                    // Process each tool call
-                    for tool_call in tool_calls {
-                        debug!("Executing tool call: {}", tool_call.function.name);
-
-                        // Execute the tool call
-                        let tool_result = self.execute_tool_call(tool_call, mcp_client).await?;
-
-                        // Add the tool result back to the conversation
-                        messages.push(Message {
-                            role: "tool".to_string(),
-                            content: serde_json::to_string(&tool_result)?,
-                            tool_call_id: Some(tool_call.id.clone()),
-                            tool_calls: None,
+                    if let Some(session) = &self.session {
+                        session.update(|session| {
+                            session.messages = messages.clone();
+                            session.iterations = iteration + 1;
+                            session.token_usage = *self.usage.lock().unwrap();
                         });
                     }
+                    self.answer_tool_calls(messages, tool_calls, pool, &mut records)
+                        .await?;
 
                     // Continue the conversation with the tool results
                     continue;
@@ -572,132 +974,203 @@ Provide insights about priorities, dependencies, complexity, and actionable reco
                         content: content.clone(),
                         tool_call_id: None,
                         tool_calls: None,
+                        reasoning_content: None,
                     });
-                    return Ok(content);
+                    return Ok((content, records));
                 }
             } else {
-                anyhow::bail!("No response choices returned from DeepSeek API");
+                anyhow::bail!(McpTasksError::DeepSeekApi(
+                    "No response choices returned from DeepSeek API".to_string()
+                ));
             }
         }
 
-        warn!("Reached maximum iteration limit for tool calls");
-        Ok("Analysis completed with maximum tool call iterations reached.".to_string())
+        warn!(
+            "Reached the limit of {} tool call iterations; asking for an answer",
+            self.max_tool_iterations
+        );
+        let content = self.final_answer(messages, tools).await?;
+        Ok((content, records))
     }
 
-    /// Chat with DeepSeek using available tools, returning content and tool call count
-    pub async fn chat_with_tools_detailed(
+    /// Run the tool calls of one model turn and add their results to `messages`.
+    /// With a session, each result is saved as soon as it is in, and results
+    /// saved before an interruption are used instead of calling the tool again.
+    async fn answer_tool_calls(
         &self,
-        user_message: &str,
-        tools: &[ToolObject],
-        mcp_client: &crate::mcp_client::McpClient,
-    ) -> Result<(String, usize)> {
-        debug!("Starting chat with {} tools available", tools.len());
-
-        let mut messages = vec![
-            Message {
-                role: "system".to_string(),
-                content: "You are an AI assistant that can analyze tasks and manage todo lists. You have access to various tools to help you provide detailed, accurate information. Use tools when they can help provide better answers.".to_string(),
-                tool_call_id: None,
-                tool_calls: None,
-            },
-            Message {
-                role: "user".to_string(),
-                content: user_message.to_string(),
-                tool_call_id: None,
-                tool_calls: None,
-            },
-        ];
-
-        let mut total_tool_calls = 0;
-
-        // Try up to 5 tool call iterations to avoid infinite loops
-        for iteration in 0..5 {
-            debug!("Chat iteration {} starting", iteration + 1);
-
-            let request = ToolChatRequest {
-                model: self.model.clone(),
-                messages: messages.clone(),
-                tools: Some(tools.to_vec()),
-                tool_choice: Some("auto".to_string()),
-                temperature: 0.7,
-                max_tokens: 4000,
-            };
-
-            let response = self.deepseek_api.chat_with_tools(request).await?;
+        messages: &mut Vec<Message>,
+        tool_calls: &[crate::tooling::ToolCall],
+        pool: &crate::mcp_client::McpClientPool,
+        records: &mut Vec<ToolCallRecord>,
+    ) -> Result<()> {
+        info!("Processing {} tool calls", tool_calls.len());
+        let saved = self
+            .session
+            .as_ref()
+            .map(|session| session.snapshot().pending_results)
+            .unwrap_or_default();
+
+        // Confirmation prompts have to come one at a time, before anything runs
+        let mut refusals = Vec::with_capacity(tool_calls.len());
+        for tool_call in tool_calls {
+            refusals.push(if saved.contains_key(&tool_call.id) {
+                None
+            } else {
+                self.gate_tool_call(tool_call, pool).await?
+            });
+        }
 
-            if let Some(choice) = response.choices.first() {
-                // Check if there are tool calls to handle
-                if let Some(tool_calls) = &choice.message.tool_calls {
-                    total_tool_calls += tool_calls.len();
+        // Tool calls of one turn are independent, so run them concurrently, at most
+        // MAX_PARALLEL_TOOL_CALLS at a time; results are added back in the order
+        // the model requested them
+        let permits = Semaphore::new(MAX_PARALLEL_TOOL_CALLS);
+        let permits = &permits;
+        let saved = &saved;
+        let calls_before = records.len();
+        let tool_results = join_all(tool_calls.iter().zip(refusals).enumerate().map(
+            |(idx, (tool_call, refusal))| async move {
+                if let Some(pending) = saved.get(&tool_call.id) {
+                    debug!("Reusing the saved result of {}", tool_call.function.name);
+                    return (Ok(pending.result.clone()), pending.record.clone());
+                }
 
-                    // Convert response tool calls to message tool calls
-                    let message_tool_calls: Vec<crate::tooling::ToolCall> = tool_calls
-                        .iter()
-                        .map(|tc| crate::tooling::ToolCall {
-                            id: tc.id.clone(),
-                            call_type: Some("function".to_string()),
-                            function: crate::tooling::ToolCallFunction {
-                                name: tc.function.name.clone(),
-                                arguments: tc.function.arguments.clone(),
+                let _permit = permits.acquire().await;
+                progress::set_phase(format!(
+                    "tool call {}: {}",
+                    calls_before + idx + 1,
+                    tool_call.function.name
+                ));
+                let started_at = Utc::now();
+                let timer = std::time::Instant::now();
+                let result = match refusal {
+                    Some(refusal) => Ok(refusal),
+                    None => {
+                        debug!("Executing tool call: {}", tool_call.function.name);
+                        self.execute_tool_call(tool_call, pool).await
+                    }
+                };
+                let record = record_tool_call(tool_call, &result, started_at, timer.elapsed());
+                if let (Some(session), Ok(result)) = (&self.session, &result) {
+                    session.update(|session| {
+                        session.pending_results.insert(
+                            tool_call.id.clone(),
+                            PendingResult {
+                                result: result.clone(),
+                                record: record.clone(),
                             },
-                        })
-                        .collect();
-
-                    // Add the assistant's response with tool calls to the conversation
-                    messages.push(Message {
-                        role: "assistant".to_string(),
-                        content: choice.message.content.clone().unwrap_or_default(),
-                        tool_call_id: None,
-                        tool_calls: Some(message_tool_calls),
+                        );
                     });
-                    info!("Processing {} tool calls", tool_calls.len());
+                }
+                (result, record)
+            },
+        ))
+        .await;
 
-                    // Process each tool call
-                    for tool_call in tool_calls {
-                        debug!("Executing tool call: {}", tool_call.function.name);
+        for (tool_call, (tool_result, record)) in tool_calls.iter().zip(tool_results) {
+            records.push(record);
 
-                        // Execute the tool call
-                        let tool_result = self.execute_tool_call(tool_call, mcp_client).await?;
+            // Add the tool result back to the conversation
+            messages.push(Message {
+                role: "tool".to_string(),
+                content: serde_json::to_string(&tool_result?)?,
+                tool_call_id: Some(tool_call.id.clone()),
+                tool_calls: None,
+                reasoning_content: None,
+            });
+        }
 
-                        // Add the tool result back to the conversation
-                        messages.push(Message {
-                            role: "tool".to_string(),
-                            content: serde_json::to_string(&tool_result)?,
-                            tool_call_id: Some(tool_call.id.clone()),
-                            tool_calls: None,
-                        });
-                    }
+        if let Some(session) = &self.session {
+            session.update(|session| {
+                session.messages = messages.clone();
+                session.tool_calls = records.clone();
+                session.pending_results.clear();
+                session.token_usage = *self.usage.lock().unwrap();
+            });
+        }
+        Ok(())
+    }
 
-                    // Continue the conversation with the tool results
-                    continue;
-                } else {
-                    // No tool calls, add the assistant's final response and return it
-                    let content = choice.message.content.clone().unwrap_or_default();
-                    messages.push(Message {
-                        role: "assistant".to_string(),
-                        content: content.clone(),
-                        tool_call_id: None,
-                        tool_calls: None,
-                    });
-                    return Ok((content, total_tool_calls));
+    /// Ask the model to answer with the tool results it has. The tools stay in
+    /// the request with `tool_choice: none`, so the prompt prefix matches the
+    /// earlier iterations and is served from DeepSeek's context cache.
+    async fn final_answer(
+        &self,
+        messages: &mut Vec<Message>,
+        tools: &[ToolObject],
+    ) -> Result<String> {
+        messages.push(Message {
+            role: "system".to_string(),
+            content: FINAL_ANSWER_PROMPT.to_string(),
+            tool_call_id: None,
+            tool_calls: None,
+            reasoning_content: None,
+        });
+
+        let mut request = self.tool_request(messages, tools);
+        if request.tools.is_some() {
+            request.tool_choice = Some("none".to_string());
+        }
+        let response = self.send(request).await?;
+        let Some(choice) = response.choices.first() else {
+            anyhow::bail!(McpTasksError::DeepSeekApi(
+                "No response choices returned from DeepSeek API".to_string()
+            ));
+        };
+
+        let content = choice.message.content.clone().unwrap_or_default();
+        messages.push(Message {
+            role: "assistant".to_string(),
+            content: content.clone(),
+            tool_call_id: None,
+            tool_calls: None,
+            reasoning_content: None,
+        });
+        Ok(content)
+    }
+
+    /// Apply the tool policy to a tool call. Returns the tool result to send
+    /// back instead of running the call, or `None` when the call may run.
+    async fn gate_tool_call(
+        &self,
+        tool_call: &crate::tooling::ToolCall,
+        pool: &crate::mcp_client::McpClientPool,
+    ) -> Result<Option<Value>> {
+        let arguments: Value = serde_json::from_str(&tool_call.function.arguments)
+            .unwrap_or_else(|_| serde_json::json!({}));
+        let annotations = tool_annotations(pool, &tool_call.function.name, &arguments).await;
+
+        let (mcp_tool_name, reason) = match self.tool_policy.decide(
+            &tool_call.function.name,
+            &arguments,
+            annotations.as_ref(),
+        ) {
+            ToolDecision::Run => return Ok(None),
+            ToolDecision::Confirm(mcp_tool_name) => {
+                let question = format!(
+                    "\n🔐 The AI wants to call '{}' with {}. Allow?",
+                    mcp_tool_name, arguments
+                );
+                if confirm(&question)? {
+                    return Ok(None);
                 }
-            } else {
-                anyhow::bail!("No response choices returned from DeepSeek API");
+                (mcp_tool_name, "the user did not allow this change")
             }
-        }
+            ToolDecision::Refuse(mcp_tool_name) => (mcp_tool_name, "blocked by the tool policy"),
+        };
 
-        warn!("Reached maximum iteration limit for tool calls");
-        Ok((
-            "Analysis completed with maximum tool call iterations reached.".to_string(),
-            total_tool_calls,
-        ))
+        info!("Refused tool call '{}': {}", mcp_tool_name, reason);
+        Ok(Some(serde_json::json!({
+            "tool_name": mcp_tool_name,
+            "success": false,
+            "error": format!("Not permitted: {}. Do not retry it.", reason),
+        })))
     }
 
     /// Execute a tool call by routing it to the appropriate MCP function
     async fn execute_tool_call(
         &self,
         tool_call: &crate::tooling::ToolCall,
-        mcp_client: &crate::mcp_client::McpClient,
+        pool: &crate::mcp_client::McpClientPool,
     ) -> Result<Value> {
         let tool_name = &tool_call.function.name;
         let arguments: Value = serde_json::from_str(&tool_call.function.arguments)
@@ -705,14 +1178,116 @@ Provide insights about priorities, dependencies, complexity, and actionable reco
 
         debug!("Executing tool '{}' with args: {}", tool_name, arguments);
 
-        match tool_name.as_str() {
-            "list_tasks" | "get_task" | "task_stats" => {
-                execute_task_tool(mcp_client, tool_name, &arguments).await
+        let execution = async {
+            match tool_name.as_str() {
+                tool_name if TASK_TOOLS.contains(&tool_name) => {
+                    execute_task_tool(pool, tool_name, &arguments).await
+                }
+                _ => {
+                    // Try to execute as an MCP tool
+                    execute_mcp_tool_call(pool, tool_name, &arguments).await
+                }
+            }
+        };
+
+        // A stalled server tool is cancelled, and the model told so, instead of
+        // holding up the whole analysis
+        let Some(timeout) = self.tool_timeouts.for_tool(tool_name, &arguments) else {
+            return execution.await;
+        };
+        match tokio::time::timeout(timeout, execution).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!(
+                    "Tool call '{}' timed out after {}s, cancelled",
+                    tool_name,
+                    timeout.as_secs()
+                );
+                Ok(tool_timeout_result(tool_name, &arguments, timeout))
+            }
+        }
+    }
+}
+
+/// Tool name and arguments with keys in a fixed order, so identical calls compare equal
+fn call_signature(function: &crate::tooling::ToolCallFunction) -> (String, String) {
+    let arguments = serde_json::from_str::<Value>(&function.arguments)
+        .map(|arguments| arguments.to_string())
+        .unwrap_or_else(|_| function.arguments.trim().to_string());
+    (function.name.clone(), arguments)
+}
+
+/// Audit record for a finished tool call. Results reporting `"success": false`
+/// (refusals, MCP tool errors) count as failures.
+fn record_tool_call(
+    tool_call: &crate::tooling::ToolCall,
+    result: &Result<Value>,
+    started_at: chrono::DateTime<Utc>,
+    elapsed: std::time::Duration,
+) -> ToolCallRecord {
+    let arguments = serde_json::from_str(&tool_call.function.arguments)
+        .unwrap_or_else(|_| Value::String(tool_call.function.arguments.clone()));
+
+    let (success, error, result_bytes) = match result {
+        Ok(value) => (
+            value.get("success").and_then(Value::as_bool) != Some(false),
+            value
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            value.to_string().len(),
+        ),
+        Err(e) => (false, Some(e.to_string()), 0),
+    };
+
+    ToolCallRecord {
+        timestamp: started_at,
+        tool: tool_call.function.name.clone(),
+        arguments,
+        success,
+        error,
+        result_bytes,
+        duration_ms: elapsed.as_millis() as u64,
+    }
+}
+
+/// Echoes streamed tokens: content to stdout, reasoning to stderr when enabled
+struct TokenPrinter {
+    show_reasoning: bool,
+    in_reasoning: bool,
+}
+
+impl TokenPrinter {
+    fn new(show_reasoning: bool) -> Self {
+        Self {
+            show_reasoning,
+            in_reasoning: false,
+        }
+    }
+
+    fn print(&mut self, token: StreamToken<'_>) {
+        match token {
+            StreamToken::Reasoning(text) if self.show_reasoning => {
+                if !self.in_reasoning {
+                    eprint!("{}", output::decorate("💭 "));
+                    self.in_reasoning = true;
+                }
+                eprint!("{}", text);
             }
-            _ => {
-                // Try to execute as an MCP tool
-                execute_mcp_tool_call(mcp_client, tool_name, &arguments).await
+            StreamToken::Reasoning(_) => {}
+            StreamToken::Content(text) => {
+                self.finish();
+                print!("{}", text);
+                let _ = std::io::stdout().flush();
             }
         }
     }
+
+    /// End a reasoning block that was not followed by content
+    fn finish(&mut self) {
+        if self.in_reasoning {
+            eprintln!("\n");
+            self.in_reasoning = false;
+        }
+    }
 }
@@ -0,0 +1,235 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+use tracing::info;
+
+use crate::dates::{self, TaskDate};
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::output::TaskStats;
+use crate::pdf;
+use crate::report::{self, OutputFormat};
+use crate::trend::{StatsSnapshot, Trend, sparkline};
+
+/// Marker the narrative prompt starts with; the mock provider recognizes it
+pub const DIGEST_PROMPT_HEADER: &str = "Task digest";
+
+/// Time span a digest covers, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl DigestPeriod {
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Day => "Daily",
+            Self::Week => "Weekly",
+            Self::Month => "Monthly",
+        }
+    }
+}
+
+/// Stats, trend, task changes and an optional AI narrative for one period
+#[derive(Debug, Serialize)]
+pub struct Digest {
+    pub generated_at: DateTime<Utc>,
+    pub period: DigestPeriod,
+    pub since: DateTime<Utc>,
+    pub stats: TaskStats,
+    pub trend: Trend,
+    /// Tasks completed during the period
+    pub completed: Vec<Task>,
+    /// Tasks created during the period
+    pub created: Vec<Task>,
+    /// Unfinished tasks past their due date
+    pub overdue: Vec<Task>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<String>,
+}
+
+impl Digest {
+    /// Collect the digest for the `period` ending at `now`; `snapshots` are the
+    /// stored stats snapshots taken during the period
+    pub fn build(
+        tasks: &[Task],
+        snapshots: &[StatsSnapshot],
+        period: DigestPeriod,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let since = now - chrono::Duration::days(period.days());
+        let in_period = |date: Option<&str>| {
+            TaskDate::parse_opt(date).is_some_and(|date| date.sort_key() >= since)
+        };
+
+        let unfinished = tasks
+            .iter()
+            .filter(|task| task.is_unfinished())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Self {
+            generated_at: now,
+            period,
+            since,
+            stats: TaskStats::compute(tasks, &unfinished),
+            trend: Trend::build(
+                snapshots,
+                std::time::Duration::from_secs(period.days() as u64 * 86_400),
+            ),
+            completed: tasks
+                .iter()
+                .filter(|task| !task.is_unfinished() && in_period(task.completed_at.as_deref()))
+                .cloned()
+                .collect(),
+            created: tasks
+                .iter()
+                .filter(|task| in_period(Some(&task.created_at)))
+                .cloned()
+                .collect(),
+            overdue: unfinished
+                .iter()
+                .filter(|task| {
+                    TaskDate::parse_opt(task.due_date.as_deref())
+                        .is_some_and(|due| due.is_overdue(now))
+                })
+                .cloned()
+                .collect(),
+            narrative: None,
+        }
+    }
+
+    /// Prompt asking the model for a short narrative of the period
+    pub fn narrative_prompt(&self) -> String {
+        let titles = |tasks: &[Task]| {
+            if tasks.is_empty() {
+                "  (none)\n".to_string()
+            } else {
+                tasks
+                    .iter()
+                    .map(|task| format!("  - {}\n", task.title))
+                    .collect()
+            }
+        };
+
+        format!(
+            "{} for the last {} days.\n\n\
+             Open tasks: {}, overdue: {}, completion rate: {:.1}%\n\n\
+             Completed:\n{}\nCreated:\n{}\nOverdue:\n{}\n\
+             In one short paragraph, summarize what got done, what came in and what needs attention next.",
+            DIGEST_PROMPT_HEADER,
+            self.period.days(),
+            self.stats.unfinished,
+            self.stats.overdue,
+            self.stats.completion_rate,
+            titles(&self.completed),
+            titles(&self.created),
+            titles(&self.overdue)
+        )
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!(
+            "# {} Task Digest\n\n**Period:** {} to {}  \n**Generated:** {}  \n\n",
+            self.period.title(),
+            dates::format_instant(self.since, "%Y-%m-%d"),
+            dates::format_instant(self.generated_at, "%Y-%m-%d"),
+            dates::format_instant(self.generated_at, "%Y-%m-%d %H:%M")
+        );
+
+        if let Some(narrative) = &self.narrative {
+            output.push_str(&format!("## 🤖 Summary\n\n{}\n\n", narrative.trim()));
+        }
+
+        output.push_str(&format!(
+            "## 📊 Stats\n\n- **Total tasks:** {}\n- **Open:** {}\n- **Completed:** {}\n- **Overdue:** {}\n- **Completion rate:** {:.1}%\n\n",
+            self.stats.total,
+            self.stats.unfinished,
+            self.stats.completed,
+            self.stats.overdue,
+            self.stats.completion_rate
+        ));
+
+        output.push_str("## 📈 Trend\n\n");
+        match (self.trend.points.first(), self.trend.points.last()) {
+            (Some(first), Some(last)) if self.trend.points.len() > 1 => {
+                output.push_str(&format!(
+                    "- **Completion rate:** `{}` {:.1}% → {:.1}%\n- **Open tasks:** `{}` {} → {}\n- **Overdue:** `{}` {} → {}\n\n",
+                    sparkline(&self.trend.completion_rates()),
+                    first.completion_rate,
+                    last.completion_rate,
+                    sparkline(&self.trend.backlog()),
+                    first.unfinished,
+                    last.unfinished,
+                    sparkline(&self.trend.overdue()),
+                    first.overdue,
+                    last.overdue
+                ));
+            }
+            _ => output.push_str(
+                "Not enough stats snapshots in this period; run `stats` regularly to see a trend.\n\n",
+            ),
+        }
+
+        for (heading, tasks) in [
+            ("## ✅ Completed", &self.completed),
+            ("## 🆕 New", &self.created),
+            ("## 🚨 Overdue", &self.overdue),
+        ] {
+            output.push_str(&format!("{} ({})\n\n", heading, tasks.len()));
+            if tasks.is_empty() {
+                output.push_str("- None\n");
+            }
+            for task in tasks.iter() {
+                output.push_str(&format!("- {} (`{}`)", task.title, task.id));
+                if let Some(due) = TaskDate::parse_opt(task.due_date.as_deref()) {
+                    output.push_str(&format!(", due {}", due.format()));
+                }
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Render in `format`: JSON, Markdown, plain text or PDF
+    pub fn render(&self, format: OutputFormat) -> Result<Vec<u8>> {
+        let content = match format {
+            OutputFormat::Json => serde_json::to_string_pretty(self)
+                .context(McpTasksError::Report(
+                    "Failed to serialize the digest".to_string(),
+                ))?
+                .into_bytes(),
+            OutputFormat::Markdown => self.to_markdown().into_bytes(),
+            OutputFormat::PlainText => report::strip_markdown(&self.to_markdown()).into_bytes(),
+            OutputFormat::Pdf => pdf::render_markdown(
+                &format!("{} Task Digest", self.period.title()),
+                &self.to_markdown(),
+            )
+            .context(McpTasksError::Report(
+                "Failed to render the digest as PDF".to_string(),
+            ))?,
+        };
+        Ok(content)
+    }
+
+    pub fn save(&self, path: &Path, format: OutputFormat) -> Result<()> {
+        report::write_file(path, &self.render(format)?)?;
+        info!("Digest saved to {} in {:?} format", path.display(), format);
+        Ok(())
+    }
+}
@@ -0,0 +1,485 @@
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::error::McpTasksError;
+use crate::llm::{self, DEEPSEEK_BASE_URL, OLLAMA_BASE_URL, ProviderKind};
+use crate::mcp_client::Task;
+
+const CACHE_FILE: &str = "embeddings.json";
+
+/// Texts sent per embedding request
+const BATCH_SIZE: usize = 64;
+
+/// Default model for OpenAI-compatible embedding endpoints
+pub const OPENAI_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+/// Default model for Ollama
+pub const OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+const MOCK_DIMENSIONS: usize = 256;
+
+/// An API turning texts into embedding vectors
+pub trait EmbeddingProvider: Send + Sync {
+    /// Provider name for log and error messages
+    fn name(&self) -> &'static str;
+
+    /// Model the vectors come from; cached vectors are only reused for the same model
+    fn model(&self) -> &str;
+
+    /// One vector per input, in input order
+    fn embed<'a>(&'a self, inputs: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>>;
+}
+
+/// Build the embedding provider from `[embeddings]`, falling back to the chat provider's settings
+pub fn create_embedder(config: &Config) -> Result<Box<dyn EmbeddingProvider>> {
+    // Mock runs stay offline even when a real embedding API is configured
+    let kind = if config.provider == ProviderKind::Mock {
+        ProviderKind::Mock
+    } else {
+        config.embedding_provider.unwrap_or(config.provider)
+    };
+    let base_url = config.embedding_base_url.clone().or_else(|| {
+        (kind == config.provider)
+            .then(|| config.llm_base_url.clone())
+            .flatten()
+    });
+    let model = config.embedding_model.clone();
+
+    let embedder: Box<dyn EmbeddingProvider> = match kind {
+        ProviderKind::Deepseek => {
+            let api_key = match &config.embedding_api_key {
+                Some(api_key) => api_key.clone(),
+                None => llm::deepseek_api_key(config)?,
+            };
+            Box::new(OpenAiEmbedder::new(
+                "DeepSeek",
+                base_url.as_deref().unwrap_or(DEEPSEEK_BASE_URL),
+                Some(api_key),
+                model.unwrap_or_else(|| OPENAI_EMBEDDING_MODEL.to_string()),
+            ))
+        }
+        ProviderKind::OpenaiCompatible => {
+            let base_url = base_url.ok_or_else(|| {
+                anyhow::anyhow!(McpTasksError::Config("Embeddings from an openai-compatible provider need a base URL (EMBEDDING_BASE_URL, [embeddings] base_url or [llm] base_url in config)".to_string()))
+            })?;
+            Box::new(OpenAiEmbedder::new(
+                "OpenAI-compatible",
+                &base_url,
+                config
+                    .embedding_api_key
+                    .clone()
+                    .or_else(|| config.llm_api_key.clone()),
+                model.unwrap_or_else(|| OPENAI_EMBEDDING_MODEL.to_string()),
+            ))
+        }
+        ProviderKind::Ollama => Box::new(OllamaEmbedder::new(
+            base_url.as_deref().unwrap_or(OLLAMA_BASE_URL),
+            model.unwrap_or_else(|| OLLAMA_EMBEDDING_MODEL.to_string()),
+        )),
+        ProviderKind::Mock => Box::new(MockEmbedder),
+    };
+
+    info!(
+        "Using {} embeddings with model {}",
+        embedder.name(),
+        embedder.model()
+    );
+    Ok(embedder)
+}
+
+/// Client for OpenAI-style `/embeddings` endpoints
+pub struct OpenAiEmbedder {
+    client: Client,
+    name: &'static str,
+    api_key: Option<String>,
+    url: String,
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiEmbedder {
+    /// `base_url` is the API root; requests go to `<base_url>/embeddings`
+    pub fn new(name: &'static str, base_url: &str, api_key: Option<String>, model: String) -> Self {
+        Self {
+            client: Client::new(),
+            name,
+            api_key,
+            url: format!("{}/embeddings", base_url.trim_end_matches('/')),
+            model,
+        }
+    }
+
+    async fn request(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        debug!("Requesting {} embeddings from {}", inputs.len(), self.name);
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "model": self.model, "input": inputs }));
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = request.send().await.with_context(|| {
+            McpTasksError::DeepSeekApi(format!(
+                "Failed to send embedding request to {} API",
+                self.name
+            ))
+        })?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "{} embedding API error {}: {}",
+                self.name, status, text
+            )));
+        }
+
+        let mut response: OpenAiEmbeddingResponse = response.json().await.with_context(|| {
+            McpTasksError::DeepSeekApi(format!("Failed to parse {} embedding response", self.name))
+        })?;
+        response.data.sort_by_key(|embedding| embedding.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+}
+
+impl EmbeddingProvider for OpenAiEmbedder {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, inputs: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>> {
+        Box::pin(self.request(inputs))
+    }
+}
+
+/// Client for Ollama's native `/api/embed` endpoint
+pub struct OllamaEmbedder {
+    client: Client,
+    url: String,
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl OllamaEmbedder {
+    /// `base_url` is the server root, e.g. `http://localhost:11434`
+    pub fn new(base_url: &str, model: String) -> Self {
+        Self {
+            client: Client::new(),
+            url: format!("{}/api/embed", base_url.trim_end_matches('/')),
+            model,
+        }
+    }
+
+    async fn request(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        debug!("Requesting {} embeddings from Ollama", inputs.len());
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "model": self.model, "input": inputs }))
+            .send()
+            .await
+            .context(McpTasksError::DeepSeekApi(
+                "Failed to send embedding request to Ollama (is `ollama serve` running?)"
+                    .to_string(),
+            ))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "Ollama embedding API error {}: {}",
+                status, text
+            )));
+        }
+
+        let response: OllamaEmbedResponse = response.json().await.context(
+            McpTasksError::DeepSeekApi("Failed to parse Ollama embedding response".to_string()),
+        )?;
+        Ok(response.embeddings)
+    }
+}
+
+impl EmbeddingProvider for OllamaEmbedder {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, inputs: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>> {
+        Box::pin(self.request(inputs))
+    }
+}
+
+/// Offline stand-in: hashed bag of words, so texts sharing words (or their
+/// first four letters, a crude stem) end up close together
+pub struct MockEmbedder;
+
+impl MockEmbedder {
+    fn vector(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; MOCK_DIMENSIONS];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            let word = word.to_lowercase();
+            vector[fnv1a(&word) as usize % MOCK_DIMENSIONS] += 1.0;
+            if let Some((stem_end, _)) = word.char_indices().nth(4) {
+                vector[fnv1a(&word[..stem_end]) as usize % MOCK_DIMENSIONS] += 1.0;
+            }
+        }
+        vector
+    }
+}
+
+impl EmbeddingProvider for MockEmbedder {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn model(&self) -> &str {
+        "mock"
+    }
+
+    fn embed<'a>(&'a self, inputs: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>> {
+        Box::pin(async move { Ok(inputs.iter().map(|text| Self::vector(text)).collect()) })
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, unlike `DefaultHasher` the same across builds
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The text embedded for a task: its title and description
+pub fn task_text(task: &Task) -> String {
+    match task.description.as_deref().map(str::trim) {
+        Some(description) if !description.is_empty() => {
+            format!("{}\n{}", task.title, description)
+        }
+        _ => task.title.clone(),
+    }
+}
+
+/// What a cached vector was computed from: the task's `updated_at`, or a hash
+/// of its text when the server does not send one
+fn task_version(task: &Task, text: &str) -> String {
+    match &task.updated_at {
+        Some(updated_at) => updated_at.clone(),
+        None => format!("text:{:016x}", fnv1a(text)),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    model: String,
+    /// Vectors by task ID
+    tasks: BTreeMap<String, CachedEmbedding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEmbedding {
+    version: String,
+    embedding: Vec<f32>,
+}
+
+/// Task embeddings of one model in `embeddings.json` in the data directory,
+/// reused while a task's `updated_at` stays the same
+pub struct EmbeddingCache {
+    path: PathBuf,
+    file: CacheFile,
+}
+
+impl EmbeddingCache {
+    /// Load the cache; vectors of another model and an unreadable file are discarded
+    pub fn load(data_dir: &Path, model: &str) -> Result<Self> {
+        let path = data_dir.join(CACHE_FILE);
+        let mut file = if path.exists() {
+            let content = std::fs::read_to_string(&path).with_context(|| {
+                McpTasksError::Report(format!("Failed to read embedding cache {}", path.display()))
+            })?;
+            serde_json::from_str::<CacheFile>(&content).unwrap_or_else(|e| {
+                warn!("Discarding unreadable embedding cache: {}", e);
+                CacheFile::default()
+            })
+        } else {
+            CacheFile::default()
+        };
+
+        if file.model != model {
+            file = CacheFile {
+                model: model.to_string(),
+                tasks: BTreeMap::new(),
+            };
+        }
+
+        Ok(Self { path, file })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        let content =
+            serde_json::to_string(&self.file).context("Failed to serialize the embedding cache")?;
+        std::fs::write(&self.path, content).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write embedding cache {}",
+                self.path.display()
+            ))
+        })
+    }
+}
+
+/// Embeddings of `tasks` in order; tasks changed since they were cached are
+/// embedded again, in batches
+pub async fn embed_tasks(
+    embedder: &dyn EmbeddingProvider,
+    cache: &mut EmbeddingCache,
+    tasks: &[Task],
+) -> Result<Vec<Vec<f32>>> {
+    let texts = tasks.iter().map(task_text).collect::<Vec<_>>();
+    let versions = tasks
+        .iter()
+        .zip(&texts)
+        .map(|(task, text)| task_version(task, text))
+        .collect::<Vec<_>>();
+
+    let missing = tasks
+        .iter()
+        .zip(&versions)
+        .enumerate()
+        .filter(|(_, (task, version))| {
+            cache
+                .file
+                .tasks
+                .get(&task.id)
+                .is_none_or(|cached| &cached.version != *version)
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    debug!(
+        "{} of {} task embeddings cached",
+        tasks.len() - missing.len(),
+        tasks.len()
+    );
+
+    for batch in missing.chunks(BATCH_SIZE) {
+        let inputs = batch
+            .iter()
+            .map(|&index| texts[index].clone())
+            .collect::<Vec<_>>();
+        let embeddings = embedder.embed(&inputs).await?;
+        if embeddings.len() != inputs.len() {
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "{} returned {} embeddings for {} texts",
+                embedder.name(),
+                embeddings.len(),
+                inputs.len()
+            )));
+        }
+
+        for (&index, embedding) in batch.iter().zip(embeddings) {
+            cache.file.tasks.insert(
+                tasks[index].id.clone(),
+                CachedEmbedding {
+                    version: versions[index].clone(),
+                    embedding,
+                },
+            );
+        }
+    }
+
+    Ok(tasks
+        .iter()
+        .map(|task| cache.file.tasks[&task.id].embedding.clone())
+        .collect())
+}
+
+/// Cosine similarity in [-1, 1]; 0 when either vector is all zeros
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// A task and how close it is to the query
+#[derive(Debug, Serialize)]
+pub struct SemanticMatch {
+    pub score: f32,
+    pub task: Task,
+}
+
+/// The `limit` tasks closest in meaning to `query`, best first
+pub async fn search(
+    embedder: &dyn EmbeddingProvider,
+    cache: &mut EmbeddingCache,
+    tasks: Vec<Task>,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SemanticMatch>> {
+    let embeddings = embed_tasks(embedder, cache, &tasks).await?;
+    let query_embedding = embedder
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .with_context(|| {
+            McpTasksError::DeepSeekApi(format!(
+                "{} returned no embedding for the query",
+                embedder.name()
+            ))
+        })?;
+
+    let mut matches = tasks
+        .into_iter()
+        .zip(&embeddings)
+        .map(|(task, embedding)| SemanticMatch {
+            score: cosine_similarity(&query_embedding, embedding),
+            task,
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+    Ok(matches)
+}
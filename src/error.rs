@@ -0,0 +1,79 @@
+use thiserror::Error;
+
+/// Kinds of failure callers may want to handle differently.
+///
+/// Errors are still passed around as `anyhow::Error` with context; one of these
+/// sits in the chain where the failure originated. Use [`McpTasksError::classify`]
+/// to find it.
+#[derive(Debug, Error)]
+pub enum McpTasksError {
+    /// Invalid configuration or command-line input, e.g. a missing API key
+    #[error("{0}")]
+    Config(String),
+    /// The MCP server could not be started or the connection to it failed
+    #[error("{0}")]
+    McpTransport(String),
+    /// The MCP server answered with an error or something unexpected
+    #[error("{0}")]
+    McpProtocol(String),
+    /// The LLM provider's API failed or returned an unusable response
+    #[error("{0}")]
+    DeepSeekApi(String),
+    /// A report or other output file (export, history, transcript) could not be written
+    #[error("{0}")]
+    Report(String),
+    /// An import source (another task service or a task file) failed or could not be read
+    #[error("{0}")]
+    Import(String),
+    /// An AI request would go over the per-run or monthly spending budget
+    #[error("{0}")]
+    Budget(String),
+}
+
+impl McpTasksError {
+    /// The typed error in `error`'s chain, if any
+    pub fn classify(error: &anyhow::Error) -> Option<&McpTasksError> {
+        error.downcast_ref()
+    }
+
+    /// Process exit code for the CLI; 1 is left for unclassified errors.
+    /// Config shares 2 with clap's exit code for invalid arguments.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Config(_) => 2,
+            Self::McpTransport(_) => 3,
+            Self::McpProtocol(_) => 4,
+            Self::DeepSeekApi(_) => 5,
+            Self::Report(_) => 6,
+            Self::Import(_) => 7,
+            Self::Budget(_) => 8,
+        }
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::Config(_) => {
+                "Check the command-line arguments (see --help) and your settings in .env, the environment, ~/.config/mcp-tasks/config.toml or ./mcp-tasks.toml (see config_template.toml)."
+            }
+            Self::McpTransport(_) => {
+                "Check that the MCP server command is correct and the server starts on its own, or that a WebSocket server's url is reachable (MCP_SERVER_COMMAND/MCP_SERVER_ARGS, MCP_SERVERS or [[servers]] in config); run with --verbose for details."
+            }
+            Self::McpProtocol(_) => {
+                "The MCP server rejected the request or answered unexpectedly; check the arguments (e.g. the task ID) and that it is a compatible todo server (`tools` lists what it offers, `doctor` which task operations it supports)."
+            }
+            Self::DeepSeekApi(_) => {
+                "Check that your API key is valid, you have API credits and the provider is reachable (see --provider and LLM_BASE_URL)."
+            }
+            Self::Report(_) => {
+                "Check that the output directory exists and is writable; report files must end in .md, .txt, .json or .pdf."
+            }
+            Self::Import(_) => {
+                "Check the import source: the API token and network access for services, the path and format for files."
+            }
+            Self::Budget(_) => {
+                "Raise --max-cost or [budget] monthly in config, or lower --max-tokens; `usage` shows what analyses cost so far."
+            }
+        }
+    }
+}
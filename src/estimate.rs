@@ -0,0 +1,297 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::structured;
+use crate::table_formatter::priority_level;
+
+/// Marker the estimate prompt contains; the mock provider recognizes it
+pub const ESTIMATE_PROMPT_MARKER: &str = "\"estimates\"";
+
+pub const ESTIMATE_SYSTEM_PROMPT: &str = "You are an experienced software delivery lead estimating work. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Task field estimates are stored in unless `--field` says otherwise
+pub const DEFAULT_FIELD: &str = "estimate";
+
+/// Prefix of the tag estimates are written to with `--apply-as tag`
+pub const ESTIMATE_TAG_PREFIX: &str = "estimate:";
+
+/// Story points the model may choose from
+const POINT_SCALE: [f64; 7] = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0];
+
+/// Unit estimates are given in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum EstimateUnit {
+    /// Story points on the Fibonacci scale (default)
+    #[default]
+    Points,
+    /// Hours of focused work
+    Hours,
+}
+
+impl EstimateUnit {
+    /// Short suffix used in tags and tables
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Self::Points => "sp",
+            Self::Hours => "h",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Points => "story points",
+            Self::Hours => "hours",
+        }
+    }
+
+    /// `5sp`, `2.5h`
+    pub fn format(self, value: f64) -> String {
+        format!("{}{}", value, self.suffix())
+    }
+}
+
+/// Where `estimate --apply` writes the estimates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ApplyTarget {
+    /// A task field (`--field`, default `estimate`) of `update_task`
+    #[default]
+    Field,
+    /// An `estimate:<value>` tag, replacing an earlier one
+    Tag,
+}
+
+#[derive(Debug, Deserialize)]
+struct EstimateList {
+    estimates: Vec<RawEstimate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEstimate {
+    id: String,
+    estimate: f64,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// The AI's estimate for one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskEstimate {
+    pub id: String,
+    pub title: String,
+    pub priority: Option<String>,
+    /// Estimate already stored on the task, from the field or tag
+    pub current: Option<String>,
+    pub estimate: f64,
+    pub rationale: String,
+}
+
+/// Prompt asking for an estimate of every formatted task
+pub fn estimate_prompt(task_summary: &str, task_count: usize, unit: EstimateUnit) -> String {
+    let scale = match unit {
+        EstimateUnit::Points => format!(
+            "story points, using only these values: {}",
+            POINT_SCALE
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        EstimateUnit::Hours => {
+            "hours of focused work for one person, rounded to half hours".to_string()
+        }
+    };
+    format!(
+        r#"Estimate the effort of these {task_count} pending tasks in {scale}. Consider the scope described, likely unknowns and how the tasks relate to each other.
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "estimates": [
+    {{
+      "id": "task ID as given above",
+      "estimate": 3,
+      "rationale": "one sentence explaining the estimate"
+    }}
+  ]
+}}
+
+Include every task exactly once."#
+    )
+}
+
+/// Estimates of one answer, paired with their tasks
+#[derive(Debug, Clone)]
+pub struct TaskEstimates {
+    pub estimates: Vec<TaskEstimate>,
+    /// IDs the model estimated that match none of the tasks
+    pub unknown_ids: Vec<String>,
+}
+
+/// Parse the model's answer and pair each estimate with its task, in task order.
+/// Estimates that are not positive numbers are dropped, those for unknown tasks
+/// are kept as [`TaskEstimates::unknown_ids`]; story points are rounded up to the
+/// next value on the scale.
+pub fn parse_estimates(
+    response: &str,
+    tasks: &[Task],
+    unit: EstimateUnit,
+    field: &str,
+) -> Result<TaskEstimates> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain JSON estimates".to_string())
+    })?;
+
+    let list: EstimateList = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI estimates".to_string(),
+    ))?;
+
+    let estimates = tasks
+        .iter()
+        .filter_map(|task| {
+            let raw = list.estimates.iter().find(|raw| raw.id == task.id)?;
+            (raw.estimate.is_finite() && raw.estimate > 0.0).then(|| TaskEstimate {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                priority: task.priority.clone(),
+                current: current_estimate(task, field),
+                estimate: match unit {
+                    EstimateUnit::Points => POINT_SCALE
+                        .iter()
+                        .copied()
+                        .find(|points| *points >= raw.estimate)
+                        .unwrap_or(POINT_SCALE[POINT_SCALE.len() - 1]),
+                    EstimateUnit::Hours => (raw.estimate * 2.0).ceil() / 2.0,
+                },
+                rationale: raw.rationale.clone(),
+            })
+        })
+        .collect();
+
+    let unknown_ids = list
+        .estimates
+        .into_iter()
+        .filter(|raw| !tasks.iter().any(|task| task.id == raw.id))
+        .map(|raw| raw.id)
+        .collect();
+
+    Ok(TaskEstimates {
+        estimates,
+        unknown_ids,
+    })
+}
+
+/// The estimate stored on `task` in `field` or as an `estimate:` tag
+pub fn current_estimate(task: &Task, field: &str) -> Option<String> {
+    match task.extra.get(field) {
+        Some(Value::String(value)) if !value.is_empty() => Some(value.clone()),
+        Some(Value::Number(value)) => Some(value.to_string()),
+        _ => task
+            .tags
+            .iter()
+            .flatten()
+            .find_map(|tag| tag.strip_prefix(ESTIMATE_TAG_PREFIX))
+            .map(str::to_string),
+    }
+}
+
+/// `task`'s tags with any earlier estimate tag replaced by `estimate`
+pub fn tags_with_estimate(
+    task_tags: Option<&[String]>,
+    estimate: &TaskEstimate,
+    unit: EstimateUnit,
+) -> Vec<String> {
+    let mut tags = task_tags
+        .unwrap_or_default()
+        .iter()
+        .filter(|tag| !tag.starts_with(ESTIMATE_TAG_PREFIX))
+        .cloned()
+        .collect::<Vec<_>>();
+    tags.push(format!(
+        "{}{}",
+        ESTIMATE_TAG_PREFIX,
+        unit.format(estimate.estimate)
+    ));
+    tags
+}
+
+/// Effort of the tasks with one priority level
+#[derive(Debug, Clone, Serialize)]
+pub struct PriorityEffort {
+    pub priority: String,
+    pub tasks: usize,
+    pub total: f64,
+}
+
+/// Total effort overall and per priority level
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateSummary {
+    pub unit: EstimateUnit,
+    pub tasks: usize,
+    pub total: f64,
+    /// High, medium, low and no priority, skipping levels without tasks
+    pub by_priority: Vec<PriorityEffort>,
+}
+
+impl EstimateSummary {
+    pub fn new(estimates: &[TaskEstimate], unit: EstimateUnit) -> Self {
+        let by_priority = ["high", "medium", "low", "none"]
+            .into_iter()
+            .filter_map(|level| {
+                let matching = estimates
+                    .iter()
+                    .filter(|estimate| priority_level(estimate.priority.as_deref()) == level)
+                    .map(|estimate| estimate.estimate)
+                    .collect::<Vec<_>>();
+                (!matching.is_empty()).then(|| PriorityEffort {
+                    priority: level.to_string(),
+                    tasks: matching.len(),
+                    total: matching.iter().sum(),
+                })
+            })
+            .collect();
+
+        Self {
+            unit,
+            tasks: estimates.len(),
+            total: estimates.iter().map(|estimate| estimate.estimate).sum(),
+            by_priority,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Task {}", id),
+            "status": "pending",
+            "created_at": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_estimates_keeps_ids_that_match_no_task() {
+        let tasks = [task("task-1"), task("task-2")];
+        let response = r#"{"estimates": [
+            {"id": "task-2", "estimate": 4, "rationale": "medium"},
+            {"id": "task-9", "estimate": 2, "rationale": "made up"},
+            {"id": "task-1", "estimate": 0, "rationale": "not a number of points"}
+        ]}"#;
+
+        let answer = parse_estimates(response, &tasks, EstimateUnit::Points, "estimate").unwrap();
+        assert_eq!(answer.estimates.len(), 1);
+        assert_eq!(answer.estimates[0].id, "task-2");
+        // Rounded up to the next value on the scale
+        assert_eq!(answer.estimates[0].estimate, 5.0);
+        assert_eq!(answer.unknown_ids, ["task-9"]);
+    }
+}
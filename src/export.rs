@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use rust_xlsxwriter::{Format, Workbook};
+use std::path::Path;
+use tracing::info;
+
+use crate::dates::{self, TaskDate};
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::obsidian;
+use crate::output::csv_row;
+
+/// File formats supported by `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    /// A directory with one Markdown note per task, e.g. in an Obsidian vault
+    Obsidian,
+}
+
+impl ExportFormat {
+    /// Infer the format from the output file extension, defaulting to CSV
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("xlsx") => Self::Xlsx,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// Task fields that can be selected with `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum ExportColumn {
+    Id,
+    Title,
+    Description,
+    Status,
+    Priority,
+    Assignee,
+    DueDate,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+    Tags,
+}
+
+impl ExportColumn {
+    pub const ALL: [ExportColumn; 11] = [
+        Self::Id,
+        Self::Title,
+        Self::Description,
+        Self::Status,
+        Self::Priority,
+        Self::Assignee,
+        Self::DueDate,
+        Self::CreatedAt,
+        Self::UpdatedAt,
+        Self::CompletedAt,
+        Self::Tags,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Title => "title",
+            Self::Description => "description",
+            Self::Status => "status",
+            Self::Priority => "priority",
+            Self::Assignee => "assignee",
+            Self::DueDate => "due_date",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::CompletedAt => "completed_at",
+            Self::Tags => "tags",
+        }
+    }
+
+    fn cell(self, task: &Task) -> Cell {
+        let date = |value: Option<&str>| value.map_or(Cell::Text(String::new()), parse_date);
+        let text = |value: Option<&String>| Cell::Text(value.cloned().unwrap_or_default());
+
+        match self {
+            Self::Id => Cell::Text(task.id.clone()),
+            Self::Title => Cell::Text(task.title.clone()),
+            Self::Description => text(task.description.as_ref()),
+            Self::Status => Cell::Text(task.status.clone()),
+            Self::Priority => text(task.priority.as_ref()),
+            Self::Assignee => text(task.assignee.as_ref()),
+            Self::DueDate => date(task.due_date.as_deref()),
+            Self::CreatedAt => date(Some(&task.created_at)),
+            Self::UpdatedAt => date(task.updated_at.as_deref()),
+            Self::CompletedAt => date(task.completed_at.as_deref()),
+            Self::Tags => Cell::Text(
+                task.tags
+                    .as_ref()
+                    .map(|tags| tags.join(";"))
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// A spreadsheet cell; dates are kept typed so XLSX files get real date cells
+enum Cell {
+    Text(String),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl Cell {
+    /// Spreadsheet-friendly text: `YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS` (display time zone)
+    fn to_text(&self) -> String {
+        match self {
+            Cell::Text(text) => text.clone(),
+            Cell::Date(date) => date.format("%Y-%m-%d").to_string(),
+            Cell::DateTime(date_time) => date_time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Normalize the date formats servers commonly return; instants are converted to the
+/// display time zone and unknown formats stay text
+fn parse_date(value: &str) -> Cell {
+    match TaskDate::parse(value) {
+        Some(TaskDate::Day(date)) => Cell::Date(date),
+        Some(TaskDate::Instant(instant)) => Cell::DateTime(dates::local_date_time(instant)),
+        None => Cell::Text(value.to_string()),
+    }
+}
+
+/// Write tasks to `path` as CSV or XLSX with the selected columns, or as notes
+/// into the directory `path` (all fields, columns are ignored)
+pub fn export_tasks(
+    tasks: &[Task],
+    columns: &[ExportColumn],
+    format: ExportFormat,
+    path: &Path,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            create_parent_dir(path)?;
+            write_csv(tasks, columns, path)?;
+        }
+        ExportFormat::Xlsx => {
+            create_parent_dir(path)?;
+            write_xlsx(tasks, columns, path)?;
+        }
+        ExportFormat::Obsidian => {
+            obsidian::export_vault(tasks, path)?;
+        }
+    }
+
+    info!(
+        "Exported {} tasks as {:?} to {}",
+        tasks.len(),
+        format,
+        path.display()
+    );
+    Ok(())
+}
+
+fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).with_context(|| {
+            McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+        })?;
+    }
+    Ok(())
+}
+
+fn write_csv(tasks: &[Task], columns: &[ExportColumn], path: &Path) -> Result<()> {
+    let header = columns
+        .iter()
+        .map(|column| column.header().to_string())
+        .collect::<Vec<_>>();
+    let mut output = csv_row(&header);
+
+    for task in tasks {
+        let fields = columns
+            .iter()
+            .map(|column| column.cell(task).to_text())
+            .collect::<Vec<_>>();
+        output.push_str(&csv_row(&fields));
+    }
+
+    std::fs::write(path, output)
+        .with_context(|| McpTasksError::Report(format!("Failed to write {}", path.display())))
+}
+
+fn write_xlsx(tasks: &[Task], columns: &[ExportColumn], path: &Path) -> Result<()> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Tasks")?;
+
+    let header_format = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let date_time_format = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+
+    for (col, column) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, column.header(), &header_format)?;
+    }
+
+    for (idx, task) in tasks.iter().enumerate() {
+        let row = idx as u32 + 1;
+        for (col, column) in columns.iter().enumerate() {
+            let col = col as u16;
+            match column.cell(task) {
+                Cell::Text(text) => worksheet.write_string(row, col, text)?,
+                Cell::Date(date) => {
+                    worksheet.write_datetime_with_format(row, col, date, &date_format)?
+                }
+                Cell::DateTime(date_time) => {
+                    worksheet.write_datetime_with_format(row, col, date_time, &date_time_format)?
+                }
+            };
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofit();
+
+    workbook
+        .save(path)
+        .with_context(|| McpTasksError::Report(format!("Failed to write {}", path.display())))
+}
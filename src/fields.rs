@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Task fields read from server responses. Servers may use other keys for them;
+/// those are mapped through built-in aliases and the `[fields]` config section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskField {
+    Id,
+    Title,
+    Description,
+    Status,
+    Priority,
+    Assignee,
+    DueDate,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+    Tags,
+}
+
+/// Keys from the config file, consulted before the built-in aliases
+static CUSTOM_KEYS: RwLock<BTreeMap<TaskField, String>> = RwLock::new(BTreeMap::new());
+
+impl TaskField {
+    pub const ALL: [TaskField; 11] = [
+        Self::Id,
+        Self::Title,
+        Self::Description,
+        Self::Status,
+        Self::Priority,
+        Self::Assignee,
+        Self::DueDate,
+        Self::CreatedAt,
+        Self::UpdatedAt,
+        Self::CompletedAt,
+        Self::Tags,
+    ];
+
+    /// Key in the CLI's own task shape
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Title => "title",
+            Self::Description => "description",
+            Self::Status => "status",
+            Self::Priority => "priority",
+            Self::Assignee => "assignee",
+            Self::DueDate => "due_date",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::CompletedAt => "completed_at",
+            Self::Tags => "tags",
+        }
+    }
+
+    /// Keys other servers commonly use for this field, tried in order
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::Id => &["task_id", "uuid", "_id"],
+            Self::Title => &["name", "summary", "subject"],
+            Self::Description => &["body", "details", "content"],
+            Self::Status => &["state"],
+            Self::Priority => &["importance"],
+            Self::Assignee => &["assigned_to", "owner"],
+            Self::DueDate => &["deadline", "due", "due_at", "dueDate"],
+            Self::CreatedAt => &["created", "createdAt", "created_on"],
+            Self::UpdatedAt => &["updated", "updatedAt", "modified_at"],
+            Self::CompletedAt => &["completedAt", "done_at", "finished_at"],
+            Self::Tags => &["labels"],
+        }
+    }
+
+    /// Fields kept as text; numbers sent for them (ids, Unix timestamps) are converted
+    fn is_text(self) -> bool {
+        !matches!(self, Self::Tags)
+    }
+
+    /// Install the `[fields]` mapping used by [`normalize_task`]; done by `Config::load`
+    pub fn set_custom_keys(keys: &BTreeMap<TaskField, String>) {
+        if let Ok(mut custom) = CUSTOM_KEYS.write() {
+            *custom = keys.clone();
+        }
+    }
+}
+
+/// Rewrite a task object as sent by a server into the CLI's task shape.
+///
+/// A field's configured key wins, then its own key, then the built-in aliases.
+/// Keys that do not map to a field are left in place and end up in `Task::extra`.
+pub fn normalize_task(task: &mut Map<String, Value>) {
+    let custom = CUSTOM_KEYS
+        .read()
+        .map(|keys| keys.clone())
+        .unwrap_or_default();
+
+    for field in TaskField::ALL {
+        let key = field.as_str();
+        let source = match custom.get(&field) {
+            Some(custom_key) if task.contains_key(custom_key) => Some(custom_key.as_str()),
+            _ if task.contains_key(key) => None,
+            _ => field
+                .aliases()
+                .iter()
+                .copied()
+                .find(|alias| task.contains_key(*alias)),
+        };
+        if let Some(source) = source
+            && source != key
+            && let Some(value) = task.remove(source)
+        {
+            task.insert(key.to_string(), value);
+        }
+
+        if field.is_text()
+            && let Some(value) = task.get_mut(key)
+            && let Value::Number(number) = value
+        {
+            *value = Value::String(number.to_string());
+        }
+    }
+}
+
+/// Normalize every task in a `list_tasks` response's `tasks` array
+pub fn normalize_task_list(response: &mut Value) {
+    if let Some(tasks) = response.get_mut("tasks").and_then(Value::as_array_mut) {
+        for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+            normalize_task(task);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::NaiveDate;
+
+use crate::dates::TaskDate;
+use crate::mcp_client::{Task, TaskQuery};
+use crate::status::TaskStatus;
+
+/// Which tasks a command works on: the fields `list_tasks` can filter by on
+/// the server, plus a due date cut-off and a limit applied locally. Built
+/// from the filter flags of `list`, `status` and `analyze`.
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub query: TaskQuery,
+    /// Only tasks due before this day; tasks without a due date are left out
+    pub due_before: Option<NaiveDate>,
+    /// Most tasks kept, in the order they were listed
+    pub limit: Option<usize>,
+}
+
+impl TaskFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(mut self, status: Option<TaskStatus>) -> Self {
+        self.query.status = status.map(|status| status.to_string());
+        self
+    }
+
+    pub fn priority(mut self, priority: Option<String>) -> Self {
+        self.query.priority = priority;
+        self
+    }
+
+    /// Tag, with or without a leading `#`
+    pub fn tag(mut self, tag: Option<String>) -> Self {
+        self.query.tag = tag.map(|tag| tag.trim_start_matches('#').to_string());
+        self
+    }
+
+    pub fn assignee(mut self, assignee: Option<String>) -> Self {
+        self.query.assignee = assignee;
+        self
+    }
+
+    pub fn due_before(mut self, due_before: Option<NaiveDate>) -> Self {
+        self.due_before = due_before;
+        self
+    }
+
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The status filtered by, if it is a known one
+    pub fn status_filter(&self) -> Option<TaskStatus> {
+        self.query.status.as_deref().and_then(TaskStatus::parse)
+    }
+
+    /// Whether `task` passes every filter except the limit
+    pub fn matches(&self, task: &Task) -> bool {
+        self.query.matches(task)
+            && self.due_before.is_none_or(|due_before| {
+                TaskDate::parse_opt(task.due_date.as_deref())
+                    .is_some_and(|due| due.date() < due_before)
+            })
+    }
+
+    /// The tasks passing the filters, at most `limit` of them
+    pub fn apply(&self, tasks: Vec<Task>) -> Vec<Task> {
+        tasks
+            .into_iter()
+            .filter(|task| self.matches(task))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Whether the server can do all of the filtering, so listings can be
+    /// paged there
+    pub fn is_server_side(&self) -> bool {
+        self.due_before.is_none() && self.limit.is_none()
+    }
+}
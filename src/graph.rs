@@ -0,0 +1,381 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::structured;
+
+/// Marker the dependency prompt contains; the mock provider recognizes it
+pub const GRAPH_PROMPT_MARKER: &str = "\"depends_on\"";
+
+pub const GRAPH_SYSTEM_PROMPT: &str = "You are a project planning expert. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Text formats `graph` can emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT, e.g. for `dot -Tsvg`
+    Dot,
+    /// Mermaid flowchart, rendered by GitHub, GitLab and many Markdown editors
+    Mermaid,
+}
+
+impl GraphFormat {
+    /// Infer the format from the output file extension, defaulting to DOT
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("mmd") | Some("mermaid") => Self::Mermaid,
+            _ => Self::Dot,
+        }
+    }
+}
+
+/// Prerequisites the AI found for one task
+#[derive(Debug, Clone, Deserialize)]
+pub struct InferredDependency {
+    pub id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DependencyList {
+    tasks: Vec<InferredDependency>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+}
+
+/// `from` has to be done before `to`
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Tasks and the dependencies between them
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Groups of tasks that depend on each other in a circle, so the graph is not a DAG
+    pub cycles: Vec<Vec<String>>,
+}
+
+/// Prompt asking which of the formatted tasks depend on which
+pub fn dependency_prompt(task_summary: &str, task_count: usize) -> String {
+    format!(
+        r#"Find the dependencies between these {task_count} tasks: which tasks have to be finished before another one can start or be completed.
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "tasks": [
+    {{
+      "id": "task ID as given above",
+      "depends_on": ["IDs of tasks that must be done first"],
+      "reason": "one sentence explaining the dependencies"
+    }}
+  ]
+}}
+
+Only list real dependencies; leave "depends_on" empty for independent tasks."#
+    )
+}
+
+/// Parse the model's answer, tolerating Markdown code fences or text around the JSON
+pub fn parse_dependencies(response: &str) -> Result<Vec<InferredDependency>> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain JSON dependencies".to_string())
+    })?;
+
+    let list: DependencyList = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI task dependencies".to_string(),
+    ))?;
+    Ok(list.tasks)
+}
+
+impl DependencyGraph {
+    /// Build the graph over `tasks`; dependencies on unknown tasks, self-references
+    /// and duplicates are dropped
+    pub fn build(tasks: &[Task], dependencies: &[InferredDependency]) -> Self {
+        let nodes = tasks
+            .iter()
+            .map(|task| GraphNode {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                status: task.status.clone(),
+            })
+            .collect::<Vec<_>>();
+        let known = |id: &str| nodes.iter().any(|node| node.id == id);
+
+        let mut seen = BTreeSet::new();
+        let mut edges = Vec::new();
+        for dependency in dependencies.iter().filter(|d| known(&d.id)) {
+            for from in &dependency.depends_on {
+                if from != &dependency.id
+                    && known(from)
+                    && seen.insert((from.clone(), dependency.id.clone()))
+                {
+                    edges.push(GraphEdge {
+                        from: from.clone(),
+                        to: dependency.id.clone(),
+                        reason: dependency.reason.clone(),
+                    });
+                }
+            }
+        }
+
+        let cycles = find_cycles(&nodes, &edges);
+        Self {
+            nodes,
+            edges,
+            cycles,
+        }
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Write the graph in `format` to `path`
+    pub fn save(&self, format: GraphFormat, path: &Path) -> Result<()> {
+        std::fs::write(path, self.render(format)).with_context(|| {
+            McpTasksError::Report(format!("Failed to write graph to {}", path.display()))
+        })
+    }
+
+    /// Graphviz digraph; edges that are part of a cycle are drawn red
+    pub fn to_dot(&self) -> String {
+        let mut output = String::from("digraph tasks {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            output.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n({})\"];\n",
+                dot_escape(&node.id),
+                dot_escape(&node.title),
+                dot_escape(&node.status)
+            ));
+        }
+        for edge in &self.edges {
+            let style = if self.in_cycle(edge) {
+                " [color=red]"
+            } else {
+                ""
+            };
+            output.push_str(&format!(
+                "    \"{}\" -> \"{}\"{};\n",
+                dot_escape(&edge.from),
+                dot_escape(&edge.to),
+                style
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Mermaid flowchart; node IDs are numbered since task IDs may contain any character
+    pub fn to_mermaid(&self) -> String {
+        let key = |id: &str| {
+            self.nodes
+                .iter()
+                .position(|node| node.id == id)
+                .map(|idx| format!("t{}", idx + 1))
+                .unwrap_or_default()
+        };
+
+        let mut output = String::from("flowchart LR\n");
+        for node in &self.nodes {
+            output.push_str(&format!(
+                "    {}[\"{}: {}\"]\n",
+                key(&node.id),
+                mermaid_escape(&node.id),
+                mermaid_escape(&node.title)
+            ));
+        }
+        for edge in &self.edges {
+            output.push_str(&format!("    {} --> {}\n", key(&edge.from), key(&edge.to)));
+        }
+        output
+    }
+
+    fn in_cycle(&self, edge: &GraphEdge) -> bool {
+        self.cycles
+            .iter()
+            .any(|cycle| cycle.contains(&edge.from) && cycle.contains(&edge.to))
+    }
+}
+
+/// Strongly connected components with more than one task (Tarjan's algorithm)
+fn find_cycles(nodes: &[GraphNode], edges: &[GraphEdge]) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        successors: HashMap<&'a str, Vec<&'a str>>,
+        index: HashMap<&'a str, usize>,
+        low_link: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        cycles: Vec<Vec<String>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, node: &'a str) {
+            let index = self.index.len();
+            self.index.insert(node, index);
+            self.low_link.insert(node, index);
+            self.stack.push(node);
+
+            for next in self.successors.get(node).cloned().unwrap_or_default() {
+                if !self.index.contains_key(next) {
+                    self.visit(next);
+                    let low = self.low_link[node].min(self.low_link[next]);
+                    self.low_link.insert(node, low);
+                } else if self.stack.contains(&next) {
+                    let low = self.low_link[node].min(self.index[next]);
+                    self.low_link.insert(node, low);
+                }
+            }
+
+            if self.low_link[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    component.push(member.to_string());
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.reverse();
+                    self.cycles.push(component);
+                }
+            }
+        }
+    }
+
+    let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        successors
+            .entry(edge.from.as_str())
+            .or_default()
+            .push(edge.to.as_str());
+    }
+
+    let mut tarjan = Tarjan {
+        successors,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        cycles: Vec::new(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(node.id.as_str()) {
+            tarjan.visit(&node.id);
+        }
+    }
+    tarjan.cycles
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Task {}", id),
+            "status": "pending",
+            "created_at": "",
+        }))
+        .unwrap()
+    }
+
+    fn depends(id: &str, on: &[&str]) -> InferredDependency {
+        InferredDependency {
+            id: id.to_string(),
+            depends_on: on.iter().map(|from| from.to_string()).collect(),
+            reason: None,
+        }
+    }
+
+    fn graph(ids: &[&str], dependencies: &[InferredDependency]) -> DependencyGraph {
+        let tasks = ids.iter().map(|id| task(id)).collect::<Vec<_>>();
+        DependencyGraph::build(&tasks, dependencies)
+    }
+
+    fn edges(graph: &DependencyGraph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn acyclic_chain_has_no_cycles() {
+        let graph = graph(
+            &["a", "b", "c"],
+            &[depends("b", &["a"]), depends("c", &["b", "a"])],
+        );
+        assert_eq!(edges(&graph), [("a", "b"), ("b", "c"), ("a", "c")]);
+        assert!(graph.cycles.is_empty());
+        assert!(!graph.to_dot().contains("color=red"));
+    }
+
+    #[test]
+    fn self_loops_are_dropped() {
+        let graph = graph(&["a", "b"], &[depends("a", &["a", "b"])]);
+        assert_eq!(edges(&graph), [("b", "a")]);
+        assert!(graph.cycles.is_empty());
+    }
+
+    #[test]
+    fn multi_node_cycle_is_reported() {
+        let graph = graph(
+            &["a", "b", "c", "d"],
+            &[
+                depends("b", &["a"]),
+                depends("c", &["b"]),
+                depends("a", &["c"]),
+                depends("d", &["c"]),
+            ],
+        );
+        assert_eq!(graph.cycles, [["a", "b", "c"]]);
+
+        // Only the edges inside the cycle are highlighted
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"c\" -> \"a\" [color=red];"));
+        assert!(dot.contains("\"c\" -> \"d\";"));
+    }
+
+    #[test]
+    fn dependencies_on_unknown_tasks_are_dropped() {
+        let graph = graph(
+            &["a", "b"],
+            &[
+                depends("b", &["a", "missing"]),
+                depends("missing", &["b"]),
+                depends("a", &["missing"]),
+            ],
+        );
+        assert_eq!(edges(&graph), [("a", "b")]);
+        assert!(graph.cycles.is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::dates;
+use crate::error::McpTasksError;
+use crate::report::AnalysisReport;
+use crate::usage::TokenUsage;
+
+const HISTORY_FILE: &str = "analyses.jsonl";
+const SPEND_FILE: &str = "spend.jsonl";
+
+/// A stored analysis report with its sequential history id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: usize,
+    pub report: AnalysisReport,
+}
+
+#[derive(Serialize)]
+struct HistoryEntryRef<'a> {
+    id: usize,
+    report: &'a AnalysisReport,
+}
+
+/// Compact view of an entry for listings
+#[derive(Debug, Serialize)]
+pub struct HistorySummary {
+    pub id: usize,
+    pub timestamp: String,
+    pub model: String,
+    pub task_count: usize,
+    pub tools_enabled: bool,
+    pub tool_calls_count: Option<usize>,
+    pub analysis_duration_seconds: Option<f64>,
+}
+
+impl From<&HistoryEntry> for HistorySummary {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: entry.id,
+            timestamp: entry.report.timestamp.to_rfc3339(),
+            model: entry.report.model.clone(),
+            task_count: entry.report.task_count,
+            tools_enabled: entry.report.metadata.tools_enabled,
+            tool_calls_count: entry.report.metadata.tool_calls_count,
+            analysis_duration_seconds: entry.report.metadata.analysis_duration_seconds,
+        }
+    }
+}
+
+/// Estimated cost of one AI request, for budgets
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpendEntry {
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// Append-only JSON-lines store of analysis reports, with a ledger of what
+/// every AI request cost next to it
+pub struct HistoryStore {
+    path: PathBuf,
+    spend_path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(HISTORY_FILE),
+            spend_path: data_dir.join(SPEND_FILE),
+        }
+    }
+
+    /// Persist a report and return its history id
+    pub fn append(&self, report: &AnalysisReport) -> Result<usize> {
+        let id = self.entries()?.last().map_or(1, |entry| entry.id + 1);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        let line = serde_json::to_string(&HistoryEntryRef { id, report })
+            .context("Failed to serialize analysis report for history")?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| {
+                McpTasksError::Report(format!(
+                    "Failed to open history file {}",
+                    self.path.display()
+                ))
+            })?;
+        writeln!(file, "{}", line).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write history file {}",
+                self.path.display()
+            ))
+        })?;
+
+        info!("Stored analysis #{} in {}", id, self.path.display());
+        Ok(id)
+    }
+
+    /// All stored entries, oldest first; unreadable lines are skipped
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&self.path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to read history file {}",
+                self.path.display()
+            ))
+        })?;
+
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(
+                |(line_no, line)| match serde_json::from_str::<HistoryEntry>(line) {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable history entry on line {}: {}",
+                            line_no + 1,
+                            e
+                        );
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Add the cost of an AI request to the spend ledger
+    pub fn record_spend(&self, entry: &SpendEntry) -> Result<()> {
+        if let Some(parent) = self.spend_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        let line = serde_json::to_string(entry).context("Failed to serialize spend entry")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.spend_path)
+            .with_context(|| {
+                McpTasksError::Report(format!(
+                    "Failed to open spend ledger {}",
+                    self.spend_path.display()
+                ))
+            })?;
+        writeln!(file, "{}", line).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write spend ledger {}",
+                self.spend_path.display()
+            ))
+        })
+    }
+
+    /// Total estimated cost of the AI requests made at or after `since`;
+    /// unreadable lines are skipped
+    pub fn spend_since(&self, since: DateTime<Utc>) -> Result<f64> {
+        if !self.spend_path.exists() {
+            return Ok(0.0);
+        }
+
+        let content = std::fs::read_to_string(&self.spend_path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to read spend ledger {}",
+                self.spend_path.display()
+            ))
+        })?;
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str::<SpendEntry>(line).ok())
+            .filter(|entry| entry.timestamp >= since)
+            .map(|entry| entry.cost_usd)
+            .sum())
+    }
+
+    pub fn get(&self, id: usize) -> Result<HistoryEntry> {
+        self.entries()?
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .with_context(|| {
+                McpTasksError::Config(format!("No analysis with id {} in history", id))
+            })
+    }
+}
+
+/// Describe how two stored analyses differ: task set changes and analysis text changes
+pub fn diff_entries(old: &HistoryEntry, new: &HistoryEntry) -> String {
+    let mut output = format!(
+        "\n📊 Analysis #{} ({}) → #{} ({})\n{}\n",
+        old.id,
+        dates::format_instant(old.report.timestamp, "%Y-%m-%d %H:%M"),
+        new.id,
+        dates::format_instant(new.report.timestamp, "%Y-%m-%d %H:%M"),
+        "=".repeat(60)
+    );
+
+    output.push_str(&format!(
+        "Tasks analyzed: {} → {}\n",
+        old.report.task_count, new.report.task_count
+    ));
+
+    let old_tasks = old
+        .report
+        .tasks
+        .iter()
+        .map(|task| (task.id.as_str(), task))
+        .collect::<HashMap<_, _>>();
+    let new_tasks = new
+        .report
+        .tasks
+        .iter()
+        .map(|task| (task.id.as_str(), task))
+        .collect::<HashMap<_, _>>();
+
+    let added = new
+        .report
+        .tasks
+        .iter()
+        .filter(|task| !old_tasks.contains_key(task.id.as_str()))
+        .collect::<Vec<_>>();
+    let removed = old
+        .report
+        .tasks
+        .iter()
+        .filter(|task| !new_tasks.contains_key(task.id.as_str()))
+        .collect::<Vec<_>>();
+
+    if !added.is_empty() {
+        output.push_str("\n➕ New tasks:\n");
+        for task in added {
+            output.push_str(&format!("  - {} ({})\n", task.title, task.id));
+        }
+    }
+
+    if !removed.is_empty() {
+        output.push_str("\n➖ No longer analyzed:\n");
+        for task in removed {
+            output.push_str(&format!("  - {} ({})\n", task.title, task.id));
+        }
+    }
+
+    let mut changed = Vec::new();
+    for task in &new.report.tasks {
+        if let Some(previous) = old_tasks.get(task.id.as_str()) {
+            if previous.status != task.status {
+                changed.push(format!(
+                    "  - {}: status {} → {}",
+                    task.title, previous.status, task.status
+                ));
+            }
+            if previous.priority != task.priority {
+                changed.push(format!(
+                    "  - {}: priority {} → {}",
+                    task.title,
+                    previous.priority.as_deref().unwrap_or("N/A"),
+                    task.priority.as_deref().unwrap_or("N/A")
+                ));
+            }
+        }
+    }
+    if !changed.is_empty() {
+        output.push_str("\n🔄 Changed tasks:\n");
+        output.push_str(&changed.join("\n"));
+        output.push('\n');
+    }
+
+    output.push_str("\n📝 Recommendation changes:\n");
+    let diff = line_diff(&old.report.analysis, &new.report.analysis);
+    if diff.is_empty() {
+        output.push_str("  (analysis text is identical)\n");
+    } else {
+        for (marker, line) in diff {
+            output.push_str(&format!("{} {}\n", marker, line));
+        }
+    }
+
+    output
+}
+
+/// Minimal LCS-based line diff returning only added (`+`) and removed (`-`) lines
+fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<(char, &'a str)> {
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] = length of the LCS of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(('-', old_lines[i]));
+            i += 1;
+        } else {
+            result.push(('+', new_lines[j]));
+            j += 1;
+        }
+    }
+    result.extend(old_lines[i..].iter().map(|line| ('-', *line)));
+    result.extend(new_lines[j..].iter().map(|line| ('+', *line)));
+
+    result
+}
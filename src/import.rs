@@ -0,0 +1,89 @@
+use serde::Serialize;
+use tracing::info;
+
+use crate::bulk::BulkResult;
+use crate::mcp_client::{McpClient, NewTask};
+use crate::progress;
+
+/// A task read from an import source
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedTask {
+    #[serde(flatten)]
+    pub task: NewTask,
+    /// Already done at the source; completed right after it is created
+    pub done: bool,
+}
+
+impl ImportedTask {
+    pub fn pending(task: NewTask) -> Self {
+        Self { task, done: false }
+    }
+}
+
+/// Create the imported tasks on the MCP server, in order, completing the ones
+/// that were already done at the source. A failure does not stop the rest;
+/// each task gets a result, so the caller can tell which ones were written.
+pub async fn create_tasks(mcp_client: &McpClient, imported: &[ImportedTask]) -> Vec<BulkResult> {
+    let mut results = Vec::with_capacity(imported.len());
+
+    for (idx, item) in imported.iter().enumerate() {
+        progress::set_phase(format!(
+            "task {} of {}: {}",
+            idx + 1,
+            imported.len(),
+            item.task.title
+        ));
+        results.push(create_task(mcp_client, item).await);
+    }
+
+    let created = results
+        .iter()
+        .filter(|result| result.task.is_some())
+        .count();
+    info!("Imported {} of {} tasks", created, imported.len());
+    results
+}
+
+async fn create_task(mcp_client: &McpClient, item: &ImportedTask) -> BulkResult {
+    let task = match mcp_client.create_task(&item.task).await {
+        Ok(task) => task,
+        Err(e) => {
+            return BulkResult {
+                id: String::new(),
+                title: item.task.title.clone(),
+                ok: false,
+                error: Some(format!("{:#}", e)),
+                task: None,
+            };
+        }
+    };
+
+    let (task, error) = if item.done {
+        match mcp_client.complete_task(&task.id).await {
+            Ok(completed) => (completed, None),
+            // The task exists now, so it is reported along with the error
+            Err(e) => {
+                let error = format!("created, but could not be completed: {:#}", e);
+                (task, Some(error))
+            }
+        }
+    } else {
+        (task, None)
+    };
+
+    BulkResult {
+        id: task.task_ref().to_string(),
+        title: task.title.clone(),
+        ok: error.is_none(),
+        error,
+        task: Some(task),
+    }
+}
+
+/// Tag for a project, heading or label name: lowercase, words joined by dashes
+pub fn tag_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+
+use crate::error::McpTasksError;
+
+/// Service name keychain entries are stored under
+pub const SERVICE: &str = "mcp-tasks";
+
+/// Account of the DeepSeek API key entry
+pub const DEEPSEEK_ACCOUNT: &str = "deepseek-api-key";
+
+/// The keychain secrets are stored in on this system
+pub fn backend() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS Keychain"
+    } else if cfg!(target_os = "windows") {
+        "Windows Credential Manager"
+    } else {
+        "Secret Service"
+    }
+}
+
+fn entry(account: &str) -> Result<::keyring::Entry> {
+    ::keyring::Entry::new(SERVICE, account).map_err(|e| failure(e, "open"))
+}
+
+/// Look up the secret stored for `account`
+pub fn get(account: &str) -> Result<Option<String>> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok((!secret.is_empty()).then_some(secret)),
+        Err(::keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(failure(e, "read")),
+    }
+}
+
+/// Store `secret` for `account`, replacing an existing entry
+pub fn set(account: &str, secret: &str) -> Result<()> {
+    if secret.is_empty() {
+        anyhow::bail!(McpTasksError::Config(
+            "The key must not be empty".to_string()
+        ));
+    }
+
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| failure(e, "store"))
+}
+
+/// Remove the entry for `account`; false when there was none
+pub fn delete(account: &str) -> Result<bool> {
+    match entry(account)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(::keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(failure(e, "remove")),
+    }
+}
+
+fn failure(error: ::keyring::Error, action: &str) -> anyhow::Error {
+    anyhow::anyhow!(McpTasksError::Config(format!(
+        "Failed to {} the key in the {}: {}",
+        action,
+        backend(),
+        error
+    )))
+}
+
+/// `sk-…1234`: enough to tell keys apart without showing them
+pub fn mask(secret: &str) -> String {
+    let chars = secret.chars().collect::<Vec<_>>();
+    if chars.len() <= 8 {
+        return "…".to_string();
+    }
+    format!(
+        "{}…{}",
+        chars[..3].iter().collect::<String>(),
+        chars[chars.len() - 4..].iter().collect::<String>()
+    )
+}
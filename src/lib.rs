@@ -0,0 +1,115 @@
+//! MCP task management and AI task analysis as a library.
+//!
+//! The `mcp-tasks` CLI is a thin layer over this crate; other programs can
+//! embed the same pipeline: fetch tasks from an MCP server, analyze them with
+//! the configured LLM provider and render the resulting report.
+//!
+//! ```no_run
+//! use mcp_tasks::{Config, DeepSeekClient, McpClientPool};
+//!
+//! # async fn run() -> mcp_tasks::Result<()> {
+//! let mut config = Config::load(None)?;
+//! config.stream = false;
+//! config.validate()?;
+//!
+//! let pool = McpClientPool::new(&config);
+//! let tasks = pool.default_client().await?.get_unfinished_tasks().await?;
+//!
+//! let client = DeepSeekClient::new(&config)?;
+//! let report = client.analyze_tasks_with_tools_report(tasks, &pool).await?;
+//! println!("{}", mcp_tasks::report::format_report_as_markdown(&report));
+//!
+//! pool.shutdown().await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Fallible functions return [`Result`], whose [`Error`] carries a chain of
+//! context messages describing what failed. Where the failure originated the
+//! chain holds a [`McpTasksError`]; [`McpTasksError::classify`] finds it so
+//! callers can branch on the kind of failure.
+
+pub mod agenda;
+pub mod archive;
+pub mod audit;
+pub mod board;
+pub mod breakdown;
+pub mod brief;
+pub mod budget;
+pub mod bulk;
+pub mod capabilities;
+pub mod chat;
+pub mod chunked;
+pub mod compare;
+pub mod config;
+pub mod daemon;
+pub mod dates;
+pub mod dedupe;
+pub mod deepseek_client;
+pub mod digest;
+pub mod embeddings;
+pub mod error;
+pub mod estimate;
+pub mod export;
+pub mod fields;
+pub mod filter;
+pub mod graph;
+pub mod history;
+pub mod import;
+pub mod keyring;
+pub mod llm;
+pub mod logger;
+pub mod mcp_client;
+pub mod metrics;
+pub mod mock_llm;
+pub mod notify;
+pub mod obsidian;
+pub mod ollama;
+pub mod output;
+pub mod pdf;
+pub mod picker;
+pub mod plan;
+pub mod progress;
+pub mod prompt_dump;
+pub mod prompts;
+pub mod rate_limit;
+pub mod recording;
+pub mod redact;
+pub mod report;
+pub mod reprioritize;
+pub mod session;
+pub mod sprint;
+pub mod status;
+pub mod structured;
+pub mod table_formatter;
+pub mod task_file;
+pub mod task_input;
+pub mod task_ref;
+pub mod template;
+pub mod themes;
+pub mod timetrack;
+pub mod todoist;
+pub mod tooling;
+pub mod trend;
+pub mod usage;
+pub mod velocity;
+pub mod watch;
+pub mod websocket;
+
+pub use anyhow::{Error, Result};
+
+pub use breakdown::TaskBreakdown;
+pub use capabilities::{Capability, ServerCapabilities};
+pub use config::Config;
+pub use deepseek_client::DeepSeekClient;
+pub use error::McpTasksError;
+pub use history::HistoryStore;
+pub use llm::{LlmProvider, ProviderKind};
+pub use mcp_client::{McpClient, McpClientPool, NewTask, Task, TaskUpdate};
+pub use output::DisplayFormat;
+pub use report::{AnalysisReport, OutputFormat};
+pub use status::TaskStatus;
+pub use table_formatter::TaskTableFormatter;
+pub use task_ref::TaskRef;
+pub use tooling::MutationPolicy;
+pub use usage::TokenUsage;
@@ -0,0 +1,140 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::budget::{Budget, BudgetedProvider};
+use crate::config::Config;
+use crate::error::McpTasksError;
+use crate::keyring;
+use crate::mock_llm::MockProvider;
+use crate::ollama::OllamaClient;
+use crate::prompt_dump::DumpingProvider;
+use crate::rate_limit::{RateLimitedProvider, RateLimiter};
+use crate::recording::{self, RecordingProvider, ReplayProvider};
+use crate::redact;
+use crate::tooling::{ChatRequest, ChatResponse, OpenAiCompatibleClient, StreamToken};
+
+pub const DEEPSEEK_BASE_URL: &str = "https://api.deepseek.com";
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Chat API used for AI commands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderKind {
+    /// DeepSeek API (default)
+    #[default]
+    Deepseek,
+    /// Any OpenAI-compatible chat completions endpoint (OpenAI, vLLM, LM Studio, ...)
+    OpenaiCompatible,
+    /// Local Ollama server
+    Ollama,
+    /// Deterministic offline answers for tests and demos; needs no API key
+    Mock,
+}
+
+/// Callback receiving content and reasoning fragments of a streamed response
+pub type TokenSink<'a> = &'a mut (dyn FnMut(StreamToken<'_>) + Send);
+
+/// A chat completion API with tool calling. Requests and responses use the
+/// OpenAI-style types from `tooling`; providers with other wire formats
+/// translate to and from them.
+pub trait LlmProvider: Send + Sync {
+    /// Provider name for log and error messages
+    fn name(&self) -> &'static str;
+
+    /// Send a request and wait for the complete response
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>>;
+
+    /// Send a request with streaming enabled, passing fragments to `on_token` as
+    /// they arrive; the fragments are assembled into a regular response
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>>;
+}
+
+/// Build the provider selected in the configuration
+pub fn create_provider(config: &Config) -> Result<Box<dyn LlmProvider>> {
+    let mut provider: Box<dyn LlmProvider> = if recording::is_replaying() {
+        info!("Replaying recorded LLM responses");
+        Box::new(ReplayProvider)
+    } else {
+        configured_provider(config)?
+    };
+    if config.requests_per_minute.is_some() || config.tokens_per_minute.is_some() {
+        provider = Box::new(RateLimitedProvider::new(
+            provider,
+            RateLimiter::shared(config.requests_per_minute, config.tokens_per_minute),
+        ));
+    }
+    // Replayed responses cost nothing
+    if !recording::is_replaying() {
+        provider = Box::new(BudgetedProvider::new(provider, Budget::shared(config)?));
+    }
+    if recording::is_recording() {
+        provider = Box::new(RecordingProvider::new(provider));
+    }
+    if let Some(dir) = &config.dump_prompts {
+        provider = Box::new(DumpingProvider::new(provider, dir)?);
+    }
+
+    info!(
+        "Using {} provider with model {}",
+        provider.name(),
+        config.deepseek_model
+    );
+    Ok(provider)
+}
+
+fn configured_provider(config: &Config) -> Result<Box<dyn LlmProvider>> {
+    let base_url = config.llm_base_url.as_deref();
+
+    Ok(match config.provider {
+        ProviderKind::Deepseek => Box::new(OpenAiCompatibleClient::new(
+            "DeepSeek",
+            base_url.unwrap_or(DEEPSEEK_BASE_URL),
+            Some(deepseek_api_key(config)?),
+        )),
+        ProviderKind::OpenaiCompatible => {
+            let base_url = base_url.ok_or_else(|| {
+                anyhow::anyhow!(McpTasksError::Config("The openai-compatible provider needs a base URL (LLM_BASE_URL or [llm] base_url in config)".to_string()))
+            })?;
+            Box::new(OpenAiCompatibleClient::new(
+                "OpenAI-compatible",
+                base_url,
+                config.llm_api_key.clone(),
+            ))
+        }
+        ProviderKind::Ollama => Box::new(OllamaClient::new(base_url.unwrap_or(OLLAMA_BASE_URL))),
+        ProviderKind::Mock => Box::new(MockProvider),
+    })
+}
+
+/// The configured DeepSeek key, else the one stored with `auth set-key`
+pub fn deepseek_api_key(config: &Config) -> Result<String> {
+    match &config.deepseek_api_key {
+        Some(api_key) => Ok(api_key.clone()),
+        None => keyring_api_key().ok_or_else(|| {
+            anyhow::anyhow!(McpTasksError::Config("DeepSeek API key is not set (DEEPSEEK_API_KEY, [deepseek] api_key in config, or `mcp-tasks auth set-key`)".to_string()))
+        }),
+    }
+}
+
+/// The DeepSeek key stored with `auth set-key`; an unavailable keychain counts as no key
+fn keyring_api_key() -> Option<String> {
+    match keyring::get(keyring::DEEPSEEK_ACCOUNT) {
+        Ok(api_key) => {
+            if let Some(api_key) = &api_key {
+                redact::add_secret(api_key);
+            }
+            api_key
+        }
+        Err(e) => {
+            debug!("No DeepSeek API key from the keychain: {:#}", e);
+            None
+        }
+    }
+}
@@ -1,57 +1,196 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tracing::{Level, info};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{
-    EnvFilter, Layer,
-    fmt::{self, format::FmtSpan},
+    EnvFilter, Layer, Registry,
+    fmt::{self, MakeWriter, format::FmtSpan},
     layer::SubscriberExt,
     util::SubscriberInitExt,
 };
 
-pub fn init_logger() -> Result<()> {
-    // Create a filter layer to control logging levels
-    let filter = EnvFilter::try_from_default_env()
+use crate::error::McpTasksError;
+use crate::output;
+use crate::progress;
+use crate::redact::RedactingWriter;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Line format of log files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines, as on the terminal but without colors
+    #[default]
+    Text,
+    /// One JSON object per line, for log ingestion
+    Json,
+}
+
+/// A log file rotated daily: `<dir>/<prefix>.YYYY-MM-DD.<suffix>`
+#[derive(Debug, Clone)]
+pub struct LogFile {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub suffix: String,
+    pub format: LogFormat,
+}
+
+impl LogFile {
+    /// `mcp-tasks.YYYY-MM-DD.log` files in `dir`
+    pub fn in_dir(dir: &Path, format: LogFormat) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            prefix: "mcp-tasks".to_string(),
+            suffix: "log".to_string(),
+            format,
+        }
+    }
+
+    /// Files named after `path`: `logs/app.log` rotates into `logs/app.YYYY-MM-DD.log`
+    pub fn from_path(path: &Path, format: LogFormat) -> Self {
+        let dir = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self {
+            dir: dir.to_path_buf(),
+            prefix: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "mcp-tasks".to_string()),
+            suffix: path
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            format,
+        }
+    }
+
+    fn layer(&self, filter: EnvFilter) -> Result<BoxedLayer> {
+        let mut builder = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(&self.prefix);
+        if !self.suffix.is_empty() {
+            builder = builder.filename_suffix(&self.suffix);
+        }
+        let appender = builder.build(&self.dir).with_context(|| {
+            McpTasksError::Config(format!("Failed to open log file in {}", self.dir.display()))
+        })?;
+
+        let layer = fmt::layer()
+            .with_target(true)
+            .with_thread_ids(true)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(Redacted(appender));
+        Ok(match self.format {
+            LogFormat::Text => layer.with_filter(filter).boxed(),
+            LogFormat::Json => layer.json().with_filter(filter).boxed(),
+        })
+    }
+}
+
+fn default_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new("info"))
-        .expect("Failed to create env filter");
+        .expect("Failed to create env filter")
+}
+
+/// Like `default_filter`, but only warnings and errors with `--quiet`
+fn stderr_filter() -> EnvFilter {
+    if output::style().quiet {
+        EnvFilter::try_from_default_env()
+            .or_else(|_| EnvFilter::try_new("warn"))
+            .expect("Failed to create env filter")
+    } else {
+        default_filter()
+    }
+}
+
+/// Stderr that hides the progress spinner while a log line is written
+struct SpinnerAwareStderr;
+
+impl std::io::Write for SpinnerAwareStderr {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        progress::suspend(|| std::io::stderr().write(buf))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        progress::suspend(|| std::io::stderr().write_all(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
 
-    // Create a formatting layer
-    let formatting_layer = fmt::layer()
+/// Makes writers that mask secrets before a line reaches `M`'s writer
+struct Redacted<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacted<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+fn stderr_layer(filter: EnvFilter) -> BoxedLayer {
+    fmt::layer()
         .with_target(true)
         .with_thread_ids(true)
         .with_thread_names(true)
         .with_span_events(FmtSpan::CLOSE)
-        .with_ansi(true)
-        .with_filter(filter);
+        .with_ansi(output::style().color)
+        .with_writer(|| RedactingWriter(SpinnerAwareStderr))
+        .with_filter(filter)
+        .boxed()
+}
 
-    // Initialize the subscriber
+fn install(layers: Vec<BoxedLayer>) -> Result<()> {
     tracing_subscriber::registry()
-        .with(formatting_layer)
+        .with(layers)
         .try_init()
-        .map_err(|e| anyhow::anyhow!("Failed to initialize logger: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to initialize logger: {}", e))
+}
+
+/// Log to stderr, and also to `log_file` when given, at the `RUST_LOG` level
+/// (default: info, or warn on stderr with `--quiet`)
+pub fn init_logger(log_file: Option<&LogFile>) -> Result<()> {
+    let mut layers = vec![stderr_layer(stderr_filter())];
+    if let Some(log_file) = log_file {
+        layers.push(log_file.layer(default_filter())?);
+    }
+    install(layers)?;
 
     info!("Logger initialized successfully");
     Ok(())
 }
 
-pub fn setup_logger_with_level(level: Level) -> Result<()> {
-    let filter = EnvFilter::new(format!("mcp_tasks={}", level));
+pub fn setup_logger_with_level(level: Level, log_file: Option<&LogFile>) -> Result<()> {
+    let filter = || EnvFilter::new(format!("mcp_tasks={}", level));
 
-    let formatting_layer = fmt::layer()
-        .with_target(true)
-        .with_thread_ids(true)
-        .with_thread_names(true)
-        .with_span_events(FmtSpan::CLOSE)
-        .with_ansi(true)
-        .with_filter(filter);
-
-    tracing_subscriber::registry()
-        .with(formatting_layer)
-        .try_init()
-        .map_err(|e| anyhow::anyhow!("Failed to initialize logger: {}", e))?;
+    let mut layers = vec![stderr_layer(filter())];
+    if let Some(log_file) = log_file {
+        layers.push(log_file.layer(filter())?);
+    }
+    install(layers)?;
 
     info!("Logger initialized with level: {}", level);
     Ok(())
 }
 
+/// Log only to `log_file`, for commands that own the terminal
+pub fn init_file_logger(log_file: &LogFile) -> Result<()> {
+    install(vec![log_file.layer(default_filter())?])?;
+
+    info!("Logger initialized successfully");
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! log_error {
     ($($arg:tt)*) => {
@@ -1,18 +1,68 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand};
+use rmcp::model::ResourceContents;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 use tracing::{error, info};
 
-mod config;
-mod deepseek_client;
-mod logger;
-mod mcp_client;
-mod table_formatter;
-mod tooling;
+use mcp_tasks::{
+    McpTasksError, agenda, archive, audit, board, breakdown, brief, budget, bulk, capabilities,
+    chat, config, daemon, dates, deepseek_client, digest, embeddings, enotice, esay, estimate,
+    export, filter, graph, history, import, keyring, llm, logger, mcp_client, mock_llm, notice,
+    notify, obsidian, output, picker, plan, progress, prompts, recording, redact, report, say,
+    session, sprint, status, structured, table_formatter, task_file, task_input, task_ref,
+    template, themes, timetrack, todoist, tooling, trend, usage, velocity, watch,
+};
 
+use agenda::{Agenda, AgendaScope};
+use archive::{SnapshotDiff, TaskSnapshot};
+use audit::AuditLog;
+use brief::Brief;
+use budget::Budget;
+use bulk::{Assignments, BulkAction};
+use capabilities::{Capability, ServerCheck};
 use config::Config;
+use daemon::DaemonOptions;
+use dates::TaskDate;
 use deepseek_client::DeepSeekClient;
-use mcp_client::McpClient;
-use table_formatter::TaskTableFormatter;
+use digest::{Digest, DigestPeriod};
+use embeddings::EmbeddingCache;
+use estimate::{ApplyTarget, EstimateSummary, EstimateUnit};
+use export::{ExportColumn, ExportFormat};
+use filter::TaskFilter;
+use graph::{DependencyGraph, GraphFormat};
+use history::{HistoryStore, HistorySummary};
+use llm::ProviderKind;
+use logger::{LogFile, LogFormat};
+use mcp_client::{DEFAULT_PAGE_SIZE, McpClientPool, NewTask, TaskPage, TaskQuery, TaskUpdate};
+use notify::DesktopNotifier;
+use output::{
+    DisplayFormat, OutputStyle, ResourceInfo, StatsBreakdown, TagSummary, TaskStats, ToolInfo,
+};
+use picker::PickAction;
+use plan::{Capacity, PlanPeriod, PlanWindow};
+use progress::Spinner;
+use prompts::Persona;
+use report::OutputFormat;
+use session::{AnalysisSession, SessionRecorder, SessionStore};
+use sprint::{Sprint, SprintCapacity, SprintReview, SprintStore};
+use status::TaskStatus;
+use structured::StructuredAnalysis;
+use table_formatter::{
+    TableOptions, TableWidth, TaskColumn, TaskGroupBy, TaskSortKey, TaskTableFormatter,
+};
+use task_ref::TaskRef;
+use template::{TemplateStore, TemplateTask};
+use themes::ThemeReport;
+use timetrack::{RunningTimer, TimeLog, Timesheet};
+use todoist::TodoistClient;
+use tooling::MutationPolicy;
+use trend::{SnapshotStore, StatsSnapshot, Trend};
+use velocity::VelocityReport;
 
 #[derive(Parser)]
 #[command(name = "mcp-tasks")]
@@ -25,362 +75,4427 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// MCP server alias to use (as configured in MCP_SERVERS or the config file)
+    #[arg(short, long, global = true)]
+    server: Option<String>,
+
+    /// Path to a TOML config file (replaces ~/.config/mcp-tasks/config.toml and ./mcp-tasks.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Config file profile to use (a [profile.<name>] section; defaults to MCP_TASKS_PROFILE)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Output format for list, status, stats, tools and task commands
+    #[arg(long, global = true, value_enum)]
+    format: Option<DisplayFormat>,
+
+    /// Shorthand for --format json
+    #[arg(long, global = true, conflicts_with = "format")]
+    json: bool,
+
+    /// Let the AI create, update or complete tasks without asking
+    #[arg(long, global = true)]
+    allow_writes: bool,
+
+    /// Never let the AI change data on the MCP server
+    #[arg(long, global = true, conflicts_with = "allow_writes")]
+    read_only: bool,
+
+    /// Chat API for AI commands
+    #[arg(long, global = true, value_enum)]
+    provider: Option<ProviderKind>,
+
+    /// Model for AI commands (deepseek-chat or deepseek-reasoner with the DeepSeek provider)
+    #[arg(long, global = true)]
+    model: Option<String>,
+
+    /// Sampling temperature for AI commands (0.0-2.0)
+    #[arg(long, global = true)]
+    temperature: Option<f32>,
+
+    /// Maximum number of tokens the model may generate per response
+    #[arg(long, global = true)]
+    max_tokens: Option<u32>,
+
+    /// Model turns with tool calls before the AI has to answer without tools (default: 5)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    max_tool_iterations: Option<u32>,
+
+    /// Most USD this run may spend on AI requests; responses are shortened, or the run stops, before going over
+    #[arg(long, global = true, value_name = "USD")]
+    max_cost: Option<f64>,
+
+    /// Audience analyses are written for, changing their tone and focus
+    #[arg(long, global = true, value_enum)]
+    persona: Option<Persona>,
+
+    /// Print the reasoning of deepseek-reasoner (to stderr) while it thinks
+    #[arg(long, global = true)]
+    show_reasoning: bool,
+
+    /// Use deterministic offline answers instead of calling a model (same as MCP_TASKS_MOCK=1)
+    #[arg(long, global = true)]
+    mock_llm: bool,
+
+    /// Write every chat request and response to numbered JSON files in this directory
+    #[arg(long, global = true, value_name = "DIR")]
+    dump_prompts: Option<PathBuf>,
+
+    /// Save all LLM requests and responses and MCP calls of this run to a session file
+    #[arg(long, global = true, value_name = "PATH")]
+    record: Option<PathBuf>,
+
+    /// Answer LLM and MCP requests from a session file made with --record, offline
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "record")]
+    replay: Option<PathBuf>,
+
+    /// Also write logs to this file, rotated daily (app.log becomes app.YYYY-MM-DD.log)
+    #[arg(long, global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Format of log files
+    #[arg(long, global = true, value_enum)]
+    log_format: Option<LogFormat>,
+
+    /// Print only results: no progress banners, hints or info logs
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Plain output without colors or emoji (also set by a non-empty NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Show task table cells in full instead of fitting the table to the terminal
+    #[arg(long, global = true)]
+    wide: bool,
+
+    /// Fit task tables to the terminal by cutting long cells off instead of wrapping them
+    #[arg(long, global = true, conflicts_with = "wide")]
+    truncate: bool,
+
+    /// Show due and created dates as "in 2 days" or "3 weeks ago" in tables and reports
+    #[arg(long, global = true)]
+    relative_dates: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Browse and compare past analysis reports
+    History {
+        #[command(subcommand)]
+        command: HistoryCommands,
+    },
     /// List all tasks from MCP server
-    List,
+    List {
+        /// Sort tasks by this field (default: server order)
+        #[arg(long, value_enum)]
+        sort: Option<TaskSortKey>,
+
+        /// Reverse the sort order
+        #[arg(long, requires = "sort")]
+        desc: bool,
+
+        /// Comma-separated table columns in display order (default: all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Vec<TaskColumn>,
+
+        /// Show one sub-table per group instead of a single table (implies --all)
+        #[arg(long, value_enum, conflicts_with_all = ["page", "page_size"])]
+        group_by: Option<TaskGroupBy>,
+
+        /// List the tasks of every configured MCP server, with IDs shown as alias:id
+        #[arg(long)]
+        all_servers: bool,
+
+        /// Only tasks with this status: pending, in_progress, completed, cancelled or an alias
+        #[arg(long)]
+        status: Option<String>,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        #[command(flatten)]
+        pages: PageArgs,
+    },
     /// Get list of available tools from MCP server
     Tools,
-    /// Show task statistics
-    Stats,
+    /// Connect to every configured MCP server and show which task operations each supports
+    Doctor,
+    /// List or read resources (documents, notes) exposed by the MCP server
+    Resources {
+        #[command(subcommand)]
+        command: ResourceCommands,
+    },
+    /// Show task statistics (each run is also recorded for --trend)
+    Stats {
+        /// Show how completion rate, backlog and overdue tasks changed over this period (e.g. 30d, 2w)
+        #[arg(long, value_parser = watch::parse_interval)]
+        trend: Option<Duration>,
+
+        /// Show only this breakdown; as CSV its rows are `<breakdown>,count`
+        #[arg(long, value_enum, conflicts_with = "trend")]
+        breakdown: Option<StatsBreakdown>,
+
+        /// Tab-separated rows instead of comma-separated ones (implies --format csv)
+        #[arg(long)]
+        tsv: bool,
+    },
     /// List tasks with a specific status
     Status {
-        /// The status to filter by (e.g., "todo", "in_progress", "completed", "pending")
+        /// The status to filter by: pending, in_progress, completed, cancelled or an alias (e.g. "todo", "done")
         status: String,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        #[command(flatten)]
+        pages: PageArgs,
+    },
+    /// Task counts per tag (open, done, overdue), most used tags first
+    Tags {
+        /// Show the tasks carrying this tag instead (case-insensitive)
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Tasks completed per week, cycle time and a burndown chart
+    Velocity {
+        /// Number of weeks to look back, including the current one
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=52))]
+        weeks: u32,
+
+        /// Have the AI comment on the trend
+        #[arg(long)]
+        ai_summary: bool,
+    },
+    /// One report with stats trend, completed, new and overdue tasks, and an AI summary
+    Digest {
+        /// Period the digest covers, ending now
+        #[arg(long, value_enum, default_value_t = DigestPeriod::Week)]
+        period: DigestPeriod,
+
+        /// Save the digest to this file (format auto-detected from extension: .md, .txt, .json, .pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Report format, overriding the extension
+        #[arg(long, value_enum)]
+        report_format: Option<OutputFormat>,
+
+        /// Leave out the AI summary
+        #[arg(long)]
+        no_ai: bool,
+    },
+    /// Short AI-written morning briefing of overdue, due-today and in-progress tasks
+    Brief {
+        /// Leave out the AI summary and only list the tasks
+        #[arg(long)]
+        no_ai: bool,
+
+        /// Also send the briefing as a desktop notification
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Unfinished tasks grouped by due window: overdue, today, tomorrow, this week, later
+    Agenda {
+        /// Only overdue tasks and tasks due today
+        #[arg(long, conflicts_with_all = ["week", "overdue"])]
+        today: bool,
+
+        /// Only tasks due by the end of this week (Sunday), including overdue ones
+        #[arg(long, conflicts_with = "overdue")]
+        week: bool,
+
+        /// Only overdue tasks
+        #[arg(long)]
+        overdue: bool,
+    },
+    /// Watch the MCP server and print task changes as they happen
+    Watch {
+        /// Polling interval (e.g. 30s, 5m, 1h)
+        #[arg(long, default_value = "30s", value_parser = watch::parse_interval)]
+        interval: Duration,
+
+        /// Also show desktop notifications for changes
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Interactive kanban board with one column per status
+    Board {
+        /// Also reload tasks from the server at this interval (e.g. 30s, 5m)
+        #[arg(long, value_parser = watch::parse_interval)]
+        refresh: Option<Duration>,
+    },
+    /// Group tasks into themes with embeddings and have the AI name them
+    Themes {
+        /// Number of themes (default: about √(tasks/2), at most 8)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=50))]
+        count: Option<u32>,
+
+        /// Include completed and cancelled tasks
+        #[arg(long)]
+        all: bool,
+
+        /// Name themes after their most frequent words instead of asking the AI
+        #[arg(long)]
+        no_ai: bool,
+    },
+    /// Fuzzy-search all tasks and act on the one you pick
+    Pick {
+        /// Action to run on the picked task instead of choosing from a menu
+        #[arg(long, value_enum)]
+        action: Option<PickAction>,
+    },
+    /// Find tasks by meaning rather than exact words, using embeddings
+    Semantic {
+        /// What to look for, e.g. "anything about billing migrations"
+        query: String,
+
+        /// Number of matches to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Only search unfinished tasks
+        #[arg(long)]
+        open: bool,
+    },
+    /// Export tasks to a CSV or Excel file, or as Markdown notes into a directory
+    Export {
+        /// Output file; the format is inferred from the extension (.csv or .xlsx)
+        #[arg(short, long, required_unless_present = "dir")]
+        output: Option<PathBuf>,
+
+        /// Write one Markdown note per task into this directory (e.g. vault/Tasks/),
+        /// rewriting only notes whose task changed
+        #[arg(long, conflicts_with_all = ["output", "columns"])]
+        dir: Option<PathBuf>,
+
+        /// File format, overriding the extension (obsidian needs --dir)
+        #[arg(long, value_enum)]
+        file_format: Option<ExportFormat>,
+
+        /// Only export tasks with this status
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Comma-separated columns to include (default: all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns: Vec<ExportColumn>,
+    },
+    /// Import tasks from another task manager into the MCP server
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Define task templates for recurring work and create tasks from them
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// Save every task to a JSON snapshot, or compare two snapshots
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+    /// Create a new task on the MCP server
+    Add {
+        /// Task title
+        #[arg(long)]
+        title: String,
+
+        /// Task description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Task priority (e.g., "low", "medium", "high")
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Due date (YYYY-MM-DD, RFC3339 or a Unix timestamp)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Person the task is assigned to
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Tag to attach to the task (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Show every field of one task
+    Get {
+        /// ID of the task to show
+        id: String,
+    },
+    /// Mark a task as completed
+    Complete {
+        /// ID of the task to complete
+        id: String,
+    },
+    /// Update fields of an existing task
+    Update {
+        /// ID of the task to update
+        id: String,
+
+        /// New status: pending, in_progress, completed, cancelled or an alias (e.g. "done")
+        #[arg(long)]
+        status: Option<String>,
+
+        /// New priority (e.g., "low", "medium", "high")
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// New due date (YYYY-MM-DD, RFC3339 or a Unix timestamp)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// New title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// New assignee
+        #[arg(long)]
+        assignee: Option<String>,
+    },
+    /// Delete tasks, after a preview and confirmation
+    Delete {
+        /// IDs of the tasks to delete
+        #[arg(required_unless_present = "filter")]
+        ids: Vec<String>,
+
+        /// Also delete every task matching this filter, e.g. status=cancelled,tag=old (keys: status, priority, tag, assignee)
+        #[arg(long, value_parser = bulk::parse_filter)]
+        filter: Option<TaskQuery>,
+
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Apply one change to every task matching a filter, after a preview and confirmation
+    #[command(group(clap::ArgGroup::new("action").required(true).args(["set", "complete", "add_tag", "delete"])))]
+    Bulk {
+        /// Tasks to change, e.g. status=pending,tag=infra (keys: status, priority, tag, assignee)
+        #[arg(long, value_parser = bulk::parse_filter)]
+        filter: TaskQuery,
+
+        /// Fields to set, e.g. priority=high,due=2025-07-01 (status, priority, due, assignee, title or a custom field)
+        #[arg(long, value_parser = bulk::parse_assignments)]
+        set: Option<Assignments>,
+
+        /// Mark the tasks as completed
+        #[arg(long)]
+        complete: bool,
+
+        /// Add this tag to the tasks
+        #[arg(long)]
+        add_tag: Option<String>,
+
+        /// Delete the tasks
+        #[arg(long)]
+        delete: bool,
+
+        /// Tasks changed at the same time
+        #[arg(long, default_value_t = bulk::DEFAULT_CONCURRENCY, value_parser = clap::value_parser!(u16).range(1..=16))]
+        concurrency: u16,
+
+        /// Apply without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Start tracking time on a task, stopping the timer of any other task
+    Start {
+        /// ID of the task to work on
+        id: String,
+    },
+    /// Stop the running timer and record the time spent
+    Stop,
+    /// Show the time tracked per task and per day
+    Timesheet {
+        /// First day to include (YYYY-MM-DD); defaults to six days ago
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only include time tracked on this task
+        #[arg(long)]
+        task: Option<String>,
+    },
+    /// Start an interactive chat with DeepSeek that can use MCP tools
+    Chat {
+        /// Wait for complete responses instead of streaming tokens as they arrive
+        #[arg(long)]
+        no_stream: bool,
     },
     /// Analyze pending tasks using DeepSeek AI
-    Analyze,
+    Analyze {
+        /// Wait for the complete response instead of streaming tokens as they arrive
+        #[arg(long)]
+        no_stream: bool,
+
+        /// Prompt template: built-in or configured template name, or a template file
+        #[arg(long, value_name = "NAME_OR_FILE")]
+        prompt_template: Option<String>,
+
+        /// Return a machine-readable assessment (priority score, complexity, risk, order and
+        /// dependencies per task) in JSON mode instead of free text
+        #[arg(long, conflicts_with = "prompt_template")]
+        structured: bool,
+
+        /// Compare with the most recent stored analysis and describe what changed
+        #[arg(long, conflicts_with = "structured")]
+        compare_last: bool,
+
+        /// Analyze backlogs larger than this many tasks in parts, then merge the findings
+        /// (default: 100, or chunk_size in [deepseek])
+        #[arg(long, value_name = "TASKS", value_parser = clap::value_parser!(u32).range(1..))]
+        chunk_size: Option<u32>,
+
+        /// Analyze tasks with this status instead of pending ones (pending, in_progress,
+        /// completed, cancelled or an alias)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Also give the AI the tasks completed in this period (e.g. 30d, 2w) as context
+        #[arg(long, value_name = "PERIOD", value_parser = watch::parse_interval)]
+        with_history: Option<Duration>,
+
+        /// Analyze the tasks read from stdin (a JSON task array or one task per line)
+        /// instead of the MCP server's
+        #[arg(long, conflicts_with_all = ["from_file", "with_history"])]
+        from_stdin: bool,
+
+        /// Analyze the tasks in this file (a JSON task array or one task per line)
+        /// instead of the MCP server's
+        #[arg(long, value_name = "FILE", conflicts_with = "with_history")]
+        from_file: Option<PathBuf>,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
     /// Analyze pending tasks using DeepSeek AI with MCP tools
     AnalyzeWithTools {
-        /// Optional path to save the analysis report (format auto-detected from extension: .json, .md, .txt)
+        /// Optional path to save the analysis report (format auto-detected from extension: .json, .md, .txt, .pdf)
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Wait for the complete response instead of streaming tokens as they arrive
+        #[arg(long)]
+        no_stream: bool,
+
+        /// Prompt template: built-in or configured template name, or a template file
+        #[arg(long, value_name = "NAME_OR_FILE")]
+        prompt_template: Option<String>,
+
+        /// Print the tool calls the AI made (tool, arguments, result size, latency, status)
+        #[arg(long)]
+        show_tool_calls: bool,
+
+        /// Return a machine-readable assessment (priority score, complexity, risk, order and
+        /// dependencies per task) in JSON mode instead of free text
+        #[arg(long, conflicts_with = "prompt_template")]
+        structured: bool,
+
+        /// Analyze backlogs larger than this many tasks in parts, then merge the findings
+        /// (default: 100, or chunk_size in [deepseek])
+        #[arg(long, value_name = "TASKS", value_parser = clap::value_parser!(u32).range(1..))]
+        chunk_size: Option<u32>,
+
+        /// Analyze tasks with this status instead of pending ones (pending, in_progress,
+        /// completed, cancelled or an alias)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Also give the AI the tasks completed in this period (e.g. 30d, 2w) as context
+        #[arg(long, value_name = "PERIOD", value_parser = watch::parse_interval)]
+        with_history: Option<Duration>,
+
+        /// Continue an interrupted analysis from its saved session (a session ID, or "last"),
+        /// without repeating the tool calls it already made
+        #[arg(long, value_name = "SESSION", conflicts_with_all = [
+            "prompt_template", "structured", "chunk_size", "status", "with_history",
+            "priority", "tag", "assignee", "due_before", "limit",
+        ])]
+        resume: Option<String>,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Deep-dive into a single task: proposed subtasks, effort estimate and blockers
+    AnalyzeTask {
+        /// ID of the task to analyze
+        id: String,
+
+        /// Create the proposed subtasks on the MCP server
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Break a large task into AI-generated subtasks and create them after confirmation
+    Expand {
+        /// ID of the task to expand
+        id: String,
+
+        /// Create the subtasks without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Let the AI find near-duplicate unfinished tasks and optionally close the duplicates
+    Dedupe {
+        /// Complete every duplicate except the task kept in its cluster, after confirmation
+        #[arg(long)]
+        merge: bool,
+
+        /// Merge without asking for confirmation
+        #[arg(short, long, requires = "merge")]
+        yes: bool,
+    },
+    /// Let the AI infer dependencies between unfinished tasks and print them as a graph
+    Graph {
+        /// Graph syntax; inferred from the --output extension (.mmd for Mermaid) when not set
+        #[arg(long, value_enum)]
+        graph_format: Option<GraphFormat>,
+
+        /// Write the graph to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Let the AI suggest a priority for each pending task and show what would change
+    Reprioritize {
+        /// Update the changed priorities on the MCP server
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Let the AI estimate the effort of each pending task and sum up the backlog
+    Estimate {
+        /// Estimate in story points or hours
+        #[arg(long, value_enum, default_value_t = EstimateUnit::Points)]
+        unit: EstimateUnit,
+
+        /// Write the estimates to the tasks on the MCP server
+        #[arg(long)]
+        apply: bool,
+
+        /// Store estimates in a task field or as an `estimate:<value>` tag
+        #[arg(long, value_enum, default_value_t = ApplyTarget::Field)]
+        apply_as: ApplyTarget,
+
+        /// Task field estimates are stored in
+        #[arg(long, default_value = estimate::DEFAULT_FIELD)]
+        field: String,
+    },
+    /// Let the AI schedule unfinished tasks into the time available, flagging what won't fit
+    Plan {
+        /// Working hours available, e.g. 20h (per week), 20h/week or 4h/day
+        #[arg(long, value_parser = plan::parse_capacity)]
+        capacity: Capacity,
+
+        /// Last day of the plan (YYYY-MM-DD); defaults to four weeks from today
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
+        /// Schedule by working day or by week
+        #[arg(long, value_enum, default_value_t = PlanPeriod::Week)]
+        by: PlanPeriod,
+
+        /// Task field estimates are read from
+        #[arg(long, default_value = estimate::DEFAULT_FIELD)]
+        field: String,
+
+        /// Also save the plan as Markdown to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Plan a sprint backlog with the AI and review the sprint at its end
+    Sprint {
+        #[command(subcommand)]
+        command: SprintCommands,
+    },
+    /// Run analyses and stats on a cron schedule, saving reports to a directory
+    Daemon {
+        /// Cron expression (minute hour day-of-month month day-of-week), e.g. "0 9 * * 1-5"
+        #[arg(long)]
+        schedule: Option<String>,
+
+        /// Let the AI query the MCP server with tools during analysis
+        #[arg(long)]
+        tools: bool,
+
+        /// Directory for saved reports (default: <data_dir>/reports)
+        #[arg(long)]
+        report_dir: Option<PathBuf>,
+
+        /// Format of saved analysis reports
+        #[arg(long, value_enum)]
+        report_format: Option<OutputFormat>,
+
+        /// Show a desktop notification after each run
+        #[arg(long)]
+        notify: bool,
+
+        /// Run once immediately instead of waiting for the first scheduled time
+        #[arg(long)]
+        run_now: bool,
+
+        /// Serve Prometheus metrics at http://<ADDR>/metrics, e.g. 127.0.0.1:9898
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<SocketAddr>,
+
+        /// Prompt template: built-in or configured template name, or a template file
+        #[arg(long, value_name = "NAME_OR_FILE")]
+        prompt_template: Option<String>,
+    },
+    /// Summarize tokens used and estimated spend of stored analyses
+    Usage {
+        /// Only include analyses from this date on (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+    },
+    /// Keep the DeepSeek API key in the system keychain
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Store the DeepSeek API key in the keychain (asked on the terminal or read from stdin)
+    SetKey,
+    /// Show where the DeepSeek API key comes from
+    Status,
+    /// Remove the DeepSeek API key from the keychain
+    DeleteKey,
+}
+
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Checkbox items from a Markdown or org-mode (.org) file; headings become tags
+    File {
+        /// Task list file, e.g. TODO.md
+        path: PathBuf,
+
+        /// Create the tasks without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Active tasks from Todoist; projects become tags
+    Todoist {
+        /// Todoist API token (default: TODOIST_API_TOKEN or [todoist] token in config)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Only import tasks from this project (case-insensitive)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Create the tasks without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// Add a task to a template in the local template file, creating the template if needed
+    Add {
+        /// Template name, e.g. monthly-invoicing
+        name: String,
+
+        /// Task title; {date}, {year}, {month}, {month_name} and {week} are filled in when applied
+        #[arg(long)]
+        title: String,
+
+        /// Task description, with the same placeholders as the title
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Task priority (e.g., "low", "medium", "high")
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Tag to attach to the task (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Due date relative to the day the template is applied, e.g. +3d, +2w or +1m
+        #[arg(long, value_parser = template::parse_due)]
+        due: Option<String>,
+    },
+    /// List templates from the config file and the local template file
+    List,
+    /// Create the tasks of a template on the MCP server
+    Apply {
+        /// Template name
+        name: String,
+
+        /// Day to apply the template on, for placeholders and due dates (default: today)
+        #[arg(long)]
+        on: Option<NaiveDate>,
+
+        /// Create the tasks without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Write all tasks to a timestamped JSON file
+    Save {
+        /// File to write (default: snapshots/tasks-<timestamp>.json in the data directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show tasks added, removed and changed between two snapshots
+    Diff {
+        /// Older snapshot: a path, or a file name in the snapshot directory
+        from: PathBuf,
+
+        /// Newer snapshot: a path, or a file name in the snapshot directory
+        to: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ResourceCommands {
+    /// List available resources
+    List,
+    /// Print the contents of a resource
+    Read {
+        /// URI of the resource (see `resources list`)
+        uri: String,
+    },
+}
+
+/// Pagination flags of `list` and `status`
+#[derive(Args)]
+struct PageArgs {
+    /// Page to show, starting at 1
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    page: u32,
+
+    /// Tasks per page
+    #[arg(long, default_value_t = DEFAULT_PAGE_SIZE, value_parser = clap::value_parser!(u32).range(1..))]
+    page_size: u32,
+
+    /// Show all tasks at once instead of one page
+    #[arg(long, conflicts_with_all = ["page", "page_size"])]
+    all: bool,
+}
+
+impl PageArgs {
+    fn query(&self, filter: &TaskQuery) -> TaskQuery {
+        TaskQuery {
+            page: Some(self.page),
+            page_size: Some(self.page_size),
+            ..filter.clone()
+        }
+    }
+}
+
+/// Task filters shared by `list`, `status` and `analyze`
+#[derive(Args)]
+struct FilterArgs {
+    /// Only tasks with this priority (case-insensitive)
+    #[arg(long)]
+    priority: Option<String>,
+
+    /// Only tasks with this tag (case-insensitive)
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Only tasks assigned to this person (case-insensitive)
+    #[arg(long)]
+    assignee: Option<String>,
+
+    /// Only tasks due before this day (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    due_before: Option<NaiveDate>,
+
+    /// At most this many tasks
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    limit: Option<u32>,
+}
+
+impl FilterArgs {
+    /// The filter with `status` parsed (aliases allowed)
+    fn build(self, status: Option<&str>) -> Result<TaskFilter> {
+        Ok(TaskFilter::new()
+            .status(status.map(parse_status).transpose()?)
+            .priority(self.priority)
+            .tag(self.tag)
+            .assignee(self.assignee)
+            .due_before(self.due_before)
+            .limit(self.limit.map(|limit| limit as usize)))
+    }
+}
+
+#[derive(Subcommand)]
+enum SprintCommands {
+    /// Let the AI propose a sprint backlog and tag the selected tasks after confirmation
+    Plan {
+        /// Sprint length, e.g. 2w or 10d
+        #[arg(long, default_value = "2w", value_parser = sprint::parse_length)]
+        length: u64,
+
+        /// Effort the sprint can take, e.g. 30pts or 40h
+        #[arg(long, value_parser = sprint::parse_capacity)]
+        capacity: SprintCapacity,
+
+        /// Sprint name, also the tag added to the selected tasks (default: sprint-<year>-<week>)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Task field estimates are read from
+        #[arg(long, default_value = estimate::DEFAULT_FIELD)]
+        field: String,
+
+        /// Tag the tasks without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Summarize a sprint: completed, carried-over and added tasks with an AI summary
+    Review {
+        /// Sprint name or tag (default: the sprint planned last)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommands {
+    /// List stored analyses
+    List,
+    /// Show a stored analysis report
+    Show {
+        /// History id of the analysis
+        id: usize,
+    },
+    /// Compare two stored analyses
+    Diff {
+        /// History id of the older analysis
+        a: usize,
+        /// History id of the newer analysis
+        b: usize,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        error!("{:#}", e);
+        esay!("❌ Error: {}", redact::redact(&format!("{:#}", e)));
+
+        let kind = McpTasksError::classify(&e);
+        if let Some(kind) = kind {
+            esay!("\n💡 {}", kind.hint());
+        }
+        std::process::exit(kind.map_or(1, McpTasksError::exit_code));
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    output::set_style(OutputStyle {
+        quiet: cli.quiet,
+        color: !cli.no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+    });
+    table_formatter::set_table_width(if cli.wide {
+        TableWidth::Wide
+    } else if cli.truncate {
+        TableWidth::Truncate
+    } else {
+        TableWidth::Fit
+    });
+
+    // Load configuration
+    let config = match Config::load_profile(cli.config.as_deref(), cli.profile.as_deref()) {
+        Ok(mut config) => {
+            if let Some(server) = cli.server {
+                config.default_server = server;
+            }
+            if cli.json {
+                config.display_format = DisplayFormat::Json;
+            } else if let Some(format) = cli.format {
+                config.display_format = format;
+            }
+            config.relative_dates |= cli.relative_dates;
+            dates::set_relative_dates(config.relative_dates);
+            if cli.read_only {
+                config.tool_writes = Some(MutationPolicy::Deny);
+            } else if cli.allow_writes {
+                config.tool_writes = Some(MutationPolicy::Allow);
+            }
+            if let Some(provider) = cli.provider {
+                config.provider = provider;
+            }
+            if let Some(model) = cli.model {
+                config.deepseek_model = model;
+            }
+            if let Some(temperature) = cli.temperature {
+                config.temperature = temperature;
+            }
+            if let Some(max_tokens) = cli.max_tokens {
+                config.max_tokens = max_tokens;
+            }
+            if let Some(max_tool_iterations) = cli.max_tool_iterations {
+                config.max_tool_iterations = max_tool_iterations;
+            }
+            if let Some(max_cost) = cli.max_cost {
+                config.max_cost = Some(max_cost);
+            }
+            if let Some(persona) = cli.persona {
+                config.analysis_persona = Some(persona);
+            }
+            config.show_reasoning |= cli.show_reasoning;
+            if let Some(dir) = cli.dump_prompts {
+                config.dump_prompts = Some(dir);
+            }
+            if cli.mock_llm {
+                config.provider = ProviderKind::Mock;
+            }
+            if config.provider == ProviderKind::Mock {
+                // Keep mock runs apart from real ones in history and usage stats
+                config.deepseek_model = mock_llm::MOCK_MODEL.to_string();
+            }
+            if let Some(log_format) = cli.log_format {
+                config.log_format = log_format;
+            }
+            config.validate()?;
+            redact::configure(
+                [
+                    &config.deepseek_api_key,
+                    &config.llm_api_key,
+                    &config.embedding_api_key,
+                    &config.todoist_token,
+                ]
+                .into_iter()
+                .flatten()
+                .cloned(),
+                &config.redact_fields,
+            );
+            config
+        }
+        Err(e) => return Err(e.context("Failed to load configuration")),
+    };
+
+    // Initialize logger; the board owns the terminal, so it only logs to stderr with --verbose
+    let log_file = match (&cli.log_file, &config.log_dir) {
+        (Some(path), _) => Some(LogFile::from_path(path, config.log_format)),
+        (None, Some(dir)) => Some(LogFile::in_dir(dir, config.log_format)),
+        (None, None) => None,
+    };
+    if cli.verbose {
+        logger::setup_logger_with_level(tracing::Level::DEBUG, log_file.as_ref())?;
+    } else if !matches!(cli.command, Commands::Board { .. }) {
+        logger::init_logger(log_file.as_ref())?;
+    } else if let Some(log_file) = &log_file {
+        logger::init_file_logger(log_file)?;
+    }
+
+    info!("MCP Tasks application started");
+
+    if let Some(path) = &cli.replay {
+        recording::start_replay(path)?;
+    } else if let Some(path) = &cli.record {
+        recording::start_recording(path);
+    }
+
+    // Servers are spawned lazily, so commands that don't need one never start it
+    let pool = McpClientPool::new(&config);
+
+    let result = tokio::select! {
+        result = run_command(cli.command, config, &pool) => result,
+        _ = tokio::signal::ctrl_c() => {
+            esay!("\n⏹️  Interrupted, shutting down MCP servers...");
+            pool.shutdown().await;
+            std::process::exit(130);
+        }
+    };
+
+    pool.shutdown().await;
+    // Saved even when the command failed, so the failure can be replayed
+    match recording::finish() {
+        Ok(Some((path, llm, mcp))) => enotice!(
+            "🎞️  Recorded {} LLM and {} MCP exchanges to {}",
+            llm,
+            mcp,
+            path.display()
+        ),
+        Ok(None) => {}
+        Err(e) => enotice!("⚠️  {:#}", e),
+    }
+    result
+}
+
+async fn run_command(command: Commands, mut config: Config, pool: &McpClientPool) -> Result<()> {
+    match command {
+        Commands::List {
+            sort,
+            desc,
+            columns,
+            group_by,
+            all_servers,
+            status,
+            filter,
+            pages,
+        } => {
+            let filter = filter.build(status.as_deref())?;
+            let options = TableOptions {
+                sort,
+                descending: desc,
+                columns: if columns.is_empty() {
+                    TaskColumn::ALL.to_vec()
+                } else {
+                    columns
+                },
+            };
+            handle_list_command(config, pool, options, group_by, all_servers, filter, pages)
+                .await?;
+        }
+        Commands::Tools => {
+            handle_tools_list_command(config, pool).await?;
+        }
+        Commands::Doctor => {
+            handle_doctor_command(config, pool).await?;
+        }
+        Commands::Resources { command } => {
+            handle_resources_command(config, pool, command).await?;
+        }
+        Commands::Stats {
+            trend,
+            breakdown,
+            tsv,
+        } => {
+            handle_stats_command(config, pool, trend, breakdown, tsv).await?;
+        }
+        Commands::Status {
+            status,
+            filter,
+            pages,
+        } => {
+            let filter = filter.build(Some(&status))?;
+            handle_status_command(config, pool, filter, pages).await?;
+        }
+        Commands::Tags { tag } => {
+            handle_tags_command(config, pool, tag).await?;
+        }
+        Commands::Velocity { weeks, ai_summary } => {
+            // Streamed tokens would end up in the middle of JSON or CSV output
+            config.stream &= config.display_format == DisplayFormat::Table;
+            handle_velocity_command(config, pool, weeks, ai_summary).await?;
+        }
+        Commands::Agenda {
+            today,
+            week,
+            overdue,
+        } => {
+            let scope = if today {
+                AgendaScope::Today
+            } else if week {
+                AgendaScope::Week
+            } else if overdue {
+                AgendaScope::Overdue
+            } else {
+                AgendaScope::All
+            };
+            handle_agenda_command(config, pool, scope).await?;
+        }
+        Commands::Watch { interval, notify } => {
+            handle_watch_command(config, pool, interval, notify).await?;
+        }
+        Commands::Board { refresh } => {
+            handle_board_command(pool, refresh).await?;
+        }
+        Commands::Pick { action } => {
+            handle_pick_command(config, pool, action).await?;
+        }
+        Commands::Themes { count, all, no_ai } => {
+            config.stream = false;
+            handle_themes_command(config, pool, count.map(|count| count as usize), all, !no_ai)
+                .await?;
+        }
+        Commands::Semantic { query, limit, open } => {
+            handle_semantic_command(config, pool, &query, limit, open).await?;
+        }
+        Commands::Export {
+            output,
+            dir,
+            file_format,
+            status,
+            columns,
+        } => {
+            let (output, format) = match (dir, output) {
+                (Some(dir), _) => {
+                    if file_format.is_some_and(|format| format != ExportFormat::Obsidian) {
+                        anyhow::bail!(McpTasksError::Config(
+                            "--dir writes Markdown notes; CSV and Excel exports need --output"
+                                .to_string()
+                        ));
+                    }
+                    (dir, ExportFormat::Obsidian)
+                }
+                (None, Some(output)) => {
+                    let format = file_format.unwrap_or_else(|| ExportFormat::from_path(&output));
+                    if format == ExportFormat::Obsidian {
+                        anyhow::bail!(McpTasksError::Config(
+                            "The obsidian format writes a directory: pass --dir instead of --output"
+                                .to_string()
+                        ));
+                    }
+                    (output, format)
+                }
+                (None, None) => anyhow::bail!(McpTasksError::Config(
+                    "Pass --output <FILE> or --dir <DIR>".to_string()
+                )),
+            };
+            let columns = if columns.is_empty() {
+                ExportColumn::ALL.to_vec()
+            } else {
+                columns
+            };
+            let status = status.as_deref().map(parse_status).transpose()?;
+            handle_export_command(config, pool, output, format, status, columns).await?;
+        }
+        Commands::Import { source } => {
+            handle_import_command(config, pool, source).await?;
+        }
+        Commands::Template { command } => {
+            handle_template_command(config, pool, command).await?;
+        }
+        Commands::Snapshot { command } => {
+            handle_snapshot_command(config, pool, command).await?;
+        }
+        Commands::Add {
+            title,
+            description,
+            priority,
+            due,
+            assignee,
+            tags,
+        } => {
+            let due_date = due.as_deref().map(normalize_due_date).transpose()?;
+            let new_task = NewTask {
+                title,
+                description,
+                priority,
+                due_date,
+                assignee,
+                tags: if tags.is_empty() { None } else { Some(tags) },
+            };
+            handle_add_command(config, pool, new_task).await?;
+        }
+        Commands::History { command } => {
+            handle_history_command(config, command)?;
+        }
+        Commands::Get { id } => {
+            handle_get_command(config, pool, id).await?;
+        }
+        Commands::Complete { id } => {
+            handle_complete_command(config, pool, id).await?;
+        }
+        Commands::Update {
+            id,
+            status,
+            priority,
+            due,
+            title,
+            assignee,
+        } => {
+            let update = TaskUpdate {
+                title,
+                status: status
+                    .as_deref()
+                    .map(parse_status)
+                    .transpose()?
+                    .map(|status| status.as_str().to_string()),
+                priority,
+                due_date: due.as_deref().map(normalize_due_date).transpose()?,
+                assignee,
+                ..Default::default()
+            };
+            if update.is_empty() {
+                anyhow::bail!(McpTasksError::Config(
+                    "Nothing to update: pass at least one of --status, --priority, --due, --title, --assignee"
+                        .to_string()
+                ));
+            }
+            handle_update_command(config, pool, id, update).await?;
+        }
+        Commands::Delete { ids, filter, force } => {
+            handle_delete_command(config, pool, ids, filter, force).await?;
+        }
+        Commands::Chat { no_stream } => {
+            config.stream &= !no_stream;
+            handle_chat_command(config, pool).await?;
+        }
+        Commands::Analyze {
+            no_stream,
+            prompt_template,
+            structured,
+            compare_last,
+            chunk_size,
+            status,
+            filter,
+            with_history,
+            from_stdin,
+            from_file,
+        } => {
+            let filter = filter.build(status.as_deref())?;
+            let input = task_input::TaskInput::from_args(from_stdin, from_file);
+            // A structured answer is JSON, rendered once it is complete
+            config.stream &= !no_stream && !structured;
+            if let Some(chunk_size) = chunk_size {
+                config.chunk_size = chunk_size as usize;
+            }
+            config.prompt_template = prompt_template.or(config.prompt_template);
+            handle_analyze_command(
+                config,
+                pool,
+                filter,
+                input,
+                structured,
+                compare_last,
+                with_history,
+            )
+            .await?;
+        }
+        Commands::AnalyzeWithTools {
+            output,
+            no_stream,
+            prompt_template,
+            show_tool_calls,
+            structured,
+            chunk_size,
+            status,
+            filter,
+            with_history,
+            resume,
+        } => {
+            let analysis = match resume {
+                Some(id) => ToolAnalysis::Resume(SessionStore::new(&config.data_dir).load(&id)?),
+                None => ToolAnalysis::New {
+                    filter: filter.build(status.as_deref())?,
+                    structured,
+                },
+            };
+            config.stream &= !no_stream && !analysis.structured();
+            if let Some(chunk_size) = chunk_size {
+                config.chunk_size = chunk_size as usize;
+            }
+            config.prompt_template = prompt_template.or(config.prompt_template);
+            handle_analyze_with_tools_command(
+                config,
+                pool,
+                analysis,
+                output,
+                show_tool_calls,
+                with_history,
+            )
+            .await?;
+        }
+        Commands::AnalyzeTask { id, apply } => {
+            // The answer is structured JSON, rendered once it is complete
+            config.stream = false;
+            handle_analyze_task_command(config, pool, id, apply).await?;
+        }
+        Commands::Expand { id, yes } => {
+            config.stream = false;
+            handle_expand_command(config, pool, id, yes).await?;
+        }
+        Commands::Digest {
+            period,
+            output,
+            report_format,
+            no_ai,
+        } => {
+            config.stream = false;
+            let format = report_format
+                .or_else(|| {
+                    output
+                        .as_deref()
+                        .map(|path| OutputFormat::from_path(&path.to_string_lossy()))
+                })
+                .unwrap_or(match config.display_format {
+                    DisplayFormat::Json => OutputFormat::Json,
+                    _ => OutputFormat::Markdown,
+                });
+            handle_digest_command(config, pool, period, output, format, !no_ai).await?;
+        }
+        Commands::Brief { no_ai, notify } => {
+            config.stream = false;
+            handle_brief_command(config, pool, !no_ai, notify).await?;
+        }
+        Commands::Dedupe { merge, yes } => {
+            config.stream = false;
+            handle_dedupe_command(config, pool, merge, yes).await?;
+        }
+        Commands::Graph {
+            graph_format,
+            output,
+        } => {
+            config.stream = false;
+            let format = graph_format
+                .or_else(|| output.as_deref().map(GraphFormat::from_path))
+                .unwrap_or(GraphFormat::Dot);
+            handle_graph_command(config, pool, format, output).await?;
+        }
+        Commands::Reprioritize { apply } => {
+            config.stream = false;
+            handle_reprioritize_command(config, pool, apply).await?;
+        }
+        Commands::Estimate {
+            unit,
+            apply,
+            apply_as,
+            field,
+        } => {
+            config.stream = false;
+            handle_estimate_command(config, pool, unit, apply.then_some(apply_as), &field).await?;
+        }
+        Commands::Plan {
+            capacity,
+            until,
+            by,
+            field,
+            output,
+        } => {
+            config.stream = false;
+            handle_plan_command(config, pool, capacity, until, by, &field, output).await?;
+        }
+        Commands::Bulk {
+            filter,
+            set,
+            complete,
+            add_tag,
+            delete,
+            concurrency,
+            yes,
+        } => {
+            let action = match (set, add_tag) {
+                (Some(assignments), _) => BulkAction::Set(bulk_update(assignments)?),
+                (None, Some(tag)) => BulkAction::AddTag(tag.trim_start_matches('#').to_string()),
+                _ if complete => BulkAction::Complete,
+                _ => {
+                    debug_assert!(delete);
+                    BulkAction::Delete
+                }
+            };
+            handle_bulk_command(config, pool, filter, action, usize::from(concurrency), yes)
+                .await?;
+        }
+        Commands::Start { id } => {
+            handle_start_command(config, pool, id).await?;
+        }
+        Commands::Stop => {
+            handle_stop_command(config)?;
+        }
+        Commands::Timesheet { since, task } => {
+            handle_timesheet_command(config, since, task)?;
+        }
+        Commands::Sprint { command } => {
+            config.stream = false;
+            handle_sprint_command(config, pool, command).await?;
+        }
+        Commands::Daemon {
+            schedule,
+            tools,
+            report_dir,
+            report_format,
+            notify,
+            run_now,
+            metrics_addr,
+            prompt_template,
+        } => {
+            // Scheduled runs have no one watching the tokens arrive
+            config.stream = false;
+            config.prompt_template = prompt_template.or(config.prompt_template);
+            config.daemon_schedule = schedule.or(config.daemon_schedule);
+            let options = DaemonOptions {
+                tools,
+                report_dir: report_dir.unwrap_or_else(|| config.report_dir()),
+                report_format: report_format.unwrap_or(config.report_format),
+                notify,
+                run_now,
+                metrics_addr: metrics_addr.or(config.metrics_addr),
+            };
+            handle_daemon_command(config, pool, options).await?;
+        }
+        Commands::Usage { since } => {
+            handle_usage_command(config, since)?;
+        }
+        Commands::Auth { command } => {
+            handle_auth_command(config, command)?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_analyze_command(
+    config: Config,
+    pool: &McpClientPool,
+    filter: TaskFilter,
+    input: Option<task_input::TaskInput>,
+    structured: bool,
+    compare_last: bool,
+    with_history: Option<Duration>,
+) -> Result<()> {
+    let status = filter.status_filter().unwrap_or(TaskStatus::Pending);
+    // Tasks read from a list are all the open ones unless --status says otherwise
+    let scope = match (&input, &filter.query.status) {
+        (Some(_), None) => "open".to_string(),
+        _ => status.to_string(),
+    };
+    info!("Starting DeepSeek analysis of {} tasks", scope);
+
+    let previous = if compare_last {
+        let previous = HistoryStore::new(&config.data_dir).entries()?.pop();
+        if previous.is_none() {
+            enotice!(
+                "ℹ️  No earlier analysis in history to compare with; running a regular analysis"
+            );
+        }
+        previous
+    } else {
+        None
+    };
+
+    let prompt_template = config
+        .prompt_template
+        .as_deref()
+        .map(|spec| prompts::resolve_template(spec, &config.prompt_templates))
+        .transpose()?;
+
+    let spinner = Spinner::start("fetching tasks");
+
+    let tasks = match &input {
+        // No MCP server is needed for a task list
+        Some(input) => {
+            let tasks = input.read()?;
+            info!("Read {} tasks from {}", tasks.len(), input);
+            filter.apply(
+                tasks
+                    .into_iter()
+                    .filter(|task| filter.query.status.is_some() || task.is_unfinished())
+                    .collect(),
+            )
+        }
+        // Fetch the tasks in scope, pending ones unless --status says otherwise
+        None => filter.apply(
+            pool.default_client()
+                .await?
+                .get_tasks_by_status(status)
+                .await?,
+        ),
+    };
+
+    if tasks.is_empty() {
+        say!("🎉 No {} tasks found to analyze!", scope);
+        return Ok(());
+    }
+
+    info!("Found {} {} tasks for analysis", tasks.len(), scope);
+
+    // Create DeepSeek client
+    let deepseek_client = DeepSeekClient::new(&config)?
+        .with_prompt_template(prompt_template)
+        .with_structured_output(structured)
+        .with_comparison(previous)
+        .with_time_tracked(TimeLog::new(&config.data_dir).hours_by_task()?);
+    let deepseek_client = match with_history {
+        Some(period) => {
+            let mcp_client = pool.default_client().await?;
+            with_recently_completed(deepseek_client, mcp_client, period).await?
+        }
+        None => deepseek_client,
+    };
+
+    // Structured JSON or CSV output keeps stdout machine-readable
+    let quiet = structured && config.display_format != DisplayFormat::Table;
+
+    // Show the tasks before analysis
+    if !quiet {
+        notice!("\n📋 Found {} {} tasks:", tasks.len(), scope);
+        for (idx, task) in tasks.iter().enumerate() {
+            notice!("  {}. {} (Status: {})", idx + 1, task.title, task.status);
+            if let Some(priority) = &task.priority {
+                notice!("     Priority: {}", priority);
+            }
+            if let Some(due_date) = &task.due_date {
+                notice!("     Due: {}", due_date);
+            }
+        }
+    }
+
+    if !quiet {
+        notice!("\n🤖 Analyzing tasks with DeepSeek AI...\n");
+    }
+
+    if config.stream {
+        notice!("📊 DeepSeek Analysis Results:\n");
+    }
+
+    // Analyze the tasks using DeepSeek
+    let result = deepseek_client.analyze_tasks_report(tasks).await;
+    drop(spinner);
+    match result {
+        Ok(report) => {
+            if let Some(analysis) = &report.structured {
+                print_structured_analysis(&config, analysis)?;
+            } else if config.stream {
+                println!();
+            } else {
+                notice!("📊 DeepSeek Analysis Results:\n");
+                println!("{}", report.analysis);
+            }
+            // A streamed answer already showed the changes section as it arrived
+            if let Some(delta) = &report.delta
+                && !config.stream
+            {
+                say!(
+                    "\n🔄 Changes since analysis #{} ({}):\n",
+                    delta.previous_id,
+                    delta.previous_timestamp.format("%Y-%m-%d %H:%M UTC")
+                );
+                println!("{}", delta.changes);
+            }
+
+            record_history(&config, &report);
+            if !quiet {
+                print_token_usage(&report);
+            }
+        }
+        Err(e) => return Err(e.context("Failed to analyze tasks")),
+    }
+
+    Ok(())
+}
+
+/// Give the analysis the tasks completed within `period` as context
+async fn with_recently_completed(
+    deepseek_client: DeepSeekClient,
+    mcp_client: &mcp_client::McpClient,
+    period: Duration,
+) -> Result<DeepSeekClient> {
+    let since = dates::local_date(
+        chrono::Utc::now() - chrono::Duration::from_std(period).unwrap_or(chrono::Duration::MAX),
+    );
+    let completed = mcp_client
+        .get_tasks_by_status(TaskStatus::Completed)
+        .await?
+        .into_iter()
+        .filter(|task| {
+            TaskDate::parse_opt(task.completed_at.as_deref())
+                .is_some_and(|completed_at| completed_at.date() >= since)
+        })
+        .collect::<Vec<_>>();
+    info!(
+        "Including {} tasks completed since {} as context",
+        completed.len(),
+        since
+    );
+
+    Ok(deepseek_client.with_recently_completed(completed, since))
+}
+
+async fn handle_chat_command(config: Config, pool: &McpClientPool) -> Result<()> {
+    info!("Starting interactive chat session");
+
+    pool.default_client().await?;
+
+    let deepseek_client = DeepSeekClient::new(&config)?
+        .with_mutation_policy(config.mutation_policy(MutationPolicy::Confirm));
+
+    chat::run_repl(&deepseek_client, pool).await
+}
+
+/// What `analyze-with-tools` works on
+enum ToolAnalysis {
+    /// Fetch the tasks the filter selects and start a new session
+    New {
+        filter: TaskFilter,
+        structured: bool,
+    },
+    /// Continue a saved session with the tasks it started with
+    Resume(AnalysisSession),
+}
+
+impl ToolAnalysis {
+    fn structured(&self) -> bool {
+        match self {
+            Self::New { structured, .. } => *structured,
+            Self::Resume(session) => session.structured,
+        }
+    }
+}
+
+async fn handle_analyze_with_tools_command(
+    config: Config,
+    pool: &McpClientPool,
+    analysis: ToolAnalysis,
+    output_file: Option<String>,
+    show_tool_calls: bool,
+    with_history: Option<Duration>,
+) -> Result<()> {
+    info!("Starting DeepSeek analysis with MCP tools");
+
+    let prompt_template = config
+        .prompt_template
+        .as_deref()
+        .map(|spec| prompts::resolve_template(spec, &config.prompt_templates))
+        .transpose()?;
+
+    let spinner = Spinner::start("fetching tasks");
+
+    // Create MCP client
+    let mcp_client = pool.default_client().await?;
+
+    // A resumed analysis keeps the tasks it started with
+    let (session, scope) = match analysis {
+        ToolAnalysis::Resume(session) => {
+            notice!(
+                "⏯️  Resuming session {} ({} tool calls made)",
+                session.id,
+                session.tool_calls.len()
+            );
+            (session, "saved".to_string())
+        }
+        ToolAnalysis::New { filter, structured } => {
+            let status = filter.status_filter().unwrap_or(TaskStatus::Pending);
+            // Fetch the tasks in scope, pending ones unless --status says otherwise
+            let tasks = filter.apply(mcp_client.get_tasks_by_status(status).await?);
+
+            if tasks.is_empty() {
+                say!("🎉 No {} tasks found to analyze!", status);
+                return Ok(());
+            }
+
+            info!(
+                "Found {} {} tasks for tool-enabled analysis",
+                tasks.len(),
+                status
+            );
+            (AnalysisSession::new(tasks, structured), status.to_string())
+        }
+    };
+    let session_id = session.id.clone();
+    let structured = session.structured;
+    let tasks = session.tasks.clone();
+
+    // Create DeepSeek client
+    let deepseek_client = DeepSeekClient::new(&config)?
+        .with_prompt_template(prompt_template)
+        .with_mutation_policy(config.mutation_policy(MutationPolicy::Confirm))
+        .with_structured_output(structured)
+        .with_time_tracked(TimeLog::new(&config.data_dir).hours_by_task()?)
+        .with_session(SessionRecorder::new(
+            SessionStore::new(&config.data_dir),
+            session,
+        ));
+    let deepseek_client = match with_history {
+        Some(period) => with_recently_completed(deepseek_client, mcp_client, period).await?,
+        None => deepseek_client,
+    };
+
+    // Structured JSON or CSV output keeps stdout machine-readable
+    let quiet = structured && config.display_format != DisplayFormat::Table;
+
+    // Show the tasks before analysis
+    if !quiet {
+        notice!("\n📋 Found {} {} tasks:", tasks.len(), scope);
+        for (idx, task) in tasks.iter().enumerate() {
+            notice!("  {}. {} (Status: {})", idx + 1, task.title, task.status);
+            if let Some(priority) = &task.priority {
+                notice!("     Priority: {}", priority);
+            }
+            if let Some(due_date) = &task.due_date {
+                notice!("     Due: {}", due_date);
+            }
+        }
+    }
+
+    if !quiet {
+        notice!("\n🚀 Analyzing tasks with DeepSeek AI using MCP tools...");
+        notice!("📡 The AI can now query the MCP server directly for real-time task data!\n");
+    }
+
+    if config.stream {
+        notice!("🔧 DeepSeek Analysis with MCP Tools:\n");
+    }
+
+    // Analyze the tasks using DeepSeek with MCP tools
+    let result = deepseek_client
+        .analyze_tasks_with_tools_report(tasks, pool)
+        .await;
+    drop(spinner);
+    match result {
+        Ok(report) => {
+            if let Some(analysis) = &report.structured {
+                print_structured_analysis(&config, analysis)?;
+            } else if config.stream {
+                println!();
+            } else {
+                notice!("🔧 DeepSeek Analysis with MCP Tools:\n");
+                println!("{}", report.analysis);
+            }
+
+            record_history(&config, &report);
+            record_tool_calls(&config, "analyze-with-tools", &report);
+            if !quiet {
+                print_token_usage(&report);
+            }
+
+            if show_tool_calls {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_tool_calls(&report.tool_calls)
+                );
+                notice!(
+                    "📝 Audit log: {}",
+                    AuditLog::new(&config.data_dir).path().display()
+                );
+            }
+
+            // Save to file if output path is specified
+            if let Some(output_path) = output_file {
+                match report::save_analysis_report(&report, &output_path) {
+                    Ok(_) => {
+                        let format_desc = match output_path.rsplit('.').next() {
+                            Some("json") => "JSON format (structured data)",
+                            Some("md") | Some("markdown") => "Markdown format (email-friendly)",
+                            Some("txt") | Some("text") => {
+                                "Plain text format (universal compatibility)"
+                            }
+                            Some("pdf") => "PDF format (print-friendly)",
+                            _ => "Markdown format (email-friendly, default)",
+                        };
+
+                        notice!("\n💾 Analysis report saved to: {}", output_path);
+                        notice!("📧 Format: {}", format_desc);
+                        info!(
+                            "Report saved with {} tasks and {} tool calls",
+                            report.task_count,
+                            report.metadata.tool_calls_count.unwrap_or(0)
+                        );
+                    }
+                    Err(e) => {
+                        error!("Failed to save analysis report: {}", e);
+                        esay!(
+                            "⚠️  Warning: Failed to save report to {}: {}",
+                            output_path,
+                            e
+                        );
+                        esay!("Analysis completed successfully but report could not be saved.");
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            if SessionStore::new(&config.data_dir)
+                .load(&session_id)
+                .is_ok()
+            {
+                enotice!(
+                    "💾 Progress was saved; continue with `analyze-with-tools --resume {}`",
+                    session_id
+                );
+            }
+            return Err(e.context("Failed to analyze tasks with tools"));
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_list_command(
+    config: Config,
+    pool: &McpClientPool,
+    options: TableOptions,
+    group_by: Option<TaskGroupBy>,
+    all_servers: bool,
+    filter: TaskFilter,
+    pages: PageArgs,
+) -> Result<()> {
+    info!("Fetching tasks from MCP server");
+
+    let all_tasks = || async {
+        if all_servers {
+            pool.get_all_tasks_from_all_servers().await
+        } else {
+            pool.default_client().await?.get_all_tasks().await
+        }
+    };
+
+    if pages.all || group_by.is_some() {
+        let mut all_tasks = all_tasks().await?;
+        options.sort_tasks(&mut all_tasks);
+        let all_tasks = filter.apply(all_tasks);
+
+        // Show the task table; the sort order applies to every format, grouping only to tables
+        let output = output::render_tasks(&all_tasks, config.display_format, || match group_by {
+            Some(group_by) => Ok(TaskTableFormatter::format_grouped_tasks(
+                &all_tasks, group_by, &options,
+            )),
+            None => TaskTableFormatter::format_all_tasks(&all_tasks, &options),
+        })?;
+        say!("{}", output.trim_end());
+        return Ok(());
+    }
+
+    // Sorting, --due-before, --limit and --all-servers need every task, so only
+    // the other listings are paged by the server
+    let page = if all_servers || options.sort.is_some() || !filter.is_server_side() {
+        let mut all_tasks = all_tasks().await?;
+        options.sort_tasks(&mut all_tasks);
+        TaskPage::from_all(filter.apply(all_tasks), pages.page, pages.page_size)
+    } else {
+        pool.default_client()
+            .await?
+            .get_tasks_page(&pages.query(&filter.query))
+            .await?
+    };
+
+    let output = output::render_tasks(&page.tasks, config.display_format, || {
+        Ok(TaskTableFormatter::format_task_page(
+            &page,
+            "📋 All Tasks",
+            &options,
+        ))
+    })?;
+    say!("{}", output.trim_end());
+
+    Ok(())
+}
+
+async fn handle_tools_list_command(config: Config, pool: &McpClientPool) -> Result<()> {
+    info!("Getting list of available tools from MCP server");
+
+    let mcp_client = pool.default_client().await?;
+
+    match mcp_client.get_tools_list().await {
+        Ok(tools) if config.display_format != DisplayFormat::Table => {
+            let tool_infos = tools.iter().map(ToolInfo::from).collect::<Vec<_>>();
+            match config.display_format {
+                DisplayFormat::Json => println!("{}", output::to_json(&tool_infos)?),
+                _ => print!("{}", output::tools_to_csv(&tool_infos)),
+            }
+        }
+        Ok(tools) => {
+            if tools.is_empty() {
+                println!("No tools available on MCP server '{}'", mcp_client.alias);
+            } else {
+                println!("Available tools on MCP server '{}':", mcp_client.alias);
+                println!();
+                for (index, tool) in tools.iter().enumerate() {
+                    println!("{}. {}", index + 1, tool.name);
+                    if let Some(description) = &tool.description {
+                        println!("   Description: {}", description);
+                    } else {
+                        println!("   Description: <No description available>");
+                    }
+                    let schema_value = tool.schema_as_json_value();
+                    if let Some(properties) = schema_value.get("properties")
+                        && let Some(props_obj) = properties.as_object()
+                        && !props_obj.is_empty()
+                    {
+                        println!(
+                            "   Parameters: {}",
+                            props_obj.keys().cloned().collect::<Vec<_>>().join(", ")
+                        );
+                    }
+                    if let Some(annotations) = &tool.annotations {
+                        let hints = [
+                            (annotations.read_only_hint, "read-only"),
+                            (annotations.destructive_hint, "destructive"),
+                            (annotations.idempotent_hint, "idempotent"),
+                        ]
+                        .into_iter()
+                        .filter_map(|(hint, label)| (hint == Some(true)).then_some(label))
+                        .collect::<Vec<_>>();
+                        if !hints.is_empty() {
+                            println!("   Hints: {}", hints.join(", "));
+                        }
+                    }
+                    println!();
+                }
+            }
+        }
+        Err(e) => return Err(e.context("Failed to get tools list")),
+    }
+
+    Ok(())
+}
+
+async fn handle_doctor_command(config: Config, pool: &McpClientPool) -> Result<()> {
+    info!("Checking the configured MCP servers");
+
+    let spinner = Spinner::start("connecting to MCP servers");
+    let mut checks = Vec::new();
+    for alias in pool.aliases() {
+        checks.push(match pool.get(&alias).await {
+            Ok(mcp_client) => ServerCheck::connected(mcp_client.capabilities().await),
+            Err(e) => ServerCheck::failed(&alias, &e),
+        });
+    }
+    drop(spinner);
+
+    match config.display_format {
+        DisplayFormat::Table => say!("{}", TaskTableFormatter::format_capability_matrix(&checks)),
+        DisplayFormat::Json => println!("{}", output::to_json(&checks)?),
+        DisplayFormat::Csv => print!("{}", output::server_checks_to_csv(&checks)),
+    }
+
+    let unreachable = checks.iter().filter(|check| !check.connected).count();
+    if unreachable > 0 {
+        anyhow::bail!(McpTasksError::McpTransport(format!(
+            "{} of {} MCP servers could not be reached",
+            unreachable,
+            checks.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_resources_command(
+    config: Config,
+    pool: &McpClientPool,
+    command: ResourceCommands,
+) -> Result<()> {
+    let mcp_client = pool.default_client().await?;
+
+    match command {
+        ResourceCommands::List => {
+            info!("Getting list of resources from MCP server");
+
+            let resources = mcp_client.list_resources().await?;
+            let resource_infos = resources.iter().map(ResourceInfo::from).collect::<Vec<_>>();
+
+            match config.display_format {
+                DisplayFormat::Table => say!(
+                    "{}",
+                    TaskTableFormatter::format_resources(&resource_infos, &mcp_client.alias)
+                ),
+                DisplayFormat::Json => println!("{}", output::to_json(&resource_infos)?),
+                DisplayFormat::Csv => print!("{}", output::resources_to_csv(&resource_infos)),
+            }
+        }
+        ResourceCommands::Read { uri } => {
+            info!("Reading resource {}", uri);
+
+            let contents = mcp_client.read_resource(&uri).await?;
+
+            if config.display_format == DisplayFormat::Json {
+                println!("{}", output::to_json(&contents)?);
+                return Ok(());
+            }
+
+            for content in contents {
+                match content {
+                    ResourceContents::TextResourceContents { text, .. } => println!("{}", text),
+                    ResourceContents::BlobResourceContents {
+                        uri,
+                        mime_type,
+                        blob,
+                    } => println!(
+                        "<binary resource {} ({}), {} bytes base64; use --json to get the data>",
+                        uri,
+                        mime_type.as_deref().unwrap_or("unknown type"),
+                        blob.len()
+                    ),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_stats_command(
+    config: Config,
+    pool: &McpClientPool,
+    trend: Option<Duration>,
+    breakdown: Option<StatsBreakdown>,
+    tsv: bool,
+) -> Result<()> {
+    info!("Fetching task statistics");
+
+    let (format, separator) = if tsv {
+        (DisplayFormat::Csv, '\t')
+    } else {
+        (config.display_format, ',')
+    };
+
+    let mcp_client = pool.default_client().await?;
+    mcp_client.require(Capability::List).await?;
+
+    let all_tasks = mcp_client.get_all_tasks().await?;
+    let unfinished_tasks = all_tasks
+        .iter()
+        .filter(|task| task.is_unfinished())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let now = Utc::now();
+    let stats = TaskStats::compute(&all_tasks, &unfinished_tasks);
+    let snapshots = SnapshotStore::new(&config.data_dir);
+    if let Err(e) = snapshots.append(&StatsSnapshot::new(&stats, now)) {
+        error!("Failed to store stats snapshot: {}", e);
+        esay!("⚠️  Warning: Failed to store stats snapshot: {}", e);
+    }
+
+    if let Some(period) = trend {
+        let since = now - chrono::Duration::from_std(period).unwrap_or(chrono::Duration::MAX);
+        let trend = Trend::build(&snapshots.since(since)?, period);
+        match format {
+            DisplayFormat::Table => say!("{}", TaskTableFormatter::format_trend(&trend)),
+            DisplayFormat::Json => println!("{}", output::to_json(&trend)?),
+            DisplayFormat::Csv => print!("{}", trend.to_delimited(separator)),
+        }
+        return Ok(());
+    }
+
+    if let Some(breakdown) = breakdown {
+        match format {
+            DisplayFormat::Table => say!(
+                "{}",
+                match breakdown {
+                    StatsBreakdown::Priority =>
+                        TaskTableFormatter::format_priority_breakdown(&unfinished_tasks),
+                    StatsBreakdown::Status =>
+                        TaskTableFormatter::format_status_breakdown(&all_tasks),
+                    StatsBreakdown::Assignee =>
+                        TaskTableFormatter::format_assignee_workload(&stats.assignees),
+                }
+            ),
+            DisplayFormat::Json => {
+                let rows = stats
+                    .breakdown(breakdown)
+                    .into_iter()
+                    .map(|(label, count)| serde_json::json!({ breakdown.as_str(): label, "count": count }))
+                    .collect::<Vec<_>>();
+                println!("{}", output::to_json(&rows)?);
+            }
+            DisplayFormat::Csv => print!("{}", stats.breakdown_to_delimited(breakdown, separator)),
+        }
+        return Ok(());
+    }
+
+    match format {
+        DisplayFormat::Json => {
+            println!("{}", output::to_json(&stats)?);
+            return Ok(());
+        }
+        DisplayFormat::Csv => {
+            print!("{}", stats.to_delimited(separator));
+            return Ok(());
+        }
+        DisplayFormat::Table => {}
+    }
+
+    // Display statistics
+    let summary = TaskTableFormatter::format_summary_statistics(&unfinished_tasks, all_tasks.len());
+    say!("{}", summary);
+
+    say!(
+        "{}",
+        TaskTableFormatter::format_status_breakdown(&all_tasks)
+    );
+
+    let priority_breakdown = TaskTableFormatter::format_priority_breakdown(&unfinished_tasks);
+    say!("{}", priority_breakdown);
+
+    say!(
+        "{}",
+        TaskTableFormatter::format_assignee_workload(&stats.assignees)
+    );
+
+    // Show overdue tasks count
+    let overdue_output = TaskTableFormatter::format_overdue_tasks(&unfinished_tasks)?;
+    if !overdue_output.contains("No overdue tasks found") {
+        say!("{}", overdue_output);
+    } else {
+        say!("\n✅ No overdue tasks found!");
+    }
+
+    Ok(())
+}
+
+async fn handle_status_command(
+    config: Config,
+    pool: &McpClientPool,
+    filter: TaskFilter,
+    pages: PageArgs,
+) -> Result<()> {
+    let status = filter.status_filter().unwrap_or(TaskStatus::Pending);
+    info!("Fetching tasks with status '{}' from MCP server", status);
+
+    // Create MCP client
+    let mcp_client = pool.default_client().await?;
+
+    if pages.all {
+        // Fetch tasks by status
+        let filtered_tasks = filter.apply(mcp_client.get_tasks_by_status(status).await?);
+
+        if filtered_tasks.is_empty() && config.display_format == DisplayFormat::Table {
+            println!("No tasks found with status '{}'", status);
+            return Ok(());
+        }
+
+        // Show the filtered task table
+        let output = output::render_tasks(&filtered_tasks, config.display_format, || {
+            TaskTableFormatter::format_tasks_by_status(&filtered_tasks, status.as_str())
+        })?;
+        say!("{}", output.trim_end());
+        return Ok(());
+    }
+
+    let page = if filter.is_server_side() {
+        mcp_client
+            .get_tasks_page(&pages.query(&filter.query))
+            .await?
+    } else {
+        let tasks = filter.apply(mcp_client.get_tasks_by_status(status).await?);
+        TaskPage::from_all(tasks, pages.page, pages.page_size)
+    };
+
+    if page.total == Some(0) && config.display_format == DisplayFormat::Table {
+        println!("No tasks found with status '{}'", status);
+        return Ok(());
+    }
+
+    let output = output::render_tasks(&page.tasks, config.display_format, || {
+        Ok(TaskTableFormatter::format_task_page(
+            &page,
+            &format!("📋 Tasks with Status '{}'", status),
+            &TableOptions::default(),
+        ))
+    })?;
+    say!("{}", output.trim_end());
+
+    Ok(())
+}
+
+async fn handle_tags_command(
+    config: Config,
+    pool: &McpClientPool,
+    tag: Option<String>,
+) -> Result<()> {
+    info!("Aggregating tasks per tag");
+
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_all_tasks().await?;
+
+    if let Some(tag) = tag {
+        let tag = tag.trim_start_matches('#');
+        let tagged = tasks
+            .into_iter()
+            .filter(|task| {
+                task.tags
+                    .iter()
+                    .flatten()
+                    .any(|t| t.eq_ignore_ascii_case(tag))
+            })
+            .collect::<Vec<_>>();
+        let output = output::render_tasks(&tagged, config.display_format, || {
+            Ok(TaskTableFormatter::format_tag_tasks(tag, &tagged))
+        })?;
+        say!("{}", output.trim_end());
+        return Ok(());
+    }
+
+    let summaries = TagSummary::compute(&tasks);
+    match config.display_format {
+        DisplayFormat::Table => {
+            let untagged = tasks
+                .iter()
+                .filter(|task| task.tags.as_ref().is_none_or(|tags| tags.is_empty()))
+                .count();
+            say!(
+                "{}",
+                TaskTableFormatter::format_tag_summaries(&summaries, untagged).trim_end()
+            );
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&summaries)?),
+        DisplayFormat::Csv => print!("{}", output::tag_summaries_to_csv(&summaries)),
+    }
+
+    Ok(())
+}
+
+async fn handle_velocity_command(
+    config: Config,
+    pool: &McpClientPool,
+    weeks: u32,
+    ai_summary: bool,
+) -> Result<()> {
+    info!("Computing velocity over {} weeks", weeks);
+
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_all_tasks().await?;
+    let report = VelocityReport::compute(&tasks, Utc::now(), weeks);
+
+    match config.display_format {
+        DisplayFormat::Table => say!("{}", TaskTableFormatter::format_velocity(&report)),
+        DisplayFormat::Json if !ai_summary => println!("{}", output::to_json(&report)?),
+        DisplayFormat::Csv => print!("{}", report.to_csv()),
+        DisplayFormat::Json => {}
+    }
+
+    if !ai_summary {
+        return Ok(());
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    if config.display_format == DisplayFormat::Table {
+        say!("🤖 AI Summary:\n");
+    }
+    let spinner = Spinner::start("summarizing velocity");
+    let summary = deepseek_client
+        .complete(
+            "You are an engineering manager reviewing a team's task throughput. Be concise and concrete.",
+            report.summary_prompt(),
+        )
+        .await
+        .map_err(|e| e.context("Failed to summarize velocity"))?;
+    drop(spinner);
+
+    match config.display_format {
+        DisplayFormat::Table if deepseek_client.is_streaming() => println!(),
+        DisplayFormat::Table => say!("{}", summary),
+        DisplayFormat::Json => {
+            let output = serde_json::json!({ "velocity": report, "ai_summary": summary });
+            println!("{}", output::to_json(&output)?);
+        }
+        DisplayFormat::Csv => esay!("\n🤖 AI Summary:\n{}", summary),
+    }
+
+    Ok(())
+}
+
+async fn handle_digest_command(
+    config: Config,
+    pool: &McpClientPool,
+    period: DigestPeriod,
+    output_path: Option<PathBuf>,
+    format: OutputFormat,
+    ai_summary: bool,
+) -> Result<()> {
+    info!("Building {:?} digest", period);
+
+    if format == OutputFormat::Pdf && output_path.is_none() {
+        anyhow::bail!(McpTasksError::Config(
+            "PDF digests need an output file; use --output digest.pdf".to_string()
+        ));
+    }
+
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_all_tasks().await?;
+
+    let now = Utc::now();
+    let since = now - chrono::Duration::days(period.days());
+    let snapshots = SnapshotStore::new(&config.data_dir).since(since)?;
+    let mut digest = Digest::build(&tasks, &snapshots, period, now);
+
+    if ai_summary {
+        enotice!("🤖 Writing the digest summary with DeepSeek AI...");
+        let deepseek_client = DeepSeekClient::new(&config)?;
+        let _spinner = Spinner::start("writing the summary");
+        let narrative = deepseek_client
+            .complete(
+                "You are a project manager writing a short status digest for the team. Be concise and concrete.",
+                digest.narrative_prompt(),
+            )
+            .await
+            .map_err(|e| e.context("Failed to summarize the digest"))?;
+        digest.narrative = Some(narrative);
+    }
+
+    match output_path {
+        Some(path) => {
+            digest.save(&path, format)?;
+            enotice!("💾 Digest saved to {}", path.display());
+        }
+        None => std::io::stdout()
+            .write_all(&digest.render(format)?)
+            .context("Failed to write the digest")?,
+    }
+
+    Ok(())
+}
+
+async fn handle_brief_command(
+    config: Config,
+    pool: &McpClientPool,
+    ai_summary: bool,
+    notify: bool,
+) -> Result<()> {
+    info!("Building the morning briefing");
+
+    let spinner = Spinner::start("fetching tasks");
+    let tasks = pool.default_client().await?.get_unfinished_tasks().await?;
+    let latest = HistoryStore::new(&config.data_dir).entries()?.pop();
+    let mut brief = Brief::build(tasks, latest.as_ref(), Utc::now());
+
+    // Nothing to brief on is worth saying without asking the model
+    if ai_summary && !brief.is_empty() {
+        if config.display_format == DisplayFormat::Table {
+            notice!("🤖 Writing the briefing with DeepSeek AI...");
+        }
+        let deepseek_client = DeepSeekClient::new(&config)?;
+        let summary = deepseek_client
+            .complete(brief::BRIEF_SYSTEM_PROMPT, brief.summary_prompt())
+            .await
+            .map_err(|e| e.context("Failed to write the briefing"))?;
+        brief.summary = Some(summary.trim().to_string());
+    }
+    drop(spinner);
+
+    match config.display_format {
+        DisplayFormat::Table if brief.is_empty() => {
+            say!("🎉 Nothing overdue, due today or in progress.")
+        }
+        DisplayFormat::Table => say!("{}", brief.to_text().trim_end()),
+        DisplayFormat::Json => println!("{}", output::to_json(&brief)?),
+        DisplayFormat::Csv => print!("{}", output::brief_to_csv(&brief)),
+    }
+
+    if notify {
+        let body = brief.summary.clone().unwrap_or_else(|| brief.counts());
+        DesktopNotifier::new(true).send(&format!("Briefing: {}", brief.counts()), &body);
+    }
+
+    Ok(())
+}
+
+async fn handle_agenda_command(
+    config: Config,
+    pool: &McpClientPool,
+    scope: AgendaScope,
+) -> Result<()> {
+    info!("Building agenda ({:?})", scope);
+
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_unfinished_tasks().await?;
+    let agenda = Agenda::build(tasks, Utc::now(), scope);
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_agenda(&agenda).trim_end())
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&agenda)?),
+        DisplayFormat::Csv => print!("{}", output::agenda_to_csv(&agenda)),
+    }
+
+    Ok(())
+}
+
+async fn handle_watch_command(
+    config: Config,
+    pool: &McpClientPool,
+    interval: Duration,
+    notify: bool,
+) -> Result<()> {
+    info!("Watching tasks every {:?}", interval);
+
+    let mcp_client = pool.default_client().await?;
+    watch::run_watch(mcp_client, interval, notify, config.display_format).await
+}
+
+async fn handle_board_command(pool: &McpClientPool, refresh: Option<Duration>) -> Result<()> {
+    info!("Opening task board");
+
+    let mcp_client = pool.default_client().await?;
+    board::run_board(mcp_client, refresh).await
+}
+
+async fn handle_semantic_command(
+    config: Config,
+    pool: &McpClientPool,
+    query: &str,
+    limit: usize,
+    open: bool,
+) -> Result<()> {
+    info!("Searching tasks semantically for '{}'", query);
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let tasks = if open {
+        mcp_client.get_unfinished_tasks().await?
+    } else {
+        mcp_client.get_all_tasks().await?
+    };
+    if tasks.is_empty() {
+        drop(spinner);
+        say!("📭 No tasks to search.");
+        return Ok(());
+    }
+
+    progress::set_phase("embedding tasks");
+    let embedder = embeddings::create_embedder(&config)?;
+    let mut cache = EmbeddingCache::load(&config.data_dir, embedder.model())?;
+    let matches = embeddings::search(embedder.as_ref(), &mut cache, tasks, query, limit).await;
+    // Keep whatever was embedded before a failure
+    cache.save()?;
+    let matches = matches?;
+    drop(spinner);
+
+    match config.display_format {
+        DisplayFormat::Table => say!(
+            "{}",
+            TaskTableFormatter::format_semantic_matches(query, &matches)
+        ),
+        DisplayFormat::Json => println!("{}", output::to_json(&matches)?),
+        DisplayFormat::Csv => print!("{}", output::semantic_matches_to_csv(&matches)),
+    }
+
+    Ok(())
+}
+
+async fn handle_themes_command(
+    config: Config,
+    pool: &McpClientPool,
+    count: Option<usize>,
+    all: bool,
+    ai_labels: bool,
+) -> Result<()> {
+    info!("Grouping tasks into themes");
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let tasks = if all {
+        mcp_client.get_all_tasks().await?
+    } else {
+        mcp_client.get_unfinished_tasks().await?
+    };
+    if tasks.is_empty() {
+        drop(spinner);
+        say!("📭 No tasks to group.");
+        return Ok(());
+    }
+
+    progress::set_phase("embedding tasks");
+    let embedder = embeddings::create_embedder(&config)?;
+    let mut cache = EmbeddingCache::load(&config.data_dir, embedder.model())?;
+    let task_embeddings = embeddings::embed_tasks(embedder.as_ref(), &mut cache, &tasks).await;
+    cache.save()?;
+    let task_embeddings = task_embeddings?;
+
+    let count = count.unwrap_or_else(|| themes::default_theme_count(tasks.len()));
+    let assignments = themes::cluster(&task_embeddings, count);
+    let groups = themes::group_tasks(tasks, &assignments);
+
+    let labels = if ai_labels {
+        progress::set_phase("naming themes");
+        if config.display_format == DisplayFormat::Table {
+            notice!("🤖 Naming {} themes with DeepSeek AI...", groups.len());
+        }
+        DeepSeekClient::new(&config)?.label_themes(&groups).await?
+    } else {
+        groups
+            .iter()
+            .enumerate()
+            .map(|(idx, group)| themes::keyword_label(idx + 1, group))
+            .collect()
+    };
+    drop(spinner);
+
+    let report = ThemeReport::new(groups, labels);
+    match config.display_format {
+        DisplayFormat::Table => say!("{}", TaskTableFormatter::format_themes(&report)),
+        DisplayFormat::Json => println!("{}", output::to_json(&report)?),
+        DisplayFormat::Csv => print!("{}", output::themes_to_csv(&report)),
+    }
+
+    Ok(())
+}
+
+async fn handle_pick_command(
+    config: Config,
+    pool: &McpClientPool,
+    action: Option<PickAction>,
+) -> Result<()> {
+    info!("Opening task picker");
+
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_all_tasks().await?;
+    if tasks.is_empty() {
+        println!("No tasks found.");
+        return Ok(());
+    }
+
+    let picked = tokio::task::spawn_blocking(move || picker::pick_task(tasks, action)).await??;
+    let Some((task, action)) = picked else {
+        return Ok(());
+    };
+
+    match action {
+        PickAction::Show => {
+            match config.display_format {
+                DisplayFormat::Table => {
+                    say!(
+                        "{}",
+                        TaskTableFormatter::format_single_task(&task, "🔍 Task")
+                    );
+                    if let Some(description) = &task.description {
+                        println!("\n{}", description);
+                    }
+                }
+                DisplayFormat::Json => println!("{}", output::to_json(&task)?),
+                DisplayFormat::Csv => {
+                    print!("{}", output::tasks_to_csv(std::slice::from_ref(&task)))
+                }
+            }
+            Ok(())
+        }
+        PickAction::Complete => handle_complete_command(config, pool, task.id).await,
+        PickAction::Analyze => handle_analyze_task_command(config, pool, task.id, false).await,
+        PickAction::CopyId => {
+            match picker::copy_to_clipboard(&task.id) {
+                Ok(tool) => say!("📋 Copied {} to the clipboard ({})", task.id, tool),
+                Err(e) => {
+                    // Still hand over the ID so it can be copied by hand
+                    esay!("⚠️  {}", e);
+                    println!("{}", task.id);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn handle_analyze_task_command(
+    config: Config,
+    pool: &McpClientPool,
+    id: String,
+    apply: bool,
+) -> Result<()> {
+    info!("Starting DeepSeek deep-dive of task {}", id);
+
+    let task = pool.task_ref(&id);
+    let mcp_client = pool.client_for(&task).await?;
+    if apply {
+        mcp_client.require(Capability::Create).await?;
+    }
+    let (task, breakdown) = propose_task_breakdown(&config, pool, &task).await?;
+
+    // A failed subtask does not stop the others; every result is reported
+    let results = if apply && !breakdown.subtasks.is_empty() {
+        breakdown::create_subtasks(mcp_client, &task, &breakdown.subtasks).await
+    } else {
+        Vec::new()
+    };
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!(
+                "{}",
+                TaskTableFormatter::format_breakdown(&task, &breakdown)
+            );
+            if !results.is_empty() {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_change_results(&subtasks_heading(&task), &results)
+                );
+            } else if !apply && !breakdown.subtasks.is_empty() {
+                notice!("💡 Run again with --apply to create these subtasks");
+            }
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "task": task,
+                "breakdown": breakdown,
+                "results": results,
+            }))?
+        ),
+        DisplayFormat::Csv => print!("{}", output::subtasks_to_csv(&breakdown.subtasks)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} subtasks could not be created",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_expand_command(
+    config: Config,
+    pool: &McpClientPool,
+    id: String,
+    yes: bool,
+) -> Result<()> {
+    info!("Expanding task {} into subtasks", id);
+
+    let task = pool.task_ref(&id);
+    let mcp_client = pool.client_for(&task).await?;
+    mcp_client.require(Capability::Create).await?;
+    let (task, breakdown) = propose_task_breakdown(&config, pool, &task).await?;
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = TaskTableFormatter::format_breakdown(&task, &breakdown);
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+    } else {
+        esay!("{}", preview);
+    }
+
+    if breakdown.subtasks.is_empty() {
+        return print_no_changes(&config, "🤷 Nothing to create.");
+    }
+
+    let question = format!(
+        "Create {} subtasks under '{}'?",
+        breakdown.subtasks.len(),
+        task.title
+    );
+    if !yes && !tooling::confirm(&question)? {
+        return print_no_changes(&config, "❎ Cancelled, no tasks were created.");
+    }
+
+    let results = breakdown::create_subtasks(mcp_client, &task, &breakdown.subtasks).await;
+    print_created_tasks(&config, &subtasks_heading(&task), &results)
+}
+
+async fn handle_dedupe_command(
+    config: Config,
+    pool: &McpClientPool,
+    merge: bool,
+    yes: bool,
+) -> Result<()> {
+    info!("Looking for duplicate tasks with DeepSeek");
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    if merge {
+        mcp_client.require(Capability::Complete).await?;
+    }
+    let tasks = mcp_client.get_unfinished_tasks().await?;
+
+    if tasks.len() < 2 {
+        return print_no_changes(
+            &config,
+            "✨ Fewer than two unfinished tasks, nothing to compare.",
+        );
+    }
+
+    if config.display_format == DisplayFormat::Table {
+        notice!(
+            "\n🤖 Comparing {} unfinished tasks with DeepSeek AI...",
+            tasks.len()
+        );
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let clusters = deepseek_client.find_duplicates(&tasks).await?;
+    drop(spinner);
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = TaskTableFormatter::format_duplicate_clusters(&clusters, &tasks);
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+    } else if merge {
+        esay!("{}", preview);
+    }
+
+    // A task kept in one cluster is never closed because of another
+    let mut to_close: Vec<mcp_client::Task> = Vec::new();
+    for id in clusters.iter().flat_map(|cluster| cluster.duplicates()) {
+        if let Some(task) = tasks.iter().find(|task| task.id == *id)
+            && !to_close.iter().any(|closing| closing.id == task.id)
+            && !clusters.iter().any(|cluster| cluster.keep == *id)
+        {
+            to_close.push(task.clone());
+        }
+    }
+
+    let action = BulkAction::Complete;
+    let mut results = Vec::new();
+    if merge && !to_close.is_empty() {
+        let question = format!("Complete {} duplicate tasks?", to_close.len());
+        if yes || tooling::confirm(&question)? {
+            let spinner = Spinner::start("completing duplicates");
+            results = bulk::apply(
+                mcp_client,
+                &to_close,
+                &action,
+                bulk::DEFAULT_CONCURRENCY as usize,
+            )
+            .await;
+            drop(spinner);
+        } else if config.display_format == DisplayFormat::Table {
+            say!("❎ Cancelled, no tasks were changed.");
+        } else {
+            esay!("❎ Cancelled, no tasks were changed.");
+        }
+    }
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            if !results.is_empty() {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_bulk_results(&action, &results)
+                );
+            } else if !merge && !to_close.is_empty() {
+                notice!("💡 Run again with --merge to complete the duplicates");
+            }
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "clusters": clusters,
+                "results": results,
+            }))?
+        ),
+        DisplayFormat::Csv if !results.is_empty() => {
+            print!("{}", output::bulk_results_to_csv(&results))
+        }
+        DisplayFormat::Csv => print!("{}", output::duplicate_clusters_to_csv(&clusters)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} duplicate tasks could not be completed",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_graph_command(
+    config: Config,
+    pool: &McpClientPool,
+    format: GraphFormat,
+    output_path: Option<PathBuf>,
+) -> Result<()> {
+    info!("Inferring task dependencies with DeepSeek");
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client
+        .get_all_tasks()
+        .await?
+        .into_iter()
+        .filter(|task| task.is_unfinished())
+        .collect::<Vec<_>>();
+
+    if tasks.is_empty() {
+        esay!("🎉 No unfinished tasks to graph!");
+        return Ok(());
+    }
+
+    // Progress goes to stderr so the graph can be piped into `dot` or a file
+    enotice!(
+        "🤖 Inferring dependencies between {} tasks with DeepSeek AI...",
+        tasks.len()
+    );
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let dependencies = deepseek_client.infer_dependencies(&tasks).await?;
+    drop(spinner);
+    let graph = DependencyGraph::build(&tasks, &dependencies);
+
+    for cycle in &graph.cycles {
+        esay!(
+            "⚠️  Warning: dependency cycle between {}",
+            cycle.join(" → ")
+        );
+    }
+
+    match &output_path {
+        Some(path) => {
+            graph.save(format, path)?;
+            enotice!(
+                "💾 Saved {} dependencies between {} tasks to {}",
+                graph.edges.len(),
+                graph.nodes.len(),
+                path.display()
+            );
+        }
+        None if config.display_format != DisplayFormat::Json => print!("{}", graph.render(format)),
+        None => {}
+    }
+
+    if config.display_format == DisplayFormat::Json {
+        println!("{}", output::to_json(&graph)?);
+    }
+
+    Ok(())
+}
+
+async fn handle_reprioritize_command(
+    config: Config,
+    pool: &McpClientPool,
+    apply: bool,
+) -> Result<()> {
+    info!("Starting DeepSeek priority review of pending tasks");
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    if apply {
+        mcp_client.require(Capability::Update).await?;
+    }
+    let pending_tasks = mcp_client.get_tasks_by_status(TaskStatus::Pending).await?;
+
+    if pending_tasks.is_empty() {
+        return print_no_changes(&config, "🎉 No pending tasks found to reprioritize!");
+    }
+
+    if config.display_format == DisplayFormat::Table {
+        notice!(
+            "\n🤖 Reviewing the priorities of {} pending tasks with DeepSeek AI...",
+            pending_tasks.len()
+        );
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let suggestions = deepseek_client.suggest_priorities(&pending_tasks).await?;
+    let changes = suggestions.changes;
+    if apply {
+        progress::set_phase("updating priorities");
+    }
+
+    // A failed update does not stop the others; every result is reported,
+    // including suggestions for tasks that do not exist
+    let mut results = Vec::new();
+    if apply {
+        let mut unknown_ids = suggestions.unknown_ids;
+        let mut updates = Vec::new();
+        for change in changes.iter().filter(|change| change.is_change()) {
+            let Some(task) = pending_tasks.iter().find(|task| task.id == change.id) else {
+                unknown_ids.push(change.id.clone());
+                continue;
+            };
+            let update = TaskUpdate {
+                priority: Some(change.suggested.clone()),
+                ..Default::default()
+            };
+            updates.push((task, BulkAction::Set(update)));
+        }
+        results = bulk::apply_each(mcp_client, &updates, bulk::DEFAULT_CONCURRENCY as usize).await;
+        results.extend(
+            unknown_ids
+                .iter()
+                .map(|id| bulk::BulkResult::unknown_task(id)),
+        );
+    }
+    drop(spinner);
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_priority_changes(&changes));
+            let changed = changes.iter().filter(|change| change.is_change()).count();
+            if apply {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_change_results("Priority updates", &results)
+                );
+            } else if changed > 0 {
+                notice!("💡 Run again with --apply to update these priorities");
+            }
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "changes": changes,
+                "results": results,
+            }))?
+        ),
+        DisplayFormat::Csv => print!("{}", output::priority_changes_to_csv(&changes)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} priorities could not be updated",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_estimate_command(
+    config: Config,
+    pool: &McpClientPool,
+    unit: EstimateUnit,
+    apply: Option<ApplyTarget>,
+    field: &str,
+) -> Result<()> {
+    info!("Starting DeepSeek effort estimation of pending tasks");
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let pending_tasks = mcp_client.get_tasks_by_status(TaskStatus::Pending).await?;
+
+    if pending_tasks.is_empty() {
+        return print_no_changes(&config, "🎉 No pending tasks found to estimate!");
+    }
+
+    // Check before spending tokens that the server can store the estimates
+    if apply.is_some() {
+        mcp_client.require(Capability::Update).await?;
+    }
+    if let Some(target) = apply
+        && let Some(schema) = mcp_client.tool_schema("update_task").await
+        && let Some(properties) = schema.get("properties").and_then(|p| p.as_object())
+    {
+        let argument = match target {
+            ApplyTarget::Field => field,
+            ApplyTarget::Tag => "tags",
+        };
+        if !properties.contains_key(argument) {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "The server's update_task tool has no '{}' argument; {}",
+                argument,
+                match target {
+                    ApplyTarget::Field => "pick another --field or use --apply-as tag",
+                    ApplyTarget::Tag => "use --apply-as field",
+                }
+            )));
+        }
+    }
+
+    if config.display_format == DisplayFormat::Table {
+        notice!(
+            "\n🤖 Estimating {} pending tasks in {} with DeepSeek AI...",
+            pending_tasks.len(),
+            unit.label()
+        );
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let answer = deepseek_client
+        .estimate_tasks(&pending_tasks, unit, field)
+        .await?;
+    let estimates = answer.estimates;
+    let summary = EstimateSummary::new(&estimates, unit);
+
+    // A failed update does not stop the others; every result is reported,
+    // including estimates for tasks that do not exist
+    let mut results = Vec::new();
+    if let Some(target) = apply {
+        progress::set_phase("saving estimates");
+        let mut unknown_ids = answer.unknown_ids;
+        let mut updates = Vec::new();
+        for estimate in &estimates {
+            let Some(task) = pending_tasks.iter().find(|task| task.id == estimate.id) else {
+                unknown_ids.push(estimate.id.clone());
+                continue;
+            };
+            let mut update = TaskUpdate::default();
+            match target {
+                ApplyTarget::Field => {
+                    update
+                        .extra
+                        .insert(field.to_string(), serde_json::json!(estimate.estimate));
+                }
+                ApplyTarget::Tag => {
+                    update.tags = Some(estimate::tags_with_estimate(
+                        task.tags.as_deref(),
+                        estimate,
+                        unit,
+                    ));
+                }
+            }
+            updates.push((task, BulkAction::Set(update)));
+        }
+        results = bulk::apply_each(mcp_client, &updates, bulk::DEFAULT_CONCURRENCY as usize).await;
+        results.extend(
+            unknown_ids
+                .iter()
+                .map(|id| bulk::BulkResult::unknown_task(id)),
+        );
+    }
+    drop(spinner);
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!(
+                "{}",
+                TaskTableFormatter::format_estimates(&estimates, &summary)
+            );
+            if apply.is_some() {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_change_results("Saved estimates", &results)
+                );
+            } else if !estimates.is_empty() {
+                notice!("💡 Run again with --apply to save these estimates");
+            }
+            let usage = deepseek_client.take_usage();
+            notice!(
+                "💰 Tokens: {}",
+                usage.describe(deepseek_client.estimate_cost(&usage))
+            );
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "estimates": estimates,
+                "summary": summary,
+                "results": results,
+            }))?
+        ),
+        DisplayFormat::Csv => print!("{}", output::estimates_to_csv(&estimates, unit)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} estimates could not be saved",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_plan_command(
+    config: Config,
+    pool: &McpClientPool,
+    capacity: Capacity,
+    until: Option<NaiveDate>,
+    by: PlanPeriod,
+    field: &str,
+    output_path: Option<PathBuf>,
+) -> Result<()> {
+    info!("Starting DeepSeek capacity planning of unfinished tasks");
+
+    let today = dates::local_date(Utc::now());
+    let until = until.unwrap_or_else(|| today + chrono::Days::new(27));
+    let window = PlanWindow::new(today, until, by, capacity)?;
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_unfinished_tasks().await?;
+
+    if tasks.is_empty() {
+        return print_no_changes(&config, "🎉 No unfinished tasks found to plan!");
+    }
+
+    if config.display_format == DisplayFormat::Table {
+        notice!(
+            "\n🤖 Planning {} tasks over {} {}s of {} with DeepSeek AI...",
+            tasks.len(),
+            window.starts.len(),
+            by.as_str(),
+            plan::format_hours(window.capacity)
+        );
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let plan = deepseek_client
+        .plan_schedule(&tasks, field, &window)
+        .await?;
+    drop(spinner);
+
+    if let Some(path) = &output_path {
+        std::fs::write(path, plan.to_markdown()).with_context(|| {
+            McpTasksError::Report(format!("Failed to write plan to {}", path.display()))
+        })?;
+    }
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_plan(&plan));
+            if let Some(path) = &output_path {
+                say!("💾 Saved the plan to {}", path.display());
+            }
+            let usage = deepseek_client.take_usage();
+            notice!(
+                "💰 Tokens: {}",
+                usage.describe(deepseek_client.estimate_cost(&usage))
+            );
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&plan)?),
+        DisplayFormat::Csv => print!("{}", output::plan_to_csv(&plan)),
+    }
+
+    Ok(())
+}
+
+async fn handle_bulk_command(
+    config: Config,
+    pool: &McpClientPool,
+    filter: TaskQuery,
+    action: BulkAction,
+    concurrency: usize,
+    yes: bool,
+) -> Result<()> {
+    info!("Bulk {} on tasks matching {:?}", action.describe(), filter);
+
+    let mcp_client = pool.default_client().await?;
+    mcp_client.require(action.capability()).await?;
+    let mut tasks = mcp_client.get_all_tasks().await?;
+    tasks.retain(|task| filter.matches(task));
+    let already = tasks.len();
+    tasks.retain(|task| !action.is_noop(task));
+    let skipped = already - tasks.len();
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = if tasks.is_empty() {
+        "No tasks found.".to_string()
+    } else {
+        TaskTableFormatter::format_all_tasks(&tasks, &TableOptions::default())?
+    };
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+    } else {
+        esay!("{}", preview);
+    }
+    if skipped > 0 {
+        enotice!(
+            "⏭️  Skipping {} matching tasks that need no change",
+            skipped
+        );
+    }
+
+    if tasks.is_empty() {
+        return print_no_changes(&config, "🤷 Nothing to change.");
+    }
+
+    let question = match action {
+        BulkAction::Delete => format!("Delete {} tasks? This cannot be undone.", tasks.len()),
+        _ => format!("Apply '{}' to {} tasks?", action.describe(), tasks.len()),
+    };
+    if !yes && !tooling::confirm(&question)? {
+        return print_no_changes(&config, "❎ Cancelled, no tasks were changed.");
+    }
+
+    let spinner = Spinner::start("applying changes");
+    let results = bulk::apply(mcp_client, &tasks, &action, concurrency).await;
+    drop(spinner);
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!(
+                "{}",
+                TaskTableFormatter::format_bulk_results(&action, &results)
+            );
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&results)?),
+        DisplayFormat::Csv => print!("{}", output::bulk_results_to_csv(&results)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} tasks could not be changed",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// End a command that changes tasks without changing anything: `message` for
+/// the person running it, and an empty result list on stdout for scripts
+fn print_no_changes(config: &Config, message: &str) -> Result<()> {
+    match config.display_format {
+        DisplayFormat::Table => say!("{}", message),
+        DisplayFormat::Json => {
+            esay!("{}", message);
+            println!("{}", output::to_json(&[] as &[bulk::BulkResult])?);
+        }
+        DisplayFormat::Csv => {
+            esay!("{}", message);
+            print!("{}", output::bulk_results_to_csv(&[]));
+        }
+    }
+    Ok(())
+}
+
+async fn handle_delete_command(
+    config: Config,
+    pool: &McpClientPool,
+    ids: Vec<String>,
+    filter: Option<TaskQuery>,
+    force: bool,
+) -> Result<()> {
+    info!("Deleting tasks {:?} and tasks matching {:?}", ids, filter);
+
+    // Every task is looked up first, so a wrong ID stops the run before anything is deleted
+    let mut tasks: Vec<mcp_client::Task> = Vec::new();
+    for id in &ids {
+        tasks.push(pool.get_task(&pool.task_ref(id)).await?);
+    }
+    if let Some(filter) = &filter {
+        let mut matching = pool.default_client().await?.get_all_tasks().await?;
+        matching.retain(|task| filter.matches(task));
+        tasks.extend(matching);
+    }
+    let mut seen = HashSet::new();
+    tasks.retain(|task| seen.insert(task.task_ref()));
+
+    // IDs may name tasks on several servers; each has to be able to delete
+    let mut by_server: BTreeMap<Option<String>, Vec<mcp_client::Task>> = BTreeMap::new();
+    for task in tasks.iter().cloned() {
+        by_server.entry(task.server.clone()).or_default().push(task);
+    }
+    for server_tasks in by_server.values() {
+        pool.client_for(&server_tasks[0].task_ref())
+            .await?
+            .require(Capability::Delete)
+            .await?;
+    }
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = if tasks.is_empty() {
+        "No tasks found.".to_string()
+    } else {
+        TaskTableFormatter::format_all_tasks(&tasks, &TableOptions::default())?
+    };
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+    } else {
+        esay!("{}", preview);
+    }
+
+    if tasks.is_empty() {
+        return print_no_changes(&config, "🤷 Nothing to delete.");
+    }
+
+    let question = format!("Delete {} tasks? This cannot be undone.", tasks.len());
+    if !force && !tooling::confirm(&question)? {
+        return print_no_changes(&config, "❎ Cancelled, no tasks were deleted.");
+    }
+
+    let spinner = Spinner::start("deleting tasks");
+    let mut results = Vec::with_capacity(tasks.len());
+    for server_tasks in by_server.values() {
+        let mcp_client = pool.client_for(&server_tasks[0].task_ref()).await?;
+        results.extend(
+            bulk::apply(
+                mcp_client,
+                server_tasks,
+                &BulkAction::Delete,
+                bulk::DEFAULT_CONCURRENCY as usize,
+            )
+            .await,
+        );
+    }
+    drop(spinner);
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!(
+                "{}",
+                TaskTableFormatter::format_bulk_results(&BulkAction::Delete, &results)
+            );
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&results)?),
+        DisplayFormat::Csv => print!("{}", output::bulk_results_to_csv(&results)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} tasks could not be deleted",
+            failed,
+            results.len()
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_sprint_command(
+    config: Config,
+    pool: &McpClientPool,
+    command: SprintCommands,
+) -> Result<()> {
+    match command {
+        SprintCommands::Plan {
+            length,
+            capacity,
+            name,
+            field,
+            yes,
+        } => handle_sprint_plan(config, pool, length, capacity, name, &field, yes).await,
+        SprintCommands::Review { name } => handle_sprint_review(config, pool, name).await,
+    }
+}
+
+async fn handle_sprint_plan(
+    config: Config,
+    pool: &McpClientPool,
+    length: u64,
+    capacity: SprintCapacity,
+    name: Option<String>,
+    field: &str,
+    yes: bool,
+) -> Result<()> {
+    let name = name.unwrap_or_else(|| sprint::default_name(dates::local_date(Utc::now())));
+    info!("Planning sprint {}", name);
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    mcp_client.require(Capability::Update).await?;
+    let pending_tasks = mcp_client.get_tasks_by_status(TaskStatus::Pending).await?;
+
+    if pending_tasks.is_empty() {
+        return print_no_changes(&config, "🎉 No pending tasks found to plan a sprint from!");
+    }
+
+    // Check before spending tokens that the server can tag the tasks
+    if let Some(schema) = mcp_client.tool_schema("update_task").await
+        && let Some(properties) = schema.get("properties").and_then(|p| p.as_object())
+        && !properties.contains_key("tags")
+    {
+        anyhow::bail!(McpTasksError::Config(
+            "The server's update_task tool has no 'tags' argument to mark sprint tasks with"
+                .to_string()
+        ));
+    }
+
+    if config.display_format == DisplayFormat::Table {
+        notice!(
+            "\n🤖 Proposing a {}-day sprint of {} from {} pending tasks with DeepSeek AI...",
+            length,
+            capacity,
+            pending_tasks.len()
+        );
+    }
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    let proposal = deepseek_client
+        .propose_sprint(&pending_tasks, field, name, length, capacity)
+        .await?;
+    drop(spinner);
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = TaskTableFormatter::format_sprint_proposal(&proposal);
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+        let usage = deepseek_client.take_usage();
+        notice!(
+            "💰 Tokens: {}",
+            usage.describe(deepseek_client.estimate_cost(&usage))
+        );
+    } else {
+        esay!("{}", preview);
+    }
+
+    if proposal.selected.is_empty() {
+        if config.display_format == DisplayFormat::Table {
+            say!("🤷 Nothing to tag.");
+        } else {
+            esay!("🤷 Nothing to tag.");
+        }
+        return Ok(());
+    }
+
+    let question = format!(
+        "Tag {} tasks with '{}'?",
+        proposal.selected.len(),
+        proposal.name
+    );
+    let action = BulkAction::AddTag(proposal.name.clone());
+    let mut results = Vec::new();
+    if yes || tooling::confirm(&question)? {
+        let tasks = proposal
+            .selected
+            .iter()
+            .filter_map(|pick| pending_tasks.iter().find(|task| task.id == pick.id))
+            .filter(|task| !action.is_noop(task))
+            .cloned()
+            .collect::<Vec<_>>();
+        let spinner = Spinner::start("tagging tasks");
+        results = bulk::apply(
+            mcp_client,
+            &tasks,
+            &action,
+            bulk::DEFAULT_CONCURRENCY as usize,
+        )
+        .await;
+        drop(spinner);
+
+        // Tasks that did get the tag belong to a sprint `sprint review` has to know about
+        if results.iter().any(|result| result.ok) || results.is_empty() {
+            SprintStore::new(&config.data_dir).append(&Sprint::from(&proposal))?;
+        }
+    } else if config.display_format == DisplayFormat::Table {
+        say!("❎ Cancelled, no tasks were tagged.");
+    } else {
+        esay!("❎ Cancelled, no tasks were tagged.");
+    }
+    let failed = results.iter().filter(|result| !result.ok).count();
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            if failed > 0 {
+                say!(
+                    "{}",
+                    TaskTableFormatter::format_bulk_results(&action, &results)
+                );
+            }
+            if results.len() > failed {
+                say!(
+                    "✅ Tagged {} tasks with '{}'; run `sprint review` when the sprint ends",
+                    results.len() - failed,
+                    proposal.name
+                );
+            }
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "sprint": proposal,
+                "results": results,
+            }))?
+        ),
+        DisplayFormat::Csv => print!("{}", output::sprint_proposal_to_csv(&proposal)),
+    }
+
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} sprint tasks could not be tagged with '{}'{}",
+            failed,
+            results.len(),
+            proposal.name,
+            if failed < results.len() {
+                "; the sprint was saved, tag the failed tasks by hand"
+            } else {
+                ", so the sprint was not saved"
+            }
+        )));
+    }
+
+    Ok(())
+}
+
+async fn handle_sprint_review(
+    config: Config,
+    pool: &McpClientPool,
+    name: Option<String>,
+) -> Result<()> {
+    let sprint = SprintStore::new(&config.data_dir).find(name.as_deref())?;
+    let Some(name) = name.or_else(|| sprint.as_ref().map(|sprint| sprint.name.clone())) else {
+        anyhow::bail!(McpTasksError::Config(
+            "No sprint has been planned yet; pass --name with the sprint tag".to_string()
+        ));
+    };
+    info!("Reviewing sprint {}", name);
+
+    let spinner = Spinner::start("fetching tasks");
+    let mcp_client = pool.default_client().await?;
+    let tasks = mcp_client.get_all_tasks().await?;
+    let mut review = SprintReview::build(&name, sprint.as_ref(), &tasks);
+
+    let deepseek_client = DeepSeekClient::new(&config)?;
+    if !review.items.is_empty() {
+        progress::set_phase("summarizing the sprint");
+        review.ai_summary = Some(
+            deepseek_client
+                .complete(sprint::SPRINT_REVIEW_SYSTEM_PROMPT, review.summary_prompt())
+                .await
+                .map_err(|e| e.context("Failed to summarize the sprint"))?,
+        );
+    }
+    drop(spinner);
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_sprint_review(&review));
+            if let Some(summary) = &review.ai_summary {
+                say!("🤖 AI Summary:\n\n{}\n", summary);
+                let usage = deepseek_client.take_usage();
+                notice!(
+                    "💰 Tokens: {}",
+                    usage.describe(deepseek_client.estimate_cost(&usage))
+                );
+            }
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&review)?),
+        DisplayFormat::Csv => {
+            print!("{}", output::sprint_review_to_csv(&review));
+            if let Some(summary) = &review.ai_summary {
+                esay!("\n🤖 AI Summary:\n{}", summary);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch a task and ask DeepSeek for a subtask breakdown. The AI may look
+/// around with read-only tools but is not allowed to change anything itself.
+async fn propose_task_breakdown(
+    config: &Config,
+    pool: &McpClientPool,
+    task: &TaskRef,
+) -> Result<(mcp_client::Task, breakdown::TaskBreakdown)> {
+    let spinner = Spinner::start("fetching the task");
+    let task = pool.get_task(task).await?;
+
+    let deepseek_client = DeepSeekClient::new(config)?
+        .with_mutation_policy(config.mutation_policy(MutationPolicy::Deny));
+
+    if config.display_format == DisplayFormat::Table {
+        notice!("\n🤖 Analyzing task '{}' with DeepSeek AI...", task.title);
+    }
+
+    let (breakdown, tool_calls) = deepseek_client
+        .analyze_task_breakdown(&task, pool)
+        .await
+        .map_err(|e| {
+            error!("DeepSeek task analysis failed: {}", e);
+            e
+        })?;
+    info!("Task breakdown finished after {} tool calls", tool_calls);
+    drop(spinner);
+
+    if config.display_format == DisplayFormat::Table {
+        let usage = deepseek_client.take_usage();
+        notice!(
+            "💰 Tokens: {}",
+            usage.describe(deepseek_client.estimate_cost(&usage))
+        );
+    }
+
+    Ok((task, breakdown))
+}
+
+/// Heading of the results of creating subtasks under `parent`
+fn subtasks_heading(parent: &mcp_client::Task) -> String {
+    format!("Subtasks tagged '{}'", breakdown::parent_tag(&parent.id))
+}
+
+async fn handle_daemon_command(
+    config: Config,
+    pool: &McpClientPool,
+    options: DaemonOptions,
+) -> Result<()> {
+    let schedule = config.daemon_schedule.as_deref().ok_or_else(|| {
+        anyhow::anyhow!(McpTasksError::Config(
+            "No schedule given: pass --schedule or set schedule in the [daemon] config section"
+                .to_string()
+        ))
+    })?;
+    let schedule = daemon::parse_schedule(schedule).map_err(McpTasksError::Config)?;
+
+    let prompt_template = config
+        .prompt_template
+        .as_deref()
+        .map(|spec| prompts::resolve_template(spec, &config.prompt_templates))
+        .transpose()?;
+
+    // Connect up front so a misconfigured server fails immediately, not at the first run
+    pool.default_client().await?;
+
+    let deepseek_client = DeepSeekClient::new(&config)?
+        .with_prompt_template(prompt_template)
+        // Nobody is around to confirm changes on a schedule
+        .with_mutation_policy(config.mutation_policy(MutationPolicy::Deny));
+
+    info!("Starting daemon with schedule '{}'", schedule.pattern);
+    daemon::run_daemon(&deepseek_client, pool, &config.data_dir, schedule, options).await
+}
+
+async fn handle_export_command(
+    config: Config,
+    pool: &McpClientPool,
+    output_path: PathBuf,
+    format: ExportFormat,
+    status: Option<TaskStatus>,
+    columns: Vec<ExportColumn>,
+) -> Result<()> {
+    info!("Exporting tasks to {}", output_path.display());
 
-    // Initialize logger
-    if cli.verbose {
-        logger::setup_logger_with_level(tracing::Level::DEBUG)?;
-    } else {
-        logger::init_logger()?;
-    }
+    let mcp_client = pool.default_client().await?;
 
-    // Load configuration
-    let config = match Config::from_env() {
-        Ok(config) => {
-            config.validate()?;
-            config
-        }
-        Err(e) => {
-            error!("Failed to load configuration: {}", e);
-            eprintln!("Error: {}", e);
-            eprintln!("\nPlease ensure you have set the following environment variables:");
-            eprintln!(
-                "- MCP_SERVER_COMMAND (optional): MCP server command (default: ./mcp_todo_task)"
-            );
-            eprintln!("- MCP_SERVER_ARGS (optional): MCP server arguments (default: empty)");
-            eprintln!(
-                "\nYou can create a .env file with these variables or export them in your shell."
-            );
-            std::process::exit(1);
-        }
+    let tasks = match status {
+        Some(status) => mcp_client.get_tasks_by_status(status).await?,
+        None => mcp_client.get_all_tasks().await?,
     };
 
-    info!("MCP Tasks application started");
+    if format == ExportFormat::Obsidian {
+        let summary = obsidian::export_vault(&tasks, &output_path)?;
 
-    match cli.command {
-        Commands::List => {
-            handle_list_command(config).await?;
-        }
-        Commands::Tools => {
-            handle_tools_list_command(config).await?;
+        enotice!(
+            "💾 Exported {} tasks to {} ({} written, {} unchanged, {} removed)",
+            tasks.len(),
+            output_path.display(),
+            summary.written,
+            summary.unchanged,
+            summary.removed
+        );
+
+        if config.display_format == DisplayFormat::Json {
+            println!(
+                "{}",
+                output::to_json(&serde_json::json!({
+                    "path": output_path,
+                    "count": tasks.len(),
+                    "written": summary.written,
+                    "unchanged": summary.unchanged,
+                    "removed": summary.removed,
+                }))?
+            );
         }
-        Commands::Stats => {
-            handle_stats_command(config).await?;
+        return Ok(());
+    }
+
+    export::export_tasks(&tasks, &columns, format, &output_path)?;
+
+    // Status goes to stderr so stdout stays clean when the file is piped elsewhere
+    enotice!(
+        "💾 Exported {} tasks to {} ({})",
+        tasks.len(),
+        output_path.display(),
+        match format {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Xlsx => "Excel",
+            ExportFormat::Obsidian => "Markdown notes",
         }
-        Commands::Status { status } => {
-            handle_status_command(config, status).await?;
+    );
+
+    if config.display_format == DisplayFormat::Json {
+        println!(
+            "{}",
+            output::to_json(&serde_json::json!({
+                "path": output_path,
+                "count": tasks.len(),
+            }))?
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_import_command(
+    config: Config,
+    pool: &McpClientPool,
+    source: ImportSource,
+) -> Result<()> {
+    pool.default_client()
+        .await?
+        .require(Capability::Create)
+        .await?;
+
+    let (label, new_tasks, yes) = match source {
+        ImportSource::File { path, yes } => {
+            info!("Importing tasks from {}", path.display());
+            let tasks = task_file::read_task_file(&path)?;
+            (path.display().to_string(), tasks, yes)
         }
-        Commands::Analyze => {
-            handle_analyze_command(config).await?;
+        ImportSource::Todoist {
+            token,
+            project,
+            yes,
+        } => {
+            let token = token.or(config.todoist_token.clone()).ok_or_else(|| {
+                McpTasksError::Config(format!(
+                    "A Todoist API token is needed: pass --token, set {} or [todoist] token in config",
+                    todoist::TODOIST_TOKEN_ENV
+                ))
+            })?;
+            info!("Importing tasks from Todoist");
+
+            let client = TodoistClient::new(&config.todoist_api_url, token);
+            let projects = client.projects().await?;
+            let mut tasks = client.tasks().await?;
+
+            if let Some(name) = project {
+                let project = projects
+                    .iter()
+                    .find(|project| project.name.eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| {
+                        McpTasksError::Import(format!("No Todoist project named '{}'", name))
+                    })?;
+                tasks.retain(|task| task.project_id.as_deref() == Some(project.id.as_str()));
+            }
+
+            (
+                "Todoist".to_string(),
+                todoist::to_imported_tasks(&tasks, &projects),
+                yes,
+            )
         }
-        Commands::AnalyzeWithTools { output } => {
-            handle_analyze_with_tools_command(config, output).await?;
+    };
+
+    if new_tasks.is_empty() {
+        return print_no_changes(&config, "🤷 Nothing to import.");
+    }
+
+    // Keep stdout clean for machine-readable output; the preview is for the person confirming
+    let preview = TaskTableFormatter::format_import_preview(&label, &new_tasks);
+    if config.display_format == DisplayFormat::Table {
+        say!("{}", preview);
+    } else {
+        esay!("{}", preview);
+    }
+
+    let question = format!("Create {} tasks on the MCP server?", new_tasks.len());
+    if !yes && !tooling::confirm(&question)? {
+        return print_no_changes(&config, "❎ Cancelled, no tasks were created.");
+    }
+
+    let mcp_client = pool.default_client().await?;
+    let spinner = Spinner::start("creating tasks");
+    let results = import::create_tasks(mcp_client, &new_tasks).await;
+    drop(spinner);
+
+    print_created_tasks(&config, &format!("Import from {}", label), &results)
+}
+
+/// Report the per-task results of creating tasks, and fail if any could not be
+/// created; the successful ones stay on the server
+fn print_created_tasks(config: &Config, heading: &str, results: &[bulk::BulkResult]) -> Result<()> {
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!(
+                "{}",
+                TaskTableFormatter::format_change_results(heading, results)
+            );
         }
+        DisplayFormat::Json => println!("{}", output::to_json(&results)?),
+        DisplayFormat::Csv => print!("{}", output::bulk_results_to_csv(results)),
+    }
+
+    let failed = results.iter().filter(|result| !result.ok).count();
+    if failed > 0 {
+        anyhow::bail!(McpTasksError::McpProtocol(format!(
+            "{} of {} tasks failed",
+            failed,
+            results.len()
+        )));
     }
 
     Ok(())
 }
 
-async fn handle_analyze_command(config: Config) -> Result<()> {
-    info!("Starting DeepSeek analysis of pending tasks");
+async fn handle_template_command(
+    config: Config,
+    pool: &McpClientPool,
+    command: TemplateCommands,
+) -> Result<()> {
+    let store = TemplateStore::new(&config.data_dir);
+
+    match command {
+        TemplateCommands::Add {
+            name,
+            title,
+            description,
+            priority,
+            tags,
+            due,
+        } => {
+            if config.task_templates.contains_key(&name) && !store.load()?.contains_key(&name) {
+                anyhow::bail!(McpTasksError::Config(format!(
+                    "Template '{}' is defined in the config file; edit it there",
+                    name
+                )));
+            }
 
-    // Create MCP client
-    let mcp_client = McpClient::new(&config).await?;
+            store.add_task(
+                &name,
+                TemplateTask {
+                    title,
+                    description,
+                    priority,
+                    tags,
+                    due,
+                },
+            )?;
+            say!(
+                "✅ Added a task to template '{}' ({})",
+                name,
+                store.path().display()
+            );
+        }
+        TemplateCommands::List => {
+            let templates = template::all_templates(&config.task_templates, &store)?;
 
-    // Fetch pending tasks
-    let pending_tasks = mcp_client.get_tasks_by_status("pending").await?;
+            match config.display_format {
+                DisplayFormat::Table if templates.is_empty() => {
+                    say!(
+                        "🤷 No templates yet. Add one with `template add` or [templates.<name>] in config."
+                    );
+                }
+                DisplayFormat::Table => {
+                    say!("{}", TaskTableFormatter::format_templates(&templates));
+                }
+                DisplayFormat::Json => println!("{}", output::to_json(&templates)?),
+                DisplayFormat::Csv => print!("{}", output::templates_to_csv(&templates)),
+            }
+        }
+        TemplateCommands::Apply { name, on, yes } => {
+            let templates = template::all_templates(&config.task_templates, &store)?;
+            let Some(named) = templates.iter().find(|named| named.name == name) else {
+                let known = templates
+                    .iter()
+                    .map(|named| named.name.as_str())
+                    .collect::<Vec<_>>();
+                anyhow::bail!(McpTasksError::Config(if known.is_empty() {
+                    format!("Unknown template '{}': no templates are defined", name)
+                } else {
+                    format!(
+                        "Unknown template '{}'. Known templates: {}",
+                        name,
+                        known.join(", ")
+                    )
+                }));
+            };
 
-    if pending_tasks.is_empty() {
-        println!("🎉 No pending tasks found to analyze!");
-        return Ok(());
+            let day = on.unwrap_or_else(|| dates::local_date(Utc::now()));
+            let new_tasks = named.template.instantiate(day)?;
+            if new_tasks.is_empty() {
+                return print_no_changes(&config, &format!("🤷 Template '{}' has no tasks.", name));
+            }
+
+            let label = format!("template '{}' on {}", name, day);
+            let preview = TaskTableFormatter::format_import_preview(&label, &new_tasks);
+            if config.display_format == DisplayFormat::Table {
+                say!("{}", preview);
+            } else {
+                esay!("{}", preview);
+            }
+
+            let question = format!("Create {} tasks on the MCP server?", new_tasks.len());
+            if !yes && !tooling::confirm(&question)? {
+                return print_no_changes(&config, "❎ Cancelled, no tasks were created.");
+            }
+
+            let mcp_client = pool.default_client().await?;
+            let spinner = Spinner::start("creating tasks");
+            let results = import::create_tasks(mcp_client, &new_tasks).await;
+            drop(spinner);
+
+            print_created_tasks(&config, &format!("Tasks from {}", label), &results)?;
+        }
     }
 
-    info!("Found {} pending tasks for analysis", pending_tasks.len());
+    Ok(())
+}
 
-    // Create DeepSeek client
-    let deepseek_client = DeepSeekClient::new().map_err(|e| {
-        error!("Failed to create DeepSeek client: {}", e);
-        eprintln!("❌ Failed to initialize DeepSeek client: {}", e);
-        eprintln!("\nPlease ensure you have set the DEEPSEEK_API_KEY environment variable.");
-        eprintln!("You can add it to your .env file or export it in your shell:");
-        eprintln!("export DEEPSEEK_API_KEY=your_api_key_here");
-        e
-    })?;
+async fn handle_snapshot_command(
+    config: Config,
+    pool: &McpClientPool,
+    command: SnapshotCommands,
+) -> Result<()> {
+    match command {
+        SnapshotCommands::Save { output } => {
+            info!("Saving a snapshot of all tasks");
 
-    // Show pending tasks before analysis
-    println!("\n📋 Found {} pending tasks:", pending_tasks.len());
-    for (idx, task) in pending_tasks.iter().enumerate() {
-        println!("  {}. {} (Status: {})", idx + 1, task.title, task.status);
-        if let Some(priority) = &task.priority {
-            println!("     Priority: {}", priority);
+            let tasks = pool.default_client().await?.get_all_tasks().await?;
+            let snapshot = TaskSnapshot::new(&config.default_server, tasks, Utc::now());
+            let path = output.unwrap_or_else(|| snapshot.default_path(&config.data_dir));
+            snapshot.save(&path)?;
+
+            say!(
+                "💾 Saved {} tasks to {}",
+                snapshot.task_count,
+                path.display()
+            );
         }
-        if let Some(due_date) = &task.due_date {
-            println!("     Due: {}", due_date);
+        SnapshotCommands::Diff { from, to } => {
+            let old = TaskSnapshot::load(&archive::resolve_path(&config.data_dir, &from))?;
+            let new = TaskSnapshot::load(&archive::resolve_path(&config.data_dir, &to))?;
+            if old.server != new.server {
+                enotice!(
+                    "⚠️  Comparing snapshots of different servers ({} and {})",
+                    old.server,
+                    new.server
+                );
+            }
+
+            let diff = SnapshotDiff::between(&old, &new);
+            match config.display_format {
+                DisplayFormat::Table => {
+                    say!("{}", TaskTableFormatter::format_snapshot_diff(&diff))
+                }
+                DisplayFormat::Json => println!("{}", output::to_json(&diff)?),
+                DisplayFormat::Csv => print!("{}", output::snapshot_diff_to_csv(&diff)),
+            }
         }
     }
 
-    println!("\n🤖 Analyzing tasks with DeepSeek AI...\n");
+    Ok(())
+}
 
-    // Analyze the tasks using DeepSeek
-    match deepseek_client.analyze_tasks(pending_tasks).await {
-        Ok(analysis) => {
-            println!("📊 DeepSeek Analysis Results:\n");
-            println!("{}", analysis);
-        }
-        Err(e) => {
-            error!("DeepSeek analysis failed: {}", e);
-            eprintln!("❌ Failed to analyze tasks: {}", e);
-            eprintln!("\nPlease check:");
-            eprintln!("1. Your DEEPSEEK_API_KEY is valid");
-            eprintln!("2. You have sufficient API credits");
-            eprintln!("3. Your internet connection is working");
-            std::process::exit(1);
+async fn handle_add_command(config: Config, pool: &McpClientPool, new_task: NewTask) -> Result<()> {
+    info!("Creating task '{}' on MCP server", new_task.title);
+
+    let mcp_client = pool.default_client().await?;
+    mcp_client.require(Capability::Create).await?;
+
+    let task = mcp_client.create_task(&new_task).await?;
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            let table_output = TaskTableFormatter::format_single_task(&task, "✅ Task created");
+            say!("{}", table_output);
         }
+        DisplayFormat::Json => println!("{}", output::to_json(&task)?),
+        DisplayFormat::Csv => print!("{}", output::tasks_to_csv(std::slice::from_ref(&task))),
     }
 
     Ok(())
 }
 
-async fn handle_analyze_with_tools_command(
+async fn handle_get_command(config: Config, pool: &McpClientPool, id: String) -> Result<()> {
+    info!("Fetching task {}", id);
+
+    let task = pool.get_task(&pool.task_ref(&id)).await?;
+
+    match config.display_format {
+        DisplayFormat::Table => say!(
+            "{}",
+            TaskTableFormatter::format_task_card(&task, Utc::now()).trim_end()
+        ),
+        DisplayFormat::Json => println!("{}", output::to_json(&task)?),
+        DisplayFormat::Csv => print!("{}", output::tasks_to_csv(std::slice::from_ref(&task))),
+    }
+
+    Ok(())
+}
+
+async fn handle_complete_command(config: Config, pool: &McpClientPool, id: String) -> Result<()> {
+    info!("Completing task {}", id);
+
+    let task = pool.task_ref(&id);
+    let mcp_client = pool.client_for(&task).await?;
+    mcp_client.require(Capability::Complete).await?;
+
+    let before = pool.get_task(&task).await?;
+    let mut after = mcp_client.complete_task(&task.id).await?;
+    after.server = task.server;
+
+    print_task_change(&config, &before, &after, "✅ Task completed")
+}
+
+async fn handle_update_command(
     config: Config,
-    output_file: Option<String>,
+    pool: &McpClientPool,
+    id: String,
+    update: TaskUpdate,
 ) -> Result<()> {
-    info!("Starting DeepSeek analysis with MCP tools");
+    info!("Updating task {}", id);
 
-    // Create MCP client
-    let mcp_client = McpClient::new(&config).await?;
+    let task = pool.task_ref(&id);
+    let mcp_client = pool.client_for(&task).await?;
+    mcp_client.require(Capability::Update).await?;
 
-    // Fetch pending tasks
-    let pending_tasks = mcp_client.get_tasks_by_status("pending").await?;
+    let before = pool.get_task(&task).await?;
+    let mut after = mcp_client.update_task(&task.id, &update).await?;
+    after.server = task.server;
 
-    if pending_tasks.is_empty() {
-        println!("🎉 No pending tasks found to analyze!");
+    print_task_change(&config, &before, &after, "✏️  Task updated")
+}
+
+async fn handle_start_command(config: Config, pool: &McpClientPool, id: String) -> Result<()> {
+    // Timers store the task as `task_ref` renders it, so compare in that form
+    let task = pool.task_ref(&id);
+    let time_log = TimeLog::new(&config.data_dir);
+    if let Some(timer) = time_log.running()?
+        && timer.task_id == task.to_string()
+    {
+        say!(
+            "⏱️  Already tracking '{}' ({}) for {}",
+            timer.title,
+            timer.task_id,
+            timetrack::format_elapsed(timer.start, Utc::now())
+        );
         return Ok(());
     }
 
-    info!(
-        "Found {} pending tasks for tool-enabled analysis",
-        pending_tasks.len()
+    let task = pool.get_task(&task).await?;
+    let timer = RunningTimer {
+        task_id: task.task_ref().to_string(),
+        title: task.title,
+        start: Utc::now(),
+    };
+
+    if let Some(previous) = time_log.start(&timer)? {
+        say!(
+            "⏹️  Stopped '{}' ({}) after {}",
+            previous.title,
+            previous.task_id,
+            timetrack::format_elapsed(previous.start, previous.end)
+        );
+    }
+    say!(
+        "⏱️  Started tracking '{}' ({}) at {}",
+        timer.title,
+        timer.task_id,
+        dates::format_instant(timer.start, "%H:%M")
     );
 
-    // Create DeepSeek client
-    let deepseek_client = DeepSeekClient::new().map_err(|e| {
-        error!("Failed to create DeepSeek client: {}", e);
-        eprintln!("❌ Failed to initialize DeepSeek client: {}", e);
-        eprintln!("\nPlease ensure you have set the DEEPSEEK_API_KEY environment variable.");
-        eprintln!("You can add it to your .env file or export it in your shell:");
-        eprintln!("export DEEPSEEK_API_KEY=your_api_key_here");
-        e
-    })?;
+    Ok(())
+}
 
-    // Show pending tasks before analysis
-    println!("\n📋 Found {} pending tasks:", pending_tasks.len());
-    for (idx, task) in pending_tasks.iter().enumerate() {
-        println!("  {}. {} (Status: {})", idx + 1, task.title, task.status);
-        if let Some(priority) = &task.priority {
-            println!("     Priority: {}", priority);
-        }
-        if let Some(due_date) = &task.due_date {
-            println!("     Due: {}", due_date);
+fn handle_stop_command(config: Config) -> Result<()> {
+    match TimeLog::new(&config.data_dir).stop(Utc::now())? {
+        Some(entry) => say!(
+            "⏹️  Stopped '{}' ({}) after {}",
+            entry.title,
+            entry.task_id,
+            timetrack::format_elapsed(entry.start, entry.end)
+        ),
+        None => say!("🤷 No timer is running."),
+    }
+    Ok(())
+}
+
+fn handle_timesheet_command(
+    config: Config,
+    since: Option<NaiveDate>,
+    task: Option<String>,
+) -> Result<()> {
+    let time_log = TimeLog::new(&config.data_dir);
+    let now = Utc::now();
+    let since = since.unwrap_or_else(|| dates::local_date(now) - chrono::Days::new(6));
+    let timesheet = Timesheet::build(time_log.entries_until(now)?, since, task.as_deref());
+
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_timesheet(&timesheet));
+            if let Some(timer) = time_log.running()? {
+                say!(
+                    "⏱️  Running: '{}' ({}) for {}",
+                    timer.title,
+                    timer.task_id,
+                    timetrack::format_elapsed(timer.start, now)
+                );
+            }
         }
+        DisplayFormat::Json => println!("{}", output::to_json(&timesheet)?),
+        DisplayFormat::Csv => print!("{}", output::time_entries_to_csv(&timesheet.entries)),
     }
 
-    println!("\n🚀 Analyzing tasks with DeepSeek AI using MCP tools...");
-    println!("📡 The AI can now query the MCP server directly for real-time task data!\n");
+    Ok(())
+}
 
-    // Analyze the tasks using DeepSeek with MCP tools
-    match deepseek_client
-        .analyze_tasks_with_tools_report(pending_tasks, &mcp_client)
-        .await
-    {
-        Ok(report) => {
-            println!("🔧 DeepSeek Analysis with MCP Tools:\n");
-            println!("{}", report.analysis);
+fn handle_history_command(config: Config, command: HistoryCommands) -> Result<()> {
+    let store = HistoryStore::new(&config.data_dir);
 
-            // Save to file if output path is specified
-            if let Some(output_path) = output_file {
-                match deepseek_client
-                    .save_analysis_report(&report, &output_path)
-                    .await
-                {
-                    Ok(_) => {
-                        let format_desc = match output_path.rsplit('.').next() {
-                            Some("json") => "JSON format (structured data)",
-                            Some("md") | Some("markdown") => "Markdown format (email-friendly)",
-                            Some("txt") | Some("text") => {
-                                "Plain text format (universal compatibility)"
-                            }
-                            _ => "Markdown format (email-friendly, default)",
-                        };
+    match command {
+        HistoryCommands::List => {
+            let entries = store.entries()?;
+            let summaries = entries.iter().map(HistorySummary::from).collect::<Vec<_>>();
 
-                        println!("\n💾 Analysis report saved to: {}", output_path);
-                        println!("📧 Format: {}", format_desc);
-                        info!(
-                            "Report saved with {} tasks and {} tool calls",
-                            report.task_count,
-                            report.metadata.tool_calls_count.unwrap_or(0)
-                        );
-                    }
-                    Err(e) => {
-                        error!("Failed to save analysis report: {}", e);
-                        eprintln!(
-                            "⚠️  Warning: Failed to save report to {}: {}",
-                            output_path, e
+            match config.display_format {
+                DisplayFormat::Table => {
+                    say!("{}", TaskTableFormatter::format_history(&summaries))
+                }
+                DisplayFormat::Json => println!("{}", output::to_json(&summaries)?),
+                DisplayFormat::Csv => {
+                    println!(
+                        "id,timestamp,model,task_count,tools_enabled,tool_calls_count,analysis_duration_seconds"
+                    );
+                    for summary in &summaries {
+                        print!(
+                            "{}",
+                            output::csv_row(&[
+                                summary.id.to_string(),
+                                summary.timestamp.clone(),
+                                summary.model.clone(),
+                                summary.task_count.to_string(),
+                                summary.tools_enabled.to_string(),
+                                summary
+                                    .tool_calls_count
+                                    .map(|c| c.to_string())
+                                    .unwrap_or_default(),
+                                summary
+                                    .analysis_duration_seconds
+                                    .map(|d| format!("{:.1}", d))
+                                    .unwrap_or_default(),
+                            ])
                         );
-                        eprintln!("Analysis completed successfully but report could not be saved.");
                     }
                 }
             }
         }
-        Err(e) => {
-            error!("DeepSeek tool-enabled analysis failed: {}", e);
-            eprintln!("❌ Failed to analyze tasks with tools: {}", e);
-            eprintln!("\nPlease check:");
-            eprintln!("1. Your DEEPSEEK_API_KEY is valid");
-            eprintln!("2. You have sufficient API credits");
-            eprintln!("3. Your internet connection is working");
-            eprintln!("4. The MCP server is running correctly");
-            std::process::exit(1);
+        HistoryCommands::Show { id } => {
+            let entry = store.get(id)?;
+            match config.display_format {
+                DisplayFormat::Json => println!("{}", output::to_json(&entry)?),
+                _ => say!("{}", report::format_report_as_markdown(&entry.report)),
+            }
+        }
+        HistoryCommands::Diff { a, b } => {
+            let old = store.get(a)?;
+            let new = store.get(b)?;
+            say!("{}", history::diff_entries(&old, &new));
         }
     }
 
     Ok(())
 }
 
-async fn handle_list_command(config: Config) -> Result<()> {
-    info!("Fetching tasks from MCP server");
-
-    // Create MCP client
-    let mcp_client = McpClient::new(&config).await?;
-
-    // Fetch all tasks
-    let all_tasks = mcp_client.get_all_tasks().await?;
+fn handle_usage_command(config: Config, since: Option<NaiveDate>) -> Result<()> {
+    let entries = HistoryStore::new(&config.data_dir).entries()?;
+    let summaries = usage::summarize(&entries, since, &config.pricing);
 
-    // Show the task table
-    let table_output = TaskTableFormatter::format_all_tasks(&all_tasks)?;
-    println!("{}", table_output);
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_usage(&summaries));
+            if let Some(monthly) = config.monthly_budget {
+                let spent = HistoryStore::new(&config.data_dir)
+                    .spend_since(budget::start_of_month(chrono::Utc::now()))?;
+                notice!(
+                    "\n💳 This month: ${:.4} of the ${} budget ({:.0}%)",
+                    spent,
+                    monthly,
+                    spent * 100.0 / monthly
+                );
+            }
+        }
+        DisplayFormat::Json => println!("{}", output::to_json(&summaries)?),
+        DisplayFormat::Csv => {
+            println!(
+                "model,runs,runs_without_usage,requests,prompt_tokens,cached_prompt_tokens,completion_tokens,cost_usd"
+            );
+            for summary in &summaries {
+                print!(
+                    "{}",
+                    output::csv_row(&[
+                        summary.model.clone(),
+                        summary.runs.to_string(),
+                        summary.runs_without_usage.to_string(),
+                        summary.usage.requests.to_string(),
+                        summary.usage.prompt_tokens.to_string(),
+                        summary.usage.cached_prompt_tokens.to_string(),
+                        summary.usage.completion_tokens.to_string(),
+                        format!("{:.6}", summary.cost_usd),
+                    ])
+                );
+            }
+        }
+    }
 
     Ok(())
 }
 
-async fn handle_tools_list_command(config: Config) -> Result<()> {
-    info!("Getting list of available tools from MCP server");
+fn handle_auth_command(config: Config, command: AuthCommands) -> Result<()> {
+    match command {
+        AuthCommands::SetKey => {
+            let api_key = read_api_key()?;
+            keyring::set(keyring::DEEPSEEK_ACCOUNT, &api_key)?;
+            say!(
+                "✅ Stored the DeepSeek API key ({}) in the {}",
+                keyring::mask(&api_key),
+                keyring::backend()
+            );
+            if std::env::var_os("DEEPSEEK_API_KEY").is_some() || config.deepseek_api_key.is_some() {
+                say!(
+                    "⚠️  DEEPSEEK_API_KEY or [deepseek] api_key is set and takes precedence; remove it to use the stored key"
+                );
+            }
+        }
+        AuthCommands::Status => {
+            let stored = keyring::get(keyring::DEEPSEEK_ACCOUNT);
+            let from_env = std::env::var("DEEPSEEK_API_KEY").ok();
 
-    let mcp_client = McpClient::new(&config).await?;
+            say!("🔑 DeepSeek API key");
+            let describe = |key: Option<&String>| match key {
+                Some(key) => format!("set ({})", keyring::mask(key)),
+                None => "not set".to_string(),
+            };
+            say!(
+                "   Environment (DEEPSEEK_API_KEY): {}",
+                describe(from_env.as_ref())
+            );
+            say!(
+                "   Config file ([deepseek] api_key): {}",
+                describe(
+                    config
+                        .deepseek_api_key
+                        .as_ref()
+                        .filter(|_| from_env.is_none())
+                )
+            );
+            match &stored {
+                Ok(key) => say!("   {}: {}", keyring::backend(), describe(key.as_ref())),
+                Err(e) => say!("   {}: unavailable ({:#})", keyring::backend(), e),
+            }
 
-    match mcp_client.get_tools_list().await {
-        Ok(tools) => {
-            if tools.is_empty() {
-                println!("No tools available on the MCP server");
+            let source = if from_env.is_some() {
+                "environment"
+            } else if config.deepseek_api_key.is_some() {
+                "config file"
+            } else if matches!(stored, Ok(Some(_))) {
+                "keychain"
             } else {
-                println!("Available tools on MCP server:");
-                println!();
-                for (index, tool) in tools.iter().enumerate() {
-                    println!("{}. {}", index + 1, tool.name);
-                    if let Some(description) = &tool.description {
-                        println!("   Description: {}", description);
-                    } else {
-                        println!("   Description: <No description available>");
-                    }
-                    let schema_value = tool.schema_as_json_value();
-                    if let Some(properties) = schema_value.get("properties")
-                        && let Some(props_obj) = properties.as_object()
-                        && !props_obj.is_empty()
-                    {
-                        println!(
-                            "   Parameters: {}",
-                            props_obj.keys().cloned().collect::<Vec<_>>().join(", ")
-                        );
-                    }
-                    println!();
-                }
-            }
+                "none; AI commands with the DeepSeek provider will fail"
+            };
+            say!("   In use: {}", source);
         }
-        Err(e) => {
-            error!("Failed to get tools list: {}", e);
-            eprintln!("❌ Failed to get tools list: {}", e);
-            eprintln!(
-                "Please ensure the MCP server command is correct: {}",
-                config.mcp_server_command
-            );
-            std::process::exit(1);
+        AuthCommands::DeleteKey => {
+            if keyring::delete(keyring::DEEPSEEK_ACCOUNT)? {
+                say!(
+                    "🗑️  Removed the DeepSeek API key from the {}",
+                    keyring::backend()
+                );
+            } else {
+                say!(
+                    "No DeepSeek API key is stored in the {}",
+                    keyring::backend()
+                );
+            }
         }
     }
-
     Ok(())
 }
 
-async fn handle_stats_command(config: Config) -> Result<()> {
-    info!("Fetching task statistics");
-
-    let mcp_client = McpClient::new(&config).await?;
+/// Ask for the key without echoing it, or read it from piped stdin
+fn read_api_key() -> Result<String> {
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use ratatui::crossterm::terminal;
+    use std::io::IsTerminal;
 
-    // Fetch all tasks
-    let all_tasks = mcp_client.get_all_tasks().await?;
-    let unfinished_tasks = mcp_client.get_unfinished_tasks().await?;
-
-    // Display statistics
-    let summary = TaskTableFormatter::format_summary_statistics(&unfinished_tasks, all_tasks.len());
-    println!("{}", summary);
+    let mut api_key = String::new();
+    if !std::io::stdin().is_terminal() {
+        std::io::stdin()
+            .read_line(&mut api_key)
+            .context("Failed to read the API key from stdin")?;
+        return Ok(api_key.trim().to_string());
+    }
 
-    let priority_breakdown = TaskTableFormatter::format_priority_breakdown(&unfinished_tasks);
-    println!("{}", priority_breakdown);
+    eprint!("DeepSeek API key: ");
+    std::io::stderr().flush()?;
+    terminal::enable_raw_mode()?;
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err(anyhow::anyhow!("Cancelled")),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Cancelled"));
+                }
+                KeyCode::Char(c) => api_key.push(c),
+                KeyCode::Backspace => {
+                    api_key.pop();
+                }
+                _ => {}
+            },
+            Ok(Event::Paste(text)) => api_key.push_str(&text),
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|()| api_key.trim().to_string())
+}
 
-    // Show overdue tasks count
-    let overdue_output = TaskTableFormatter::format_overdue_tasks(&unfinished_tasks)?;
-    if !overdue_output.contains("No overdue tasks found") {
-        println!("{}", overdue_output);
-    } else {
-        println!("\n✅ No overdue tasks found!");
+/// Store a finished analysis in the local history; failures only produce a warning
+fn record_history(config: &Config, report: &report::AnalysisReport) {
+    match HistoryStore::new(&config.data_dir).append(report) {
+        Ok(id) => info!("Analysis stored in history as #{}", id),
+        Err(e) => {
+            error!("Failed to store analysis in history: {}", e);
+            esay!("⚠️  Warning: Failed to store analysis in history: {}", e);
+        }
     }
+}
 
+/// Print a structured analysis as a table, its raw JSON, or CSV
+fn print_structured_analysis(config: &Config, analysis: &StructuredAnalysis) -> Result<()> {
+    match config.display_format {
+        DisplayFormat::Table => say!(
+            "{}",
+            TaskTableFormatter::format_structured_analysis(analysis)
+        ),
+        DisplayFormat::Json => println!("{}", output::to_json(analysis)?),
+        DisplayFormat::Csv => print!("{}", output::assessments_to_csv(analysis)),
+    }
     Ok(())
 }
 
-async fn handle_status_command(config: Config, status: String) -> Result<()> {
-    info!("Fetching tasks with status '{}' from MCP server", status);
+/// Print the tokens an analysis used and their estimated cost
+fn print_token_usage(report: &report::AnalysisReport) {
+    if let Some(usage) = &report.metadata.token_usage {
+        notice!(
+            "\n💰 Tokens: {}{}",
+            usage.describe(report.metadata.estimated_cost_usd.unwrap_or_default()),
+            report.metadata.cache_savings_summary()
+        );
+    }
+    if let Some(budget) = Budget::active().filter(|budget| budget.is_limited()) {
+        notice!("💳 Spent: {}", budget.describe());
+    }
+}
 
-    // Create MCP client
-    let mcp_client = McpClient::new(&config).await?;
+fn record_tool_calls(config: &Config, command: &str, report: &report::AnalysisReport) {
+    if let Err(e) = AuditLog::new(&config.data_dir).append(command, &report.tool_calls) {
+        error!("Failed to write tool call audit log: {}", e);
+        esay!("⚠️  Warning: Failed to write tool call audit log: {}", e);
+    }
+}
 
-    // Fetch tasks by status
-    let filtered_tasks = mcp_client.get_tasks_by_status(&status).await?;
+/// Print a mutated task together with the fields that changed
+fn print_task_change(
+    config: &Config,
+    before: &mcp_client::Task,
+    after: &mcp_client::Task,
+    heading: &str,
+) -> Result<()> {
+    match config.display_format {
+        DisplayFormat::Table => {
+            say!("{}", TaskTableFormatter::format_single_task(after, heading));
+            say!("{}", TaskTableFormatter::format_task_diff(before, after));
+        }
+        DisplayFormat::Json => println!(
+            "{}",
+            output::to_json(&serde_json::json!({ "before": before, "after": after }))?
+        ),
+        DisplayFormat::Csv => print!("{}", output::tasks_to_csv(std::slice::from_ref(after))),
+    }
 
-    if filtered_tasks.is_empty() {
-        println!("No tasks found with status '{}'", status);
-        return Ok(());
+    Ok(())
+}
+
+/// Turn `--set` assignments into an update, validating status and due date
+fn bulk_update(assignments: Assignments) -> Result<TaskUpdate> {
+    let mut update = TaskUpdate::default();
+    for (field, value) in assignments.0 {
+        match field.as_str() {
+            "status" => update.status = Some(parse_status(&value)?.as_str().to_string()),
+            "priority" => update.priority = Some(value),
+            "due" | "due_date" => update.due_date = Some(normalize_due_date(&value)?),
+            "assignee" => update.assignee = Some(value),
+            "title" => update.title = Some(value),
+            "tags" => anyhow::bail!(McpTasksError::Config(
+                "Tags cannot be set in bulk; use --add-tag".to_string()
+            )),
+            _ => {
+                update.extra.insert(field, serde_json::json!(value));
+            }
+        }
     }
+    Ok(update)
+}
 
-    // Show the filtered task table
-    let table_output = TaskTableFormatter::format_tasks_by_status(&filtered_tasks, &status)?;
-    println!("{}", table_output);
+/// Validate a user-supplied status; aliases from the config file are accepted
+fn parse_status(input: &str) -> Result<TaskStatus> {
+    Ok(input.parse().map_err(McpTasksError::Config)?)
+}
 
-    Ok(())
+/// Validate a user-supplied due date and normalize it to RFC3339 (UTC).
+/// Date-only input is treated as due at the end of that day in the configured time zone.
+fn normalize_due_date(input: &str) -> Result<String> {
+    match TaskDate::parse(input) {
+        Some(TaskDate::Instant(instant)) => Ok(instant.to_rfc3339()),
+        Some(TaskDate::Day(date)) => Ok(dates::end_of_day(date).to_rfc3339()),
+        None => anyhow::bail!(McpTasksError::Config(format!(
+            "Invalid due date '{}': expected YYYY-MM-DD, RFC3339 (e.g. 2025-07-01T17:00:00Z) or a Unix timestamp",
+            input
+        ))),
+    }
 }
@@ -1,17 +1,42 @@
 use anyhow::{Context, Result};
 use rmcp::{
-    model::{CallToolRequestParam, Tool},
-    service::{Peer, RoleClient, ServiceExt},
-    transport::TokioChildProcess,
+    ClientHandler,
+    model::{
+        CallToolRequestParam, CallToolResult, JsonObject, LoggingLevel,
+        LoggingMessageNotificationParam, ProgressNotificationParam, ProtocolVersion,
+        ReadResourceRequestParam, Resource, ResourceContents, ResourceUpdatedNotificationParam,
+        Tool,
+    },
+    service::{
+        ClientInitializeError, NotificationContext, Peer, RoleClient, RunningService, ServiceError,
+        ServiceExt,
+    },
+    transport::{Transport, async_rw::AsyncRwTransport},
 };
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::process::Stdio;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{debug, error, info};
+use std::time::{Duration, Instant};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::{Notify, OnceCell};
+use tracing::{debug, error, info, warn};
 
-use crate::config::Config;
+use crate::capabilities::{self, Capability, ServerCapabilities};
+use crate::config::{Config, McpServerConfig, McpTransportKind};
+use crate::error::McpTasksError;
+use crate::fields;
+use crate::metrics;
+use crate::progress;
+use crate::recording::{self, RecordingTransport, ReplayTransport};
+use crate::status::TaskStatus;
+use crate::task_ref::TaskRef;
+use crate::websocket::WsTransport;
 
+/// A task in the CLI's shape; server responses are mapped onto it by
+/// [`fields::normalize_task`] before deserializing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
@@ -19,116 +44,943 @@ pub struct Task {
     pub description: Option<String>,
     pub status: String,
     pub priority: Option<String>,
+    #[serde(default)]
+    pub assignee: Option<String>,
     pub due_date: Option<String>,
+    /// Empty when the server does not send a creation date
+    #[serde(default)]
     pub created_at: String,
     pub updated_at: Option<String>,
     pub completed_at: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Alias of the server the task came from, set when listing several servers
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Server fields the CLI does not know, kept so JSON output passes them through
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Task {
+    /// The task's ID, namespaced by its server when it came from a named one
+    pub fn task_ref(&self) -> TaskRef {
+        TaskRef {
+            server: self.server.clone(),
+            id: self.id.clone(),
+        }
+    }
+
+    /// The server's status mapped to a [`TaskStatus`], if it is a known one
+    pub fn normalized_status(&self) -> Option<TaskStatus> {
+        TaskStatus::parse(&self.status)
+    }
+
+    /// Whether the task still needs work, judged by status and completion date
+    pub fn is_unfinished(&self) -> bool {
+        match self.normalized_status() {
+            Some(status) => status.is_open(),
+            // For unknown statuses, check if there's a completion date
+            None => self.completed_at.is_none(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 pub struct TaskListResponse {
     pub tasks: Vec<Task>,
+    #[serde(default)]
     pub count: u32,
     pub filters_applied: Option<serde_json::Value>,
+    /// Matching tasks on all pages, sent by servers that paginate
+    #[serde(default)]
+    pub total: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
-#[allow(dead_code)]
+/// Arguments for the MCP `create_task` tool
+#[derive(Debug, Clone, Serialize)]
+pub struct NewTask {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Field changes for the MCP `update_task` tool; `None` fields are left untouched
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaskUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    /// Replaces all of the task's tags
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Fields outside the CLI's task shape, passed as extra `update_task` arguments
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TaskUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.status.is_none()
+            && self.priority.is_none()
+            && self.assignee.is_none()
+            && self.due_date.is_none()
+            && self.tags.is_none()
+            && self.extra.is_empty()
+    }
+}
+
+/// Parse a single task returned by a tool, either bare or wrapped as `{"task": {...}}`
+fn parse_task_response(json_text: &str) -> Result<Task> {
+    let value: Value = serde_json::from_str(json_text)?;
+    let mut task_value = match value.get("task") {
+        Some(task) => task.clone(),
+        None => value,
+    };
+    if let Some(task) = task_value.as_object_mut() {
+        fields::normalize_task(task);
+    }
+    Ok(serde_json::from_value(task_value)?)
+}
+
+/// Parse a `list_tasks` response, mapping each task's fields
+fn parse_task_list(json_text: &str) -> Result<TaskListResponse> {
+    let mut value: Value = serde_json::from_str(json_text)?;
+    fields::normalize_task_list(&mut value);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Wrap a failed request: an error answer from the server is a protocol error,
+/// anything else (closed pipe, timeout, ...) a transport error
+fn request_error(error: ServiceError, message: String) -> anyhow::Error {
+    let kind = match &error {
+        ServiceError::McpError(_) | ServiceError::UnexpectedResponse => {
+            McpTasksError::McpProtocol(message)
+        }
+        _ => McpTasksError::McpTransport(message),
+    };
+    anyhow::Error::new(error).context(kind)
+}
+
+/// Arguments for a filtered, paginated `list_tasks` call
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct TaskQuery {
+    /// Page number, starting at 1
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
 }
 
-/// Main MCP client that wraps the rmcp client and provides task-specific functionality
-pub struct McpClient {
-    pub client: Arc<Mutex<rmcp::service::RunningService<RoleClient, ()>>>,
+impl TaskQuery {
+    /// Whether `task` passes the status, priority, tag and assignee filters
+    /// (case-insensitive; status aliases count as the same status)
+    pub fn matches(&self, task: &Task) -> bool {
+        let field_matches = |wanted: &Option<String>, value: Option<&str>| match wanted {
+            Some(wanted) => value.is_some_and(|value| value.eq_ignore_ascii_case(wanted)),
+            None => true,
+        };
+        let tag_matches = match &self.tag {
+            Some(tag) => task
+                .tags
+                .iter()
+                .flatten()
+                .any(|t| t.eq_ignore_ascii_case(tag)),
+            None => true,
+        };
+
+        let status_matches = match self.status.as_deref().map(TaskStatus::parse) {
+            Some(Some(status)) => task.normalized_status() == Some(status),
+            _ => field_matches(&self.status, Some(&task.status)),
+        };
+
+        status_matches
+            && field_matches(&self.priority, task.priority.as_deref())
+            && field_matches(&self.assignee, task.assignee.as_deref())
+            && tag_matches
+    }
 }
 
-impl McpClient {
-    pub async fn new(config: &Config) -> Result<Self> {
-        debug!(
-            "Starting MCP server: {} {:?}",
-            config.mcp_server_command, config.mcp_server_args
-        );
+/// Default number of tasks per page for `list` and `status`
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// One page of a task listing
+#[derive(Debug)]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    /// Page number, starting at 1
+    pub page: u32,
+    pub page_size: u32,
+    /// Matching tasks on all pages, when known
+    pub total: Option<usize>,
+}
+
+impl TaskPage {
+    /// Cut page `page` out of a complete list of tasks
+    pub fn from_all(tasks: Vec<Task>, page: u32, page_size: u32) -> Self {
+        let total = tasks.len();
+        let start = (page.saturating_sub(1) as usize).saturating_mul(page_size as usize);
+
+        Self {
+            tasks: tasks
+                .into_iter()
+                .skip(start)
+                .take(page_size as usize)
+                .collect(),
+            page,
+            page_size,
+            total: Some(total),
+        }
+    }
+
+    pub fn total_pages(&self) -> Option<u32> {
+        self.total
+            .map(|total| (total.div_ceil(self.page_size as usize)).max(1) as u32)
+    }
+}
+
+/// Read-only task tools whose results [`ToolCache`] keeps
+const CACHED_TOOLS: &[&str] = &["list_tasks", "get_task", "task_stats"];
+
+/// Results of read-only tool calls, keyed by tool name and arguments. Entries
+/// expire after `ttl`; any other tool call or a server change notification
+/// clears the cache, since it may have changed tasks.
+struct ToolCache {
+    ttl: Duration,
+    entries: std::sync::Mutex<HashMap<String, (Instant, CallToolResult)>>,
+}
 
-        // Create the command for the MCP server
-        let mut command = tokio::process::Command::new(&config.mcp_server_command);
-        command.args(&config.mcp_server_args);
+impl ToolCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
 
-        // Create the transport using TokioChildProcess
-        let transport =
-            TokioChildProcess::new(command).context("Failed to create MCP server transport")?;
+    fn get(&self, key: &str) -> Option<CallToolResult> {
+        let entries = self.entries.lock().ok()?;
+        let (stored_at, result) = entries.get(key)?;
+        (stored_at.elapsed() < self.ttl).then(|| result.clone())
+    }
 
-        // Start the client service with unit type handler
-        let client = ().serve(transport).await.context("Failed to start MCP client service")?;
+    fn insert(&self, key: String, result: CallToolResult) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (Instant::now(), result));
+        }
+    }
 
-        info!("MCP server started and initialized successfully");
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Tools the server offers, looked up on first use and forgotten when the server
+/// announces that its tool list changed
+#[derive(Default)]
+struct ToolList {
+    tools: std::sync::Mutex<Option<Arc<Vec<Tool>>>>,
+}
+
+impl ToolList {
+    fn get(&self) -> Option<Arc<Vec<Tool>>> {
+        self.tools.lock().ok()?.clone()
+    }
+
+    fn set(&self, tools: Arc<Vec<Tool>>) {
+        if let Ok(mut current) = self.tools.lock() {
+            *current = Some(tools);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut current) = self.tools.lock() {
+            *current = None;
+        }
+    }
+}
+
+/// Client handler for server notifications: resource changes wake up watchers and
+/// drop cached results, a changed tool list is looked up again, progress drives the
+/// progress spinner and log messages are forwarded to tracing
+#[derive(Clone)]
+struct NotificationDispatcher {
+    alias: String,
+    changed: Arc<Notify>,
+    cache: Arc<ToolCache>,
+    tools: Arc<ToolList>,
+}
+
+impl ClientHandler for NotificationDispatcher {
+    fn on_resource_updated(
+        &self,
+        _params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.cache.clear();
+        self.changed.notify_one();
+        std::future::ready(())
+    }
+
+    fn on_resource_list_changed(
+        &self,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.cache.clear();
+        self.changed.notify_one();
+        std::future::ready(())
+    }
+
+    fn on_tool_list_changed(
+        &self,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        debug!("Tool list of MCP server '{}' changed", self.alias);
+        self.tools.clear();
+        std::future::ready(())
+    }
+
+    fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        let amount = match params.total {
+            Some(total) => format!("{}/{}", params.progress, total),
+            None => params.progress.to_string(),
+        };
+        let phase = match params
+            .message
+            .as_deref()
+            .filter(|message| !message.is_empty())
+        {
+            Some(message) => format!("{}: {} ({})", self.alias, message, amount),
+            None => format!("{}: {}", self.alias, amount),
+        };
+        debug!("Progress from MCP server {}", phase);
+        progress::set_phase(phase);
+        std::future::ready(())
+    }
+
+    fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        let message = match &params.data {
+            Value::String(text) => text.clone(),
+            data => data.to_string(),
+        };
+        let logger = params.logger.as_deref().unwrap_or("server");
+        match params.level {
+            LoggingLevel::Debug => debug!(server = %self.alias, logger, "{}", message),
+            LoggingLevel::Info | LoggingLevel::Notice => {
+                info!(server = %self.alias, logger, "{}", message)
+            }
+            LoggingLevel::Warning => warn!(server = %self.alias, logger, "{}", message),
+            _ => error!(server = %self.alias, logger, "{}", message),
+        }
+        std::future::ready(())
+    }
+}
+
+/// MCP revisions this client speaks; rmcp offers the newest during initialization
+/// and servers may answer with an older one
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[ProtocolVersion] =
+    &[ProtocolVersion::V_2025_03_26, ProtocolVersion::V_2024_11_05];
+
+/// A running server and the MCP session with it
+struct Connection {
+    service: RunningService<RoleClient, NotificationDispatcher>,
+    /// The server process; WebSocket servers run elsewhere
+    child: Option<Child>,
+    /// Restarts before this connection was opened
+    generation: u32,
+}
+
+impl Connection {
+    /// Spawn or connect to `server` and initialize a session with it
+    async fn open(
+        server: &McpServerConfig,
+        listener: NotificationDispatcher,
+        generation: u32,
+    ) -> Result<Self> {
+        let (service, child) = match server.transport {
+            _ if recording::is_replaying() => {
+                debug!(
+                    "Replaying recorded responses of MCP server '{}'",
+                    server.name
+                );
+                (
+                    listener.serve(ReplayTransport::new(&server.name)).await,
+                    None,
+                )
+            }
+            McpTransportKind::Stdio => {
+                let (mut child, stdout, stdin) = Self::spawn(server)?;
+                // Start the client service; the handler only listens for change notifications
+                let transport = AsyncRwTransport::new_client(stdout, stdin);
+                match Self::serve(server, listener, transport).await {
+                    Ok(service) => (Ok(service), Some(child)),
+                    Err(e) => {
+                        let _ = child.start_kill();
+                        (Err(e), None)
+                    }
+                }
+            }
+            McpTransportKind::Ws => {
+                let url = server.url.as_deref().unwrap_or_default();
+                debug!("Connecting to MCP server '{}' at {}", server.name, url);
+                let transport = WsTransport::connect(url).await.with_context(|| {
+                    McpTasksError::McpTransport(format!(
+                        "Failed to connect to MCP server '{}'",
+                        server.name
+                    ))
+                })?;
+                (Self::serve(server, listener, transport).await, None)
+            }
+        };
+        let service = service.with_context(|| {
+            McpTasksError::McpTransport(format!(
+                "Failed to start MCP client service for '{}'",
+                server.name
+            ))
+        })?;
+
+        if let Some(info) = service.peer_info() {
+            let version = &info.protocol_version;
+            if !SUPPORTED_PROTOCOL_VERSIONS.contains(version) {
+                anyhow::bail!(McpTasksError::McpProtocol(format!(
+                    "MCP server '{}' uses protocol version {}, but only {} are supported",
+                    server.name,
+                    version,
+                    SUPPORTED_PROTOCOL_VERSIONS
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                )));
+            }
+            debug!(
+                "MCP server '{}' uses protocol version {}",
+                server.name, version
+            );
+        }
 
         Ok(Self {
-            client: Arc::new(Mutex::new(client)),
+            service,
+            child,
+            generation,
         })
     }
 
-    /// Get the peer for making requests
-    async fn get_peer(&self) -> Result<Peer<RoleClient>> {
-        let client = self.client.lock().await;
-        // RunningService implements Deref to Peer<RoleClient>, so we can access it directly
-        Ok(client.clone())
+    /// Start the client service on `transport`, recording its traffic with `--record`
+    async fn serve<T>(
+        server: &McpServerConfig,
+        listener: NotificationDispatcher,
+        transport: T,
+    ) -> Result<RunningService<RoleClient, NotificationDispatcher>, ClientInitializeError>
+    where
+        T: Transport<RoleClient> + Send + 'static,
+    {
+        if recording::is_recording() {
+            listener
+                .serve(RecordingTransport::new(&server.name, transport))
+                .await
+        } else {
+            listener.serve(transport).await
+        }
     }
 
-    pub async fn get_all_tasks(&self) -> Result<Vec<Task>> {
-        debug!("Fetching all tasks from MCP server");
+    fn spawn(server: &McpServerConfig) -> Result<(Child, ChildStdout, ChildStdin)> {
+        debug!(
+            "Starting MCP server '{}': {} {:?}",
+            server.name, server.command, server.args
+        );
 
-        let peer = self.get_peer().await?;
+        // Spawn the server ourselves (rather than via TokioChildProcess) so we keep the
+        // process handle and can wait for it to exit on shutdown
+        let mut command = tokio::process::Command::new(&server.command);
+        if let Some(cwd) = &server.cwd {
+            command.current_dir(cwd);
+        }
+        let mut child = command
+            .args(&server.args)
+            .envs(&server.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| {
+                McpTasksError::McpTransport(format!(
+                    "Failed to start MCP server process for '{}'",
+                    server.name
+                ))
+            })?;
 
-        // Call the list_tasks tool
-        let params = CallToolRequestParam {
-            name: Cow::Borrowed("list_tasks"),
-            arguments: None,
+        let (Some(stdout), Some(stdin)) = (child.stdout.take(), child.stdin.take()) else {
+            anyhow::bail!(McpTasksError::McpTransport(format!(
+                "Failed to capture stdio of MCP server '{}'",
+                server.name
+            )));
         };
+        Ok((child, stdout, stdin))
+    }
+
+    /// Whether the server process has exited or the connection is gone for good
+    fn is_dead(&mut self) -> bool {
+        self.service.peer().is_transport_closed()
+            || self
+                .child
+                .as_mut()
+                .is_some_and(|child| child.try_wait().is_ok_and(|status| status.is_some()))
+    }
+}
 
-        let result = peer.call_tool(params).await?;
+/// Main MCP client that wraps the rmcp client and provides task-specific functionality.
+///
+/// rmcp reads server messages on a background task and routes each response to its
+/// request by id, so the client can be shared and used for concurrent calls. When
+/// restarts are enabled, a server that dies mid-session is started again and the
+/// failed request is sent once more.
+pub struct McpClient {
+    pub alias: String,
+    server: McpServerConfig,
+    listener: NotificationDispatcher,
+    connection: std::sync::Mutex<Connection>,
+    /// Held while the server is restarted, so concurrent failures restart it once
+    restarting: tokio::sync::Mutex<()>,
+    /// Restarts allowed over the client's lifetime; 0 turns them off
+    max_restarts: u32,
+    changed: Arc<Notify>,
+    cache: Arc<ToolCache>,
+    tools: Arc<ToolList>,
+}
 
-        // Extract content from the result
-        let content = result.content;
-        if let Some(content_vec) = content {
-            if content_vec.is_empty() {
-                anyhow::bail!("No content returned from MCP server");
-            }
+impl McpClient {
+    /// Start `server` and connect to it; read-only tool results are reused for `cache_ttl`,
+    /// and a server that dies is restarted up to `max_restarts` times
+    pub async fn connect(
+        server: &McpServerConfig,
+        cache_ttl: Duration,
+        max_restarts: u32,
+    ) -> Result<Self> {
+        let listener = NotificationDispatcher {
+            alias: server.name.clone(),
+            changed: Arc::new(Notify::new()),
+            cache: Arc::new(ToolCache::new(cache_ttl)),
+            tools: Arc::new(ToolList::default()),
+        };
+        let connection = Connection::open(server, listener.clone(), 0).await?;
+
+        info!(
+            "MCP server '{}' started and initialized successfully",
+            server.name
+        );
+
+        let client = Self {
+            alias: server.name.clone(),
+            server: server.clone(),
+            changed: listener.changed.clone(),
+            cache: listener.cache.clone(),
+            connection: std::sync::Mutex::new(connection),
+            restarting: tokio::sync::Mutex::new(()),
+            max_restarts,
+            tools: listener.tools.clone(),
+            listener,
+        };
+        // Commands check the tool list before calling tools, so look it up right away
+        client.tools().await;
+
+        Ok(client)
+    }
 
-            // Get the first content item
-            let first_content = &content_vec[0];
-
-            // Parse the raw text content as JSON
-            let json_text = match &first_content.raw {
-                rmcp::model::RawContent::Text(text_content) => &text_content.text,
-                _ => anyhow::bail!("Expected text content from MCP server"),
-            };
-
-            // Parse the JSON text directly
-            match serde_json::from_str::<TaskListResponse>(json_text) {
-                Ok(task_response) => {
-                    debug!(
-                        "Retrieved {} tasks from MCP server",
-                        task_response.tasks.len()
+    /// Stop the server: close the connection (the stdio transport's shutdown signal),
+    /// and for spawned servers wait up to the server's `shutdown_timeout` for the
+    /// process to exit, then kill it
+    pub async fn shutdown(self) {
+        let alias = self.alias;
+        let timeout = Duration::from_secs(self.server.shutdown_timeout);
+        let Connection { service, child, .. } = self
+            .connection
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        debug!("Shutting down MCP server '{}'", alias);
+
+        if let Err(e) = service.cancel().await {
+            warn!("MCP client service for '{}' ended abnormally: {}", alias, e);
+        }
+        let Some(mut child) = child else {
+            return;
+        };
+
+        match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(Ok(status)) => debug!("MCP server '{}' exited with {}", alias, status),
+            Ok(Err(e)) => warn!("Failed to wait for MCP server '{}': {}", alias, e),
+            Err(_) => {
+                if timeout.is_zero() {
+                    debug!("Killing MCP server '{}'", alias);
+                } else {
+                    warn!(
+                        "MCP server '{}' did not exit within {:?}, killing it",
+                        alias, timeout
                     );
-                    Ok(task_response.tasks)
                 }
-                Err(e) => {
-                    error!("Failed to parse tasks response: {}", e);
-                    anyhow::bail!("Failed to parse tasks response from MCP server");
+                if let Err(e) = child.kill().await {
+                    error!("Failed to kill MCP server '{}': {}", alias, e);
                 }
             }
-        } else {
-            anyhow::bail!("No content returned from MCP server");
         }
     }
 
+    fn lock_connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Send a request with `send`. If it fails because the server died and restarts
+    /// are left, the server is started again and the request replayed once.
+    async fn request<T, F, Fut>(&self, send: F) -> Result<T, ServiceError>
+    where
+        F: Fn(Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = Result<T, ServiceError>>,
+    {
+        let (peer, generation) = {
+            let connection = self.lock_connection();
+            (connection.service.peer().clone(), connection.generation)
+        };
+
+        match send(peer).await {
+            Err(error) if self.max_restarts > 0 && self.server_died(&error) => {
+                match self.restart(generation).await {
+                    Ok(true) => send(self.peer()).await,
+                    Ok(false) => Err(error),
+                    Err(e) => {
+                        error!("{:#}", e);
+                        Err(error)
+                    }
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn server_died(&self, error: &ServiceError) -> bool {
+        matches!(
+            error,
+            ServiceError::TransportSend(_)
+                | ServiceError::TransportClosed
+                | ServiceError::Cancelled { .. }
+        ) && self.lock_connection().is_dead()
+    }
+
+    /// Replace the dead connection opened after `failed_generation` restarts; false
+    /// when no restarts are left
+    async fn restart(&self, failed_generation: u32) -> Result<bool> {
+        let _restarting = self.restarting.lock().await;
+        if self.lock_connection().generation != failed_generation {
+            // Another request restarted the server while this one waited
+            return Ok(true);
+        }
+        if failed_generation >= self.max_restarts {
+            debug!(
+                "MCP server '{}' exited and was already restarted {} times",
+                self.alias, failed_generation
+            );
+            return Ok(false);
+        }
+
+        warn!(
+            "MCP server '{}' exited, restarting it ({}/{})",
+            self.alias,
+            failed_generation + 1,
+            self.max_restarts
+        );
+        let connection =
+            Connection::open(&self.server, self.listener.clone(), failed_generation + 1).await?;
+        self.cache.clear();
+        self.tools.clear();
+        // The old session's service task and process go away when dropped
+        let _dead = std::mem::replace(&mut *self.lock_connection(), connection);
+
+        info!("MCP server '{}' restarted", self.alias);
+        Ok(true)
+    }
+
+    /// Resolves when the server sends a resource change notification.
+    /// Servers without notification support simply never wake this up.
+    pub async fn changed(&self) {
+        self.changed.notified().await
+    }
+
+    /// Handle for making requests; cheap to clone and safe to use concurrently
+    pub fn peer(&self) -> Peer<RoleClient> {
+        self.lock_connection().service.peer().clone()
+    }
+
+    /// Forget cached tool results, so the next read goes to the server
+    pub fn invalidate_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Call an MCP tool. Successful results of read-only task tools are served from
+    /// the cache while fresh; calling any other tool clears the cache.
+    pub async fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Option<JsonObject>,
+    ) -> Result<CallToolResult> {
+        let cache_key = CACHED_TOOLS.contains(&tool_name).then(|| {
+            format!(
+                "{}:{}",
+                tool_name,
+                serde_json::to_string(&arguments).unwrap_or_default()
+            )
+        });
+        if let Some(result) = cache_key.as_deref().and_then(|key| self.cache.get(key)) {
+            debug!("Using cached result of MCP tool '{}'", tool_name);
+            return Ok(result);
+        }
+
+        // A tool missing from a known tool list fails here rather than as a tools/call error
+        let tools = self.tools().await;
+        if !tools.is_empty() && !tools.iter().any(|tool| tool.name == tool_name) {
+            anyhow::bail!(capabilities::missing_tool(&self.alias, tool_name));
+        }
+
+        let params = CallToolRequestParam {
+            name: Cow::Owned(tool_name.to_string()),
+            arguments,
+        };
+
+        let started = Instant::now();
+        let result = self
+            .request(|peer| {
+                let params = params.clone();
+                async move { peer.call_tool(params).await }
+            })
+            .await;
+        metrics::record_mcp_call(
+            tool_name,
+            started.elapsed(),
+            result
+                .as_ref()
+                .is_ok_and(|result| !result.is_error.unwrap_or(false)),
+        );
+        let result = result
+            .map_err(|e| request_error(e, format!("Failed to call MCP tool '{}'", tool_name)))?;
+
+        match cache_key {
+            Some(key) if !result.is_error.unwrap_or(false) => {
+                self.cache.insert(key, result.clone())
+            }
+            Some(_) => {}
+            None => self.cache.clear(),
+        }
+
+        Ok(result)
+    }
+
+    /// Call an MCP tool and return the text of its first content item
+    async fn call_tool_text(
+        &self,
+        tool_name: &str,
+        arguments: Option<serde_json::Map<String, serde_json::Value>>,
+    ) -> Result<String> {
+        let result = self.call_tool(tool_name, arguments).await?;
+
+        // Servers on the 2025 revisions may answer with structured content only
+        if let Some(structured) = &result.structured_content
+            && !result.is_error.unwrap_or(false)
+            && result.content.as_ref().is_none_or(Vec::is_empty)
+        {
+            return Ok(structured.to_string());
+        }
+
+        // Extract content from the result
+        let Some(content_vec) = result.content else {
+            anyhow::bail!(McpTasksError::McpProtocol(
+                "No content returned from MCP server".to_string()
+            ));
+        };
+
+        // Get the first content item
+        let Some(first_content) = content_vec.first() else {
+            anyhow::bail!(McpTasksError::McpProtocol(
+                "No content returned from MCP server".to_string()
+            ));
+        };
+
+        let text = match &first_content.raw {
+            rmcp::model::RawContent::Text(text_content) => text_content.text.clone(),
+            _ => anyhow::bail!(McpTasksError::McpProtocol(
+                "Expected text content from MCP server".to_string()
+            )),
+        };
+
+        if result.is_error.unwrap_or(false) {
+            anyhow::bail!(McpTasksError::McpProtocol(format!(
+                "MCP tool '{}' reported an error: {}",
+                tool_name, text
+            )));
+        }
+
+        Ok(text)
+    }
+
+    pub async fn get_all_tasks(&self) -> Result<Vec<Task>> {
+        debug!("Fetching all tasks from MCP server");
+
+        // Call the list_tasks tool
+        let json_text = self.call_tool_text("list_tasks", None).await?;
+
+        // Parse the JSON text directly
+        match parse_task_list(&json_text) {
+            Ok(task_response) => {
+                debug!(
+                    "Retrieved {} tasks from MCP server",
+                    task_response.tasks.len()
+                );
+                Ok(task_response.tasks)
+            }
+            Err(e) => {
+                error!("Failed to parse tasks response: {}", e);
+                anyhow::bail!(McpTasksError::McpProtocol(
+                    "Failed to parse tasks response from MCP server".to_string()
+                ));
+            }
+        }
+    }
+
+    /// Create a task through the server's `create_task` tool
+    pub async fn create_task(&self, new_task: &NewTask) -> Result<Task> {
+        debug!("Creating task '{}' on MCP server", new_task.title);
+
+        let arguments = match serde_json::to_value(new_task)? {
+            serde_json::Value::Object(map) => map,
+            _ => anyhow::bail!("Failed to encode create_task arguments"),
+        };
+
+        let json_text = self.call_tool_text("create_task", Some(arguments)).await?;
+        let task = parse_task_response(&json_text).context(McpTasksError::McpProtocol(
+            "Failed to parse create_task response from MCP server".to_string(),
+        ))?;
+
+        info!("Created task '{}' with id {}", task.title, task.id);
+        Ok(task)
+    }
+
+    /// Fetch a single task through the server's `get_task` tool
+    pub async fn get_task(&self, id: &str) -> Result<Task> {
+        debug!("Fetching task {} from MCP server", id);
+
+        let mut arguments = serde_json::Map::new();
+        arguments.insert("id".to_string(), serde_json::json!(id));
+
+        let json_text = self.call_tool_text("get_task", Some(arguments)).await?;
+        parse_task_response(&json_text).context(McpTasksError::McpProtocol(
+            "Failed to parse get_task response from MCP server".to_string(),
+        ))
+    }
+
+    /// Apply field changes through the server's `update_task` tool
+    pub async fn update_task(&self, id: &str, update: &TaskUpdate) -> Result<Task> {
+        debug!("Updating task {} on MCP server", id);
+
+        let mut arguments = match serde_json::to_value(update)? {
+            serde_json::Value::Object(map) => map,
+            _ => anyhow::bail!("Failed to encode update_task arguments"),
+        };
+        arguments.insert("id".to_string(), serde_json::json!(id));
+
+        let json_text = self.call_tool_text("update_task", Some(arguments)).await?;
+        let task = parse_task_response(&json_text).context(McpTasksError::McpProtocol(
+            "Failed to parse update_task response from MCP server".to_string(),
+        ))?;
+
+        info!("Updated task {}", task.id);
+        Ok(task)
+    }
+
+    /// Mark a task as completed through the server's `complete_task` tool
+    pub async fn complete_task(&self, id: &str) -> Result<Task> {
+        debug!("Completing task {} on MCP server", id);
+
+        let mut arguments = serde_json::Map::new();
+        arguments.insert("id".to_string(), serde_json::json!(id));
+
+        let json_text = self
+            .call_tool_text("complete_task", Some(arguments))
+            .await?;
+        let task = parse_task_response(&json_text).context(McpTasksError::McpProtocol(
+            "Failed to parse complete_task response from MCP server".to_string(),
+        ))?;
+
+        info!("Completed task {}", task.id);
+        Ok(task)
+    }
+
+    /// Delete a task through the server's `delete_task` tool
+    pub async fn delete_task(&self, id: &str) -> Result<()> {
+        debug!("Deleting task {} on MCP server", id);
+
+        let mut arguments = serde_json::Map::new();
+        arguments.insert("id".to_string(), serde_json::json!(id));
+
+        // Servers answer with the deleted task, a message or nothing at all
+        let result = self.call_tool("delete_task", Some(arguments)).await?;
+        if result.is_error.unwrap_or(false) {
+            let text = result
+                .content
+                .iter()
+                .flatten()
+                .find_map(|content| match &content.raw {
+                    rmcp::model::RawContent::Text(text_content) => Some(text_content.text.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            anyhow::bail!(McpTasksError::McpProtocol(format!(
+                "MCP tool 'delete_task' reported an error: {}",
+                text
+            )));
+        }
+
+        info!("Deleted task {}", id);
+        Ok(())
+    }
+
     pub async fn get_unfinished_tasks(&self) -> Result<Vec<Task>> {
         debug!("Fetching unfinished tasks from MCP server");
 
@@ -137,14 +989,14 @@ impl McpClient {
         let all_tasks = self.get_all_tasks().await?;
         let unfinished_tasks = all_tasks
             .into_iter()
-            .filter(|task| self.is_task_unfinished(task))
+            .filter(Task::is_unfinished)
             .collect::<Vec<_>>();
 
         info!("Found {} unfinished tasks", unfinished_tasks.len());
         Ok(unfinished_tasks)
     }
 
-    pub async fn get_tasks_by_status(&self, status: &str) -> Result<Vec<Task>> {
+    pub async fn get_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<Task>> {
         debug!("Fetching tasks with status '{}' from MCP server", status);
 
         // First, let's get all tasks and filter by status
@@ -153,7 +1005,7 @@ impl McpClient {
         let all_tasks = self.get_all_tasks().await?;
         let filtered_tasks = all_tasks
             .into_iter()
-            .filter(|task| task.status.to_lowercase() == status.to_lowercase())
+            .filter(|task| task.normalized_status() == Some(status))
             .collect::<Vec<_>>();
 
         info!(
@@ -164,39 +1016,330 @@ impl McpClient {
         Ok(filtered_tasks)
     }
 
-    fn is_task_unfinished(&self, task: &Task) -> bool {
-        let status = task.status.to_lowercase();
+    /// Fetch one page of tasks matching `query`. Servers whose `list_tasks` tool
+    /// takes `page`/`page_size` do the filtering and paging; otherwise all tasks
+    /// are fetched and paged here.
+    pub async fn get_tasks_page(&self, query: &TaskQuery) -> Result<TaskPage> {
+        let page = query.page.unwrap_or(1);
+        let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
 
-        // Consider task unfinished if:
-        // - Status indicates it's not complete
-        // - Has no completion date but has other indicators
-        match status.as_str() {
-            "completed" | "done" | "finished" | "closed" | "resolved" => false,
-            "pending" | "in_progress" | "todo" | "incomplete" | "new" | "open" | "active" => true,
-            _ => {
-                // For unknown statuses, check if there's a completion date
-                task.completed_at.is_none()
-            }
+        // Filtering by assignee, priority or tag on the server needs a list_tasks
+        // that knows about them
+        let server_filters = self.list_tasks_accepts("page").await
+            && (query.assignee.is_none() || self.list_tasks_accepts("assignee").await)
+            && (query.priority.is_none() || self.list_tasks_accepts("priority").await)
+            && (query.tag.is_none() || self.list_tasks_accepts("tag").await);
+        if !server_filters {
+            let tasks = self
+                .get_all_tasks()
+                .await?
+                .into_iter()
+                .filter(|task| query.matches(task))
+                .collect();
+            return Ok(TaskPage::from_all(tasks, page, page_size));
+        }
+
+        debug!("Fetching page {} of tasks from MCP server", page);
+        let arguments = match serde_json::to_value(TaskQuery {
+            page: Some(page),
+            page_size: Some(page_size),
+            status: query.status.clone(),
+            priority: query.priority.clone(),
+            tag: query.tag.clone(),
+            assignee: query.assignee.clone(),
+        })? {
+            serde_json::Value::Object(map) => map,
+            _ => anyhow::bail!("Failed to encode list_tasks arguments"),
+        };
+
+        let json_text = self.call_tool_text("list_tasks", Some(arguments)).await?;
+        let response = parse_task_list(&json_text).context(McpTasksError::McpProtocol(
+            "Failed to parse tasks response from MCP server".to_string(),
+        ))?;
+
+        Ok(TaskPage {
+            tasks: response.tasks,
+            page,
+            page_size,
+            total: response.total,
+        })
+    }
+
+    /// Whether the server's `list_tasks` tool declares the argument `name`
+    async fn list_tasks_accepts(&self, name: &str) -> bool {
+        self.tool_schema("list_tasks").await.is_some_and(|schema| {
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .is_some_and(|properties| properties.contains_key(name))
+        })
+    }
+
+    /// Input schema of the server's tool `name`
+    pub async fn tool_schema(&self, name: &str) -> Option<Arc<JsonObject>> {
+        self.tool(name).await.map(|tool| tool.input_schema)
+    }
+
+    /// The server's tool `name`, with its schemas and annotations
+    pub async fn tool(&self, name: &str) -> Option<Tool> {
+        self.tools()
+            .await
+            .iter()
+            .find(|tool| tool.name == name)
+            .cloned()
+    }
+
+    /// The server's tools, fetched when it connects and again after the server
+    /// reports a change; if that fails, no tool is known
+    async fn tools(&self) -> Arc<Vec<Tool>> {
+        if let Some(tools) = self.tools.get() {
+            return tools;
+        }
+        let tools = Arc::new(self.get_tools_list().await.unwrap_or_else(|e| {
+            warn!("Could not look up the tools of '{}': {}", self.alias, e);
+            Vec::new()
+        }));
+        self.tools.set(tools.clone());
+        tools
+    }
+
+    /// Summary of what the server can do, from its cached tool list
+    pub async fn capabilities(&self) -> ServerCapabilities {
+        ServerCapabilities {
+            server: self.alias.clone(),
+            tools: self
+                .tools()
+                .await
+                .iter()
+                .map(|tool| tool.name.to_string())
+                .collect(),
+            resources: self.supports_resources(),
         }
     }
 
+    /// Fail fast, before any work is done, when the server lacks the tool `capability` needs
+    pub async fn require(&self, capability: Capability) -> Result<()> {
+        self.capabilities().await.require(capability)
+    }
+
     pub async fn get_tools_list(&self) -> Result<Vec<Tool>> {
         debug!("Getting list of available tools from MCP server");
 
-        let peer = self.get_peer().await?;
-
         // Use the list_tools method from rmcp with default parameters
-        let result = peer.list_tools(Default::default()).await?;
+        let result = self
+            .request(|peer| async move { peer.list_tools(Default::default()).await })
+            .await
+            .map_err(|e| request_error(e, "Failed to list MCP tools".to_string()))?;
 
         debug!("Retrieved {} tools from MCP server", result.tools.len());
 
         Ok(result.tools)
     }
+
+    /// Whether the server advertised the resources capability during initialization
+    pub fn supports_resources(&self) -> bool {
+        self.lock_connection()
+            .service
+            .peer_info()
+            .is_some_and(|info| info.capabilities.resources.is_some())
+    }
+
+    /// List all resources (documents, notes, ...) exposed by the server
+    pub async fn list_resources(&self) -> Result<Vec<Resource>> {
+        debug!("Getting list of resources from MCP server '{}'", self.alias);
+
+        if !self.supports_resources() {
+            anyhow::bail!(McpTasksError::McpProtocol(format!(
+                "MCP server '{}' does not expose resources",
+                self.alias
+            )));
+        }
+
+        let resources = self
+            .request(|peer| async move { peer.list_all_resources().await })
+            .await
+            .map_err(|e| {
+                request_error(
+                    e,
+                    format!("Failed to list resources on MCP server '{}'", self.alias),
+                )
+            })?;
+
+        debug!("Retrieved {} resources from MCP server", resources.len());
+        Ok(resources)
+    }
+
+    /// Read the contents of a resource by URI
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<ResourceContents>> {
+        debug!("Reading resource {} from MCP server '{}'", uri, self.alias);
+
+        let result = self
+            .request(|peer| async move {
+                peer.read_resource(ReadResourceRequestParam {
+                    uri: uri.to_string(),
+                })
+                .await
+            })
+            .await
+            .map_err(|e| request_error(e, format!("Failed to read resource '{}'", uri)))?;
+
+        Ok(result.contents)
+    }
+}
+
+/// Set of named MCP servers, spawned lazily on first use and addressed by alias
+pub struct McpClientPool {
+    servers: HashMap<String, McpServerConfig>,
+    clients: HashMap<String, OnceCell<McpClient>>,
+    default_alias: String,
+    cache_ttl: Duration,
+    max_restarts: u32,
 }
 
-impl Drop for McpClient {
-    fn drop(&mut self) {
-        // The rmcp client will handle cleanup automatically
-        debug!("MCP client dropped, cleaning up resources");
+impl McpClientPool {
+    pub fn new(config: &Config) -> Self {
+        let servers = config
+            .mcp_servers
+            .iter()
+            .map(|server| (server.name.clone(), server.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let clients = servers
+            .keys()
+            .map(|alias| (alias.clone(), OnceCell::new()))
+            .collect();
+
+        Self {
+            servers,
+            clients,
+            default_alias: config.default_server.clone(),
+            cache_ttl: Duration::from_secs(config.cache_ttl),
+            max_restarts: if config.auto_restart {
+                config.max_restarts
+            } else {
+                0
+            },
+        }
+    }
+
+    /// Get the client for `alias`, spawning the server if it is not running yet
+    pub async fn get(&self, alias: &str) -> Result<&McpClient> {
+        let (Some(server), Some(cell)) = (self.servers.get(alias), self.clients.get(alias)) else {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "Unknown MCP server '{}'. Configured servers: {}",
+                alias,
+                self.aliases().join(", ")
+            )));
+        };
+
+        cell.get_or_try_init(|| McpClient::connect(server, self.cache_ttl, self.max_restarts))
+            .await
+    }
+
+    /// Get the client for the default (or `--server` selected) MCP server
+    pub async fn default_client(&self) -> Result<&McpClient> {
+        self.get(&self.default_alias).await
+    }
+
+    /// Shut down every server that was started, in parallel
+    pub async fn shutdown(self) {
+        let clients = self
+            .clients
+            .into_values()
+            .filter_map(OnceCell::into_inner)
+            .map(McpClient::shutdown);
+
+        futures::future::join_all(clients).await;
+    }
+
+    /// Parse a task ID given on the command line or by the model, see [`TaskRef::parse`]
+    pub fn task_ref(&self, value: &str) -> TaskRef {
+        TaskRef::parse(value, &self.aliases())
+    }
+
+    /// Get the client for the server `task` lives on
+    pub async fn client_for(&self, task: &TaskRef) -> Result<&McpClient> {
+        match &task.server {
+            Some(alias) => self.get(alias).await,
+            None => self.default_client().await,
+        }
+    }
+
+    /// Fetch `task` from its server; a namespaced task keeps its server alias
+    pub async fn get_task(&self, task: &TaskRef) -> Result<Task> {
+        let mut fetched = self.client_for(task).await?.get_task(&task.id).await?;
+        fetched.server = task.server.clone();
+        Ok(fetched)
+    }
+
+    /// Every task of every configured server, fetched in parallel, each tagged
+    /// with its server alias so IDs that collide across servers stay apart
+    pub async fn get_all_tasks_from_all_servers(&self) -> Result<Vec<Task>> {
+        let fetches = self.aliases().into_iter().map(|alias| async move {
+            let mut tasks = self
+                .get(&alias)
+                .await?
+                .get_all_tasks()
+                .await
+                .with_context(|| format!("Failed to list tasks of MCP server '{}'", alias))?;
+            for task in &mut tasks {
+                task.server = Some(alias.clone());
+            }
+            Ok::<_, anyhow::Error>(tasks)
+        });
+
+        let tasks = futures::future::try_join_all(fetches).await?;
+        Ok(tasks.into_iter().flatten().collect())
+    }
+
+    /// Sorted aliases of all configured servers
+    pub fn aliases(&self) -> Vec<String> {
+        let mut aliases = self.servers.keys().cloned().collect::<Vec<_>>();
+        aliases.sort();
+        aliases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks(count: usize) -> Vec<Task> {
+        (1..=count)
+            .map(|n| {
+                serde_json::from_value(serde_json::json!({
+                    "id": format!("task-{}", n),
+                    "title": format!("Task {}", n),
+                    "status": "pending",
+                    "created_at": "",
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn ids(page: &TaskPage) -> Vec<&str> {
+        page.tasks.iter().map(|task| task.id.as_str()).collect()
+    }
+
+    #[test]
+    fn from_all_cuts_the_requested_page() {
+        let page = TaskPage::from_all(tasks(5), 2, 2);
+        assert_eq!(ids(&page), ["task-3", "task-4"]);
+        assert_eq!(page.total, Some(5));
+        assert_eq!(page.total_pages(), Some(3));
+
+        let last = TaskPage::from_all(tasks(5), 3, 2);
+        assert_eq!(ids(&last), ["task-5"]);
+    }
+
+    #[test]
+    fn from_all_handles_pages_past_the_end_and_page_zero() {
+        assert!(TaskPage::from_all(tasks(5), 4, 2).tasks.is_empty());
+        assert_eq!(
+            ids(&TaskPage::from_all(tasks(3), 0, 2)),
+            ["task-1", "task-2"]
+        );
+        assert_eq!(TaskPage::from_all(Vec::new(), 1, 50).total_pages(), Some(1));
     }
 }
@@ -0,0 +1,357 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info};
+
+use crate::error::McpTasksError;
+use crate::output::TaskStats;
+use crate::report::AnalysisReport;
+
+/// Upper bounds in seconds of the latency histogram buckets
+const MCP_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+const ANALYSIS_BUCKETS: [f64; 8] = [1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket, not cumulative; the last entry counts those above every bound
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, bounds: &[f64], value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; bounds.len() + 1];
+        }
+        let idx = bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(bounds.len());
+        self.buckets[idx] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str, bounds: &[f64]) {
+        let mut cumulative = 0;
+        for (idx, bound) in bounds.iter().enumerate() {
+            cumulative += self.buckets.get(idx).copied().unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}le=\"{}\"}} {}",
+                name, labels, bound, cumulative
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}le=\"+Inf\"}} {}",
+            name, labels, self.count
+        );
+        let labels = labels.trim_end_matches(',');
+        let braces = |labels: &str| {
+            if labels.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", labels)
+            }
+        };
+        let _ = writeln!(out, "{}_sum{} {}", name, braces(labels), self.sum);
+        let _ = writeln!(out, "{}_count{} {}", name, braces(labels), self.count);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct McpToolMetrics {
+    latency: Histogram,
+    errors: u64,
+}
+
+/// Process-wide counters exposed by the daemon's `/metrics` endpoint
+#[derive(Debug)]
+struct Metrics {
+    tasks: Option<(usize, usize, usize)>,
+    runs: u64,
+    run_failures: u64,
+    last_success: Option<i64>,
+    analysis_duration: Option<Histogram>,
+    prompt_tokens: u64,
+    cached_prompt_tokens: u64,
+    completion_tokens: u64,
+    llm_requests: u64,
+    estimated_cost_usd: f64,
+    mcp_calls: BTreeMap<String, McpToolMetrics>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            tasks: None,
+            runs: 0,
+            run_failures: 0,
+            last_success: None,
+            analysis_duration: None,
+            prompt_tokens: 0,
+            cached_prompt_tokens: 0,
+            completion_tokens: 0,
+            llm_requests: 0,
+            estimated_cost_usd: 0.0,
+            mcp_calls: BTreeMap::new(),
+        }
+    }
+}
+
+fn with_metrics(update: impl FnOnce(&mut Metrics)) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        update(&mut metrics);
+    }
+}
+
+/// Count one MCP tool call that reached the server
+pub fn record_mcp_call(tool: &str, latency: Duration, success: bool) {
+    with_metrics(|metrics| {
+        let entry = metrics.mcp_calls.entry(tool.to_string()).or_default();
+        entry.latency.observe(&MCP_BUCKETS, latency.as_secs_f64());
+        if !success {
+            entry.errors += 1;
+        }
+    });
+}
+
+/// Update the task count gauges
+pub fn record_task_stats(stats: &TaskStats) {
+    with_metrics(|metrics| {
+        metrics.tasks = Some((stats.total, stats.unfinished, stats.overdue));
+    });
+}
+
+/// Count a finished daemon run
+pub fn record_run(success: bool) {
+    with_metrics(|metrics| {
+        metrics.runs += 1;
+        if success {
+            metrics.last_success = Some(chrono::Utc::now().timestamp());
+        } else {
+            metrics.run_failures += 1;
+        }
+    });
+}
+
+/// Add an analysis' duration, tokens and cost
+pub fn record_analysis(report: &AnalysisReport) {
+    with_metrics(|metrics| {
+        if let Some(seconds) = report.metadata.analysis_duration_seconds {
+            metrics
+                .analysis_duration
+                .get_or_insert_with(Histogram::default)
+                .observe(&ANALYSIS_BUCKETS, seconds);
+        }
+        if let Some(usage) = &report.metadata.token_usage {
+            metrics.prompt_tokens += usage.prompt_tokens;
+            metrics.cached_prompt_tokens += usage.cached_prompt_tokens;
+            metrics.completion_tokens += usage.completion_tokens;
+            metrics.llm_requests += usage.requests;
+        }
+        metrics.estimated_cost_usd += report.metadata.estimated_cost_usd.unwrap_or_default();
+    });
+}
+
+/// All metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    let Ok(metrics) = METRICS.lock() else {
+        return String::new();
+    };
+    let mut out = String::new();
+
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+
+    if let Some((total, open, overdue)) = metrics.tasks {
+        metric(
+            "mcp_tasks_tasks",
+            "gauge",
+            "Tasks on the MCP server at the last run",
+            &[
+                ("{state=\"total\"}".to_string(), total.to_string()),
+                ("{state=\"open\"}".to_string(), open.to_string()),
+                ("{state=\"overdue\"}".to_string(), overdue.to_string()),
+            ],
+        );
+    }
+    metric(
+        "mcp_tasks_daemon_runs_total",
+        "counter",
+        "Scheduled daemon runs",
+        &[(String::new(), metrics.runs.to_string())],
+    );
+    metric(
+        "mcp_tasks_daemon_run_failures_total",
+        "counter",
+        "Scheduled daemon runs that failed",
+        &[(String::new(), metrics.run_failures.to_string())],
+    );
+    if let Some(timestamp) = metrics.last_success {
+        metric(
+            "mcp_tasks_daemon_last_success_timestamp_seconds",
+            "gauge",
+            "Unix time of the last successful run",
+            &[(String::new(), timestamp.to_string())],
+        );
+    }
+    metric(
+        "mcp_tasks_llm_tokens_total",
+        "counter",
+        "Tokens used by analyses",
+        &[
+            (
+                "{kind=\"prompt\"}".to_string(),
+                metrics.prompt_tokens.to_string(),
+            ),
+            (
+                "{kind=\"cached_prompt\"}".to_string(),
+                metrics.cached_prompt_tokens.to_string(),
+            ),
+            (
+                "{kind=\"completion\"}".to_string(),
+                metrics.completion_tokens.to_string(),
+            ),
+        ],
+    );
+    metric(
+        "mcp_tasks_llm_requests_total",
+        "counter",
+        "LLM API requests made by analyses",
+        &[(String::new(), metrics.llm_requests.to_string())],
+    );
+    metric(
+        "mcp_tasks_llm_estimated_cost_usd_total",
+        "counter",
+        "Estimated cost of analyses in USD",
+        &[(String::new(), metrics.estimated_cost_usd.to_string())],
+    );
+
+    if let Some(histogram) = &metrics.analysis_duration {
+        let name = "mcp_tasks_analysis_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Time an analysis took", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        histogram.render(&mut out, name, "", &ANALYSIS_BUCKETS);
+    }
+
+    if !metrics.mcp_calls.is_empty() {
+        let name = "mcp_tasks_mcp_call_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Latency of MCP tool calls", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (tool, calls) in &metrics.mcp_calls {
+            let labels = format!("tool=\"{}\",", label_value(tool));
+            calls.latency.render(&mut out, name, &labels, &MCP_BUCKETS);
+        }
+
+        let name = "mcp_tasks_mcp_call_errors_total";
+        let _ = writeln!(out, "# HELP {} MCP tool calls that failed", name);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        for (tool, calls) in &metrics.mcp_calls {
+            let _ = writeln!(
+                out,
+                "{}{{tool=\"{}\"}} {}",
+                name,
+                label_value(tool),
+                calls.errors
+            );
+        }
+    }
+
+    out
+}
+
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Bind the metrics endpoint; fails right away when the address is taken
+pub async fn bind(addr: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .with_context(|| McpTasksError::Config(format!("Failed to listen for metrics on {}", addr)))
+}
+
+/// Answer `GET /metrics` on `listener` until the process exits
+pub async fn serve(listener: TcpListener) {
+    if let Ok(addr) = listener.local_addr() {
+        info!("Serving metrics on http://{}/metrics", addr);
+    }
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream).await {
+                        debug!("Metrics request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => debug!("Failed to accept metrics connection: {}", e),
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    // Only the request line matters; stop at the end of the headers
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < 8192 {
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buffer))
+            .await
+            .context("Timed out reading request")??;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+
+    let (status, body) = match (method, path.split('?').next().unwrap_or_default()) {
+        ("GET", "/metrics") => ("200 OK", render()),
+        ("GET", _) => (
+            "404 Not Found",
+            "Not found; metrics are at /metrics\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "Only GET is supported\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,533 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use crate::brief::BRIEF_PROMPT_HEADER;
+use crate::chunked::PART_FINDINGS_MARKER;
+use crate::compare::{COMPARE_PROMPT_MARKER, DELTA_HEADING};
+use crate::dedupe::DEDUPE_PROMPT_MARKER;
+use crate::digest::DIGEST_PROMPT_HEADER;
+use crate::estimate::ESTIMATE_PROMPT_MARKER;
+use crate::graph::GRAPH_PROMPT_MARKER;
+use crate::llm::{LlmProvider, TokenSink};
+use crate::plan::{CAPACITY_LINE, PERIODS_LINE, PLAN_PROMPT_MARKER};
+use crate::reprioritize::REPRIORITIZE_PROMPT_MARKER;
+use crate::sprint::{SPRINT_PROMPT_MARKER, SPRINT_REVIEW_HEADER};
+use crate::structured::STRUCTURED_PROMPT_MARKER;
+use crate::themes::THEMES_PROMPT_MARKER;
+use crate::tooling::{
+    ChatRequest, ChatResponse, Choice, ResponseMessage, StreamToken, ToolCall, ToolCallFunction,
+};
+use crate::velocity::VELOCITY_PROMPT_HEADER;
+
+/// Model name recorded in reports produced by the mock provider
+pub const MOCK_MODEL: &str = "mock";
+
+/// Read-only tool the mock calls once per turn, so the tool loop is exercised too
+const MOCK_TOOL: &str = "task_stats";
+
+/// Deterministic offline stand-in for a real model, for CI and demos.
+/// Answers are built from the prompt alone: a priority list for analysis
+/// prompts (merged recommendations for analyses done in parts), JSON for breakdown, structured analysis, reprioritize, estimate, plan, sprint, dependency, dedupe and theme prompts and an echo otherwise.
+pub struct MockProvider;
+
+impl MockProvider {
+    fn respond(&self, request: &ChatRequest) -> ChatResponse {
+        // Only look at the current turn: the last user message and what follows it
+        let turn_start = request
+            .messages
+            .iter()
+            .rposition(|message| message.role == "user")
+            .unwrap_or(0);
+        let turn = &request.messages[turn_start..];
+        let prompt = turn
+            .first()
+            .map(|message| message.content.as_str())
+            .unwrap_or_default();
+        let used_tool = turn.iter().any(|message| message.role == "tool");
+
+        let offers_tool = request.tool_choice.as_deref() != Some("none")
+            && request
+                .tools
+                .iter()
+                .flatten()
+                .any(|tool| tool.function.name == MOCK_TOOL);
+
+        if offers_tool && !used_tool {
+            return response(
+                None,
+                Some(vec![ToolCall {
+                    id: format!("mock_call_{}", request.messages.len()),
+                    call_type: Some("function".to_string()),
+                    function: ToolCallFunction {
+                        name: MOCK_TOOL.to_string(),
+                        arguments: "{}".to_string(),
+                    },
+                }]),
+            );
+        }
+
+        let content = if prompt.contains("\"subtasks\"") {
+            mock_breakdown(prompt)
+        } else if prompt.contains(STRUCTURED_PROMPT_MARKER) {
+            mock_structured(prompt)
+        } else if prompt.contains(THEMES_PROMPT_MARKER) {
+            mock_themes(prompt)
+        } else if prompt.contains(DEDUPE_PROMPT_MARKER) {
+            mock_duplicates(prompt)
+        } else if prompt.contains(GRAPH_PROMPT_MARKER) {
+            mock_dependencies(prompt)
+        } else if prompt.contains(REPRIORITIZE_PROMPT_MARKER) {
+            mock_priorities(prompt)
+        } else if prompt.contains(SPRINT_PROMPT_MARKER) {
+            mock_sprint(prompt)
+        } else if prompt.contains(PLAN_PROMPT_MARKER) {
+            mock_plan(prompt)
+        } else if prompt.contains(ESTIMATE_PROMPT_MARKER) {
+            mock_estimates(prompt)
+        } else if prompt.contains(PART_FINDINGS_MARKER) {
+            mock_synthesis(prompt)
+        } else if prompt.contains(COMPARE_PROMPT_MARKER) {
+            format!(
+                "{}\n{}\n\nMock comparison with the previous analysis; no model was called. \
+                 See the new and no longer pending tasks listed in the prompt.\n",
+                mock_analysis(prompt, used_tool),
+                DELTA_HEADING
+            )
+        } else if prompt.contains("Task 1: ") {
+            mock_analysis(prompt, used_tool)
+        } else if prompt.starts_with(DIGEST_PROMPT_HEADER) {
+            "Mock digest summary; no model was called. Check the completed and new tasks below, \
+             then start with the overdue ones."
+                .to_string()
+        } else if prompt.starts_with(BRIEF_PROMPT_HEADER) {
+            "Mock morning briefing; no model was called. Clear the overdue tasks first, then the ones \
+             due today, and keep the in-progress work moving."
+                .to_string()
+        } else if prompt.starts_with(SPRINT_REVIEW_HEADER) {
+            "Mock sprint review; no model was called. Finish the carried-over tasks first and \
+             plan less than the full capacity next time."
+                .to_string()
+        } else if prompt.starts_with(VELOCITY_PROMPT_HEADER) {
+            "Mock velocity summary; no model was called. Compare completed and created tasks per week: \
+             when more tasks arrive than get done, the backlog grows."
+                .to_string()
+        } else {
+            format!(
+                "This is a mock response; no model was called.\n\nYou said: {}",
+                prompt.trim()
+            )
+        };
+
+        response(Some(content), None)
+    }
+}
+
+impl LlmProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move { Ok(self.respond(&request)) })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            let response = self.respond(&request);
+            if let Some(content) = &response.choices[0].message.content {
+                for word in content.split_inclusive(' ') {
+                    on_token(StreamToken::Content(word));
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn response(content: Option<String>, tool_calls: Option<Vec<ToolCall>>) -> ChatResponse {
+    ChatResponse {
+        choices: vec![Choice {
+            message: ResponseMessage {
+                content,
+                reasoning_content: None,
+                tool_calls,
+            },
+        }],
+        usage: None,
+    }
+}
+
+/// A task as listed in an analysis prompt
+#[derive(Default)]
+struct PromptTask {
+    id: Option<String>,
+    title: String,
+    priority: Option<String>,
+    due: Option<String>,
+}
+
+/// Tasks listed in an analysis prompt, in prompt order
+fn parse_prompt_tasks(prompt: &str) -> Vec<PromptTask> {
+    let mut tasks: Vec<PromptTask> = Vec::new();
+
+    for line in prompt.lines() {
+        if let Some(rest) = line.strip_prefix("Task ")
+            && let Some((number, title)) = rest.split_once(": ")
+            && number.chars().all(|c| c.is_ascii_digit())
+        {
+            tasks.push(PromptTask {
+                title: title.trim().to_string(),
+                ..Default::default()
+            });
+        } else if let Some(task) = tasks.last_mut() {
+            if let Some(id) = line.trim().strip_prefix("ID: ") {
+                task.id = Some(id.to_string());
+            } else if let Some(priority) = line.trim().strip_prefix("Priority: ") {
+                task.priority = Some(priority.to_lowercase());
+            } else if let Some(due) = line.trim().strip_prefix("Due Date: ") {
+                task.due = Some(due.to_string());
+            }
+        }
+    }
+
+    tasks
+}
+
+fn priority_rank(priority: Option<&str>) -> u8 {
+    match priority {
+        Some("high") => 0,
+        Some("medium") => 1,
+        Some("low") => 2,
+        _ => 3,
+    }
+}
+
+/// Merged analysis of a backlog that was analyzed in parts
+fn mock_synthesis(prompt: &str) -> String {
+    let parts = prompt.matches(PART_FINDINGS_MARKER).count();
+    let starts = prompt
+        .lines()
+        .filter_map(|line| line.strip_prefix("- Start with "))
+        .collect::<Vec<_>>();
+
+    let mut analysis = format!(
+        "## Summary\n\nMock analysis merged from {} parts; no model was called.\n\n## Recommendations\n\n",
+        parts
+    );
+    for start in &starts {
+        analysis.push_str(&format!("- Start with {}\n", start));
+    }
+    if starts.is_empty() {
+        analysis.push_str("- There is nothing to work on.\n");
+    }
+    analysis
+}
+
+fn mock_analysis(prompt: &str, used_tool: bool) -> String {
+    let mut tasks = parse_prompt_tasks(prompt);
+    tasks.sort_by_key(|task| priority_rank(task.priority.as_deref()));
+
+    let mut analysis = format!(
+        "## Summary\n\nMock analysis of {} tasks; no model was called.\n\n## Priorities\n\n",
+        tasks.len()
+    );
+
+    for (idx, task) in tasks.iter().enumerate() {
+        analysis.push_str(&format!(
+            "{}. **{}** (priority: {}",
+            idx + 1,
+            task.title,
+            task.priority.as_deref().unwrap_or("none")
+        ));
+        if let Some(due) = &task.due {
+            analysis.push_str(&format!(", due {}", due));
+        }
+        analysis.push_str(")\n");
+    }
+
+    analysis.push_str("\n## Recommendations\n\n");
+    match tasks.first() {
+        Some(task) => analysis.push_str(&format!("- Start with \"{}\".\n", task.title)),
+        None => analysis.push_str("- There is nothing to work on.\n"),
+    }
+    if used_tool {
+        analysis.push_str(&format!(
+            "- Task statistics were checked with the `{}` tool.\n",
+            MOCK_TOOL
+        ));
+    }
+
+    analysis
+}
+
+/// Scores follow the stated priority; each task depends on the one before it in the order
+fn mock_structured(prompt: &str) -> String {
+    let mut tasks = parse_prompt_tasks(prompt);
+    tasks.sort_by_key(|task| priority_rank(task.priority.as_deref()));
+
+    let ids = tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| task.id.clone().unwrap_or_else(|| (idx + 1).to_string()))
+        .collect::<Vec<_>>();
+
+    let assessments = tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let (score, level) = match priority_rank(task.priority.as_deref()) {
+                0 => (9, "high"),
+                1 => (6, "medium"),
+                _ => (3, "low"),
+            };
+            serde_json::json!({
+                "id": ids[idx],
+                "title": task.title,
+                "priority_score": score,
+                "complexity": level,
+                "risk": if task.due.is_some() { "high" } else { "low" },
+                "dependencies": ids[..idx].last().into_iter().collect::<Vec<_>>(),
+                "rationale": format!("Mock score from priority {}", task.priority.as_deref().unwrap_or("none")),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "summary": format!("Mock structured analysis of {} tasks; no model was called.", tasks.len()),
+        "tasks": assessments,
+        "recommended_order": ids,
+    })
+    .to_string()
+}
+
+/// Tasks whose titles match when lowercased and trimmed are duplicates; the first one is kept
+fn mock_duplicates(prompt: &str) -> String {
+    let tasks = parse_prompt_tasks(prompt);
+    let mut clusters: Vec<(String, Vec<String>)> = Vec::new();
+    for (idx, task) in tasks.iter().enumerate() {
+        let key = task.title.trim().to_lowercase();
+        let id = task.id.clone().unwrap_or_else(|| (idx + 1).to_string());
+        match clusters.iter_mut().find(|(title, _)| *title == key) {
+            Some((_, ids)) => ids.push(id),
+            None => clusters.push((key, vec![id])),
+        }
+    }
+
+    let clusters = clusters
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(title, ids)| {
+            serde_json::json!({
+                "keep": ids[0],
+                "ids": ids,
+                "similarity": 100,
+                "rationale": format!("Mock: same title \"{}\"", title),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "clusters": clusters }).to_string()
+}
+
+/// Each group is named after its number and first task
+fn mock_themes(prompt: &str) -> String {
+    let mut themes = Vec::new();
+    let mut lines = prompt.lines();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("Group ")
+            && let Some((number, _)) = rest.split_once(' ')
+            && let Ok(group) = number.parse::<usize>()
+        {
+            let first = lines
+                .next()
+                .and_then(|line| line.strip_prefix("- "))
+                .unwrap_or_default();
+            themes.push(serde_json::json!({
+                "group": group,
+                "label": format!("Mock theme {}", group),
+                "summary": format!("Mock: tasks like \"{}\"", first),
+            }));
+        }
+    }
+
+    serde_json::json!({ "themes": themes }).to_string()
+}
+
+/// Every task depends on the one listed before it
+fn mock_dependencies(prompt: &str) -> String {
+    let ids = parse_prompt_tasks(prompt)
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| task.id.clone().unwrap_or_else(|| (idx + 1).to_string()))
+        .collect::<Vec<_>>();
+
+    let tasks = ids
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| {
+            serde_json::json!({
+                "id": id,
+                "depends_on": ids[..idx].last().into_iter().collect::<Vec<_>>(),
+                "reason": "Mock: tasks are done in the order they were listed",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "tasks": tasks }).to_string()
+}
+
+/// Tasks with a due date become high priority, all others medium
+fn mock_priorities(prompt: &str) -> String {
+    let suggestions = parse_prompt_tasks(prompt)
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let (priority, justification) = match &task.due {
+                Some(due) => ("high", format!("Mock: due {}", due)),
+                None => ("medium", "Mock: no due date".to_string()),
+            };
+            serde_json::json!({
+                "id": task.id.clone().unwrap_or_else(|| (idx + 1).to_string()),
+                "suggested_priority": priority,
+                "justification": justification,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "suggestions": suggestions }).to_string()
+}
+
+/// Effort by priority; the same numbers serve as points and hours
+fn mock_effort(priority: Option<&str>) -> f64 {
+    match priority {
+        Some("high") => 5.0,
+        Some("medium") => 3.0,
+        Some("low") => 1.0,
+        _ => 2.0,
+    }
+}
+
+fn mock_estimates(prompt: &str) -> String {
+    let estimates = parse_prompt_tasks(prompt)
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            serde_json::json!({
+                "id": task.id.clone().unwrap_or_else(|| (idx + 1).to_string()),
+                "estimate": mock_effort(task.priority.as_deref()),
+                "rationale": "Mock: estimated from the priority",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "estimates": estimates }).to_string()
+}
+
+fn mock_sprint(prompt: &str) -> String {
+    // Every task is proposed with its mock estimate; the client trims to capacity
+    let selected = parse_prompt_tasks(prompt)
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            serde_json::json!({
+                "id": task.id.clone().unwrap_or_else(|| (idx + 1).to_string()),
+                "estimate": mock_effort(task.priority.as_deref()),
+                "rationale": "Mock: picked in listed order",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "sprint_goal": "Mock sprint goal; no model was called.",
+        "selected": selected,
+        "left_out": [],
+    })
+    .to_string()
+}
+
+fn mock_plan(prompt: &str) -> String {
+    let line = |prefix: &str| {
+        prompt
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .unwrap_or_default()
+    };
+    let periods = line(PERIODS_LINE)
+        .split(" (")
+        .next()
+        .unwrap_or_default()
+        .split(", ")
+        .filter(|period| !period.is_empty())
+        .collect::<Vec<_>>();
+    let capacity = line(CAPACITY_LINE)
+        .trim_end_matches('h')
+        .parse::<f64>()
+        .unwrap_or_default();
+
+    // Fill the periods in task order, with hours taken from the priority
+    let mut loads = vec![0.0; periods.len()];
+    let mut schedule = vec![Vec::new(); periods.len()];
+    let mut unscheduled = Vec::new();
+    for (idx, task) in parse_prompt_tasks(prompt).iter().enumerate() {
+        let id = task.id.clone().unwrap_or_else(|| (idx + 1).to_string());
+        let hours = mock_effort(task.priority.as_deref());
+        match loads.iter().position(|load| load + hours <= capacity) {
+            Some(period) => {
+                loads[period] += hours;
+                schedule[period].push(serde_json::json!({ "id": id, "hours": hours }));
+            }
+            None => unscheduled.push(serde_json::json!({
+                "id": id,
+                "reason": "Mock: no period has room left",
+                "suggestion": "Defer past the planning window",
+            })),
+        }
+    }
+
+    serde_json::json!({
+        "schedule": periods
+            .iter()
+            .zip(schedule)
+            .map(|(period, tasks)| serde_json::json!({ "period": period, "tasks": tasks }))
+            .collect::<Vec<_>>(),
+        "unscheduled": unscheduled,
+        "notes": "Mock plan; no model was called. Tasks were placed in order until the capacity ran out.",
+    })
+    .to_string()
+}
+
+fn mock_breakdown(prompt: &str) -> String {
+    // The task is embedded as pretty-printed JSON between "Task:" and the next blank line
+    let title = prompt
+        .split_once("Task:\n")
+        .and_then(|(_, rest)| rest.split("\n\n").next())
+        .and_then(|json| serde_json::from_str::<Value>(json).ok())
+        .and_then(|task| task["title"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "the task".to_string());
+
+    let subtask = |verb: &str, priority: &str, effort: &str| {
+        serde_json::json!({
+            "title": format!("{} {}", verb, title),
+            "description": format!("Mock subtask: {} {}", verb.to_lowercase(), title),
+            "priority": priority,
+            "effort": effort,
+        })
+    };
+
+    serde_json::json!({
+        "summary": format!("Mock breakdown of \"{}\"; no model was called.", title),
+        "effort": "7h",
+        "subtasks": [
+            subtask("Clarify", "high", "1h"),
+            subtask("Implement", "medium", "4h"),
+            subtask("Review", "low", "2h"),
+        ],
+        "blockers": [],
+    })
+    .to_string()
+}
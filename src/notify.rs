@@ -0,0 +1,42 @@
+use tracing::warn;
+
+use crate::output;
+
+/// Best-effort desktop notifications through `notify-send` (Linux) or `osascript` (macOS)
+pub struct DesktopNotifier {
+    enabled: bool,
+}
+
+impl DesktopNotifier {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn send(&mut self, summary: &str, body: &str) {
+        if !self.enabled {
+            return;
+        }
+
+        let result = if cfg!(target_os = "macos") {
+            std::process::Command::new("osascript")
+                .arg("-e")
+                .arg(format!(
+                    "display notification {} with title {}",
+                    output::to_json(body).unwrap_or_default(),
+                    output::to_json(summary).unwrap_or_default()
+                ))
+                .status()
+        } else {
+            std::process::Command::new("notify-send")
+                .args(["--app-name", "mcp-tasks", summary, body])
+                .status()
+        };
+
+        if let Err(e) = result {
+            // Don't retry on every event once we know notifications can't be shown
+            warn!("Disabling desktop notifications: {}", e);
+            crate::esay!("⚠️  Desktop notifications unavailable: {}", e);
+            self.enabled = false;
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tracing::info;
+
+use crate::dates::TaskDate;
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+
+/// File in the export directory recording which note belongs to which task
+pub const MANIFEST_FILE: &str = ".mcp-tasks-export.json";
+
+/// Task ID to note file name of the previous export
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    notes: BTreeMap<String, String>,
+}
+
+/// What an export changed in the directory
+#[derive(Debug, Default, Serialize)]
+pub struct VaultExport {
+    /// New or changed notes
+    pub written: usize,
+    pub unchanged: usize,
+    /// Notes of tasks that are gone or renamed
+    pub removed: usize,
+}
+
+/// Write one Markdown note with YAML frontmatter per task into `dir`. Notes whose
+/// content did not change are left alone, and notes written by an earlier export
+/// for tasks that are no longer exported (or were renamed) are removed.
+pub fn export_vault(tasks: &[Task], dir: &Path) -> Result<VaultExport> {
+    std::fs::create_dir_all(dir).with_context(|| {
+        McpTasksError::Report(format!("Failed to create directory {}", dir.display()))
+    })?;
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let previous = match std::fs::read_to_string(&manifest_path) {
+        Ok(content) => serde_json::from_str(&content).with_context(|| {
+            McpTasksError::Report(format!("Failed to parse {}", manifest_path.display()))
+        })?,
+        Err(_) => Manifest::default(),
+    };
+
+    let mut summary = VaultExport::default();
+    let mut manifest = Manifest::default();
+    let mut used = HashSet::new();
+
+    for task in tasks {
+        let mut file_name = format!("{}.md", note_name(&task.title));
+        if !used.insert(file_name.to_lowercase()) {
+            file_name = format!("{} ({}).md", note_name(&task.title), note_name(&task.id));
+            used.insert(file_name.to_lowercase());
+        }
+
+        let path = dir.join(&file_name);
+        let content = render_note(task);
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+            summary.unchanged += 1;
+        } else {
+            std::fs::write(&path, content).with_context(|| {
+                McpTasksError::Report(format!("Failed to write {}", path.display()))
+            })?;
+            summary.written += 1;
+        }
+        manifest.notes.insert(task.id.clone(), file_name);
+    }
+
+    let current = manifest.notes.values().collect::<HashSet<_>>();
+    for file_name in previous.notes.values() {
+        let path = dir.join(file_name);
+        if !current.contains(file_name) && path.exists() {
+            std::fs::remove_file(&path).with_context(|| {
+                McpTasksError::Report(format!("Failed to remove {}", path.display()))
+            })?;
+            summary.removed += 1;
+        }
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)?;
+    std::fs::write(&manifest_path, manifest_json).with_context(|| {
+        McpTasksError::Report(format!("Failed to write {}", manifest_path.display()))
+    })?;
+
+    info!(
+        "Exported {} tasks to {}: {} written, {} unchanged, {} removed",
+        tasks.len(),
+        dir.display(),
+        summary.written,
+        summary.unchanged,
+        summary.removed
+    );
+    Ok(summary)
+}
+
+/// The note for one task: frontmatter, title heading and description
+pub fn render_note(task: &Task) -> String {
+    let mut output = String::from("---\n");
+    output.push_str(&format!("id: {}\n", yaml_string(&task.id)));
+    output.push_str(&format!("status: {}\n", yaml_string(&task.status)));
+    if let Some(priority) = &task.priority {
+        output.push_str(&format!("priority: {}\n", yaml_string(priority)));
+    }
+    if let Some(due) = &task.due_date {
+        output.push_str(&format!("due: {}\n", yaml_date(due)));
+    }
+    if let Some(assignee) = &task.assignee {
+        output.push_str(&format!("assignee: {}\n", yaml_string(assignee)));
+    }
+    match task.tags.as_deref() {
+        Some(tags) if !tags.is_empty() => {
+            output.push_str("tags:\n");
+            for tag in tags {
+                // Obsidian tags cannot contain spaces
+                output.push_str(&format!("  - {}\n", yaml_string(&tag.replace(' ', "-"))));
+            }
+        }
+        _ => {}
+    }
+    output.push_str(&format!("created: {}\n", yaml_date(&task.created_at)));
+    if let Some(completed) = &task.completed_at {
+        output.push_str(&format!("completed: {}\n", yaml_date(completed)));
+    }
+    output.push_str("---\n\n");
+
+    output.push_str(&format!("# {}\n", task.title));
+    if let Some(description) = task.description.as_deref().map(str::trim)
+        && !description.is_empty()
+    {
+        output.push_str(&format!("\n{}\n", description));
+    }
+
+    output
+}
+
+/// File name for a note: the title without characters Obsidian does not allow in links
+fn note_name(title: &str) -> String {
+    let name = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect::<String>();
+    let name = name.trim().trim_start_matches('.').trim();
+    if name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        name.chars().take(120).collect()
+    }
+}
+
+/// Dates as `YYYY-MM-DD` (or RFC3339 for instants) so Obsidian shows them as dates
+fn yaml_date(value: &str) -> String {
+    match TaskDate::parse(value) {
+        Some(TaskDate::Day(date)) => date.format("%Y-%m-%d").to_string(),
+        Some(TaskDate::Instant(instant)) => instant.to_rfc3339(),
+        None => yaml_string(value),
+    }
+}
+
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
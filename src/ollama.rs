@@ -0,0 +1,312 @@
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::error::McpTasksError;
+use crate::llm::{LlmProvider, TokenSink};
+use crate::tooling::{
+    ChatRequest, ChatResponse, Choice, LineBuffer, Message, ResponseMessage, StreamToken, ToolCall,
+    ToolCallFunction, ToolObject, Usage,
+};
+
+/// Client for Ollama's native `/api/chat` endpoint
+pub struct OllamaClient {
+    client: Client,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest {
+    model: String,
+    messages: Vec<OllamaMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<ToolObject>>,
+    stream: bool,
+    /// `json` constrains the answer to valid JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    /// Ollama's name for `max_tokens`
+    num_predict: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OllamaMessage {
+    role: String,
+    #[serde(default)]
+    content: String,
+    /// Reasoning of thinking models
+    #[serde(default, skip_serializing)]
+    thinking: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<OllamaToolCall>,
+    /// Name of the tool a `tool` message answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunction,
+}
+
+/// Unlike the OpenAI format, arguments are a JSON object rather than a string
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaFunction {
+    name: String,
+    arguments: Value,
+}
+
+/// A complete response, or one line of a streamed response
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    #[serde(default)]
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
+impl OllamaClient {
+    /// `base_url` is the server root, e.g. `http://localhost:11434`
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: format!("{}/api/chat", base_url.trim_end_matches('/')),
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest, stream: bool) -> Result<reqwest::Response> {
+        debug!(
+            "Sending chat request to Ollama with {} tools",
+            request.tools.as_ref().map_or(0, |t| t.len())
+        );
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&to_ollama_request(request, stream))
+            .send()
+            .await
+            .context(McpTasksError::DeepSeekApi(
+                "Failed to send request to Ollama (is `ollama serve` running?)".to_string(),
+            ))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "Ollama API error {}: {}",
+                status, text
+            )));
+        }
+
+        Ok(response)
+    }
+
+    async fn chat_complete(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let response: OllamaChatResponse =
+            self.send(&request, false)
+                .await?
+                .json()
+                .await
+                .context(McpTasksError::DeepSeekApi(
+                    "Failed to parse Ollama response".to_string(),
+                ))?;
+
+        let token_usage = usage(&response);
+        let message = response.message.unwrap_or_default();
+        Ok(to_chat_response(
+            &request,
+            message.content,
+            message.thinking.unwrap_or_default(),
+            message.tool_calls,
+            token_usage,
+        ))
+    }
+
+    /// Ollama streams one JSON object per line
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_token: TokenSink<'_>,
+    ) -> Result<ChatResponse> {
+        let mut response = self.send(&request, true).await?;
+
+        let mut buffer = LineBuffer::default();
+        let mut content = String::new();
+        let mut thinking = String::new();
+        let mut tool_calls = Vec::new();
+        let mut token_usage = None;
+
+        while let Some(chunk) = response.chunk().await.context(McpTasksError::DeepSeekApi(
+            "Failed to read Ollama stream".to_string(),
+        ))? {
+            buffer.push(&chunk);
+
+            while let Some(line) = buffer.next_line().context(McpTasksError::DeepSeekApi(
+                "Ollama stream is not valid UTF-8".to_string(),
+            ))? {
+                if line.is_empty() {
+                    continue;
+                }
+
+                let part: OllamaChatResponse = serde_json::from_str(&line).context(
+                    McpTasksError::DeepSeekApi("Failed to parse Ollama stream chunk".to_string()),
+                )?;
+
+                if part.done {
+                    token_usage = usage(&part);
+                }
+
+                if let Some(message) = part.message {
+                    if let Some(text) = message.thinking
+                        && !text.is_empty()
+                    {
+                        on_token(StreamToken::Reasoning(&text));
+                        thinking.push_str(&text);
+                    }
+                    if !message.content.is_empty() {
+                        on_token(StreamToken::Content(&message.content));
+                        content.push_str(&message.content);
+                    }
+                    tool_calls.extend(message.tool_calls);
+                }
+            }
+        }
+
+        Ok(to_chat_response(
+            &request,
+            content,
+            thinking,
+            tool_calls,
+            token_usage,
+        ))
+    }
+}
+
+impl LlmProvider for OllamaClient {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(self.chat_complete(request))
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(self.chat_stream(request, on_token))
+    }
+}
+
+fn to_ollama_request(request: &ChatRequest, stream: bool) -> OllamaChatRequest {
+    let messages = request
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| OllamaMessage {
+            role: message.role.clone(),
+            content: message.content.clone(),
+            thinking: None,
+            tool_calls: message
+                .tool_calls
+                .iter()
+                .flatten()
+                .map(|tool_call| OllamaToolCall {
+                    function: OllamaFunction {
+                        name: tool_call.function.name.clone(),
+                        arguments: serde_json::from_str(&tool_call.function.arguments)
+                            .unwrap_or_else(|_| Value::Object(Default::default())),
+                    },
+                })
+                .collect(),
+            tool_name: message
+                .tool_call_id
+                .as_deref()
+                .and_then(|id| tool_name_for(&request.messages[..idx], id)),
+        })
+        .collect();
+
+    OllamaChatRequest {
+        model: request.model.clone(),
+        messages,
+        // Ollama has no tool_choice; leave the tools out when none may be called
+        tools: request
+            .tools
+            .clone()
+            .filter(|_| request.tool_choice.as_deref() != Some("none")),
+        stream,
+        format: request.response_format.as_ref().map(|_| "json".to_string()),
+        options: OllamaOptions {
+            temperature: request.temperature,
+            num_predict: request.max_tokens,
+        },
+    }
+}
+
+/// Ollama matches tool results by tool name; find the call a result answers
+fn tool_name_for(earlier: &[Message], tool_call_id: &str) -> Option<String> {
+    earlier
+        .iter()
+        .rev()
+        .flat_map(|message| message.tool_calls.iter().flatten())
+        .find(|tool_call| tool_call.id == tool_call_id)
+        .map(|tool_call| tool_call.function.name.clone())
+}
+
+fn to_chat_response(
+    request: &ChatRequest,
+    content: String,
+    thinking: String,
+    tool_calls: Vec<OllamaToolCall>,
+    usage: Option<Usage>,
+) -> ChatResponse {
+    // Ollama has no tool call ids; make ones that are unique within the conversation
+    let tool_calls = tool_calls
+        .into_iter()
+        .enumerate()
+        .map(|(idx, tool_call)| ToolCall {
+            id: format!("call_{}_{}", request.messages.len(), idx),
+            call_type: Some("function".to_string()),
+            function: ToolCallFunction {
+                name: tool_call.function.name,
+                arguments: tool_call.function.arguments.to_string(),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    ChatResponse {
+        choices: vec![Choice {
+            message: ResponseMessage {
+                content: (!content.is_empty()).then_some(content),
+                reasoning_content: (!thinking.is_empty()).then_some(thinking),
+                tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            },
+        }],
+        usage,
+    }
+}
+
+fn usage(response: &OllamaChatResponse) -> Option<Usage> {
+    Some(Usage {
+        prompt_tokens: response.prompt_eval_count?,
+        completion_tokens: response.eval_count.unwrap_or_default(),
+        prompt_cache_hit_tokens: 0,
+        prompt_tokens_details: None,
+    })
+}
@@ -0,0 +1,1110 @@
+use anyhow::Result;
+use chrono::Utc;
+use clap::ValueEnum;
+use rmcp::model::{Resource, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::sync::RwLock;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::agenda::Agenda;
+use crate::archive::SnapshotDiff;
+use crate::breakdown::ProposedSubtask;
+use crate::brief::Brief;
+use crate::bulk::BulkResult;
+use crate::capabilities::{Capability, ServerCheck};
+use crate::dates::TaskDate;
+use crate::dedupe::DuplicateCluster;
+use crate::embeddings::SemanticMatch;
+use crate::estimate::{EstimateUnit, TaskEstimate};
+use crate::mcp_client::Task;
+use crate::plan::Plan;
+use crate::reprioritize::PriorityChange;
+use crate::sprint::{SprintProposal, SprintReview};
+use crate::status::TaskStatus;
+use crate::structured::StructuredAnalysis;
+use crate::table_formatter::{overdue_tasks, priority_level, task_field_changes};
+use crate::template::NamedTemplate;
+use crate::themes::ThemeReport;
+use crate::timetrack::TimeEntry;
+use crate::watch::TaskChange;
+
+/// How command results are rendered on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayFormat {
+    /// Human-friendly tables and summaries
+    #[default]
+    Table,
+    /// Structured JSON, suitable for piping into `jq`
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// How console messages are decorated, set once from `--quiet` and `--no-color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputStyle {
+    /// Leave out progress banners and hints, print only results
+    pub quiet: bool,
+    /// Use ANSI colors and emoji
+    pub color: bool,
+}
+
+static OUTPUT_STYLE: RwLock<OutputStyle> = RwLock::new(OutputStyle {
+    quiet: false,
+    color: true,
+});
+
+/// Use `style` for all console output; done by `main` before the logger starts
+pub fn set_style(style: OutputStyle) {
+    if let Ok(mut current) = OUTPUT_STYLE.write() {
+        *current = style;
+    }
+}
+
+/// The current output style
+pub fn style() -> OutputStyle {
+    OUTPUT_STYLE
+        .read()
+        .map(|style| *style)
+        .unwrap_or(OutputStyle {
+            quiet: false,
+            color: true,
+        })
+}
+
+/// `text` as it should be printed: unchanged, or without emoji when color is off
+pub fn decorate(text: &str) -> Cow<'_, str> {
+    if style().color {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(strip_emoji(text))
+    }
+}
+
+/// Remove emoji along with the spaces that separate them from the text. Arrows,
+/// box drawing and sparkline characters are kept.
+pub fn strip_emoji(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_emoji(c) {
+            while chars
+                .next_if(|next| is_emoji(*next) || *next == ' ')
+                .is_some()
+            {}
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{2139}'
+            | '\u{FE0F}'
+            | '\u{200D}'
+    )
+}
+
+/// Print a result line to stdout, hiding the progress spinner meanwhile; emoji are
+/// dropped when color is off
+#[macro_export]
+macro_rules! say {
+    () => {
+        $crate::progress::suspend(|| println!())
+    };
+    ($($arg:tt)*) => {{
+        let text = format!($($arg)*);
+        $crate::progress::suspend(|| println!("{}", $crate::output::decorate(&text)))
+    }};
+}
+
+/// Print an error or warning to stderr, hiding the progress spinner meanwhile; emoji
+/// are dropped when color is off
+#[macro_export]
+macro_rules! esay {
+    ($($arg:tt)*) => {{
+        let text = format!($($arg)*);
+        $crate::progress::suspend(|| eprintln!("{}", $crate::output::decorate(&text)))
+    }};
+}
+
+/// Print a progress banner or hint to stdout unless `--quiet` is set
+#[macro_export]
+macro_rules! notice {
+    ($($arg:tt)*) => {
+        if !$crate::output::style().quiet {
+            $crate::say!($($arg)*);
+        }
+    };
+}
+
+/// Print a progress banner or hint to stderr unless `--quiet` is set
+#[macro_export]
+macro_rules! enotice {
+    ($($arg:tt)*) => {
+        if !$crate::output::style().quiet {
+            $crate::esay!($($arg)*);
+        }
+    };
+}
+
+/// Breakdowns `stats --breakdown` shows on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsBreakdown {
+    /// Unfinished tasks per priority
+    Priority,
+    /// All tasks per status
+    Status,
+    /// Unfinished tasks per assignee
+    Assignee,
+}
+
+impl StatsBreakdown {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::Status => "status",
+            Self::Assignee => "assignee",
+        }
+    }
+}
+
+/// Task statistics shared by the table, JSON and CSV renderers
+#[derive(Debug, Serialize)]
+pub struct TaskStats {
+    pub total: usize,
+    pub unfinished: usize,
+    pub completed: usize,
+    pub completion_rate: f64,
+    pub priority: PriorityCounts,
+    pub status: StatusCounts,
+    pub overdue: usize,
+    pub overdue_tasks: Vec<Task>,
+    /// Open work per assignee, busiest first; unassigned tasks come last
+    pub assignees: Vec<AssigneeWorkload>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriorityCounts {
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    pub none: usize,
+}
+
+/// Tasks per status; `other` counts statuses without a known mapping
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusCounts {
+    pub pending: usize,
+    pub in_progress: usize,
+    pub completed: usize,
+    pub cancelled: usize,
+    pub other: usize,
+}
+
+impl StatusCounts {
+    pub fn compute(tasks: &[Task]) -> Self {
+        let mut counts = Self::default();
+        for task in tasks {
+            match task.normalized_status() {
+                Some(TaskStatus::Pending) => counts.pending += 1,
+                Some(TaskStatus::InProgress) => counts.in_progress += 1,
+                Some(TaskStatus::Completed) => counts.completed += 1,
+                Some(TaskStatus::Cancelled) => counts.cancelled += 1,
+                None => counts.other += 1,
+            }
+        }
+        counts
+    }
+}
+
+impl TaskStats {
+    pub fn compute(all_tasks: &[Task], unfinished_tasks: &[Task]) -> Self {
+        let total = all_tasks.len();
+        let unfinished = unfinished_tasks.len();
+        let completed = total.saturating_sub(unfinished);
+        let completion_rate = if total > 0 {
+            (completed as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let mut priority = PriorityCounts::default();
+        for task in unfinished_tasks {
+            match priority_level(task.priority.as_deref()) {
+                "high" => priority.high += 1,
+                "medium" => priority.medium += 1,
+                "low" => priority.low += 1,
+                _ => priority.none += 1,
+            }
+        }
+
+        let overdue_tasks = overdue_tasks(unfinished_tasks)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Self {
+            total,
+            unfinished,
+            completed,
+            completion_rate,
+            priority,
+            status: StatusCounts::compute(all_tasks),
+            overdue: overdue_tasks.len(),
+            overdue_tasks,
+            assignees: AssigneeWorkload::compute(unfinished_tasks),
+        }
+    }
+
+    /// `(label, count)` rows of one breakdown, in display order
+    pub fn breakdown(&self, breakdown: StatsBreakdown) -> Vec<(String, usize)> {
+        let rows = match breakdown {
+            StatsBreakdown::Priority => vec![
+                ("high", self.priority.high),
+                ("medium", self.priority.medium),
+                ("low", self.priority.low),
+                ("none", self.priority.none),
+            ],
+            StatsBreakdown::Status => vec![
+                ("pending", self.status.pending),
+                ("in_progress", self.status.in_progress),
+                ("completed", self.status.completed),
+                ("cancelled", self.status.cancelled),
+                ("other", self.status.other),
+            ],
+            StatsBreakdown::Assignee => {
+                return self
+                    .assignees
+                    .iter()
+                    .map(|workload| (workload.name().to_string(), workload.open))
+                    .collect();
+            }
+        };
+        rows.into_iter()
+            .map(|(label, count)| (label.to_string(), count))
+            .collect()
+    }
+
+    /// Render one breakdown as `<breakdown>,count` rows separated by `separator`
+    pub fn breakdown_to_delimited(&self, breakdown: StatsBreakdown, separator: char) -> String {
+        let mut output = delimited_row(
+            &[breakdown.as_str().to_string(), "count".to_string()],
+            separator,
+        );
+        for (label, count) in self.breakdown(breakdown) {
+            output.push_str(&delimited_row(&[label, count.to_string()], separator));
+        }
+        output
+    }
+
+    /// Render as `metric,value` rows separated by `separator`
+    pub fn to_delimited(&self, separator: char) -> String {
+        let rows = [
+            ("total", self.total.to_string()),
+            ("unfinished", self.unfinished.to_string()),
+            ("completed", self.completed.to_string()),
+            ("completion_rate", format!("{:.1}", self.completion_rate)),
+            ("priority_high", self.priority.high.to_string()),
+            ("priority_medium", self.priority.medium.to_string()),
+            ("priority_low", self.priority.low.to_string()),
+            ("priority_none", self.priority.none.to_string()),
+            ("status_pending", self.status.pending.to_string()),
+            ("status_in_progress", self.status.in_progress.to_string()),
+            ("status_completed", self.status.completed.to_string()),
+            ("status_cancelled", self.status.cancelled.to_string()),
+            ("status_other", self.status.other.to_string()),
+            ("overdue", self.overdue.to_string()),
+        ];
+
+        let mut output = delimited_row(&["metric".to_string(), "value".to_string()], separator);
+        for (metric, value) in rows {
+            output.push_str(&delimited_row(&[metric.to_string(), value], separator));
+        }
+        for workload in &self.assignees {
+            let name = workload.name();
+            for (metric, value) in [
+                ("open", workload.open),
+                ("in_progress", workload.in_progress),
+                ("overdue", workload.overdue),
+                ("high_priority", workload.high_priority),
+            ] {
+                output.push_str(&delimited_row(
+                    &[format!("assignee_{}:{}", metric, name), value.to_string()],
+                    separator,
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Unfinished tasks of one assignee; names differing only in case are counted together
+#[derive(Debug, Serialize)]
+pub struct AssigneeWorkload {
+    /// `None` for tasks nobody is assigned to
+    pub assignee: Option<String>,
+    pub open: usize,
+    pub in_progress: usize,
+    pub overdue: usize,
+    pub high_priority: usize,
+}
+
+impl AssigneeWorkload {
+    /// One workload per assignee of `unfinished_tasks`, most open tasks first
+    pub fn compute(unfinished_tasks: &[Task]) -> Vec<Self> {
+        let now = Utc::now();
+        let mut workloads: Vec<Self> = Vec::new();
+
+        for task in unfinished_tasks {
+            let assignee = task
+                .assignee
+                .as_deref()
+                .map(str::trim)
+                .filter(|assignee| !assignee.is_empty());
+            let workload = match workloads.iter_mut().position(|workload| {
+                match (workload.assignee.as_deref(), assignee) {
+                    (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }) {
+                Some(idx) => &mut workloads[idx],
+                None => {
+                    workloads.push(Self {
+                        assignee: assignee.map(str::to_string),
+                        open: 0,
+                        in_progress: 0,
+                        overdue: 0,
+                        high_priority: 0,
+                    });
+                    workloads.last_mut().expect("just pushed")
+                }
+            };
+
+            workload.open += 1;
+            if task.normalized_status() == Some(TaskStatus::InProgress) {
+                workload.in_progress += 1;
+            }
+            if TaskDate::parse_opt(task.due_date.as_deref()).is_some_and(|due| due.is_overdue(now))
+            {
+                workload.overdue += 1;
+            }
+            if priority_level(task.priority.as_deref()) == "high" {
+                workload.high_priority += 1;
+            }
+        }
+
+        workloads.sort_by(|a, b| {
+            a.assignee
+                .is_none()
+                .cmp(&b.assignee.is_none())
+                .then_with(|| b.open.cmp(&a.open))
+                .then_with(|| {
+                    let name =
+                        |workload: &Self| workload.assignee.as_deref().map(str::to_lowercase);
+                    name(a).cmp(&name(b))
+                })
+        });
+        workloads
+    }
+
+    /// Assignee name, or `unassigned`
+    pub fn name(&self) -> &str {
+        self.assignee.as_deref().unwrap_or("unassigned")
+    }
+}
+
+/// Task counts for one tag; tags differing only in case are counted together
+#[derive(Debug, Serialize)]
+pub struct TagSummary {
+    pub tag: String,
+    pub total: usize,
+    pub open: usize,
+    pub done: usize,
+    pub overdue: usize,
+}
+
+impl TagSummary {
+    /// One summary per tag, most used first
+    pub fn compute(tasks: &[Task]) -> Vec<Self> {
+        let now = Utc::now();
+        let mut summaries: Vec<Self> = Vec::new();
+
+        for task in tasks {
+            let overdue = task.is_unfinished()
+                && TaskDate::parse_opt(task.due_date.as_deref())
+                    .is_some_and(|due| due.is_overdue(now));
+            let mut seen = Vec::new();
+            for tag in task.tags.iter().flatten() {
+                let key = tag.to_lowercase();
+                if seen.contains(&key) {
+                    continue;
+                }
+                seen.push(key);
+
+                let summary = match summaries
+                    .iter_mut()
+                    .position(|summary| summary.tag.eq_ignore_ascii_case(tag))
+                {
+                    Some(idx) => &mut summaries[idx],
+                    None => {
+                        summaries.push(Self {
+                            tag: tag.clone(),
+                            total: 0,
+                            open: 0,
+                            done: 0,
+                            overdue: 0,
+                        });
+                        summaries.last_mut().expect("just pushed")
+                    }
+                };
+                summary.total += 1;
+                if task.is_unfinished() {
+                    summary.open += 1;
+                } else {
+                    summary.done += 1;
+                }
+                if overdue {
+                    summary.overdue += 1;
+                }
+            }
+        }
+
+        summaries.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()))
+        });
+        summaries
+    }
+}
+
+/// Summary of an MCP tool for machine-readable output
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub parameters: Vec<String>,
+    pub input_schema: Value,
+}
+
+impl From<&Tool> for ToolInfo {
+    fn from(tool: &Tool) -> Self {
+        let input_schema = tool.schema_as_json_value();
+        let parameters = input_schema
+            .get("properties")
+            .and_then(|properties| properties.as_object())
+            .map(|props| props.keys().cloned().collect())
+            .unwrap_or_default();
+
+        Self {
+            name: tool.name.to_string(),
+            description: tool.description.as_ref().map(|d| d.to_string()),
+            parameters,
+            input_schema,
+        }
+    }
+}
+
+/// Summary of an MCP resource for machine-readable output
+#[derive(Debug, Serialize)]
+pub struct ResourceInfo {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl From<&Resource> for ResourceInfo {
+    fn from(resource: &Resource) -> Self {
+        Self {
+            uri: resource.uri.clone(),
+            name: resource.name.clone(),
+            description: resource.description.clone(),
+            mime_type: resource.mime_type.clone(),
+        }
+    }
+}
+
+/// Columns `text` takes up in a terminal; CJK characters and most emoji count
+/// as two
+pub fn text_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// `text` cut down to `max_width` columns, ending in `...` when it was cut. Cuts
+/// fall between grapheme clusters, so accented letters and emoji stay whole.
+pub fn truncate(text: &str, max_width: usize) -> String {
+    if text_width(text) <= max_width {
+        return text.to_string();
+    }
+    let (head, _) = split_at_width(text, max_width.saturating_sub(3));
+    format!("{}...", head)
+}
+
+/// `text` padded with spaces on the right to `width` columns
+pub fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(text_width(text)))
+    )
+}
+
+/// The longest start of `text` that fits in `max_width` columns, and the rest
+fn split_at_width(text: &str, max_width: usize) -> (&str, &str) {
+    let mut width = 0;
+    let mut end = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        width += text_width(grapheme);
+        if width > max_width {
+            break;
+        }
+        end = idx + grapheme.len();
+    }
+    text.split_at(end)
+}
+
+/// Greedy word wrap to `max_width` columns; words longer than a line are split
+pub fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for mut word in text.split_whitespace() {
+        while text_width(word) > max_width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let (head, rest) = split_at_width(word, max_width);
+            if head.is_empty() {
+                // Not even one character fits; give it a line of its own
+                break;
+            }
+            lines.push(head.to_string());
+            word = rest;
+        }
+
+        if !current.is_empty() && text_width(&current) + 1 + text_width(word) > max_width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+/// Render tasks as CSV with one row per task; tags are joined with `;`
+pub fn tasks_to_csv(tasks: &[Task]) -> String {
+    let mut output = String::from(
+        "id,title,description,status,priority,assignee,due_date,created_at,updated_at,completed_at,tags\n",
+    );
+
+    for task in tasks {
+        let fields = [
+            task.id.clone(),
+            task.title.clone(),
+            task.description.clone().unwrap_or_default(),
+            task.status.clone(),
+            task.priority.clone().unwrap_or_default(),
+            task.assignee.clone().unwrap_or_default(),
+            task.due_date.clone().unwrap_or_default(),
+            task.created_at.clone(),
+            task.updated_at.clone().unwrap_or_default(),
+            task.completed_at.clone().unwrap_or_default(),
+            task.tags
+                .as_ref()
+                .map(|tags| tags.join(";"))
+                .unwrap_or_default(),
+        ];
+        output.push_str(&csv_row(&fields));
+    }
+
+    output
+}
+
+/// Render tools as `name,description,parameters` CSV rows
+pub fn tools_to_csv(tools: &[ToolInfo]) -> String {
+    let mut output = String::from("name,description,parameters\n");
+    for tool in tools {
+        output.push_str(&csv_row(&[
+            tool.name.clone(),
+            tool.description.clone().unwrap_or_default(),
+            tool.parameters.join(";"),
+        ]));
+    }
+    output
+}
+
+/// Render `doctor` results as CSV, one row per server with a column per operation
+pub fn server_checks_to_csv(checks: &[ServerCheck]) -> String {
+    let mut header = vec!["server", "connected"];
+    header.extend(Capability::ALL.map(Capability::as_str));
+    header.extend(["resources", "error"]);
+    let mut output = format!("{}\n", header.join(","));
+    for check in checks {
+        let mut row = vec![check.server.clone(), check.connected.to_string()];
+        for capability in Capability::ALL {
+            row.push(
+                check
+                    .capabilities
+                    .as_ref()
+                    .filter(|capabilities| capabilities.known())
+                    .map(|capabilities| capabilities.supports(capability).to_string())
+                    .unwrap_or_default(),
+            );
+        }
+        row.push(
+            check
+                .capabilities
+                .as_ref()
+                .map(|capabilities| capabilities.resources.to_string())
+                .unwrap_or_default(),
+        );
+        row.push(check.error.clone().unwrap_or_default());
+        output.push_str(&csv_row(&row));
+    }
+    output
+}
+
+/// Render resources as `uri,name,description,mime_type` CSV rows
+pub fn resources_to_csv(resources: &[ResourceInfo]) -> String {
+    let mut output = String::from("uri,name,description,mime_type\n");
+    for resource in resources {
+        output.push_str(&csv_row(&[
+            resource.uri.clone(),
+            resource.name.clone(),
+            resource.description.clone().unwrap_or_default(),
+            resource.mime_type.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// Render an agenda as CSV with one row per task, most urgent first
+pub fn agenda_to_csv(agenda: &Agenda) -> String {
+    let mut output = String::from("window,due,when,id,title,status,priority\n");
+    for entry in &agenda.entries {
+        output.push_str(&csv_row(&[
+            entry.window.as_str().to_string(),
+            entry.due.clone().unwrap_or_default(),
+            entry.relative.clone().unwrap_or_default(),
+            entry.task.id.clone(),
+            entry.task.title.clone(),
+            entry.task.status.clone(),
+            entry.task.priority.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// One row per task of the briefing; the AI summary is left out
+pub fn brief_to_csv(brief: &Brief) -> String {
+    let mut output = String::from("section,due,when,id,title,status,priority\n");
+    for entry in brief.overdue.iter().chain(&brief.due_today) {
+        output.push_str(&csv_row(&[
+            entry.window.as_str().to_string(),
+            entry.due.clone().unwrap_or_default(),
+            entry.relative.clone().unwrap_or_default(),
+            entry.task.id.clone(),
+            entry.task.title.clone(),
+            entry.task.status.clone(),
+            entry.task.priority.clone().unwrap_or_default(),
+        ]));
+    }
+    for task in &brief.in_progress {
+        output.push_str(&csv_row(&[
+            "in_progress".to_string(),
+            task.due_date.clone().unwrap_or_default(),
+            String::new(),
+            task.id.clone(),
+            task.title.clone(),
+            task.status.clone(),
+            task.priority.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// One row per changed field of updated tasks and one per other change
+pub fn snapshot_diff_to_csv(diff: &SnapshotDiff) -> String {
+    let mut output = String::from("change,id,title,field,before,after\n");
+    for change in &diff.changes {
+        let task = change.task();
+        match change {
+            TaskChange::Updated { before, after } => {
+                for (label, old_value, new_value) in task_field_changes(before, after) {
+                    output.push_str(&csv_row(&[
+                        change.kind().to_string(),
+                        task.id.clone(),
+                        task.title.clone(),
+                        label.to_lowercase(),
+                        old_value,
+                        new_value,
+                    ]));
+                }
+            }
+            _ => output.push_str(&csv_row(&[
+                change.kind().to_string(),
+                task.id.clone(),
+                task.title.clone(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ])),
+        }
+    }
+    output
+}
+
+pub fn tag_summaries_to_csv(summaries: &[TagSummary]) -> String {
+    let mut output = String::from("tag,total,open,done,overdue\n");
+    for summary in summaries {
+        output.push_str(&csv_row(&[
+            summary.tag.clone(),
+            summary.total.to_string(),
+            summary.open.to_string(),
+            summary.done.to_string(),
+            summary.overdue.to_string(),
+        ]));
+    }
+    output
+}
+
+pub fn subtasks_to_csv(subtasks: &[ProposedSubtask]) -> String {
+    let mut output = String::from("title,description,priority,effort\n");
+    for subtask in subtasks {
+        output.push_str(&csv_row(&[
+            subtask.title.clone(),
+            subtask.description.clone().unwrap_or_default(),
+            subtask.priority.clone().unwrap_or_default(),
+            subtask.effort.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// One row per assessed task, in the recommended order; dependencies are `;`-separated
+pub fn assessments_to_csv(analysis: &StructuredAnalysis) -> String {
+    let mut output =
+        String::from("order,id,title,priority_score,complexity,risk,dependencies,rationale\n");
+    for (idx, task) in analysis.ordered().into_iter().enumerate() {
+        output.push_str(&csv_row(&[
+            (idx + 1).to_string(),
+            task.id.clone(),
+            task.title.clone(),
+            task.priority_score.to_string(),
+            task.complexity.as_str().to_string(),
+            task.risk.as_str().to_string(),
+            task.dependencies.join(";"),
+            task.rationale.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+/// One row per task in a duplicate cluster, with `keep` or `close` as its action
+pub fn duplicate_clusters_to_csv(clusters: &[DuplicateCluster]) -> String {
+    let mut output = String::from("cluster,id,action,similarity,rationale\n");
+    for (idx, cluster) in clusters.iter().enumerate() {
+        for id in &cluster.ids {
+            output.push_str(&csv_row(&[
+                (idx + 1).to_string(),
+                id.clone(),
+                if *id == cluster.keep { "keep" } else { "close" }.to_string(),
+                cluster
+                    .similarity
+                    .map(|similarity| similarity.to_string())
+                    .unwrap_or_default(),
+                cluster.rationale.clone(),
+            ]));
+        }
+    }
+    output
+}
+
+pub fn priority_changes_to_csv(changes: &[PriorityChange]) -> String {
+    let mut output = String::from("id,title,current,suggested,changed,justification\n");
+    for change in changes {
+        output.push_str(&csv_row(&[
+            change.id.clone(),
+            change.title.clone(),
+            change.current.clone().unwrap_or_default(),
+            change.suggested.clone(),
+            change.is_change().to_string(),
+            change.justification.clone(),
+        ]));
+    }
+    output
+}
+
+pub fn estimates_to_csv(estimates: &[TaskEstimate], unit: EstimateUnit) -> String {
+    let mut output = String::from("id,title,priority,current,estimate,unit,rationale\n");
+    for estimate in estimates {
+        output.push_str(&csv_row(&[
+            estimate.id.clone(),
+            estimate.title.clone(),
+            estimate.priority.clone().unwrap_or_default(),
+            estimate.current.clone().unwrap_or_default(),
+            estimate.estimate.to_string(),
+            unit.suffix().to_string(),
+            estimate.rationale.clone(),
+        ]));
+    }
+    output
+}
+
+/// One row per planned task per period, then the tasks that did not fit with
+/// an empty period
+pub fn plan_to_csv(plan: &Plan) -> String {
+    let mut output = String::from("period,id,title,hours,due_date,late,reason,suggestion\n");
+    for period in &plan.periods {
+        for item in &period.items {
+            output.push_str(&csv_row(&[
+                period.start.to_string(),
+                item.id.clone(),
+                item.title.clone(),
+                item.hours.to_string(),
+                item.due_date.clone().unwrap_or_default(),
+                item.late.to_string(),
+                String::new(),
+                String::new(),
+            ]));
+        }
+    }
+    for deferral in &plan.unscheduled {
+        output.push_str(&csv_row(&[
+            String::new(),
+            deferral.id.clone(),
+            deferral.title.clone(),
+            String::new(),
+            String::new(),
+            String::new(),
+            deferral.reason.clone(),
+            deferral.suggestion.clone(),
+        ]));
+    }
+    output
+}
+
+pub fn sprint_proposal_to_csv(proposal: &SprintProposal) -> String {
+    let mut output = String::from("sprint,id,title,priority,estimate,unit,rationale\n");
+    for pick in &proposal.selected {
+        output.push_str(&csv_row(&[
+            proposal.name.clone(),
+            pick.id.clone(),
+            pick.title.clone(),
+            pick.priority.clone().unwrap_or_default(),
+            pick.estimate.to_string(),
+            proposal.capacity.unit.suffix().to_string(),
+            pick.rationale.clone(),
+        ]));
+    }
+    output
+}
+
+pub fn sprint_review_to_csv(review: &SprintReview) -> String {
+    let mut output = String::from("sprint,id,title,status,estimate,outcome\n");
+    for item in &review.items {
+        output.push_str(&csv_row(&[
+            review.name.clone(),
+            item.id.clone(),
+            item.title.clone(),
+            item.status.clone(),
+            item.estimate
+                .map(|estimate| estimate.to_string())
+                .unwrap_or_default(),
+            item.outcome.as_str().to_string(),
+        ]));
+    }
+    output
+}
+
+pub fn bulk_results_to_csv(results: &[BulkResult]) -> String {
+    let mut output = String::from("id,title,ok,error\n");
+    for result in results {
+        output.push_str(&csv_row(&[
+            result.id.clone(),
+            result.title.clone(),
+            result.ok.to_string(),
+            result.error.clone().unwrap_or_default(),
+        ]));
+    }
+    output
+}
+
+pub fn semantic_matches_to_csv(matches: &[SemanticMatch]) -> String {
+    let mut output = String::from("score,id,title,status\n");
+    for semantic_match in matches {
+        output.push_str(&csv_row(&[
+            format!("{:.4}", semantic_match.score),
+            semantic_match.task.id.clone(),
+            semantic_match.task.title.clone(),
+            semantic_match.task.status.clone(),
+        ]));
+    }
+    output
+}
+
+/// One row per task, with the theme it belongs to
+pub fn themes_to_csv(report: &ThemeReport) -> String {
+    let mut output = String::from("theme,share,id,title,status\n");
+    for theme in &report.themes {
+        for task in &theme.tasks {
+            output.push_str(&csv_row(&[
+                theme.label.clone(),
+                format!("{:.1}", theme.share),
+                task.id.clone(),
+                task.title.clone(),
+                task.status.clone(),
+            ]));
+        }
+    }
+    output
+}
+
+/// One row per template task
+pub fn templates_to_csv(templates: &[NamedTemplate]) -> String {
+    let mut output = String::from("template,source,title,description,priority,tags,due\n");
+    for named in templates {
+        for task in &named.template.tasks {
+            output.push_str(&csv_row(&[
+                named.name.clone(),
+                named.source.as_str().to_string(),
+                task.title.clone(),
+                task.description.clone().unwrap_or_default(),
+                task.priority.clone().unwrap_or_default(),
+                task.tags.join(";"),
+                task.due.clone().unwrap_or_default(),
+            ]));
+        }
+    }
+    output
+}
+
+pub fn time_entries_to_csv(entries: &[TimeEntry]) -> String {
+    let mut output = String::from("task_id,title,start,end,hours\n");
+    for entry in entries {
+        output.push_str(&csv_row(&[
+            entry.task_id.clone(),
+            entry.title.clone(),
+            entry.start.to_rfc3339(),
+            entry.end.to_rfc3339(),
+            format!("{:.2}", entry.hours()),
+        ]));
+    }
+    output
+}
+
+pub fn csv_row(fields: &[String]) -> String {
+    let escaped = fields
+        .iter()
+        .map(|field| csv_escape(field))
+        .collect::<Vec<_>>();
+    format!("{}\n", escaped.join(","))
+}
+
+/// One row of `fields` joined by `separator`: a CSV row for `,`; for other
+/// separators such as tabs, separators and line breaks inside fields become spaces
+pub fn delimited_row(fields: &[String], separator: char) -> String {
+    if separator == ',' {
+        return csv_row(fields);
+    }
+    let fields = fields
+        .iter()
+        .map(|field| field.replace([separator, '\n', '\r'], " "))
+        .collect::<Vec<_>>();
+    format!("{}\n", fields.join(&separator.to_string()))
+}
+
+/// Quote a CSV field when it contains separators, quotes or line breaks
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Render a task list in the requested format, using `table` for the human-friendly view
+pub fn render_tasks(
+    tasks: &[Task],
+    format: DisplayFormat,
+    table: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    match format {
+        DisplayFormat::Table => table(),
+        DisplayFormat::Json => to_json(tasks),
+        DisplayFormat::Csv => Ok(tasks_to_csv(tasks)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_counts_display_width() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("Prepare release notes", 10), "Prepare...");
+        // CJK characters take two columns each
+        assert_eq!(truncate("你好世界你好世界", 9), "你好世...");
+        assert_eq!(text_width(&truncate("你好世界你好世界", 8)), 7);
+    }
+
+    #[test]
+    fn truncate_keeps_graphemes_whole() {
+        assert_eq!(truncate("Задача номер один", 9), "Задача...");
+        // The family emoji is one grapheme made of several code points
+        let family = "👨‍👩‍👧";
+        let text = format!("{}{}{}", family, family, family);
+        let truncated = truncate(&text, 5);
+        assert_eq!(truncated, format!("{}...", family));
+        assert!(text_width(&truncated) <= 5);
+    }
+
+    #[test]
+    fn pad_fills_to_display_width() {
+        assert_eq!(pad("你好", 6), "你好  ");
+        assert_eq!(pad("too long", 3), "too long");
+    }
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert!(wrap("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn wrap_splits_wide_words_by_width() {
+        let lines = wrap("你好世界你好 ok", 5);
+        assert_eq!(lines, ["你好", "世界", "你好", "ok"]);
+        assert!(lines.iter().all(|line| text_width(line) <= 5));
+
+        assert_eq!(wrap("Привет, мир", 7), ["Привет,", "мир"]);
+    }
+}
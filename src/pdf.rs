@@ -0,0 +1,143 @@
+use anyhow::Result;
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Point,
+};
+
+use crate::output::wrap;
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const PT_TO_MM: f32 = 0.3528;
+
+/// Average glyph width of Helvetica relative to the font size, used for line wrapping
+const AVG_CHAR_WIDTH: f32 = 0.5;
+
+/// Render a Markdown document as an A4 PDF using the built-in Helvetica fonts.
+///
+/// Headings, rules and list items are laid out; inline Markdown is stripped. The
+/// built-in fonts only cover ASCII, so other characters are transliterated or
+/// replaced with `?`.
+pub fn render_markdown(title: &str, markdown: &str) -> Result<Vec<u8>> {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+
+    let mut writer = PdfWriter {
+        layer: doc.get_page(page).get_layer(layer),
+        doc,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    for raw_line in markdown.lines() {
+        let line = raw_line.trim_end();
+        let trimmed = line.trim_start();
+
+        if trimmed.is_empty() {
+            writer.space(3.0);
+        } else if trimmed.chars().all(|c| c == '-' || c == '=') && trimmed.len() >= 3 {
+            writer.rule();
+        } else if let Some(heading) = trimmed.strip_prefix("# ") {
+            writer.paragraph(heading, &bold, 18.0, 0.0);
+        } else if let Some(heading) = trimmed.strip_prefix("## ") {
+            writer.space(2.0);
+            writer.paragraph(heading, &bold, 14.0, 0.0);
+        } else if let Some(heading) = trimmed.strip_prefix("### ") {
+            writer.paragraph(heading, &bold, 12.0, 0.0);
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            let indent = (line.len() - trimmed.len()) as f32 * 1.5 + 4.0;
+            writer.paragraph(&format!("- {}", item), &regular, 10.0, indent);
+        } else {
+            let indent = (line.len() - trimmed.len()) as f32 * 1.5;
+            writer.paragraph(trimmed, &regular, 10.0, indent);
+        }
+    }
+
+    Ok(writer.doc.save_to_bytes()?)
+}
+
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    /// Baseline of the next line, in mm from the bottom of the page
+    y: f32,
+}
+
+impl PdfWriter {
+    fn paragraph(&mut self, text: &str, font: &IndirectFontRef, size: f32, indent: f32) {
+        let text = to_pdf_text(&strip_inline_markdown(text));
+        let line_height = size * 1.4 * PT_TO_MM;
+        let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        let max_chars = (width / (size * AVG_CHAR_WIDTH * PT_TO_MM)).max(10.0) as usize;
+
+        for line in wrap(&text, max_chars) {
+            self.ensure_space(line_height);
+            self.y -= line_height;
+            self.layer
+                .use_text(line, size, Mm(MARGIN + indent), Mm(self.y), font);
+        }
+    }
+
+    fn rule(&mut self) {
+        self.ensure_space(6.0);
+        self.y -= 3.0;
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= 3.0;
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Start a new page when `height` more mm would run into the bottom margin
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self
+                .doc
+                .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+}
+
+fn strip_inline_markdown(text: &str) -> String {
+    text.replace("**", "").replace('`', "")
+}
+
+/// Map text onto the ASCII range the built-in PDF fonts can display
+fn to_pdf_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            ' '..='~' => output.push(c),
+            '\t' => output.push(' '),
+            '‘' | '’' => output.push('\''),
+            '“' | '”' => output.push('"'),
+            '–' | '—' => output.push('-'),
+            '•' | '·' => output.push('-'),
+            '→' => output.push_str("->"),
+            '…' => output.push_str("..."),
+            // Emoji and their modifiers are decoration in our reports; drop them
+            c if is_emoji(c) => {}
+            _ => output.push('?'),
+        }
+    }
+
+    output.trim().to_string()
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x2190..=0x2BFF | 0xFE00..=0xFE0F | 0x1F000..=0x1FAFF | 0x200D)
+}
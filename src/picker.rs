@@ -0,0 +1,276 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::mcp_client::Task;
+use crate::output;
+
+/// What to do with the task chosen in `pick`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PickAction {
+    /// Show the task's details
+    Show,
+    /// Mark the task as completed
+    Complete,
+    /// Break the task down with the AI, like `analyze-task`
+    Analyze,
+    /// Copy the task ID to the clipboard
+    CopyId,
+}
+
+impl PickAction {
+    const ALL: [PickAction; 4] = [Self::Show, Self::Complete, Self::Analyze, Self::CopyId];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Show => "🔍 Show details",
+            Self::Complete => "✅ Complete",
+            Self::Analyze => "🤖 Analyze with AI",
+            Self::CopyId => "📋 Copy ID",
+        }
+    }
+}
+
+/// Picker state: the query, the tasks matching it and the cursor
+struct Picker {
+    tasks: Vec<Task>,
+    query: String,
+    /// Indices into `tasks`, best match first
+    matches: Vec<usize>,
+    list: ListState,
+    /// Set once a task is chosen and the action menu is open
+    chosen: Option<usize>,
+    menu: ListState,
+    matcher: SkimMatcherV2,
+}
+
+impl Picker {
+    fn new(tasks: Vec<Task>) -> Self {
+        let mut picker = Self {
+            tasks,
+            query: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+            chosen: None,
+            menu: ListState::default().with_selected(Some(0)),
+            matcher: SkimMatcherV2::default(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// Re-rank tasks against the query; an empty query keeps the server order
+    fn update_matches(&mut self) {
+        let mut scored = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, task)| {
+                if self.query.is_empty() {
+                    return Some((0, idx));
+                }
+                self.matcher
+                    .fuzzy_match(&search_text(task), &self.query)
+                    .map(|score| (score, idx))
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by_key(|(score, idx)| (std::cmp::Reverse(*score), *idx));
+
+        self.matches = scored.into_iter().map(|(_, idx)| idx).collect();
+        self.list.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [input_area, list_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::new().fg(Color::Cyan)),
+            Span::raw(&self.query),
+        ]))
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            " Pick a task ({}/{}) ",
+            self.matches.len(),
+            self.tasks.len()
+        )));
+        frame.render_widget(input, input_area);
+
+        let help = match self.chosen {
+            Some(idx) => {
+                let items = PickAction::ALL
+                    .iter()
+                    .map(|action| ListItem::new(action.label()));
+                let menu = List::new(items)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!(" {} ", self.tasks[idx].title)),
+                    )
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+                frame.render_stateful_widget(menu, list_area, &mut self.menu);
+                "↑/↓ action  Enter run  Esc back"
+            }
+            None => {
+                let items = self.matches.iter().map(|&idx| {
+                    let task = &self.tasks[idx];
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{} ", output::pad(&task.id, 10)),
+                            Style::new().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            format!("{} ", output::pad(&task.status, 12)),
+                            Style::new().fg(Color::Yellow),
+                        ),
+                        Span::raw(task.title.clone()),
+                    ]))
+                });
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL))
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+                frame.render_stateful_widget(list, list_area, &mut self.list);
+                "type to filter  ↑/↓ select  Enter choose  Esc cancel"
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(help, Style::new().fg(Color::DarkGray))),
+            help_area,
+        );
+    }
+}
+
+/// Text a task is matched against: title, ID, status and tags
+fn search_text(task: &Task) -> String {
+    let mut text = format!("{} {} {}", task.title, task.id, task.status);
+    for tag in task.tags.iter().flatten() {
+        text.push_str(" #");
+        text.push_str(tag);
+    }
+    text
+}
+
+/// Let the user fuzzy-search `tasks` and choose one. Without a preset `action`
+/// an action menu follows. Returns `None` if the picker was cancelled.
+pub fn pick_task(
+    tasks: Vec<Task>,
+    action: Option<PickAction>,
+) -> Result<Option<(Task, PickAction)>> {
+    let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+    let result = run_picker(&mut terminal, Picker::new(tasks), action);
+    ratatui::restore();
+
+    result
+}
+
+fn run_picker(
+    terminal: &mut DefaultTerminal,
+    mut picker: Picker,
+    action: Option<PickAction>,
+) -> Result<Option<(Task, PickAction)>> {
+    loop {
+        terminal.draw(|frame| picker.draw(frame))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(None);
+        }
+
+        match picker.chosen {
+            Some(idx) => match key.code {
+                KeyCode::Esc => picker.chosen = None,
+                KeyCode::Up | KeyCode::Char('k') => picker.menu.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => picker.menu.select_next(),
+                KeyCode::Enter => {
+                    let action = PickAction::ALL[picker
+                        .menu
+                        .selected()
+                        .unwrap_or(0)
+                        .min(PickAction::ALL.len() - 1)];
+                    return Ok(Some((picker.tasks.swap_remove(idx), action)));
+                }
+                _ => {}
+            },
+            None => match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Up => picker.list.select_previous(),
+                KeyCode::Down => picker.list.select_next(),
+                KeyCode::Enter => {
+                    let Some(&idx) = picker
+                        .list
+                        .selected()
+                        .and_then(|pos| picker.matches.get(pos))
+                    else {
+                        continue;
+                    };
+                    match action {
+                        Some(action) => return Ok(Some((picker.tasks.swap_remove(idx), action))),
+                        None => picker.chosen = Some(idx),
+                    }
+                }
+                KeyCode::Backspace => {
+                    picker.query.pop();
+                    picker.update_matches();
+                }
+                KeyCode::Char(c) => {
+                    picker.query.push(c);
+                    picker.update_matches();
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+/// Copy `text` with the platform's clipboard tool (`pbcopy`, `clip`, `wl-copy`,
+/// `xclip` or `xsel`); returns the name of the tool that worked
+pub fn copy_to_clipboard(text: &str) -> Result<&'static str> {
+    let candidates: &[(&'static str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+
+    for (program, args) in candidates {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(program);
+        }
+    }
+
+    anyhow::bail!("No clipboard tool available")
+}
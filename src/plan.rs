@@ -0,0 +1,495 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use tracing::warn;
+
+use crate::dates::TaskDate;
+use crate::error::McpTasksError;
+use crate::estimate;
+use crate::mcp_client::Task;
+use crate::structured;
+
+/// Marker the plan prompt contains; the mock provider recognizes it
+pub const PLAN_PROMPT_MARKER: &str = "\"unscheduled\"";
+
+pub const PLAN_SYSTEM_PROMPT: &str = "You are a pragmatic project planner scheduling work within a fixed capacity. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Lines of the plan prompt listing the periods and their capacity; the mock reads them
+pub const PERIODS_LINE: &str = "Periods: ";
+pub const CAPACITY_LINE: &str = "Capacity per period: ";
+
+/// Working days in a week, used to convert daily and weekly capacity
+const WORKDAYS_PER_WEEK: f64 = 5.0;
+
+/// Keeps the prompt and the table readable
+const MAX_PERIODS: usize = 60;
+
+/// Length of the periods a plan is made of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanPeriod {
+    /// Working days, Monday to Friday
+    Day,
+    /// Weeks starting today (default)
+    #[default]
+    Week,
+}
+
+impl PlanPeriod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+}
+
+/// Working hours available per day or week
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Capacity {
+    pub hours: f64,
+    pub per: PlanPeriod,
+}
+
+impl Capacity {
+    /// Hours available in one period of length `period`
+    pub fn hours_per(self, period: PlanPeriod) -> f64 {
+        match (self.per, period) {
+            (PlanPeriod::Day, PlanPeriod::Week) => self.hours * WORKDAYS_PER_WEEK,
+            (PlanPeriod::Week, PlanPeriod::Day) => self.hours / WORKDAYS_PER_WEEK,
+            _ => self.hours,
+        }
+    }
+}
+
+/// `2.5h`, rounded to a tenth of an hour
+pub fn format_hours(hours: f64) -> String {
+    format!("{}h", (hours * 10.0).round() / 10.0)
+}
+
+/// Parse a capacity such as `20h`, `20h/week`, `4h/day` or a bare number of
+/// hours per week
+pub fn parse_capacity(input: &str) -> Result<Capacity, String> {
+    let input = input.trim();
+    let (amount, per) = match input.split_once('/') {
+        Some((amount, per)) => (amount, per.trim()),
+        None => (input, "week"),
+    };
+    let per = match per {
+        "d" | "day" => PlanPeriod::Day,
+        "w" | "wk" | "week" => PlanPeriod::Week,
+        other => {
+            return Err(format!(
+                "unknown capacity period '{}': use day or week",
+                other
+            ));
+        }
+    };
+    let hours = amount
+        .trim()
+        .trim_end_matches('h')
+        .parse::<f64>()
+        .ok()
+        .filter(|hours| hours.is_finite() && *hours > 0.0)
+        .ok_or_else(|| format!("invalid capacity '{}': expected e.g. 20h or 4h/day", input))?;
+    Ok(Capacity { hours, per })
+}
+
+/// The periods a plan covers and the hours available in each
+#[derive(Debug, Clone)]
+pub struct PlanWindow {
+    pub period: PlanPeriod,
+    /// Hours available per period
+    pub capacity: f64,
+    pub today: NaiveDate,
+    /// Start date of each period
+    pub starts: Vec<NaiveDate>,
+    pub until: NaiveDate,
+}
+
+impl PlanWindow {
+    /// Periods from `today` through `until`; day periods skip weekends
+    pub fn new(
+        today: NaiveDate,
+        until: NaiveDate,
+        period: PlanPeriod,
+        capacity: Capacity,
+    ) -> Result<Self> {
+        if until < today {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "--until {} is in the past",
+                until
+            )));
+        }
+
+        let step = match period {
+            PlanPeriod::Day => 1,
+            PlanPeriod::Week => 7,
+        };
+        let starts =
+            std::iter::successors(Some(today), |date| date.checked_add_days(Days::new(step)))
+                .take_while(|date| *date <= until)
+                .filter(|date| {
+                    period == PlanPeriod::Week
+                        || !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                })
+                .take(MAX_PERIODS + 1)
+                .collect::<Vec<_>>();
+
+        if starts.len() > MAX_PERIODS {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "The plan would have more than {} {}s; pick an earlier --until or plan by week",
+                MAX_PERIODS,
+                period.as_str()
+            )));
+        }
+        if starts.is_empty() {
+            anyhow::bail!(McpTasksError::Config(format!(
+                "There is no working day between {} and {}",
+                today, until
+            )));
+        }
+
+        Ok(Self {
+            period,
+            capacity: capacity.hours_per(period),
+            today,
+            starts,
+            until,
+        })
+    }
+}
+
+/// Prompt asking for a schedule of the formatted tasks over `periods`
+pub fn plan_prompt(task_summary: &str, tasks: &[Task], field: &str, window: &PlanWindow) -> String {
+    let estimates = tasks
+        .iter()
+        .filter_map(|task| {
+            estimate::current_estimate(task, field)
+                .map(|estimate| format!("{}: {}", task.id, estimate))
+        })
+        .collect::<Vec<_>>();
+    let estimates = if estimates.is_empty() {
+        "none; estimate each task yourself".to_string()
+    } else {
+        format!(
+            "{} (sp = story points, h = hours; estimate tasks without one yourself)",
+            estimates.join(", ")
+        )
+    };
+    let periods = window
+        .starts
+        .iter()
+        .map(NaiveDate::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let task_count = tasks.len();
+    let period = window.period.as_str();
+    let hours = format_hours(window.capacity);
+    let today = window.today;
+
+    format!(
+        r#"Today is {today}. Schedule these {task_count} unfinished tasks into the {period}s listed below without exceeding the capacity of any {period}. Work on the most important and most urgent tasks first and finish tasks before their due dates where possible. A task may be split across several {period}s.
+
+{PERIODS_LINE}{periods} (the start date of each {period})
+{CAPACITY_LINE}{hours}
+Stored estimates: {estimates}
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "schedule": [
+    {{
+      "period": "start date of the {period} as listed above",
+      "tasks": [{{ "id": "task ID as given above", "hours": 4 }}]
+    }}
+  ],
+  "unscheduled": [
+    {{
+      "id": "task ID that does not fit",
+      "reason": "why it does not fit",
+      "suggestion": "what to do instead, e.g. defer it, drop it or cut its scope"
+    }}
+  ],
+  "notes": "two or three sentences on the plan's main risks and trade-offs"
+}}
+
+Every task has to appear in the schedule, in "unscheduled", or both when only part of it fits."#
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPlan {
+    #[serde(default)]
+    schedule: Vec<RawPeriod>,
+    #[serde(default)]
+    unscheduled: Vec<RawDeferral>,
+    #[serde(default)]
+    notes: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPeriod {
+    period: String,
+    #[serde(default)]
+    tasks: Vec<RawItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawItem {
+    id: String,
+    hours: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDeferral {
+    id: String,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    suggestion: String,
+}
+
+/// Work on one task within a period
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanItem {
+    pub id: String,
+    pub title: String,
+    pub hours: f64,
+    pub due_date: Option<String>,
+    /// The task is due before the period starts
+    pub late: bool,
+}
+
+/// One day or week of the plan
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedPeriod {
+    pub start: NaiveDate,
+    pub items: Vec<PlanItem>,
+    pub hours: f64,
+    pub over_capacity: bool,
+}
+
+/// A task that does not fit into the plan
+#[derive(Debug, Clone, Serialize)]
+pub struct Deferral {
+    pub id: String,
+    pub title: String,
+    pub reason: String,
+    pub suggestion: String,
+}
+
+/// Schedule of pending tasks over the planning window
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    pub period: PlanPeriod,
+    /// Hours available per period
+    pub capacity: f64,
+    pub until: NaiveDate,
+    pub periods: Vec<PlannedPeriod>,
+    pub unscheduled: Vec<Deferral>,
+    pub notes: String,
+}
+
+/// Parse the model's answer into a plan over `window`. Unknown tasks are
+/// dropped; tasks the model left out or put into a period that does not exist
+/// are listed as unscheduled.
+pub fn parse_plan(response: &str, tasks: &[Task], window: &PlanWindow) -> Result<Plan> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain a JSON plan".to_string())
+    })?;
+    let raw: RawPlan = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI plan".to_string(),
+    ))?;
+
+    let find_task = |id: &str| tasks.iter().find(|task| task.id == id);
+    let mut unscheduled = Vec::new();
+    let mut planned = window
+        .starts
+        .iter()
+        .map(|start| PlannedPeriod {
+            start: *start,
+            items: Vec::new(),
+            hours: 0.0,
+            over_capacity: false,
+        })
+        .collect::<Vec<_>>();
+
+    for raw_period in &raw.schedule {
+        let mut target = NaiveDate::parse_from_str(raw_period.period.trim(), "%Y-%m-%d")
+            .ok()
+            .and_then(|start| planned.iter_mut().find(|period| period.start == start));
+        for item in &raw_period.tasks {
+            let Some(task) = find_task(&item.id) else {
+                warn!("Ignoring plan entry for unknown task '{}'", item.id);
+                continue;
+            };
+            match target {
+                Some(ref mut period) if item.hours.is_finite() && item.hours > 0.0 => {
+                    let due = TaskDate::parse_opt(task.due_date.as_deref());
+                    period.items.push(PlanItem {
+                        id: task.id.clone(),
+                        title: task.title.clone(),
+                        hours: item.hours,
+                        due_date: task.due_date.clone(),
+                        late: due.is_some_and(|due| due.date() < period.start),
+                    });
+                }
+                Some(_) => {}
+                None => unscheduled.push(Deferral {
+                    id: task.id.clone(),
+                    title: task.title.clone(),
+                    reason: format!(
+                        "Scheduled for '{}', which is not a {} of the plan",
+                        raw_period.period,
+                        window.period.as_str()
+                    ),
+                    suggestion: String::new(),
+                }),
+            }
+        }
+    }
+
+    for period in &mut planned {
+        period.hours = period
+            .items
+            .iter()
+            .fold(0.0, |hours, item| hours + item.hours);
+        // Allow for rounding in the model's arithmetic
+        period.over_capacity = period.hours > window.capacity + 0.01;
+    }
+
+    for deferral in &raw.unscheduled {
+        match find_task(&deferral.id) {
+            Some(task)
+                if !unscheduled
+                    .iter()
+                    .any(|known: &Deferral| known.id == task.id) =>
+            {
+                unscheduled.push(Deferral {
+                    id: task.id.clone(),
+                    title: task.title.clone(),
+                    reason: deferral.reason.clone(),
+                    suggestion: deferral.suggestion.clone(),
+                })
+            }
+            Some(_) => {}
+            None => warn!("Ignoring deferral of unknown task '{}'", deferral.id),
+        }
+    }
+
+    for task in tasks {
+        let scheduled = planned
+            .iter()
+            .any(|period| period.items.iter().any(|item| item.id == task.id));
+        if !scheduled && !unscheduled.iter().any(|deferral| deferral.id == task.id) {
+            unscheduled.push(Deferral {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                reason: "Left out of the plan by the AI".to_string(),
+                suggestion: String::new(),
+            });
+        }
+    }
+
+    Ok(Plan {
+        period: window.period,
+        capacity: window.capacity,
+        until: window.until,
+        periods: planned,
+        unscheduled,
+        notes: raw.notes.trim().to_string(),
+    })
+}
+
+impl Plan {
+    /// Heading of a period: `Week of 2025-06-02` or `Mon 2025-06-02`
+    pub fn period_label(&self, start: NaiveDate) -> String {
+        match self.period {
+            PlanPeriod::Day => start.format("%a %Y-%m-%d").to_string(),
+            PlanPeriod::Week => format!("Week of {}", start),
+        }
+    }
+
+    pub fn planned_hours(&self) -> f64 {
+        self.periods
+            .iter()
+            .fold(0.0, |hours, period| hours + period.hours)
+    }
+
+    pub fn available_hours(&self) -> f64 {
+        self.capacity * self.periods.len() as f64
+    }
+
+    /// The plan as a Markdown checklist per period
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Capacity Plan\n\n");
+        let _ = writeln!(
+            out,
+            "**Capacity:** {} per {}  \n**Until:** {}  \n**Planned:** {} of {}\n",
+            format_hours(self.capacity),
+            self.period.as_str(),
+            self.until,
+            format_hours(self.planned_hours()),
+            format_hours(self.available_hours())
+        );
+
+        for period in &self.periods {
+            let _ = writeln!(
+                out,
+                "## {} ({} / {}){}\n",
+                self.period_label(period.start),
+                format_hours(period.hours),
+                format_hours(self.capacity),
+                if period.over_capacity {
+                    " ⚠️ over capacity"
+                } else {
+                    ""
+                }
+            );
+            if period.items.is_empty() {
+                out.push_str("_Nothing planned_\n\n");
+                continue;
+            }
+            for item in &period.items {
+                let _ = write!(
+                    out,
+                    "- [ ] {} (`{}`) - {}",
+                    item.title,
+                    item.id,
+                    format_hours(item.hours)
+                );
+                if let Some(due) = TaskDate::parse_opt(item.due_date.as_deref()) {
+                    let _ = write!(out, ", due {}", due.format_date());
+                }
+                if item.late {
+                    out.push_str(" ⚠️ late");
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+
+        if !self.unscheduled.is_empty() {
+            out.push_str("## Won't Fit\n\n");
+            for deferral in &self.unscheduled {
+                let _ = write!(out, "- **{}** (`{}`)", deferral.title, deferral.id);
+                if !deferral.reason.is_empty() {
+                    let _ = write!(out, ": {}", deferral.reason);
+                }
+                if !deferral.suggestion.is_empty() {
+                    let _ = write!(out, " Suggestion: {}", deferral.suggestion);
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+
+        if !self.notes.is_empty() {
+            let _ = writeln!(out, "## Notes\n\n{}", self.notes);
+        }
+        out
+    }
+}
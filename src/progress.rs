@@ -0,0 +1,93 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::output;
+
+/// Spinner of the running command, if one is shown
+static CURRENT: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Spinner on stderr with the phase of a long-running command and how long it has
+/// taken so far; removed when dropped. Nothing is shown with `--quiet` or when
+/// stdout or stderr is not a terminal.
+pub struct Spinner {
+    bar: Option<ProgressBar>,
+}
+
+impl Spinner {
+    /// Show a spinner at `phase`; while one is already shown, only its phase changes
+    pub fn start(phase: impl Into<String>) -> Self {
+        let phase = phase.into();
+        let Ok(mut current) = CURRENT.lock() else {
+            return Self { bar: None };
+        };
+        if let Some(bar) = current.as_ref() {
+            update(bar, phase);
+            return Self { bar: None };
+        }
+
+        let style = output::style();
+        if style.quiet || !std::io::stdout().is_terminal() || !std::io::stderr().is_terminal() {
+            return Self { bar: None };
+        }
+
+        let template = if style.color {
+            "{spinner:.cyan} {msg}, {elapsed}"
+        } else {
+            "{spinner} {msg}, {elapsed}"
+        };
+        let bar = ProgressBar::new_spinner()
+            .with_style(
+                ProgressStyle::with_template(template)
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            )
+            .with_message(phase);
+        bar.enable_steady_tick(Duration::from_millis(100));
+        *current = Some(bar.clone());
+        Self { bar: Some(bar) }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            if let Ok(mut current) = CURRENT.lock() {
+                *current = None;
+            }
+        }
+    }
+}
+
+fn update(bar: &ProgressBar, phase: String) {
+    bar.set_message(phase);
+    bar.reset_elapsed();
+}
+
+/// Move the shown spinner, if any, to the next phase
+pub fn set_phase(phase: impl Into<String>) {
+    if let Ok(current) = CURRENT.lock()
+        && let Some(bar) = current.as_ref()
+    {
+        update(bar, phase.into());
+    }
+}
+
+/// Remove the spinner for the rest of the command, e.g. when a response is streamed
+pub fn clear() {
+    if let Ok(mut current) = CURRENT.lock()
+        && let Some(bar) = current.take()
+    {
+        bar.finish_and_clear();
+    }
+}
+
+/// Run `f`, which writes to the terminal, with the spinner hidden
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    let bar = CURRENT.lock().ok().and_then(|current| current.clone());
+    match bar {
+        Some(bar) => bar.suspend(f),
+        None => f(),
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{debug, warn};
+
+use crate::error::McpTasksError;
+use crate::llm::{LlmProvider, TokenSink};
+use crate::tooling::{ChatRequest, ChatResponse};
+
+/// Provider that writes each request and response of the one it wraps to
+/// numbered files: `001-request.json`, `001-response.json`, ...
+pub struct DumpingProvider {
+    inner: Box<dyn LlmProvider>,
+    dir: PathBuf,
+    /// Number of the last exchange written
+    counter: AtomicUsize,
+}
+
+impl DumpingProvider {
+    /// Numbering continues after the files already in `dir`, so several
+    /// analyses in one run or across runs do not overwrite each other
+    pub fn new(inner: Box<dyn LlmProvider>, dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| {
+            McpTasksError::Config(format!(
+                "Failed to create prompt dump directory {}",
+                dir.display()
+            ))
+        })?;
+        let last = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                name.strip_suffix("-request.json")?.parse::<usize>().ok()
+            })
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            inner,
+            dir: dir.to_path_buf(),
+            counter: AtomicUsize::new(last),
+        })
+    }
+
+    fn write(&self, number: usize, kind: &str, payload: &impl Serialize) {
+        let path = self.dir.join(format!("{:03}-{}.json", number, kind));
+        let result = serde_json::to_string_pretty(payload)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| std::fs::write(&path, json).map_err(anyhow::Error::from));
+        match result {
+            Ok(()) => debug!("Wrote {}", path.display()),
+            Err(e) => warn!("Failed to write {}: {}", path.display(), e),
+        }
+    }
+
+    fn dump_request(&self, request: &ChatRequest) -> usize {
+        let number = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+        self.write(number, "request", request);
+        number
+    }
+
+    fn dump_response(&self, number: usize, response: &Result<ChatResponse>) {
+        match response {
+            Ok(response) => self.write(number, "response", response),
+            Err(e) => self.write(number, "error", &json!({ "error": format!("{:#}", e) })),
+        }
+    }
+}
+
+impl LlmProvider for DumpingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let number = self.dump_request(&request);
+            let response = self.inner.chat(request).await;
+            self.dump_response(number, &response);
+            response
+        })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            let number = self.dump_request(&request);
+            let response = self.inner.chat_streaming(request, on_token).await;
+            self.dump_response(number, &response);
+            response
+        })
+    }
+}
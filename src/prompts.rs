@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::warn;
+
+use crate::dates::TaskDate;
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+
+/// Prompt used by `analyze` when no template is configured
+pub const DEFAULT_PROMPT: &str = "Please analyze the following {{task_count}} pending tasks and provide:
+
+1. **Priority Assessment**: Identify high-priority tasks based on due dates, dependencies, and business impact
+2. **Complexity Analysis**: Categorize tasks by estimated complexity (simple, moderate, complex)
+3. **Dependency Mapping**: Identify any potential task dependencies or conflicts
+4. **Actionable Recommendations**: Suggest an optimal execution order and resource allocation
+5. **Risk Assessment**: Highlight any tasks that might be at risk of delays or conflicts
+
+Here are the pending tasks:
+
+{{tasks}}
+
+Please provide a structured analysis that will help prioritize and organize the work effectively.";
+
+const PRIORITY_FOCUS_PROMPT: &str = "Today is {{today}}. Rank the following {{task_count}} pending tasks from most to least urgent.
+
+For each task give a one-line justification based on its priority, due date and likely impact. Finish with the three tasks that should be started first.
+
+Here are the pending tasks:
+
+{{tasks}}";
+
+const SPRINT_PLANNING_PROMPT: &str = "Today is {{today}}. Plan a two-week sprint from the following {{task_count}} pending tasks.
+
+1. **Sprint Goal**: Summarize what this sprint should achieve in one sentence
+2. **Sprint Backlog**: Pick the tasks that fit the sprint, with a rough size estimate (S/M/L) for each
+3. **Deferred**: List tasks that should wait for a later sprint and why
+4. **Schedule**: Suggest an order of work across week 1 and week 2
+
+Here are the pending tasks:
+
+{{tasks}}";
+
+const RISK_ONLY_PROMPT: &str = "Today is {{today}}. Review the following {{task_count}} pending tasks strictly for risk.
+
+Only report tasks that are overdue, due soon, underspecified, blocked by other tasks, or likely to slip. For each one state the risk and a concrete mitigation. If there are no significant risks, say so briefly.
+
+Here are the pending tasks:
+
+{{tasks}}";
+
+/// Templates shipped with the binary, selectable by name
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("default", DEFAULT_PROMPT),
+    ("priority-focus", PRIORITY_FOCUS_PROMPT),
+    ("sprint-planning", SPRINT_PLANNING_PROMPT),
+    ("risk-only", RISK_ONLY_PROMPT),
+];
+
+/// System prompt of `analyze` unless `system_prompt` is configured
+pub const ANALYSIS_SYSTEM_PROMPT: &str = "You are a task analysis expert. Analyze the provided pending tasks and provide insights about priorities, dependencies, complexity, and actionable recommendations.";
+
+/// System prompt of tool-calling analyses unless `system_prompt` is configured
+pub const TOOL_SYSTEM_PROMPT: &str = "You are an AI assistant that can analyze tasks and manage todo lists. You have access to various tools to help you provide detailed, accurate information. Use tools when they can help provide better answers.";
+
+/// Audience an analysis is written for, setting its tone and focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Persona {
+    /// Team view: sprint scope, blockers, who is overloaded
+    ScrumMaster,
+    /// One person's to-do list: what to do next and what to drop
+    SoloDev,
+    /// Short summary for leadership: status, risks, decisions needed
+    ExecBrief,
+}
+
+impl Persona {
+    /// Instructions appended to the system prompt
+    pub fn instructions(self) -> &'static str {
+        match self {
+            Persona::ScrumMaster => {
+                "Write as a scrum master addressing the team. Focus on sprint scope, blockers and dependencies between tasks, uneven workload across assignees, and what to raise at the next stand-up. Keep a facilitating, collaborative tone."
+            }
+            Persona::SoloDev => {
+                "Write for a solo developer managing their own backlog. Focus on what to work on next, quick wins, tasks that can be dropped or deferred, and avoiding context switches. Be direct and practical, addressing the reader as \"you\"."
+            }
+            Persona::ExecBrief => {
+                "Write an executive brief. Lead with a three-sentence summary of overall status, then only the top risks and the decisions needed from leadership. Avoid implementation detail and keep the whole answer short."
+            }
+        }
+    }
+}
+
+/// `base` with the persona's instructions appended
+pub fn system_prompt(base: &str, persona: Option<Persona>) -> String {
+    match persona {
+        Some(persona) => format!("{}\n\n{}", base, persona.instructions()),
+        None => base.to_string(),
+    }
+}
+
+const TEMPLATE_VARIABLES: &[&str] = &["task_count", "tasks", "today"];
+
+/// Resolve a template by name (config templates first, then built-ins) or,
+/// failing that, read it from a file path
+pub fn resolve_template(spec: &str, custom: &BTreeMap<String, String>) -> Result<String> {
+    if let Some(template) = custom.get(spec) {
+        return Ok(template.clone());
+    }
+
+    if let Some((_, template)) = BUILTIN_TEMPLATES.iter().find(|(name, _)| *name == spec) {
+        return Ok(template.to_string());
+    }
+
+    let path = Path::new(spec);
+    if path.is_file() {
+        return std::fs::read_to_string(path).with_context(|| {
+            McpTasksError::Config(format!("Failed to read prompt template {}", path.display()))
+        });
+    }
+
+    let mut names = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    names.extend(custom.keys().cloned());
+
+    anyhow::bail!(McpTasksError::Config(format!(
+        "Unknown prompt template '{}': not a template file or one of: {}",
+        spec,
+        names.join(", ")
+    )))
+}
+
+/// Fill `{{task_count}}`, `{{tasks}}` and `{{today}}` placeholders in a template
+pub fn render_template(template: &str, task_summary: &str, task_count: usize) -> String {
+    let mut rendered = String::with_capacity(template.len() + task_summary.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+
+        let Some(end) = after_open.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        match after_open[..end].trim() {
+            "task_count" => rendered.push_str(&task_count.to_string()),
+            "tasks" => rendered.push_str(task_summary.trim_end()),
+            "today" => rendered.push_str(&Utc::now().format("%Y-%m-%d").to_string()),
+            other => {
+                warn!(
+                    "Unknown prompt template variable '{}' (available: {})",
+                    other,
+                    TEMPLATE_VARIABLES.join(", ")
+                );
+                rendered.push_str(&rest[start..start + 2 + end + 2]);
+            }
+        }
+
+        rest = &after_open[end + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Most recently completed tasks given to an analysis as context
+const MAX_RECENTLY_COMPLETED: usize = 50;
+
+/// Context section listing tasks completed since `since`, most recent first,
+/// so an analysis can build on what just shipped
+pub fn recently_completed_section(tasks: &[Task], since: NaiveDate) -> String {
+    let mut completed = tasks
+        .iter()
+        .filter_map(|task| {
+            let completed_at = TaskDate::parse_opt(task.completed_at.as_deref())?;
+            (completed_at.date() >= since).then_some((completed_at, task))
+        })
+        .collect::<Vec<_>>();
+    completed.sort_by_key(|(completed_at, _)| std::cmp::Reverse(completed_at.sort_key()));
+
+    let mut section = format!(
+        "\nRecently completed tasks (since {}), for context only; they are not part of the work to analyze. Take what just shipped into account and point out recurring patterns (areas that keep needing work, similar tasks coming back):\n",
+        since.format("%Y-%m-%d")
+    );
+    if completed.is_empty() {
+        section.push_str("none\n");
+    }
+    for (completed_at, task) in completed.iter().take(MAX_RECENTLY_COMPLETED) {
+        section.push_str(&format!(
+            "- {} ({}, completed {}",
+            task.title,
+            task.id,
+            completed_at.format_date()
+        ));
+        if let Some(tags) = task.tags.as_ref().filter(|tags| !tags.is_empty()) {
+            section.push_str(&format!(", tags: {}", tags.join(", ")));
+        }
+        section.push_str(")\n");
+    }
+    if completed.len() > MAX_RECENTLY_COMPLETED {
+        section.push_str(&format!(
+            "... and {} more\n",
+            completed.len() - MAX_RECENTLY_COMPLETED
+        ));
+    }
+
+    section
+}
@@ -0,0 +1,266 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::llm::{LlmProvider, TokenSink};
+use crate::progress;
+use crate::tooling::{ChatRequest, ChatResponse};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Limiter shared by every provider of the process, so separate analyses in
+/// one run (daemon jobs, bulk AI commands) count against the same budget
+static SHARED: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+/// Requests and tokens sent in the last minute
+struct Sent {
+    id: u64,
+    at: Instant,
+    tokens: u64,
+}
+
+#[derive(Default)]
+struct Window {
+    sent: VecDeque<Sent>,
+    next_id: u64,
+}
+
+/// Client-side requests-per-minute and tokens-per-minute limits over a sliding
+/// one-minute window. Callers wait their turn in order.
+pub struct RateLimiter {
+    requests_per_minute: Option<u32>,
+    tokens_per_minute: Option<u64>,
+    window: Mutex<Window>,
+    /// Held while waiting, so requests go out in the order they were made
+    queue: tokio::sync::Mutex<()>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: Option<u32>, tokens_per_minute: Option<u64>) -> Self {
+        Self {
+            requests_per_minute,
+            tokens_per_minute,
+            window: Mutex::new(Window::default()),
+            queue: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// The process-wide limiter, created with these limits on first use
+    pub fn shared(requests_per_minute: Option<u32>, tokens_per_minute: Option<u64>) -> Arc<Self> {
+        SHARED
+            .get_or_init(|| Arc::new(Self::new(requests_per_minute, tokens_per_minute)))
+            .clone()
+    }
+
+    /// Wait until a request of about `tokens` tokens fits into the limits and
+    /// count it; returns an ID for [`settle`](Self::settle)
+    pub async fn acquire(&self, tokens: u64) -> u64 {
+        let _turn = self.queue.lock().await;
+
+        loop {
+            let wait = {
+                let mut window = self.window.lock().unwrap();
+                let now = Instant::now();
+                while window
+                    .sent
+                    .front()
+                    .is_some_and(|sent| now.duration_since(sent.at) >= WINDOW)
+                {
+                    window.sent.pop_front();
+                }
+
+                match self.wait_time(&window, tokens, now) {
+                    None => {
+                        let id = window.next_id;
+                        window.next_id += 1;
+                        window.sent.push_back(Sent {
+                            id,
+                            at: now,
+                            tokens,
+                        });
+                        return id;
+                    }
+                    Some(wait) => wait,
+                }
+            };
+
+            let seconds = wait.as_secs_f64().ceil() as u64;
+            info!(
+                "Waiting {}s for rate limit ({})",
+                seconds.max(1),
+                self.describe()
+            );
+            progress::set_phase(format!("waiting {}s for rate limit", seconds.max(1)));
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Replace the estimate of request `id` with the tokens it really used
+    pub fn settle(&self, id: u64, tokens: u64) {
+        let mut window = self.window.lock().unwrap();
+        if let Some(sent) = window.sent.iter_mut().find(|sent| sent.id == id) {
+            sent.tokens = tokens;
+        }
+    }
+
+    /// How long until the oldest requests leave the window far enough for this
+    /// one; `None` when it can go now. A request larger than the whole token
+    /// budget goes alone once the window is empty.
+    fn wait_time(&self, window: &Window, tokens: u64, now: Instant) -> Option<Duration> {
+        let until_oldest_expires = |skip: usize| {
+            window
+                .sent
+                .get(skip)
+                .map(|sent| (sent.at + WINDOW).saturating_duration_since(now))
+                .unwrap_or_default()
+        };
+
+        if let Some(limit) = self.requests_per_minute
+            && window.sent.len() >= limit as usize
+        {
+            return Some(until_oldest_expires(window.sent.len() - limit as usize));
+        }
+
+        if let Some(limit) = self.tokens_per_minute {
+            let mut used = window.sent.iter().map(|sent| sent.tokens).sum::<u64>();
+            let mut expiring = 0;
+            while used + tokens > limit && expiring < window.sent.len() {
+                used -= window.sent[expiring].tokens;
+                expiring += 1;
+            }
+            if expiring > 0 {
+                return Some(until_oldest_expires(expiring - 1));
+            }
+        }
+
+        None
+    }
+
+    fn describe(&self) -> String {
+        let mut limits = Vec::new();
+        if let Some(limit) = self.requests_per_minute {
+            limits.push(format!("{} requests/min", limit));
+        }
+        if let Some(limit) = self.tokens_per_minute {
+            limits.push(format!("{} tokens/min", limit));
+        }
+        limits.join(", ")
+    }
+}
+
+/// Provider that holds requests back until they fit the rate limits
+pub struct RateLimitedProvider {
+    inner: Box<dyn LlmProvider>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    fn settle(&self, id: u64, estimate: u64, response: &Result<ChatResponse>) {
+        let used = match response {
+            Ok(response) => response
+                .usage
+                .as_ref()
+                .map(|usage| usage.prompt_tokens + usage.completion_tokens)
+                .unwrap_or(estimate),
+            Err(_) => estimate,
+        };
+        debug!("Request used {} tokens (estimated {})", used, estimate);
+        self.limiter.settle(id, used);
+    }
+}
+
+impl LlmProvider for RateLimitedProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let estimate = request.estimated_prompt_tokens();
+            let id = self.limiter.acquire(estimate).await;
+            progress::set_phase(format!("waiting on {}", self.inner.name()));
+            let response = self.inner.chat(request).await;
+            self.settle(id, estimate, &response);
+            response
+        })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            let estimate = request.estimated_prompt_tokens();
+            let id = self.limiter.acquire(estimate).await;
+            let response = self.inner.chat_streaming(request, on_token).await;
+            self.settle(id, estimate, &response);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seconds `acquire` waited for a request of `tokens`; time is paused, so
+    /// sleeps end as soon as nothing else can run
+    async fn wait_for(limiter: &RateLimiter, tokens: u64) -> (u64, u64) {
+        let start = Instant::now();
+        let id = limiter.acquire(tokens).await;
+        (id, start.elapsed().as_secs())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_per_minute_limit_waits_for_the_oldest_request() {
+        let limiter = RateLimiter::new(Some(2), None);
+        assert_eq!(wait_for(&limiter, 0).await, (0, 0));
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert_eq!(wait_for(&limiter, 0).await, (1, 0));
+
+        // The first request leaves the window 60s after it was sent
+        assert_eq!(wait_for(&limiter, 0).await, (2, 40));
+        assert_eq!(wait_for(&limiter, 0).await, (3, 20));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokens_per_minute_limit_waits_until_the_request_fits() {
+        let limiter = RateLimiter::new(None, Some(100));
+        assert_eq!(wait_for(&limiter, 60).await.1, 0);
+        assert_eq!(wait_for(&limiter, 40).await.1, 0);
+        assert_eq!(wait_for(&limiter, 60).await.1, 60);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn settled_usage_replaces_the_estimate() {
+        let limiter = RateLimiter::new(None, Some(100));
+        let (id, _) = wait_for(&limiter, 60).await;
+        limiter.settle(id, 10);
+        assert_eq!(wait_for(&limiter, 90).await.1, 0);
+        assert_eq!(wait_for(&limiter, 1).await.1, 60);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn oversized_request_goes_alone() {
+        let limiter = RateLimiter::new(None, Some(100));
+        assert_eq!(wait_for(&limiter, 500).await.1, 0);
+        assert_eq!(wait_for(&limiter, 1).await.1, 60);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_limits_never_wait() {
+        let limiter = RateLimiter::new(None, None);
+        for _ in 0..100 {
+            assert_eq!(wait_for(&limiter, 1_000_000).await.1, 0);
+        }
+    }
+}
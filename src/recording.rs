@@ -0,0 +1,452 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use rmcp::service::{RoleClient, RxJsonRpcMessage, TxJsonRpcMessage};
+use rmcp::transport::Transport;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::error::McpTasksError;
+use crate::llm::{LlmProvider, TokenSink};
+use crate::tooling::{ChatRequest, ChatResponse, StreamToken};
+
+/// Version of the session file format
+const SESSION_VERSION: u32 = 1;
+
+/// JSON-RPC internal error, returned for requests missing from a recording
+const REPLAY_MISS_CODE: i64 = -32603;
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+static REPLAY: Mutex<Option<Replay>> = Mutex::new(None);
+
+/// Everything an `--record` run exchanged with the model and the MCP servers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub recorded_at: DateTime<Utc>,
+    #[serde(default)]
+    pub llm: Vec<LlmExchange>,
+    #[serde(default)]
+    pub mcp: Vec<McpExchange>,
+}
+
+/// One chat request and the response the model gave
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LlmExchange {
+    pub request: Value,
+    pub response: Value,
+}
+
+/// One JSON-RPC request to an MCP server and its result or error
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpExchange {
+    pub server: String,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+}
+
+struct Recording {
+    path: PathBuf,
+    session: Session,
+}
+
+struct Replay {
+    session: Session,
+    next_llm: usize,
+    used_mcp: Vec<bool>,
+}
+
+/// Capture LLM and MCP traffic from now on, to be written to `path` by [`finish`]
+pub fn start_recording(path: &Path) {
+    if let Ok(mut recording) = RECORDING.lock() {
+        *recording = Some(Recording {
+            path: path.to_path_buf(),
+            session: Session {
+                version: SESSION_VERSION,
+                recorded_at: Utc::now(),
+                llm: Vec::new(),
+                mcp: Vec::new(),
+            },
+        });
+    }
+}
+
+/// Answer LLM and MCP requests from the session recorded in `path`
+pub fn start_replay(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path).with_context(|| {
+        McpTasksError::Config(format!("Failed to read recording {}", path.display()))
+    })?;
+    let session: Session = serde_json::from_str(&content)
+        .with_context(|| McpTasksError::Config(format!("Invalid recording {}", path.display())))?;
+    if session.version != SESSION_VERSION {
+        anyhow::bail!(McpTasksError::Config(format!(
+            "Recording {} has format version {}, but only {} is supported",
+            path.display(),
+            session.version,
+            SESSION_VERSION
+        )));
+    }
+    debug!(
+        "Replaying {} LLM and {} MCP exchanges recorded at {}",
+        session.llm.len(),
+        session.mcp.len(),
+        session.recorded_at
+    );
+
+    if let Ok(mut replay) = REPLAY.lock() {
+        *replay = Some(Replay {
+            used_mcp: vec![false; session.mcp.len()],
+            next_llm: 0,
+            session,
+        });
+    }
+    Ok(())
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.lock().is_ok_and(|recording| recording.is_some())
+}
+
+pub fn is_replaying() -> bool {
+    REPLAY.lock().is_ok_and(|replay| replay.is_some())
+}
+
+/// Write the recording, if one was started; returns its path and the number
+/// of LLM and MCP exchanges in it
+pub fn finish() -> Result<Option<(PathBuf, usize, usize)>> {
+    let Some(Recording { path, session }) = RECORDING
+        .lock()
+        .ok()
+        .and_then(|mut recording| recording.take())
+    else {
+        return Ok(None);
+    };
+    let json = serde_json::to_string_pretty(&session)?;
+    std::fs::write(&path, json).with_context(|| {
+        McpTasksError::Config(format!("Failed to write recording {}", path.display()))
+    })?;
+    Ok(Some((path, session.llm.len(), session.mcp.len())))
+}
+
+fn record(update: impl FnOnce(&mut Session)) {
+    if let Ok(mut recording) = RECORDING.lock()
+        && let Some(recording) = recording.as_mut()
+    {
+        update(&mut recording.session);
+    }
+}
+
+/// Params as matched on replay: `_meta` carries per-run progress tokens
+fn comparable_params(params: Option<&Value>) -> Option<Value> {
+    let mut params = params?.clone();
+    if let Value::Object(map) = &mut params {
+        map.remove("_meta");
+    }
+    Some(params)
+}
+
+/// Provider that records every request and response of the one it wraps
+pub struct RecordingProvider {
+    inner: Box<dyn LlmProvider>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+fn record_llm(request: Value, response: &Result<ChatResponse>) {
+    if let Ok(response) = response
+        && let Ok(response) = serde_json::to_value(response)
+    {
+        record(|session| session.llm.push(LlmExchange { request, response }));
+    }
+}
+
+impl LlmProvider for RecordingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let recorded = serde_json::to_value(&request).unwrap_or_default();
+            let response = self.inner.chat(request).await;
+            record_llm(recorded, &response);
+            response
+        })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            let recorded = serde_json::to_value(&request).unwrap_or_default();
+            let response = self.inner.chat_streaming(request, on_token).await;
+            record_llm(recorded, &response);
+            response
+        })
+    }
+}
+
+/// Provider answering with the recorded responses, in the order they were given
+pub struct ReplayProvider;
+
+impl ReplayProvider {
+    fn next_response(&self, request: &ChatRequest) -> Result<ChatResponse> {
+        let mut replay = REPLAY.lock().map_err(|_| {
+            anyhow::anyhow!(McpTasksError::DeepSeekApi(
+                "Replay state is poisoned".to_string()
+            ))
+        })?;
+        let replay = replay.as_mut().ok_or_else(|| {
+            anyhow::anyhow!(McpTasksError::DeepSeekApi(
+                "No recording loaded".to_string()
+            ))
+        })?;
+        let Some(exchange) = replay.session.llm.get(replay.next_llm) else {
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "The recording has no more LLM responses ({} recorded)",
+                replay.session.llm.len()
+            )));
+        };
+        replay.next_llm += 1;
+
+        // Prompts mention the current date, so requests rarely match exactly
+        let sent = serde_json::to_value(request).unwrap_or_default();
+        if exchange.request.get("messages") != sent.get("messages") {
+            debug!(
+                "LLM request {} differs from the recorded one",
+                replay.next_llm
+            );
+        }
+        serde_json::from_value(exchange.response.clone()).with_context(|| {
+            McpTasksError::DeepSeekApi(format!(
+                "Recorded LLM response {} is invalid",
+                replay.next_llm
+            ))
+        })
+    }
+}
+
+impl LlmProvider for ReplayProvider {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move { self.next_response(&request) })
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(async move {
+            let response = self.next_response(&request)?;
+            if let Some(choice) = response.choices.first() {
+                if let Some(reasoning) = &choice.message.reasoning_content {
+                    on_token(StreamToken::Reasoning(reasoning));
+                }
+                if let Some(content) = &choice.message.content {
+                    on_token(StreamToken::Content(content));
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Transport that records the requests sent through the one it wraps, paired
+/// with their responses
+pub struct RecordingTransport<T> {
+    server: String,
+    inner: T,
+    /// Method and params of requests awaiting a response, by JSON-RPC id
+    pending: HashMap<String, (String, Option<Value>)>,
+}
+
+impl<T> RecordingTransport<T> {
+    pub fn new(server: &str, inner: T) -> Self {
+        Self {
+            server: server.to_string(),
+            inner,
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Transport<RoleClient> + Send> Transport<RoleClient> for RecordingTransport<T> {
+    type Error = T::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        if let Ok(Value::Object(message)) = serde_json::to_value(&item)
+            && let (Some(id), Some(Value::String(method))) =
+                (message.get("id"), message.get("method"))
+        {
+            self.pending.insert(
+                id.to_string(),
+                (method.clone(), comparable_params(message.get("params"))),
+            );
+        }
+        self.inner.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleClient>> {
+        let message = self.inner.receive().await?;
+        if let Ok(Value::Object(mut response)) = serde_json::to_value(&message)
+            && let Some(id) = response.get("id")
+            && let Some((method, params)) = self.pending.remove(&id.to_string())
+        {
+            let exchange = McpExchange {
+                server: self.server.clone(),
+                method,
+                params,
+                result: response.remove("result"),
+                error: response.remove("error"),
+            };
+            record(|session| session.mcp.push(exchange));
+        }
+        Some(message)
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
+
+/// Transport answering requests from the recording instead of a server
+pub struct ReplayTransport {
+    server: String,
+    responses: Option<mpsc::UnboundedSender<RxJsonRpcMessage<RoleClient>>>,
+    incoming: mpsc::UnboundedReceiver<RxJsonRpcMessage<RoleClient>>,
+}
+
+impl ReplayTransport {
+    pub fn new(server: &str) -> Self {
+        let (responses, incoming) = mpsc::unbounded_channel();
+        Self {
+            server: server.to_string(),
+            responses: Some(responses),
+            incoming,
+        }
+    }
+
+    /// The recorded outcome of `method` with `params`: the first unused exact
+    /// match, else the last one used, so repeated calls keep working
+    fn lookup(&self, method: &str, params: Option<&Value>) -> Result<Value, Value> {
+        let params = comparable_params(params);
+        let mut replay = REPLAY
+            .lock()
+            .map_err(|_| miss("Replay state is poisoned"))?;
+        let replay = replay.as_mut().ok_or_else(|| miss("No recording loaded"))?;
+
+        let matches = replay
+            .session
+            .mcp
+            .iter()
+            .enumerate()
+            .filter(|(_, exchange)| {
+                exchange.server == self.server
+                    && exchange.method == method
+                    && exchange.params == params
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let Some(idx) = matches
+            .iter()
+            .copied()
+            .find(|idx| !replay.used_mcp[*idx])
+            .or(matches.last().copied())
+        else {
+            warn!(
+                "No recorded response for '{}' on MCP server '{}'",
+                method, self.server
+            );
+            return Err(miss(&format!(
+                "No recorded response for '{}' on MCP server '{}'",
+                method, self.server
+            )));
+        };
+        replay.used_mcp[idx] = true;
+
+        let exchange = &replay.session.mcp[idx];
+        match (&exchange.result, &exchange.error) {
+            (_, Some(error)) => Err(error.clone()),
+            (Some(result), None) => Ok(result.clone()),
+            (None, None) => Ok(Value::Object(Map::new())),
+        }
+    }
+}
+
+fn miss(message: &str) -> Value {
+    json!({ "code": REPLAY_MISS_CODE, "message": message })
+}
+
+impl Transport<RoleClient> for ReplayTransport {
+    type Error = io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        // Notifications and replies to server requests need no answer
+        let answer = match serde_json::to_value(&item) {
+            Ok(Value::Object(message)) => match (message.get("id"), message.get("method")) {
+                (Some(id), Some(Value::String(method))) => {
+                    let mut response = json!({ "jsonrpc": "2.0", "id": id });
+                    match self.lookup(method, message.get("params")) {
+                        Ok(result) => response["result"] = result,
+                        Err(error) => response["error"] = error,
+                    }
+                    Some(response)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let result = match answer {
+            Some(response) => serde_json::from_value(response)
+                .map_err(io::Error::other)
+                .and_then(|response| {
+                    self.responses
+                        .as_ref()
+                        .and_then(|responses| responses.send(response).ok())
+                        .ok_or_else(|| {
+                            io::Error::new(io::ErrorKind::BrokenPipe, "Replay transport closed")
+                        })
+                }),
+            None => Ok(()),
+        };
+        std::future::ready(result)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        self.incoming.recv()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.responses = None;
+        std::future::ready(Ok(()))
+    }
+}
@@ -0,0 +1,176 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::{LazyLock, RwLock};
+
+/// What masked text is replaced with
+pub const MASK: &str = "***";
+
+/// Field names whose values are always masked
+pub const DEFAULT_FIELDS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "api-key",
+    "x-api-key",
+    "authorization",
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "password",
+    "secret",
+    "token",
+];
+
+/// `Bearer <token>` as in Authorization headers
+static BEARER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9._~+/=-]{8,}").expect("valid regex"));
+
+/// Key-looking strings: `sk-...` API keys
+static KEY_LIKE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bsk-[A-Za-z0-9_-]{8,}").expect("valid regex"));
+
+static REDACTOR: LazyLock<RwLock<Redactor>> =
+    LazyLock::new(|| RwLock::new(Redactor::new(Vec::new(), &[])));
+
+struct Redactor {
+    /// Exact secret values known from the configuration
+    secrets: Vec<String>,
+    /// `name: value`, `name=value` and `"name": "value"` for sensitive field names
+    fields: Regex,
+}
+
+impl Redactor {
+    fn new(secrets: Vec<String>, extra_fields: &[String]) -> Self {
+        let names = DEFAULT_FIELDS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(extra_fields.iter().cloned())
+            .map(|name| regex::escape(&name))
+            .collect::<Vec<_>>()
+            .join("|");
+        let fields = Regex::new(&format!(
+            r#"(?i)((?:^|[^A-Za-z0-9_-])"?(?:{})"?\s*[:=]\s*"?)([^"\s,;&}}]+)"#,
+            names
+        ))
+        .expect("escaped field names form a valid regex");
+        Self { secrets, fields }
+    }
+
+    fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), MASK));
+            }
+        }
+        for (pattern, replacement) in [
+            (&*BEARER, format!("${{1}}{}", MASK)),
+            (&*KEY_LIKE, format!("sk-{}", MASK)),
+            (&self.fields, format!("${{1}}{}", MASK)),
+        ] {
+            if let Cow::Owned(replaced) = pattern.replace_all(&text, replacement.as_str()) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+}
+
+/// Mask `secrets` wherever they appear, and the values of `fields` in addition
+/// to [`DEFAULT_FIELDS`]; done once the configuration is loaded
+pub fn configure(secrets: impl IntoIterator<Item = String>, fields: &[String]) {
+    let secrets = secrets
+        .into_iter()
+        // Very short values would mask ordinary words
+        .filter(|secret| secret.len() >= 8)
+        .collect();
+    if let Ok(mut redactor) = REDACTOR.write() {
+        *redactor = Redactor::new(secrets, fields);
+    }
+}
+
+/// Mask one more secret value, e.g. a key read from the keychain
+pub fn add_secret(secret: &str) {
+    if secret.len() >= 8
+        && let Ok(mut redactor) = REDACTOR.write()
+        && !redactor.secrets.iter().any(|known| known == secret)
+    {
+        redactor.secrets.push(secret.to_string());
+    }
+}
+
+/// `text` with bearer tokens, key-looking strings, configured secrets and the
+/// values of sensitive fields masked
+pub fn redact(text: &str) -> Cow<'_, str> {
+    match REDACTOR.read() {
+        Ok(redactor) => redactor.redact(text),
+        Err(_) => Cow::Borrowed(text),
+    }
+}
+
+/// Writer that redacts every log line before passing it on
+pub struct RedactingWriter<W>(pub W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_bearer_tokens_and_key_like_strings() {
+        let redactor = Redactor::new(Vec::new(), &[]);
+        assert_eq!(
+            redactor.redact("sending Bearer abcdef123456"),
+            "sending Bearer ***"
+        );
+        assert_eq!(
+            redactor.redact("header bearer abcdef123456 sent"),
+            "header bearer *** sent"
+        );
+        assert_eq!(
+            redactor.redact("key sk-0123456789abcdef failed"),
+            "key sk-*** failed"
+        );
+    }
+
+    #[test]
+    fn masks_sensitive_field_values() {
+        let redactor = Redactor::new(Vec::new(), &["session_id".to_string()]);
+        assert_eq!(
+            redactor.redact(r#"{"api_key": "abc123", "model": "chat"}"#),
+            r#"{"api_key": "***", "model": "chat"}"#
+        );
+        assert_eq!(
+            redactor.redact("url?token=xyz&page=2"),
+            "url?token=***&page=2"
+        );
+        assert_eq!(redactor.redact("session_id=42; ok"), "session_id=***; ok");
+        // Only whole field names count
+        assert_eq!(redactor.redact("max_tokens=100"), "max_tokens=100");
+    }
+
+    #[test]
+    fn masks_configured_secrets_anywhere() {
+        let redactor = Redactor::new(vec!["hunter2hunter2".to_string()], &[]);
+        assert_eq!(
+            redactor.redact("login failed for hunter2hunter2!"),
+            "login failed for ***!"
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use tracing::info;
+
+use crate::compare::AnalysisDelta;
+use crate::dates::{self, TaskDate};
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::pdf;
+use crate::structured::StructuredAnalysis;
+use crate::usage::TokenUsage;
+
+/// Analysis report structure for JSON serialization
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    /// Timestamp when the analysis was generated
+    pub timestamp: DateTime<Utc>,
+    /// Model used for analysis
+    pub model: String,
+    /// Number of tasks analyzed
+    pub task_count: usize,
+    /// List of tasks that were analyzed
+    pub tasks: Vec<Task>,
+    /// The actual analysis content from DeepSeek
+    pub analysis: String,
+    /// Analysis metadata
+    pub metadata: AnalysisMetadata,
+    /// Tool calls the AI made while producing the analysis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Chain of thought returned by reasoning models such as `deepseek-reasoner`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Parsed analysis when it was requested with `--structured`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<StructuredAnalysis>,
+    /// What changed since an earlier analysis, with `analyze --compare-last`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<AnalysisDelta>,
+}
+
+/// One tool call made by the AI, for auditing and debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Tool name as called by the model (e.g. `mcp_get_task`, `list_tasks`)
+    pub tool: String,
+    pub arguments: Value,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Size of the JSON result returned to the model, in bytes
+    pub result_bytes: usize,
+    pub duration_ms: u64,
+}
+
+/// Metadata about the analysis process
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisMetadata {
+    /// Whether tools were used during analysis
+    pub tools_enabled: bool,
+    /// Number of tool calls made during analysis
+    pub tool_calls_count: Option<usize>,
+    /// Duration of analysis in seconds
+    pub analysis_duration_seconds: Option<f64>,
+    /// Tokens used by the analysis, summed over all API requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
+    /// Estimated cost of the analysis in USD at the time it ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost_usd: Option<f64>,
+    /// Estimated USD saved by prompt tokens served from the context cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cache_savings_usd: Option<f64>,
+}
+
+impl AnalysisMetadata {
+    /// Token usage and cost for report footers, `N/A` for older reports
+    fn token_usage_summary(&self) -> String {
+        match &self.token_usage {
+            Some(usage) => format!(
+                "{}{}",
+                usage.describe(self.estimated_cost_usd.unwrap_or_default()),
+                self.cache_savings_summary()
+            ),
+            None => "N/A".to_string(),
+        }
+    }
+
+    /// `, $0.0012 saved by caching` when the context cache saved anything
+    pub fn cache_savings_summary(&self) -> String {
+        match self.estimated_cache_savings_usd {
+            Some(savings) if savings > 0.0 => format!(", ${:.4} saved by caching", savings),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Output format for saving analysis reports
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Json,
+    Markdown,
+    #[serde(rename = "text")]
+    #[value(name = "text")]
+    PlainText,
+    Pdf,
+}
+
+impl OutputFormat {
+    /// Determine output format from file extension
+    pub fn from_path(file_path: &str) -> Self {
+        let path = Path::new(file_path);
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => OutputFormat::Json,
+            Some("md") | Some("markdown") => OutputFormat::Markdown,
+            Some("txt") | Some("text") => OutputFormat::PlainText,
+            Some("pdf") => OutputFormat::Pdf,
+            _ => OutputFormat::Markdown, // Default to Markdown for email convenience
+        }
+    }
+
+    /// File extension used when a report file name is generated
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Markdown => "md",
+            OutputFormat::PlainText => "txt",
+            OutputFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Format analysis report as Markdown (email-friendly)
+pub fn format_report_as_markdown(report: &AnalysisReport) -> String {
+    let duration = report
+        .metadata
+        .analysis_duration_seconds
+        .map(|d| format!("{:.1}s", d))
+        .unwrap_or_else(|| "N/A".to_string());
+
+    let tool_calls = report
+        .metadata
+        .tool_calls_count
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "N/A".to_string());
+
+    format!(
+        r#"# Task Analysis Report
+
+**Generated:** {timestamp}  
+**Model:** {model}  
+**Tasks Analyzed:** {task_count}  
+**Analysis Duration:** {duration}  
+**Tool Calls:** {tool_calls}  
+
+---
+
+## 📋 Tasks Summary
+
+{tasks_summary}
+
+---
+
+## 🤖 AI Analysis
+
+{analysis}
+{delta}{reasoning}
+---
+
+## 📊 Report Metadata
+
+- **Tools Enabled:** {tools_enabled}
+- **Generation Time:** {timestamp}
+- **Processing Duration:** {duration}
+- **MCP Tool Interactions:** {tool_calls}
+- **Token Usage:** {token_usage}
+{tool_call_log}
+---
+
+*This report was generated automatically by DeepSeek MCP Tasks analyzer.*
+"#,
+        timestamp = report.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        model = report.model,
+        task_count = report.task_count,
+        duration = duration,
+        tool_calls = tool_calls,
+        token_usage = report.metadata.token_usage_summary(),
+        tasks_summary = format_tasks_summary(&report.tasks),
+        tool_call_log = format_tool_call_log(&report.tool_calls, "\n### 🔧 Tool Call Log\n\n"),
+        analysis = report.analysis,
+        delta = format_delta(
+            report.delta.as_ref(),
+            "\n---\n\n## 🔄 Changes Since Analysis",
+            "\n\n"
+        ),
+        reasoning = format_reasoning(
+            report.reasoning.as_deref(),
+            "\n---\n\n## 💭 Model Reasoning\n\n"
+        ),
+        tools_enabled = if report.metadata.tools_enabled {
+            "Yes"
+        } else {
+            "No"
+        },
+    )
+}
+
+/// Format analysis report as plain text (maximum compatibility)
+pub fn format_report_as_text(report: &AnalysisReport) -> String {
+    let duration = report
+        .metadata
+        .analysis_duration_seconds
+        .map(|d| format!("{:.1}s", d))
+        .unwrap_or_else(|| "N/A".to_string());
+
+    let tool_calls = report
+        .metadata
+        .tool_calls_count
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "N/A".to_string());
+
+    format!(
+        r#"===============================================
+        TASK ANALYSIS REPORT
+===============================================
+
+Generated: {timestamp}
+Model: {model}
+Tasks Analyzed: {task_count}
+Analysis Duration: {duration}
+Tool Calls: {tool_calls}
+
+===============================================
+            TASKS SUMMARY
+===============================================
+
+{tasks_summary}
+
+===============================================
+           AI ANALYSIS
+===============================================
+
+{analysis}
+{delta}{reasoning}
+===============================================
+          REPORT METADATA
+===============================================
+
+Tools Enabled: {tools_enabled}
+Generation Time: {timestamp}
+Processing Duration: {duration}
+MCP Tool Interactions: {tool_calls}
+Token Usage: {token_usage}
+{tool_call_log}
+===============================================
+
+This report was generated automatically by DeepSeek MCP Tasks analyzer.
+"#,
+        timestamp = report.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        model = report.model,
+        task_count = report.task_count,
+        duration = duration,
+        tool_calls = tool_calls,
+        token_usage = report.metadata.token_usage_summary(),
+        tasks_summary = format_tasks_summary_text(&report.tasks),
+        tool_call_log = strip_markdown(&format_tool_call_log(
+            &report.tool_calls,
+            "\nTool Call Log:\n"
+        )),
+        analysis = strip_markdown(&report.analysis),
+        delta = strip_markdown(&format_delta(
+            report.delta.as_ref(),
+            "\n===============================================\n   CHANGES SINCE ANALYSIS",
+            "\n===============================================\n\n"
+        )),
+        reasoning = strip_markdown(&format_reasoning(
+            report.reasoning.as_deref(),
+            "\n===============================================\n           MODEL REASONING\n===============================================\n\n"
+        )),
+        tools_enabled = if report.metadata.tools_enabled {
+            "Yes"
+        } else {
+            "No"
+        },
+    )
+}
+
+/// The comparison with an earlier analysis under a heading naming that
+/// analysis' history id and date; empty when there is none
+fn format_delta(delta: Option<&AnalysisDelta>, heading: &str, heading_end: &str) -> String {
+    match delta {
+        Some(delta) => format!(
+            "{} #{} ({}){}{}\n",
+            heading,
+            delta.previous_id,
+            delta.previous_timestamp.format("%Y-%m-%d %H:%M UTC"),
+            heading_end,
+            delta.changes.trim()
+        ),
+        None => String::new(),
+    }
+}
+
+/// The model's reasoning under `heading`; empty when there is none
+fn format_reasoning(reasoning: Option<&str>, heading: &str) -> String {
+    match reasoning {
+        Some(reasoning) => format!("{}{}\n", heading, reasoning.trim()),
+        None => String::new(),
+    }
+}
+
+/// Numbered list of tool calls under `heading`; empty when there were none
+fn format_tool_call_log(tool_calls: &[ToolCallRecord], heading: &str) -> String {
+    if tool_calls.is_empty() {
+        return String::new();
+    }
+
+    let mut log = heading.to_string();
+    for (idx, call) in tool_calls.iter().enumerate() {
+        let status = match &call.error {
+            Some(error) => format!("failed: {}", error),
+            None if call.success => "ok".to_string(),
+            None => "failed".to_string(),
+        };
+        log.push_str(&format!(
+            "{}. `{}` {} - {}, {} ms, {} bytes\n",
+            idx + 1,
+            call.tool,
+            call.arguments,
+            status,
+            call.duration_ms,
+            call.result_bytes
+        ));
+    }
+    log
+}
+
+/// A task date as the server sent it, or relative to now with `--relative-dates`
+fn format_task_date(value: &str) -> String {
+    match TaskDate::parse(value) {
+        Some(date) if dates::relative_dates() => dates::humanize(date, Utc::now()),
+        _ => value.to_string(),
+    }
+}
+
+/// Format tasks as a summary for Markdown
+fn format_tasks_summary(tasks: &[Task]) -> String {
+    let mut summary = String::new();
+
+    for (idx, task) in tasks.iter().enumerate() {
+        summary.push_str(&format!("### {}. {}\n\n", idx + 1, task.title));
+
+        if let Some(description) = &task.description {
+            summary.push_str(&format!("**Description:** {}\n\n", description));
+        }
+
+        summary.push_str(&format!("**Status:** {}\n", task.status));
+
+        if let Some(priority) = &task.priority {
+            summary.push_str(&format!("**Priority:** {}\n", priority));
+        }
+
+        if let Some(due_date) = &task.due_date {
+            summary.push_str(&format!("**Due Date:** {}\n", format_task_date(due_date)));
+        }
+
+        if let Some(tags) = &task.tags
+            && !tags.is_empty()
+        {
+            summary.push_str(&format!("**Tags:** {}\n", tags.join(", ")));
+        }
+
+        summary.push_str(&format!(
+            "**Created:** {}\n\n",
+            format_task_date(&task.created_at)
+        ));
+        summary.push_str("---\n\n");
+    }
+
+    summary
+}
+
+/// Format tasks as a summary for plain text
+fn format_tasks_summary_text(tasks: &[Task]) -> String {
+    let mut summary = String::new();
+
+    for (idx, task) in tasks.iter().enumerate() {
+        summary.push_str(&format!("{}. {}\n", idx + 1, task.title));
+
+        if let Some(description) = &task.description {
+            summary.push_str(&format!("   Description: {}\n", description));
+        }
+
+        summary.push_str(&format!("   Status: {}\n", task.status));
+
+        if let Some(priority) = &task.priority {
+            summary.push_str(&format!("   Priority: {}\n", priority));
+        }
+
+        if let Some(due_date) = &task.due_date {
+            summary.push_str(&format!("   Due Date: {}\n", format_task_date(due_date)));
+        }
+
+        if let Some(tags) = &task.tags
+            && !tags.is_empty()
+        {
+            summary.push_str(&format!("   Tags: {}\n", tags.join(", ")));
+        }
+
+        summary.push_str(&format!(
+            "   Created: {}\n",
+            format_task_date(&task.created_at)
+        ));
+        summary.push('\n');
+    }
+
+    summary
+}
+
+/// Strip Markdown formatting for plain text output
+pub fn strip_markdown(markdown: &str) -> String {
+    markdown
+        .replace("### ", "")
+        .replace("## ", "")
+        .replace("# ", "")
+        .replace("**", "")
+        .replace("*", "")
+        .replace("`", "")
+        .replace("|", "")
+        .replace("---", "-----------------------------------------------")
+}
+
+/// Write `content` to `path`, creating missing parent directories
+pub fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+        })?;
+    }
+    std::fs::write(path, content)
+        .with_context(|| McpTasksError::Report(format!("Failed to write {}", path.display())))
+}
+
+/// Save analysis report to a file in the specified format
+pub fn save_analysis_report(report: &AnalysisReport, file_path: &str) -> Result<()> {
+    info!("Saving analysis report to {}", file_path);
+
+    let format = OutputFormat::from_path(file_path);
+
+    let content = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(report)
+            .map_err(|e| {
+                anyhow::anyhow!(McpTasksError::Report(format!(
+                    "Failed to serialize analysis report: {}",
+                    e
+                )))
+            })?
+            .into_bytes(),
+        OutputFormat::Markdown => format_report_as_markdown(report).into_bytes(),
+        OutputFormat::PlainText => format_report_as_text(report).into_bytes(),
+        OutputFormat::Pdf => {
+            pdf::render_markdown("Task Analysis Report", &format_report_as_markdown(report))
+                .context(McpTasksError::Report(
+                    "Failed to render the report as PDF".to_string(),
+                ))?
+        }
+    };
+
+    let path = Path::new(file_path);
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            anyhow::anyhow!(McpTasksError::Report(format!(
+                "Failed to create directory {}: {}",
+                parent.display(),
+                e
+            )))
+        })?;
+    }
+
+    let mut file = File::create(path).map_err(|e| {
+        anyhow::anyhow!(McpTasksError::Report(format!(
+            "Failed to create file {}: {}",
+            file_path, e
+        )))
+    })?;
+
+    file.write_all(&content).map_err(|e| {
+        anyhow::anyhow!(McpTasksError::Report(format!(
+            "Failed to write to file {}: {}",
+            file_path, e
+        )))
+    })?;
+
+    info!(
+        "Analysis report saved successfully to {} in {:?} format",
+        file_path, format
+    );
+    Ok(())
+}
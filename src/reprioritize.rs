@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::structured;
+use crate::table_formatter::priority_level;
+
+/// Marker the reprioritize prompt contains; the mock provider recognizes it
+pub const REPRIORITIZE_PROMPT_MARKER: &str = "\"suggested_priority\"";
+
+pub const REPRIORITIZE_SYSTEM_PROMPT: &str = "You are a task prioritization expert. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// A priority the AI suggests for one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrioritySuggestion {
+    pub id: String,
+    pub suggested_priority: String,
+    #[serde(default)]
+    pub justification: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestionList {
+    suggestions: Vec<PrioritySuggestion>,
+}
+
+/// Current and suggested priority of one task
+#[derive(Debug, Clone, Serialize)]
+pub struct PriorityChange {
+    pub id: String,
+    pub title: String,
+    pub current: Option<String>,
+    /// Normalized to `high`, `medium` or `low`
+    pub suggested: String,
+    pub justification: String,
+}
+
+impl PriorityChange {
+    /// Whether the suggestion differs from the task's current priority level
+    pub fn is_change(&self) -> bool {
+        priority_level(self.current.as_deref()) != self.suggested
+    }
+}
+
+/// Prompt asking for a suggested priority for every formatted task
+pub fn reprioritize_prompt(task_summary: &str, task_count: usize) -> String {
+    format!(
+        r#"Review the priorities of these {task_count} pending tasks. Suggest the priority each task should have, considering due dates, impact and how the tasks relate to each other.
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "suggestions": [
+    {{
+      "id": "task ID as given above",
+      "suggested_priority": "high|medium|low",
+      "justification": "one sentence explaining the priority"
+    }}
+  ]
+}}
+
+Include every task exactly once, also when its priority should stay the same."#
+    )
+}
+
+/// Suggestions of one answer, paired with their tasks
+#[derive(Debug, Clone)]
+pub struct PrioritySuggestions {
+    pub changes: Vec<PriorityChange>,
+    /// IDs the model suggested a priority for that match none of the tasks
+    pub unknown_ids: Vec<String>,
+}
+
+/// Parse the model's answer and pair each suggestion with its task, in task order.
+/// Suggestions with an unknown priority are dropped; those for unknown tasks are
+/// kept as [`PrioritySuggestions::unknown_ids`].
+pub fn parse_suggestions(response: &str, tasks: &[Task]) -> Result<PrioritySuggestions> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi(
+            "AI response did not contain JSON priority suggestions".to_string(),
+        )
+    })?;
+
+    let list: SuggestionList = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI priority suggestions".to_string(),
+    ))?;
+
+    let changes = tasks
+        .iter()
+        .filter_map(|task| {
+            let suggestion = list
+                .suggestions
+                .iter()
+                .find(|suggestion| suggestion.id == task.id)?;
+            let suggested = priority_level(Some(&suggestion.suggested_priority));
+            (suggested != "none").then(|| PriorityChange {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                current: task.priority.clone(),
+                suggested: suggested.to_string(),
+                justification: suggestion.justification.clone(),
+            })
+        })
+        .collect();
+
+    let unknown_ids = list
+        .suggestions
+        .into_iter()
+        .filter(|suggestion| !tasks.iter().any(|task| task.id == suggestion.id))
+        .map(|suggestion| suggestion.id)
+        .collect();
+
+    Ok(PrioritySuggestions {
+        changes,
+        unknown_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, priority: Option<&str>) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Task {}", id),
+            "status": "pending",
+            "priority": priority,
+            "created_at": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_suggestions_keeps_ids_that_match_no_task() {
+        let tasks = [task("task-1", Some("low")), task("task-2", None)];
+        let response = r#"{"suggestions": [
+            {"id": "task-2", "suggested_priority": "High", "justification": "due soon"},
+            {"id": "task-9", "suggested_priority": "low", "justification": "made up"},
+            {"id": "task-1", "suggested_priority": "low", "justification": "can wait"}
+        ]}"#;
+
+        let suggestions = parse_suggestions(response, &tasks).unwrap();
+        let ids = suggestions
+            .changes
+            .iter()
+            .map(|change| change.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["task-1", "task-2"]);
+        assert!(!suggestions.changes[0].is_change());
+        assert_eq!(suggestions.changes[1].suggested, "high");
+        assert_eq!(suggestions.unknown_ids, ["task-9"]);
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::report::ToolCallRecord;
+use crate::tooling::Message;
+use crate::usage::TokenUsage;
+
+const SESSION_DIR: &str = "sessions";
+
+/// `--resume` value for the most recently saved session
+pub const LAST_SESSION: &str = "last";
+
+/// Result of a tool call whose turn was interrupted before all its calls finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingResult {
+    pub result: Value,
+    pub record: ToolCallRecord,
+}
+
+/// Conversation of an `analyze-with-tools` run, saved after every model turn and
+/// tool result so the run can be resumed where it stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisSession {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub structured: bool,
+    /// Tasks the analysis is about
+    pub tasks: Vec<Task>,
+    pub messages: Vec<Message>,
+    /// Tool calls of the turns that finished
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Results of the last turn's tool calls that already finished, by tool call ID
+    #[serde(default)]
+    pub pending_results: BTreeMap<String, PendingResult>,
+    /// Model turns with tool calls so far
+    pub iterations: u32,
+    /// Tokens used so far, so the report covers the whole analysis
+    pub token_usage: TokenUsage,
+}
+
+impl AnalysisSession {
+    pub fn new(tasks: Vec<Task>, structured: bool) -> Self {
+        let now = Utc::now();
+        Self {
+            id: now.format("%Y%m%d-%H%M%S").to_string(),
+            started_at: now,
+            updated_at: now,
+            structured,
+            tasks,
+            messages: Vec::new(),
+            tool_calls: Vec::new(),
+            pending_results: BTreeMap::new(),
+            iterations: 0,
+            token_usage: TokenUsage::default(),
+        }
+    }
+}
+
+/// Saved sessions, one JSON file each in `<data_dir>/sessions`. A session is
+/// removed once its analysis finishes.
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join(SESSION_DIR),
+        }
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// Write the session to a temporary file first, so a crash mid-write keeps
+    /// the previous state
+    pub fn save(&self, session: &AnalysisSession) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            McpTasksError::Report(format!("Failed to create directory {}", self.dir.display()))
+        })?;
+
+        let path = self.path(&session.id);
+        let partial = path.with_extension("json.partial");
+        let content = serde_json::to_string(session).context("Failed to serialize session")?;
+        std::fs::write(&partial, content)
+            .and_then(|_| std::fs::rename(&partial, &path))
+            .with_context(|| {
+                McpTasksError::Report(format!("Failed to write session {}", path.display()))
+            })?;
+
+        debug!("Saved session {} to {}", session.id, path.display());
+        Ok(())
+    }
+
+    /// The session with this ID, or the most recent one for [`LAST_SESSION`]
+    pub fn load(&self, id: &str) -> Result<AnalysisSession> {
+        if id == LAST_SESSION {
+            return self.list()?.pop().ok_or_else(|| {
+                McpTasksError::Report("No saved analysis session to resume".to_string()).into()
+            });
+        }
+
+        let path = self.path(id);
+        let content = std::fs::read_to_string(&path).with_context(|| {
+            McpTasksError::Report(format!(
+                "No saved analysis session '{}' in {}",
+                id,
+                self.dir.display()
+            ))
+        })?;
+        serde_json::from_str(&content).with_context(|| {
+            McpTasksError::Report(format!("Failed to parse session {}", path.display()))
+        })
+    }
+
+    /// All saved sessions, least recently updated first; unreadable files are skipped
+    pub fn list(&self) -> Result<Vec<AnalysisSession>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = std::fs::read_dir(&self.dir).with_context(|| {
+            McpTasksError::Report(format!("Failed to read {}", self.dir.display()))
+        })?;
+
+        let mut sessions = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(serde_json::from_str::<AnalysisSession>(&content)?))
+            {
+                Ok(session) => sessions.push(session),
+                Err(e) => warn!("Skipping unreadable session {}: {}", path.display(), e),
+            }
+        }
+
+        sessions.sort_by_key(|session| session.updated_at);
+        Ok(sessions)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let path = self.path(id);
+        if path.exists() {
+            std::fs::remove_file(&path).with_context(|| {
+                McpTasksError::Report(format!("Failed to remove session {}", path.display()))
+            })?;
+            info!("Removed finished session {}", id);
+        }
+        Ok(())
+    }
+}
+
+/// Session saved by the tool loop as it goes; failures to save are logged, not
+/// fatal, since the analysis itself can still finish
+pub struct SessionRecorder {
+    store: SessionStore,
+    session: Mutex<AnalysisSession>,
+}
+
+impl SessionRecorder {
+    pub fn new(store: SessionStore, session: AnalysisSession) -> Self {
+        Self {
+            store,
+            session: Mutex::new(session),
+        }
+    }
+
+    pub fn id(&self) -> String {
+        self.session.lock().unwrap().id.clone()
+    }
+
+    /// Copy of the session as last saved
+    pub fn snapshot(&self) -> AnalysisSession {
+        self.session.lock().unwrap().clone()
+    }
+
+    /// Change the session and save it
+    pub fn update(&self, change: impl FnOnce(&mut AnalysisSession)) {
+        let mut session = self.session.lock().unwrap();
+        change(&mut session);
+        session.updated_at = Utc::now();
+        if let Err(e) = self.store.save(&session) {
+            warn!("Failed to save analysis session: {:#}", e);
+        }
+    }
+
+    /// Drop the saved session once the analysis is done
+    pub fn finish(&self) {
+        if let Err(e) = self.store.remove(&self.id()) {
+            warn!("{:#}", e);
+        }
+    }
+}
@@ -0,0 +1,536 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::error::McpTasksError;
+use crate::estimate::{self, EstimateUnit};
+use crate::mcp_client::Task;
+use crate::structured;
+
+/// Marker the sprint planning prompt contains; the mock provider recognizes it
+pub const SPRINT_PROMPT_MARKER: &str = "\"sprint_goal\"";
+
+pub const SPRINT_SYSTEM_PROMPT: &str = "You are an experienced scrum master helping a team commit to a realistic sprint. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Marker the review prompt starts with; the mock provider recognizes it
+pub const SPRINT_REVIEW_HEADER: &str = "Sprint review";
+
+pub const SPRINT_REVIEW_SYSTEM_PROMPT: &str =
+    "You are a scrum master writing a short sprint review for the team. Be concise and concrete.";
+
+const SPRINT_FILE: &str = "sprints.jsonl";
+
+/// Parse a sprint length such as `2w`, `10d` or a bare number of days
+pub fn parse_length(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let (amount, days_per_unit) = if let Some(weeks) = input.strip_suffix('w') {
+        (weeks, 7)
+    } else {
+        (input.strip_suffix('d').unwrap_or(input), 1)
+    };
+    amount
+        .parse::<u64>()
+        .ok()
+        .map(|amount| amount * days_per_unit)
+        .filter(|days| (1..=90).contains(days))
+        .ok_or_else(|| {
+            format!(
+                "invalid sprint length '{}': expected e.g. 2w or 10d, at most 90 days",
+                input
+            )
+        })
+}
+
+/// Effort a sprint can take, in story points or hours
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SprintCapacity {
+    pub amount: f64,
+    pub unit: EstimateUnit,
+}
+
+impl std::fmt::Display for SprintCapacity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.unit.format(self.amount))
+    }
+}
+
+/// Parse a capacity such as `30pts`, `30sp`, `40h` or a bare number of points
+pub fn parse_capacity(input: &str) -> Result<SprintCapacity, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (amount, unit) = input.split_at(split);
+    let unit = match unit.trim() {
+        "" | "pt" | "pts" | "sp" | "points" => EstimateUnit::Points,
+        "h" | "hours" => EstimateUnit::Hours,
+        other => return Err(format!("unknown capacity unit '{}': use pts or h", other)),
+    };
+    amount
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| amount.is_finite() && *amount > 0.0)
+        .map(|amount| SprintCapacity { amount, unit })
+        .ok_or_else(|| format!("invalid capacity '{}': expected e.g. 30pts or 40h", input))
+}
+
+/// Sprint name and tag derived from the ISO week it starts in: `sprint-2025-26`
+pub fn default_name(start: NaiveDate) -> String {
+    let week = start.iso_week();
+    format!("sprint-{}-{:02}", week.year(), week.week())
+}
+
+/// A task the AI picked for the sprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintPick {
+    pub id: String,
+    pub title: String,
+    pub priority: Option<String>,
+    pub estimate: f64,
+    pub rationale: String,
+}
+
+/// A task considered but left out of the sprint
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftOut {
+    pub id: String,
+    pub title: String,
+    pub reason: String,
+}
+
+/// The AI's proposed sprint backlog
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintProposal {
+    pub name: String,
+    pub goal: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub capacity: SprintCapacity,
+    pub selected: Vec<SprintPick>,
+    pub left_out: Vec<LeftOut>,
+}
+
+impl SprintProposal {
+    pub fn committed(&self) -> f64 {
+        self.selected
+            .iter()
+            .fold(0.0, |total, pick| total + pick.estimate)
+    }
+}
+
+/// Prompt asking for a sprint backlog from the formatted pending tasks
+pub fn sprint_prompt(
+    task_summary: &str,
+    tasks: &[Task],
+    field: &str,
+    capacity: SprintCapacity,
+    days: u64,
+) -> String {
+    let estimates = tasks
+        .iter()
+        .filter_map(|task| {
+            estimate::current_estimate(task, field)
+                .map(|estimate| format!("{}: {}", task.id, estimate))
+        })
+        .collect::<Vec<_>>();
+    let estimates = if estimates.is_empty() {
+        "none".to_string()
+    } else {
+        estimates.join(", ")
+    };
+    let task_count = tasks.len();
+    let unit = capacity.unit.label();
+
+    format!(
+        r#"Propose a backlog for a {days}-day sprint from these {task_count} pending tasks. The team's capacity is {capacity} in {unit}; the selected tasks must not add up to more. Favor high priority and soon-due tasks, keep related tasks together and leave some slack for unknowns.
+
+Stored estimates: {estimates} (estimate tasks without one yourself, in {unit})
+
+{task_summary}
+Answer with a single JSON object and nothing else, in this shape:
+{{
+  "sprint_goal": "one sentence describing what the sprint delivers",
+  "selected": [
+    {{
+      "id": "task ID as given above",
+      "estimate": 3,
+      "rationale": "one sentence on why it belongs in this sprint"
+    }}
+  ],
+  "left_out": [
+    {{ "id": "task ID", "reason": "why it waits, for notable tasks only" }}
+  ]
+}}
+
+List the selected tasks in the order the team should work on them."#
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProposal {
+    #[serde(default)]
+    sprint_goal: String,
+    #[serde(default)]
+    selected: Vec<RawPick>,
+    #[serde(default)]
+    left_out: Vec<RawLeftOut>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPick {
+    id: String,
+    estimate: f64,
+    #[serde(default)]
+    rationale: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLeftOut {
+    id: String,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Parse the model's proposal. Unknown tasks are dropped, and selected tasks
+/// that would take the sprint over capacity are moved to the left-out list.
+pub fn parse_proposal(
+    response: &str,
+    tasks: &[Task],
+    name: String,
+    start: NaiveDate,
+    days: u64,
+    capacity: SprintCapacity,
+) -> Result<SprintProposal> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain a JSON sprint backlog".to_string())
+    })?;
+    let raw: RawProposal = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI sprint backlog".to_string(),
+    ))?;
+
+    let find_task = |id: &str| tasks.iter().find(|task| task.id == id);
+    let mut selected: Vec<SprintPick> = Vec::new();
+    let mut left_out = Vec::new();
+    let mut committed = 0.0;
+
+    for pick in &raw.selected {
+        let Some(task) = find_task(&pick.id) else {
+            warn!("Ignoring sprint pick for unknown task '{}'", pick.id);
+            continue;
+        };
+        if selected.iter().any(|known| known.id == task.id)
+            || !(pick.estimate.is_finite() && pick.estimate > 0.0)
+        {
+            continue;
+        }
+        // Allow for rounding in the model's arithmetic
+        if committed + pick.estimate > capacity.amount + 0.01 {
+            left_out.push(LeftOut {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                reason: format!(
+                    "Does not fit: {} left of {}",
+                    capacity.unit.format(capacity.amount - committed),
+                    capacity
+                ),
+            });
+            continue;
+        }
+        committed += pick.estimate;
+        selected.push(SprintPick {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            priority: task.priority.clone(),
+            estimate: pick.estimate,
+            rationale: pick.rationale.clone(),
+        });
+    }
+
+    for raw_left_out in &raw.left_out {
+        match find_task(&raw_left_out.id) {
+            Some(task)
+                if !selected.iter().any(|pick| pick.id == task.id)
+                    && !left_out.iter().any(|known: &LeftOut| known.id == task.id) =>
+            {
+                left_out.push(LeftOut {
+                    id: task.id.clone(),
+                    title: task.title.clone(),
+                    reason: raw_left_out.reason.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    Ok(SprintProposal {
+        name,
+        goal: raw.sprint_goal.trim().to_string(),
+        start,
+        end: start + Days::new(days - 1),
+        capacity,
+        selected,
+        left_out,
+    })
+}
+
+/// A committed sprint, as stored by `sprint plan`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sprint {
+    pub name: String,
+    pub goal: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub capacity: SprintCapacity,
+    pub tasks: Vec<SprintPick>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&SprintProposal> for Sprint {
+    fn from(proposal: &SprintProposal) -> Self {
+        Self {
+            name: proposal.name.clone(),
+            goal: proposal.goal.clone(),
+            start: proposal.start,
+            end: proposal.end,
+            capacity: proposal.capacity,
+            tasks: proposal.selected.clone(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Append-only JSON-lines store of committed sprints
+pub struct SprintStore {
+    path: PathBuf,
+}
+
+impl SprintStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(SPRINT_FILE),
+        }
+    }
+
+    pub fn append(&self, sprint: &Sprint) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        let line = serde_json::to_string(sprint).context("Failed to serialize sprint")?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| {
+                McpTasksError::Report(format!("Failed to open sprint log {}", self.path.display()))
+            })?;
+        writeln!(file, "{}", line).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write sprint log {}",
+                self.path.display()
+            ))
+        })?;
+
+        info!("Stored sprint {} in {}", sprint.name, self.path.display());
+        Ok(())
+    }
+
+    /// The sprint planned last under `name`, or the last one planned at all;
+    /// unreadable lines are skipped
+    pub fn find(&self, name: Option<&str>) -> Result<Option<Sprint>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&self.path).with_context(|| {
+            McpTasksError::Report(format!("Failed to read sprint log {}", self.path.display()))
+        })?;
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<Sprint>(line) {
+                Ok(sprint) => Some(sprint),
+                Err(e) => {
+                    warn!("Skipping unreadable sprint: {}", e);
+                    None
+                }
+            })
+            .rfind(|sprint| name.is_none_or(|name| sprint.name.eq_ignore_ascii_case(name))))
+    }
+}
+
+/// Where a task stands at the end of a sprint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SprintOutcome {
+    Completed,
+    /// Planned but not finished
+    CarriedOver,
+    /// Tagged with the sprint after it was planned
+    Added,
+    /// Added after planning and already finished
+    AddedCompleted,
+}
+
+impl SprintOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::CarriedOver => "carried_over",
+            Self::Added => "added",
+            Self::AddedCompleted => "added_completed",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Completed => "✅ completed",
+            Self::CarriedOver => "⏭️ carried over",
+            Self::Added => "➕ added",
+            Self::AddedCompleted => "✅ added, completed",
+        }
+    }
+
+    pub fn is_completed(self) -> bool {
+        matches!(self, Self::Completed | Self::AddedCompleted)
+    }
+}
+
+/// One task of a sprint review
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewItem {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    /// Estimate at planning time; none for tasks added later
+    pub estimate: Option<f64>,
+    pub outcome: SprintOutcome,
+}
+
+/// Outcome of a sprint: what got done, what carries over and what was added
+#[derive(Debug, Clone, Serialize)]
+pub struct SprintReview {
+    pub name: String,
+    pub goal: String,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub capacity: Option<SprintCapacity>,
+    pub items: Vec<ReviewItem>,
+    pub planned: f64,
+    pub completed: f64,
+    pub ai_summary: Option<String>,
+}
+
+impl SprintReview {
+    /// Review of the tasks tagged `name` or planned in `sprint`, found among `tasks`
+    pub fn build(name: &str, sprint: Option<&Sprint>, tasks: &[Task]) -> Self {
+        let planned_estimate = |id: &str| {
+            sprint
+                .and_then(|sprint| sprint.tasks.iter().find(|pick| pick.id == id))
+                .map(|pick| pick.estimate)
+        };
+
+        let items = tasks
+            .iter()
+            .filter(|task| {
+                planned_estimate(&task.id).is_some()
+                    || task
+                        .tags
+                        .iter()
+                        .flatten()
+                        .any(|tag| tag.eq_ignore_ascii_case(name))
+            })
+            .map(|task| {
+                let estimate = planned_estimate(&task.id);
+                let outcome = match (estimate.is_some(), task.is_unfinished()) {
+                    (true, false) => SprintOutcome::Completed,
+                    (true, true) => SprintOutcome::CarriedOver,
+                    (false, false) => SprintOutcome::AddedCompleted,
+                    (false, true) => SprintOutcome::Added,
+                };
+                ReviewItem {
+                    id: task.id.clone(),
+                    title: task.title.clone(),
+                    status: task.status.clone(),
+                    estimate,
+                    outcome,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let planned = sprint.map_or(0.0, |sprint| {
+            sprint
+                .tasks
+                .iter()
+                .fold(0.0, |total, pick| total + pick.estimate)
+        });
+        let completed = items
+            .iter()
+            .filter(|item| item.outcome == SprintOutcome::Completed)
+            .fold(0.0, |total, item| total + item.estimate.unwrap_or_default());
+
+        Self {
+            name: sprint.map_or_else(|| name.to_string(), |sprint| sprint.name.clone()),
+            goal: sprint.map(|sprint| sprint.goal.clone()).unwrap_or_default(),
+            start: sprint.map(|sprint| sprint.start),
+            end: sprint.map(|sprint| sprint.end),
+            capacity: sprint.map(|sprint| sprint.capacity),
+            items,
+            planned,
+            completed,
+            ai_summary: None,
+        }
+    }
+
+    pub fn count(&self, outcome: SprintOutcome) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.outcome == outcome)
+            .count()
+    }
+
+    /// Prompt asking the model to summarize the sprint
+    pub fn summary_prompt(&self) -> String {
+        let mut prompt = format!("{} of {}", SPRINT_REVIEW_HEADER, self.name);
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            prompt.push_str(&format!(" ({} to {})", start, end));
+        }
+        prompt.push_str(".\n\n");
+        if !self.goal.is_empty() {
+            prompt.push_str(&format!("Sprint goal: {}\n", self.goal));
+        }
+        if let Some(capacity) = self.capacity {
+            prompt.push_str(&format!(
+                "Committed {} of {} capacity; completed {} of it.\n",
+                capacity.unit.format(self.planned),
+                capacity,
+                capacity.unit.format(self.completed)
+            ));
+        }
+        prompt.push_str("\nTasks:\n");
+        for item in &self.items {
+            let outcome = match item.outcome {
+                SprintOutcome::Completed => "completed",
+                SprintOutcome::CarriedOver => "not finished",
+                SprintOutcome::Added => "added during the sprint, not finished",
+                SprintOutcome::AddedCompleted => "added during the sprint, completed",
+            };
+            prompt.push_str(&format!(
+                "- {} ({}): {}, status {}\n",
+                item.title, item.id, outcome, item.status
+            ));
+        }
+        prompt.push_str(
+            "\nSummarize the outcome in a few sentences: whether the goal was met, what carries over and why it might have slipped, how scope changed, and one or two suggestions for the next sprint.",
+        );
+        prompt
+    }
+}
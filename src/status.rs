@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// Task lifecycle states understood by the CLI. Servers may use other words for
+/// them; those are mapped through built-in aliases and the `[statuses]` config section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+/// Aliases from the config file, consulted before the built-in ones
+static CUSTOM_ALIASES: RwLock<BTreeMap<String, TaskStatus>> = RwLock::new(BTreeMap::new());
+
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 4] = [
+        Self::Pending,
+        Self::InProgress,
+        Self::Completed,
+        Self::Cancelled,
+    ];
+
+    /// Value sent to the MCP server
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Human-friendly name for tables and the board
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "To Do",
+            Self::InProgress => "In Progress",
+            Self::Completed => "Done",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Whether the task still needs work
+    pub fn is_open(self) -> bool {
+        matches!(self, Self::Pending | Self::InProgress)
+    }
+
+    /// Map a status as written by a server or user, ignoring case, `-` and spaces.
+    /// Config aliases win over the built-in ones.
+    pub fn parse(value: &str) -> Option<Self> {
+        let key = normalize(value);
+
+        if let Some(status) = CUSTOM_ALIASES
+            .read()
+            .ok()
+            .and_then(|aliases| aliases.get(&key).copied())
+        {
+            return Some(status);
+        }
+
+        match key.as_str() {
+            "pending" | "todo" | "to_do" | "new" | "open" | "incomplete" => Some(Self::Pending),
+            "in_progress" | "active" | "started" => Some(Self::InProgress),
+            "completed" | "done" | "finished" | "closed" | "resolved" => Some(Self::Completed),
+            "cancelled" | "canceled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Install the `[statuses]` aliases used by [`TaskStatus::parse`]; done by `Config::load`
+    pub fn set_aliases(aliases: &BTreeMap<String, TaskStatus>) {
+        if let Ok(mut custom) = CUSTOM_ALIASES.write() {
+            *custom = aliases
+                .iter()
+                .map(|(alias, status)| (normalize(alias), *status))
+                .collect();
+        }
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase().replace(['-', ' '], "_")
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value).ok_or_else(|| {
+            format!(
+                "Unknown status '{}': expected pending, in_progress, completed or cancelled, or an alias from [statuses] in the config file",
+                value
+            )
+        })
+    }
+}
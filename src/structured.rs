@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::error::McpTasksError;
+
+/// Marker the structured prompt contains; the mock provider recognizes it
+pub const STRUCTURED_PROMPT_MARKER: &str = "\"recommended_order\"";
+
+pub const STRUCTURED_SYSTEM_PROMPT: &str = "You are a task analysis expert. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// A low / medium / high rating; models' capitalization and spelling vary, so parsing is lenient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Level {
+    Low,
+    Medium,
+    High,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl TryFrom<String> for Level {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim().to_lowercase().as_str() {
+            "low" | "minor" | "small" => Ok(Self::Low),
+            "medium" | "moderate" | "normal" => Ok(Self::Medium),
+            "high" | "major" | "large" | "critical" => Ok(Self::High),
+            _ => Err(format!("unknown level '{}'", value)),
+        }
+    }
+}
+
+/// The AI's assessment of one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAssessment {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    /// 1 (can wait) to 10 (do first)
+    pub priority_score: u8,
+    pub complexity: Level,
+    pub risk: Level,
+    /// IDs of tasks that have to be done first
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub rationale: Option<String>,
+}
+
+/// Machine-readable analysis returned by `analyze --structured`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredAnalysis {
+    #[serde(default)]
+    pub summary: String,
+    pub tasks: Vec<TaskAssessment>,
+    /// Task IDs in the order they should be worked on
+    #[serde(default)]
+    pub recommended_order: Vec<String>,
+}
+
+impl StructuredAnalysis {
+    /// Tasks in the recommended order; tasks the order leaves out follow by priority score
+    pub fn ordered(&self) -> Vec<&TaskAssessment> {
+        let position = |task: &TaskAssessment| {
+            self.recommended_order
+                .iter()
+                .position(|id| *id == task.id)
+                .unwrap_or(usize::MAX)
+        };
+
+        let mut tasks = self.tasks.iter().collect::<Vec<_>>();
+        tasks.sort_by_key(|task| (position(task), std::cmp::Reverse(task.priority_score)));
+        tasks
+    }
+}
+
+/// Prompt asking for a [`StructuredAnalysis`] of the formatted tasks
+pub fn structured_prompt(task_summary: &str, task_count: usize, with_tools: bool) -> String {
+    let tools_hint = if with_tools {
+        "You can use the available tools to get more data about the tasks first, but do not create, update or complete any tasks.\n\n"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"Assess these {task_count} pending tasks.
+
+{task_summary}
+{tools_hint}Answer with a single JSON object and nothing else, in this shape:
+{{
+  "summary": "two or three sentences on the overall state of the work",
+  "tasks": [
+    {{
+      "id": "task ID as given above",
+      "title": "task title",
+      "priority_score": 1-10, where 10 means do it first,
+      "complexity": "low|medium|high",
+      "risk": "low|medium|high",
+      "dependencies": ["IDs of tasks that must be done first"],
+      "rationale": "one sentence explaining the score"
+    }}
+  ],
+  "recommended_order": ["task IDs in the order to work on them"]
+}}
+
+Include every task exactly once."#
+    )
+}
+
+/// The outermost JSON object in a model answer, skipping Markdown code fences or text around it
+pub fn json_object(response: &str) -> Option<&str> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    (start < end).then(|| &response[start..=end])
+}
+
+/// Parse the model's answer, tolerating Markdown code fences or text around the JSON
+pub fn parse_structured_analysis(response: &str) -> Result<StructuredAnalysis> {
+    let json = json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain a JSON analysis".to_string())
+    })?;
+
+    let mut analysis: StructuredAnalysis = serde_json::from_str(json).context(
+        McpTasksError::DeepSeekApi("Failed to parse the structured AI analysis".to_string()),
+    )?;
+    for task in &mut analysis.tasks {
+        task.priority_score = task.priority_score.clamp(1, 10);
+    }
+
+    Ok(analysis)
+}
@@ -1,9 +1,45 @@
-use crate::mcp_client::Task;
+use crate::agenda::{self, Agenda, AgendaEntry};
+use crate::archive::SnapshotDiff;
+use crate::breakdown::{ProposedSubtask, TaskBreakdown};
+use crate::bulk::{BulkAction, BulkResult};
+use crate::capabilities::{Capability, ServerCheck};
+use crate::dates::{self, TaskDate};
+use crate::dedupe::DuplicateCluster;
+use crate::embeddings::SemanticMatch;
+use crate::estimate::{EstimateSummary, TaskEstimate};
+use crate::history::HistorySummary;
+use crate::import::ImportedTask;
+use crate::mcp_client::{Task, TaskPage};
+use crate::output::{self, AssigneeWorkload, ResourceInfo, StatusCounts, TagSummary};
+use crate::plan::{Plan, format_hours};
+use crate::report::ToolCallRecord;
+use crate::reprioritize::PriorityChange;
+use crate::sprint::{SprintOutcome, SprintProposal, SprintReview};
+use crate::status::TaskStatus;
+use crate::structured::{StructuredAnalysis, TaskAssessment};
+use crate::task_ref::TaskRef;
+use crate::template::NamedTemplate;
+use crate::themes::ThemeReport;
+use crate::timetrack::Timesheet;
+use crate::trend::{Trend, sparkline};
+use crate::usage::ModelUsage;
+use crate::velocity::{VelocityReport, WeekVelocity};
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
+use clap::ValueEnum;
+use ratatui::crossterm::terminal;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::sync::RwLock;
 use tabled::{
     Table, Tabled,
-    settings::{Alignment, Modify, Style, object::Column},
+    builder::Builder,
+    grid::util::string::get_text_width,
+    settings::{
+        Alignment, Color, Modify, Style, Width,
+        object::{Cell, Column, Row},
+    },
 };
 
 #[derive(Debug, Tabled)]
@@ -20,6 +56,9 @@ pub struct TaskTableRow {
     #[tabled(rename = "Priority")]
     pub priority: String,
 
+    #[tabled(rename = "Assignee")]
+    pub assignee: String,
+
     #[tabled(rename = "Due Date")]
     pub due_date: String,
 
@@ -31,157 +70,630 @@ pub struct TaskTableRow {
 
     #[tabled(rename = "Tags")]
     pub tags: String,
+
+    #[tabled(skip)]
+    pub urgency: Urgency,
+}
+
+/// What makes a task row stand out in a colored table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Urgency {
+    pub due: Option<DueUrgency>,
+    pub high_priority: bool,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueUrgency {
+    Overdue,
+    Today,
+}
+
+impl Urgency {
+    /// How urgent `task` is as of `now`; only open tasks can be overdue or due today
+    pub fn of(task: &Task, now: DateTime<Utc>) -> Self {
+        let due = TaskDate::parse_opt(task.due_date.as_deref())
+            .filter(|_| task.is_unfinished())
+            .and_then(|due| {
+                if due.is_overdue(now) {
+                    Some(DueUrgency::Overdue)
+                } else if due.date() == dates::local_date(now) {
+                    Some(DueUrgency::Today)
+                } else {
+                    None
+                }
+            });
+
+        Self {
+            due,
+            high_priority: priority_level(task.priority.as_deref()) == "high",
+            completed: task.normalized_status() == Some(TaskStatus::Completed),
+        }
+    }
+}
+
+/// Task table columns that can be selected with `list --columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TaskColumn {
+    Id,
+    Title,
+    Status,
+    Priority,
+    Assignee,
+    Due,
+    Created,
+    Completed,
+    Tags,
+}
+
+impl TaskColumn {
+    pub const ALL: [TaskColumn; 9] = [
+        Self::Id,
+        Self::Title,
+        Self::Status,
+        Self::Priority,
+        Self::Assignee,
+        Self::Due,
+        Self::Created,
+        Self::Completed,
+        Self::Tags,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            Self::Id => "ID",
+            Self::Title => "Title",
+            Self::Status => "Status",
+            Self::Priority => "Priority",
+            Self::Assignee => "Assignee",
+            Self::Due => "Due Date",
+            Self::Created => "Created",
+            Self::Completed => "Completed",
+            Self::Tags => "Tags",
+        }
+    }
+
+    fn value(self, row: &TaskTableRow) -> &str {
+        match self {
+            Self::Id => &row.id,
+            Self::Title => &row.title,
+            Self::Status => &row.status,
+            Self::Priority => &row.priority,
+            Self::Assignee => &row.assignee,
+            Self::Due => &row.due_date,
+            Self::Created => &row.created_at,
+            Self::Completed => &row.completed_at,
+            Self::Tags => &row.tags,
+        }
+    }
+
+    fn centered(self) -> bool {
+        matches!(self, Self::Id | Self::Status | Self::Priority)
+    }
+
+    /// Columns with free text that are narrowed to fit the terminal
+    fn flexible(self) -> bool {
+        matches!(self, Self::Title | Self::Assignee | Self::Tags)
+    }
+}
+
+/// How task tables use the terminal's width, set once from `--wide` and `--truncate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableWidth {
+    /// Fit the terminal, wrapping long titles, assignees and tags over several lines
+    #[default]
+    Fit,
+    /// Fit the terminal, cutting long cells off with `...`
+    Truncate,
+    /// Show every cell in full, however wide the table gets
+    Wide,
+}
+
+/// Width assumed when output is not a terminal and `COLUMNS` is not set
+const DEFAULT_TABLE_COLUMNS: usize = 120;
+
+/// Flexible columns are not narrowed below this many characters
+const MIN_FLEXIBLE_WIDTH: usize = 10;
+
+static TABLE_WIDTH: RwLock<TableWidth> = RwLock::new(TableWidth::Fit);
+
+/// Use `width` for all task tables; done by `main` before any output
+pub fn set_table_width(width: TableWidth) {
+    if let Ok(mut current) = TABLE_WIDTH.write() {
+        *current = width;
+    }
+}
+
+/// The current table width mode
+pub fn table_width() -> TableWidth {
+    TABLE_WIDTH.read().map(|width| *width).unwrap_or_default()
+}
+
+/// Sort keys for `list --sort`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TaskSortKey {
+    /// Earliest due date first
+    Due,
+    /// High priority first
+    Priority,
+    /// Oldest first
+    Created,
+    /// Alphabetical, ignoring case
+    Title,
+}
+
+/// Groupings for `list --group-by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TaskGroupBy {
+    /// To do, in progress, done, cancelled
+    Status,
+    /// High, medium, low, none
+    Priority,
+    /// One group per tag; tasks with several tags appear in each
+    Tag,
+    /// Week (starting Monday) of the due date, earliest first
+    DueWeek,
+}
+
+impl TaskGroupBy {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Status => "Status",
+            Self::Priority => "Priority",
+            Self::Tag => "Tag",
+            Self::DueWeek => "Due Week",
+        }
+    }
+
+    /// Groups `task` belongs to as `(sort key, label)`
+    fn groups(self, task: &Task) -> Vec<((u8, String), String)> {
+        match self {
+            Self::Status => match task.normalized_status() {
+                Some(status) => vec![((status as u8, String::new()), status.label().to_string())],
+                None => vec![((u8::MAX, task.status.to_lowercase()), task.status.clone())],
+            },
+            Self::Priority => {
+                let level = priority_level(task.priority.as_deref());
+                let label = match level {
+                    "high" => "🔴 High",
+                    "medium" => "🟡 Medium",
+                    "low" => "🟢 Low",
+                    _ => "⚪ No Priority",
+                };
+                let rank = priority_rank(Some(level)).unwrap_or(3);
+                vec![((rank, String::new()), label.to_string())]
+            }
+            Self::Tag => match task.tags.as_deref() {
+                Some(tags) if !tags.is_empty() => tags
+                    .iter()
+                    .map(|tag| ((0, tag.to_lowercase()), format!("#{}", tag)))
+                    .collect(),
+                _ => vec![((1, String::new()), "Untagged".to_string())],
+            },
+            Self::DueWeek => match TaskDate::parse_opt(task.due_date.as_deref()) {
+                Some(due) => {
+                    let due = due.date();
+                    let monday =
+                        due - chrono::Days::new(due.weekday().num_days_from_monday().into());
+                    let week = monday.format("%Y-%m-%d").to_string();
+                    vec![((0, week.clone()), format!("Week of {}", week))]
+                }
+                None => vec![((1, String::new()), "No Due Date".to_string())],
+            },
+        }
+    }
+}
+
+/// Order and columns of the task table
+#[derive(Debug, Clone)]
+pub struct TableOptions {
+    /// `None` keeps the server's order
+    pub sort: Option<TaskSortKey>,
+    pub descending: bool,
+    pub columns: Vec<TaskColumn>,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            sort: None,
+            descending: false,
+            columns: TaskColumn::ALL.to_vec(),
+        }
+    }
+}
+
+impl TableOptions {
+    /// Sort `tasks` in place by the selected key. Tasks missing the value
+    /// (no due date, no priority) go last in either direction.
+    pub fn sort_tasks(&self, tasks: &mut [Task]) {
+        let Some(key) = self.sort else {
+            return;
+        };
+
+        tasks.sort_by(|a, b| match key {
+            TaskSortKey::Due => compare_present(
+                parse_task_date(a.due_date.as_deref()),
+                parse_task_date(b.due_date.as_deref()),
+                self.descending,
+            ),
+            TaskSortKey::Priority => compare_present(
+                priority_rank(a.priority.as_deref()),
+                priority_rank(b.priority.as_deref()),
+                self.descending,
+            ),
+            TaskSortKey::Created => compare_present(
+                parse_task_date(Some(&a.created_at)),
+                parse_task_date(Some(&b.created_at)),
+                self.descending,
+            ),
+            TaskSortKey::Title => {
+                let ordering = a.title.to_lowercase().cmp(&b.title.to_lowercase());
+                if self.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        });
+    }
+}
+
+/// Compare two optional values, keeping `None` after any value
+fn compare_present<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Point in time of a task date for sorting; unparseable dates are `None`
+fn parse_task_date(date: Option<&str>) -> Option<DateTime<Utc>> {
+    TaskDate::parse_opt(date).map(TaskDate::sort_key)
+}
+
+/// Rank for sorting by priority: high first, unknown priorities are `None`
+fn priority_rank(priority: Option<&str>) -> Option<u8> {
+    match priority_level(priority) {
+        "high" => Some(0),
+        "medium" => Some(1),
+        "low" => Some(2),
+        _ => None,
+    }
 }
 
 impl From<Task> for TaskTableRow {
     fn from(task: Task) -> Self {
+        let urgency = Urgency::of(&task, Utc::now());
         Self {
-            id: truncate_string(&task.id, 8),
-            title: truncate_string(&task.title, 40),
+            id: format_task_id(&task),
+            // Title, assignee and tags are kept whole and fitted to the terminal
+            // by `styled_table`
+            title: task.title,
             status: format_status(&task.status),
             priority: task.priority.unwrap_or_else(|| "N/A".to_string()),
+            assignee: task.assignee.unwrap_or_else(|| "N/A".to_string()),
             due_date: format_date_string(task.due_date.as_deref()),
             created_at: format_date_string(Some(&task.created_at)),
             completed_at: format_date_string(task.completed_at.as_deref()),
             tags: format_tags(task.tags.as_deref()),
+            urgency,
         }
     }
 }
 
-pub struct TaskTableFormatter;
+#[derive(Debug, Tabled)]
+pub struct AgendaTableRow {
+    #[tabled(rename = "ID")]
+    pub id: String,
 
-impl TaskTableFormatter {
-    pub fn format_all_tasks(tasks: &[Task]) -> Result<String> {
-        if tasks.is_empty() {
-            return Ok("No tasks found.".to_string());
+    #[tabled(rename = "Title")]
+    pub title: String,
+
+    #[tabled(rename = "Priority")]
+    pub priority: String,
+
+    #[tabled(rename = "Status")]
+    pub status: String,
+
+    #[tabled(rename = "Due")]
+    pub due: String,
+
+    #[tabled(rename = "When")]
+    pub relative: String,
+}
+
+impl From<&AgendaEntry> for AgendaTableRow {
+    fn from(entry: &AgendaEntry) -> Self {
+        Self {
+            id: format_task_id(&entry.task),
+            title: output::truncate(&entry.task.title, 40),
+            priority: entry
+                .task
+                .priority
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            status: format_status(&entry.task.status),
+            due: entry.due.clone().unwrap_or_else(|| "N/A".to_string()),
+            relative: entry.relative.clone().unwrap_or_default(),
         }
+    }
+}
 
-        let table_rows: Vec<TaskTableRow> = tasks
-            .iter()
-            .map(|task| TaskTableRow::from(task.clone()))
-            .collect();
+#[derive(Debug, Tabled)]
+pub struct HistoryTableRow {
+    #[tabled(rename = "ID")]
+    pub id: usize,
 
-        let mut table = Table::new(table_rows);
+    #[tabled(rename = "Generated")]
+    pub timestamp: String,
 
-        // Apply styling
-        table
-            .with(Style::modern())
-            .with(Modify::new(Column::from(0)).with(Alignment::center())) // ID column centered
-            .with(Modify::new(Column::from(2)).with(Alignment::center())) // Status column centered
-            .with(Modify::new(Column::from(3)).with(Alignment::center())); // Priority column centered
+    #[tabled(rename = "Model")]
+    pub model: String,
 
-        let output = format!(
-            "\n📋 All Tasks ({} total)\n{}\n{}",
-            tasks.len(),
-            "=".repeat(80),
-            table
-        );
+    #[tabled(rename = "Tasks")]
+    pub task_count: usize,
 
-        Ok(output)
+    #[tabled(rename = "Tool Calls")]
+    pub tool_calls: String,
+
+    #[tabled(rename = "Duration")]
+    pub duration: String,
+}
+
+impl From<&HistorySummary> for HistoryTableRow {
+    fn from(summary: &HistorySummary) -> Self {
+        Self {
+            id: summary.id,
+            timestamp: format_date_time_string(&summary.timestamp),
+            model: summary.model.clone(),
+            task_count: summary.task_count,
+            tool_calls: summary
+                .tool_calls_count
+                .map(|count| count.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            duration: summary
+                .analysis_duration_seconds
+                .map(|seconds| format!("{:.1}s", seconds))
+                .unwrap_or_else(|| "N/A".to_string()),
+        }
     }
+}
 
-    pub fn format_summary_statistics(tasks: &[Task], total_tasks: usize) -> String {
-        let unfinished_count = tasks.len();
-        let completion_rate = if total_tasks > 0 {
-            ((total_tasks - unfinished_count) as f64 / total_tasks as f64) * 100.0
-        } else {
-            0.0
-        };
+#[derive(Debug, Tabled)]
+pub struct UsageTableRow {
+    #[tabled(rename = "Model")]
+    pub model: String,
 
-        format!(
-            "\n📊 Task Summary\n{}\nTotal Tasks: {}\nUnfinished Tasks: {}\nCompletion Rate: {:.1}%\n",
-            "=".repeat(40),
-            total_tasks,
-            unfinished_count,
-            completion_rate
-        )
+    #[tabled(rename = "Runs")]
+    pub runs: usize,
+
+    #[tabled(rename = "Prompt Tokens")]
+    pub prompt_tokens: u64,
+
+    #[tabled(rename = "Cached")]
+    pub cached_prompt_tokens: u64,
+
+    #[tabled(rename = "Completion Tokens")]
+    pub completion_tokens: u64,
+
+    #[tabled(rename = "Est. Cost")]
+    pub cost: String,
+}
+
+impl From<&ModelUsage> for UsageTableRow {
+    fn from(summary: &ModelUsage) -> Self {
+        Self {
+            model: summary.model.clone(),
+            runs: summary.runs,
+            prompt_tokens: summary.usage.prompt_tokens,
+            cached_prompt_tokens: summary.usage.cached_prompt_tokens,
+            completion_tokens: summary.usage.completion_tokens,
+            cost: format!("${:.4}", summary.cost_usd),
+        }
     }
+}
 
-    pub fn format_priority_breakdown(tasks: &[Task]) -> String {
-        let mut high_count = 0;
-        let mut medium_count = 0;
-        let mut low_count = 0;
-        let mut no_priority_count = 0;
+#[derive(Debug, Tabled)]
+pub struct ResourceTableRow {
+    #[tabled(rename = "Name")]
+    pub name: String,
 
-        for task in tasks {
-            match task
-                .priority
+    #[tabled(rename = "URI")]
+    pub uri: String,
+
+    #[tabled(rename = "Type")]
+    pub mime_type: String,
+
+    #[tabled(rename = "Description")]
+    pub description: String,
+}
+
+impl From<&ResourceInfo> for ResourceTableRow {
+    fn from(resource: &ResourceInfo) -> Self {
+        Self {
+            name: output::truncate(&resource.name, 30),
+            uri: resource.uri.clone(),
+            mime_type: resource
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            description: resource
+                .description
                 .as_deref()
-                .unwrap_or("")
-                .to_lowercase()
-                .as_str()
-            {
-                "high" | "urgent" | "critical" => high_count += 1,
-                "medium" | "normal" => medium_count += 1,
-                "low" => low_count += 1,
-                _ => no_priority_count += 1,
-            }
+                .map(|description| output::truncate(description, 50))
+                .unwrap_or_else(|| "N/A".to_string()),
         }
+    }
+}
 
-        let mut output = format!("\n⚡ Priority Breakdown\n{}\n", "=".repeat(30));
+#[derive(Debug, Tabled)]
+pub struct SubtaskTableRow {
+    #[tabled(rename = "#")]
+    pub index: usize,
 
-        if high_count > 0 {
-            output.push_str(&format!("🔴 High Priority: {}\n", high_count));
-        }
-        if medium_count > 0 {
-            output.push_str(&format!("🟡 Medium Priority: {}\n", medium_count));
-        }
-        if low_count > 0 {
-            output.push_str(&format!("🟢 Low Priority: {}\n", low_count));
-        }
-        if no_priority_count > 0 {
-            output.push_str(&format!("⚪ No Priority Set: {}\n", no_priority_count));
+    #[tabled(rename = "Title")]
+    pub title: String,
+
+    #[tabled(rename = "Priority")]
+    pub priority: String,
+
+    #[tabled(rename = "Effort")]
+    pub effort: String,
+}
+
+impl SubtaskTableRow {
+    fn new(index: usize, subtask: &ProposedSubtask) -> Self {
+        Self {
+            index,
+            title: output::truncate(&subtask.title, 50),
+            priority: subtask
+                .priority
+                .clone()
+                .unwrap_or_else(|| "N/A".to_string()),
+            effort: subtask.effort.clone().unwrap_or_else(|| "N/A".to_string()),
         }
+    }
+}
 
-        output
+#[derive(Debug, Tabled)]
+pub struct ImportTableRow {
+    #[tabled(rename = "#")]
+    pub index: usize,
+
+    #[tabled(rename = "Title")]
+    pub title: String,
+
+    #[tabled(rename = "Status")]
+    pub status: String,
+
+    #[tabled(rename = "Priority")]
+    pub priority: String,
+
+    #[tabled(rename = "Due Date")]
+    pub due_date: String,
+
+    #[tabled(rename = "Tags")]
+    pub tags: String,
+}
+
+impl ImportTableRow {
+    fn new(index: usize, imported: &ImportedTask) -> Self {
+        let task = &imported.task;
+        let status = if imported.done {
+            TaskStatus::Completed
+        } else {
+            TaskStatus::Pending
+        };
+        Self {
+            index,
+            title: output::truncate(&task.title, 50),
+            status: status.as_str().to_string(),
+            priority: task.priority.clone().unwrap_or_else(|| "N/A".to_string()),
+            due_date: TaskDate::parse_opt(task.due_date.as_deref())
+                .map(|date| date.format())
+                .unwrap_or_else(|| "N/A".to_string()),
+            tags: task
+                .tags
+                .as_ref()
+                .map(|tags| tags.join(", "))
+                .unwrap_or_default(),
+        }
     }
+}
 
-    pub fn format_overdue_tasks(tasks: &[Task]) -> Result<String> {
-        let now = Utc::now();
-        let overdue_tasks: Vec<&Task> = tasks
-            .iter()
-            .filter(|task| {
-                task.due_date
-                    .as_ref()
-                    .and_then(|due_date_str| DateTime::parse_from_rfc3339(due_date_str).ok())
-                    .map(|due_date| due_date.with_timezone(&Utc) < now)
-                    .unwrap_or(false)
-            })
-            .collect();
+#[derive(Debug, Tabled)]
+pub struct AssessmentTableRow {
+    #[tabled(rename = "#")]
+    pub index: usize,
 
-        if overdue_tasks.is_empty() {
-            return Ok("No overdue tasks found.".to_string());
+    #[tabled(rename = "ID")]
+    pub id: String,
+
+    #[tabled(rename = "Title")]
+    pub title: String,
+
+    #[tabled(rename = "Score")]
+    pub score: u8,
+
+    #[tabled(rename = "Complexity")]
+    pub complexity: String,
+
+    #[tabled(rename = "Risk")]
+    pub risk: String,
+
+    #[tabled(rename = "Depends On")]
+    pub dependencies: String,
+}
+
+impl AssessmentTableRow {
+    fn new(index: usize, task: &TaskAssessment) -> Self {
+        Self {
+            index,
+            id: task.id.clone(),
+            title: output::truncate(&task.title, 40),
+            score: task.priority_score,
+            complexity: task.complexity.as_str().to_string(),
+            risk: task.risk.as_str().to_string(),
+            dependencies: if task.dependencies.is_empty() {
+                "-".to_string()
+            } else {
+                task.dependencies.join(", ")
+            },
         }
+    }
+}
 
-        let overdue_rows: Vec<TaskTableRow> = overdue_tasks
-            .into_iter()
-            .map(|task| TaskTableRow::from(task.clone()))
-            .collect();
+#[derive(Debug, Tabled)]
+pub struct ToolCallTableRow {
+    #[tabled(rename = "#")]
+    pub index: usize,
 
-        let row_count = overdue_rows.len();
+    #[tabled(rename = "Tool")]
+    pub tool: String,
 
-        let mut table = Table::new(overdue_rows);
-        table
-            .with(Style::modern())
-            .with(Modify::new(Column::from(0)).with(Alignment::center()))
-            .with(Modify::new(Column::from(2)).with(Alignment::center()))
-            .with(Modify::new(Column::from(3)).with(Alignment::center()));
+    #[tabled(rename = "Arguments")]
+    pub arguments: String,
 
-        let table_output = table.to_string();
+    #[tabled(rename = "Result")]
+    pub result: String,
 
-        let output = format!(
-            "\n🚨 Overdue Tasks ({} total)\n{}\n{}",
-            row_count,
-            "=".repeat(80),
-            table_output
-        );
+    #[tabled(rename = "Latency")]
+    pub latency: String,
 
-        Ok(output)
+    #[tabled(rename = "Status")]
+    pub status: String,
+}
+
+impl ToolCallTableRow {
+    fn new(index: usize, call: &ToolCallRecord) -> Self {
+        Self {
+            index,
+            tool: call.tool.clone(),
+            arguments: output::truncate(&call.arguments.to_string(), 50),
+            result: format!("{} B", call.result_bytes),
+            latency: format!("{} ms", call.duration_ms),
+            // Cells are decorated before layout so stripped emoji keep the columns aligned
+            status: output::decorate(&match &call.error {
+                Some(error) => format!("❌ {}", output::truncate(error, 40)),
+                None if call.success => "✅ ok".to_string(),
+                None => "❌ failed".to_string(),
+            })
+            .into_owned(),
+        }
     }
+}
 
-    pub fn format_tasks_by_status(tasks: &[Task], status: &str) -> Result<String> {
+pub struct TaskTableFormatter;
+
+impl TaskTableFormatter {
+    /// Table of `tasks` in the given order with the columns in `options`
+    pub fn format_all_tasks(tasks: &[Task], options: &TableOptions) -> Result<String> {
         if tasks.is_empty() {
-            return Ok(format!("No tasks found with status '{}'.", status));
+            return Ok("No tasks found.".to_string());
         }
 
         let table_rows: Vec<TaskTableRow> = tasks
@@ -189,18 +701,10 @@ impl TaskTableFormatter {
             .map(|task| TaskTableRow::from(task.clone()))
             .collect();
 
-        let mut table = Table::new(table_rows);
-
-        // Apply styling
-        table
-            .with(Style::modern())
-            .with(Modify::new(Column::from(0)).with(Alignment::center())) // ID column centered
-            .with(Modify::new(Column::from(2)).with(Alignment::center())) // Status column centered
-            .with(Modify::new(Column::from(3)).with(Alignment::center())); // Priority column centered
+        let table = styled_table(table_rows, &options.columns);
 
         let output = format!(
-            "\n📋 Tasks with Status '{}' ({} total)\n{}\n{}",
-            status,
+            "\n📋 All Tasks ({} total)\n{}\n{}",
             tasks.len(),
             "=".repeat(80),
             table
@@ -208,50 +712,1656 @@ impl TaskTableFormatter {
 
         Ok(output)
     }
+
+    /// One sub-table per group, each with its own heading and count.
+    /// Tasks keep their order within a group.
+    pub fn format_grouped_tasks(
+        tasks: &[Task],
+        group_by: TaskGroupBy,
+        options: &TableOptions,
+    ) -> String {
+        if tasks.is_empty() {
+            return "No tasks found.".to_string();
+        }
+
+        let mut groups: BTreeMap<(u8, String), (String, Vec<TaskTableRow>)> = BTreeMap::new();
+        for task in tasks {
+            for (key, label) in group_by.groups(task) {
+                groups
+                    .entry(key)
+                    .or_insert_with(|| (label, Vec::new()))
+                    .1
+                    .push(TaskTableRow::from(task.clone()));
+            }
+        }
+
+        let mut output = format!(
+            "\n📋 Tasks by {} ({} total, {} groups)\n{}\n",
+            group_by.heading(),
+            tasks.len(),
+            groups.len(),
+            "=".repeat(80)
+        );
+        for (label, rows) in groups.into_values() {
+            output.push_str(&format!(
+                "\n{} ({})\n{}\n",
+                label,
+                rows.len(),
+                styled_table(rows, &options.columns)
+            ));
+        }
+
+        output
+    }
+
+    /// Table of one page of tasks under `heading`, followed by the page footer
+    pub fn format_task_page(page: &TaskPage, heading: &str, options: &TableOptions) -> String {
+        if page.tasks.is_empty() {
+            return format!(
+                "No tasks on page {}. {}",
+                page.page,
+                Self::format_page_footer(page)
+            );
+        }
+
+        let table_rows: Vec<TaskTableRow> = page
+            .tasks
+            .iter()
+            .map(|task| TaskTableRow::from(task.clone()))
+            .collect();
+
+        format!(
+            "\n{}\n{}\n{}\n{}",
+            heading,
+            "=".repeat(80),
+            styled_table(table_rows, &options.columns),
+            Self::format_page_footer(page)
+        )
+    }
+
+    /// Footer such as `Page 2 of 5 (230 total)`
+    pub fn format_page_footer(page: &TaskPage) -> String {
+        match (page.total_pages(), page.total) {
+            (Some(pages), Some(total)) => {
+                format!("Page {} of {} ({} total)", page.page, pages, total)
+            }
+            _ => format!("Page {} ({} tasks)", page.page, page.tasks.len()),
+        }
+    }
+
+    /// One sub-table per due window, most urgent first
+    pub fn format_agenda(agenda: &Agenda) -> String {
+        let mut output = format!(
+            "\n📆 Agenda for {} ({})\n{}\n",
+            agenda.today.format("%A, %Y-%m-%d"),
+            agenda.timezone,
+            "=".repeat(80)
+        );
+
+        if agenda.entries.is_empty() {
+            output.push_str("Nothing due. 🎉\n");
+            return output;
+        }
+
+        for (window, entries) in agenda.windows() {
+            let rows = entries
+                .iter()
+                .map(|entry| AgendaTableRow::from(*entry))
+                .collect::<Vec<_>>();
+            let mut table = Table::new(rows);
+            table.with(Style::modern());
+            output.push_str(&format!(
+                "\n{} ({})\n{}\n",
+                window.heading(),
+                entries.len(),
+                table
+            ));
+        }
+
+        output
+    }
+
+    /// Sparklines of completion rate, backlog and overdue tasks plus one row per day
+    pub fn format_trend(trend: &Trend) -> String {
+        let mut output = format!(
+            "\n📈 Trend over the last {} days\n{}\n",
+            trend.period_days,
+            "=".repeat(80)
+        );
+
+        let (Some(first), Some(last)) = (trend.points.first(), trend.points.last()) else {
+            output
+                .push_str("No stats snapshots in this period yet. Each `stats` run records one.\n");
+            return output;
+        };
+
+        output.push_str(&format!(
+            "Completion rate  {}  {:.1}% → {:.1}% ({:+.1} pts)\n",
+            sparkline(&trend.completion_rates()),
+            first.completion_rate,
+            last.completion_rate,
+            last.completion_rate - first.completion_rate
+        ));
+        output.push_str(&format!(
+            "Backlog          {}  {} → {} ({:+})\n",
+            sparkline(&trend.backlog()),
+            first.unfinished,
+            last.unfinished,
+            last.unfinished as i64 - first.unfinished as i64
+        ));
+        output.push_str(&format!(
+            "Overdue          {}  {} → {} ({:+})\n",
+            sparkline(&trend.overdue()),
+            first.overdue,
+            last.overdue,
+            last.overdue as i64 - first.overdue as i64
+        ));
+        if trend.points.len() < 2 {
+            output.push_str(
+                "\nOnly one day of snapshots so far; run `stats` on other days to see changes.\n",
+            );
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Date",
+            "Total",
+            "Unfinished",
+            "Completed",
+            "Rate",
+            "Overdue",
+        ]);
+        for point in &trend.points {
+            builder.push_record([
+                point.date.to_string(),
+                point.total.to_string(),
+                point.unfinished.to_string(),
+                point.completed.to_string(),
+                format!("{:.1}%", point.completion_rate),
+                point.overdue.to_string(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("\n{}\n", table));
+
+        output
+    }
+
+    /// Weekly throughput table, averages and a burndown chart of open tasks
+    pub fn format_velocity(report: &VelocityReport) -> String {
+        let mut output = format!(
+            "\n🚀 Velocity over the last {} weeks\n{}\n",
+            report.weeks.len(),
+            "=".repeat(80)
+        );
+
+        let max_completed = report
+            .weeks
+            .iter()
+            .map(|week| week.completed)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let mut builder = Builder::default();
+        builder.push_record(["Week of", "Created", "Completed", "Open", "Throughput"]);
+        for week in &report.weeks {
+            builder.push_record([
+                week.week_start.format("%Y-%m-%d").to_string(),
+                week.created.to_string(),
+                week.completed.to_string(),
+                week.open.to_string(),
+                "█".repeat(week.completed * 20 / max_completed),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n\n", table));
+
+        output.push_str(&format!(
+            "Completed per week: {:.1} on average\n",
+            report.average_completed
+        ));
+        match report.average_cycle_days {
+            Some(days) => output.push_str(&format!(
+                "Cycle time (created → completed): {:.1} days on average over {} tasks\n",
+                days, report.cycle_samples
+            )),
+            None => output.push_str("Cycle time: no tasks completed in this period\n"),
+        }
+        if report.missing_completed_at > 0 {
+            output.push_str(&format!(
+                "⚠️  {} finished tasks have no completed_at timestamp and are not counted\n",
+                report.missing_completed_at
+            ));
+        }
+
+        output.push_str("\n📉 Burndown (open tasks at the end of each week)\n");
+        output.push_str(&burndown_chart(&report.weeks));
+
+        output
+    }
+
+    pub fn format_summary_statistics(tasks: &[Task], total_tasks: usize) -> String {
+        let unfinished_count = tasks.len();
+        let completion_rate = if total_tasks > 0 {
+            ((total_tasks - unfinished_count) as f64 / total_tasks as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        format!(
+            "\n📊 Task Summary\n{}\nTotal Tasks: {}\nUnfinished Tasks: {}\nCompletion Rate: {:.1}%\n",
+            "=".repeat(40),
+            total_tasks,
+            unfinished_count,
+            completion_rate
+        )
+    }
+
+    pub fn format_priority_breakdown(tasks: &[Task]) -> String {
+        let mut high_count = 0;
+        let mut medium_count = 0;
+        let mut low_count = 0;
+        let mut no_priority_count = 0;
+
+        for task in tasks {
+            match priority_level(task.priority.as_deref()) {
+                "high" => high_count += 1,
+                "medium" => medium_count += 1,
+                "low" => low_count += 1,
+                _ => no_priority_count += 1,
+            }
+        }
+
+        let mut output = format!("\n⚡ Priority Breakdown\n{}\n", "=".repeat(30));
+
+        if high_count > 0 {
+            output.push_str(&format!("🔴 High Priority: {}\n", high_count));
+        }
+        if medium_count > 0 {
+            output.push_str(&format!("🟡 Medium Priority: {}\n", medium_count));
+        }
+        if low_count > 0 {
+            output.push_str(&format!("🟢 Low Priority: {}\n", low_count));
+        }
+        if no_priority_count > 0 {
+            output.push_str(&format!("⚪ No Priority Set: {}\n", no_priority_count));
+        }
+
+        output
+    }
+
+    pub fn format_status_breakdown(tasks: &[Task]) -> String {
+        let counts = StatusCounts::compute(tasks);
+        let mut output = format!("\n📌 Status Breakdown\n{}\n", "=".repeat(30));
+
+        for (label, count) in [
+            ("📝 To Do", counts.pending),
+            ("🔄 In Progress", counts.in_progress),
+            ("✅ Done", counts.completed),
+            ("🚫 Cancelled", counts.cancelled),
+            ("❔ Other", counts.other),
+        ] {
+            if count > 0 {
+                output.push_str(&format!("{}: {}\n", label, count));
+            }
+        }
+
+        output
+    }
+
+    /// Open, in-progress, overdue and high-priority tasks per assignee
+    pub fn format_assignee_workload(workloads: &[AssigneeWorkload]) -> String {
+        let mut output = format!("\n👥 Workload by Assignee\n{}\n", "=".repeat(30));
+
+        if workloads.is_empty() {
+            output.push_str("No open tasks.\n");
+            return output;
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record(["Assignee", "Open", "In Progress", "Overdue", "High"]);
+        for workload in workloads {
+            builder.push_record([
+                workload.name().to_string(),
+                workload.open.to_string(),
+                workload.in_progress.to_string(),
+                workload.overdue.to_string(),
+                workload.high_priority.to_string(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+        output
+    }
+
+    pub fn format_overdue_tasks(tasks: &[Task]) -> Result<String> {
+        let overdue_tasks = overdue_tasks(tasks);
+
+        if overdue_tasks.is_empty() {
+            return Ok("No overdue tasks found.".to_string());
+        }
+
+        let overdue_rows: Vec<TaskTableRow> = overdue_tasks
+            .into_iter()
+            .map(|task| TaskTableRow::from(task.clone()))
+            .collect();
+
+        let row_count = overdue_rows.len();
+
+        let table_output = styled_table(overdue_rows, &TaskColumn::ALL).to_string();
+
+        let output = format!(
+            "\n🚨 Overdue Tasks ({} total)\n{}\n{}",
+            row_count,
+            "=".repeat(80),
+            table_output
+        );
+
+        Ok(output)
+    }
+
+    pub fn format_tasks_by_status(tasks: &[Task], status: &str) -> Result<String> {
+        if tasks.is_empty() {
+            return Ok(format!("No tasks found with status '{}'.", status));
+        }
+
+        let table_rows: Vec<TaskTableRow> = tasks
+            .iter()
+            .map(|task| TaskTableRow::from(task.clone()))
+            .collect();
+
+        let table = styled_table(table_rows, &TaskColumn::ALL);
+
+        let output = format!(
+            "\n📋 Tasks with Status '{}' ({} total)\n{}\n{}",
+            status,
+            tasks.len(),
+            "=".repeat(80),
+            table
+        );
+
+        Ok(output)
+    }
+
+    /// Tag table sorted by volume, followed by the number of untagged tasks
+    pub fn format_tag_summaries(summaries: &[TagSummary], untagged: usize) -> String {
+        let mut output = format!(
+            "\n🏷️  Tags ({} total)\n{}\n",
+            summaries.len(),
+            "=".repeat(80)
+        );
+
+        if summaries.is_empty() {
+            output.push_str("No tagged tasks found.\n");
+        } else {
+            let mut builder = Builder::default();
+            builder.push_record(["Tag", "Tasks", "Open", "Done", "Overdue", "Done %"]);
+            for summary in summaries {
+                builder.push_record([
+                    format!("#{}", summary.tag),
+                    summary.total.to_string(),
+                    summary.open.to_string(),
+                    summary.done.to_string(),
+                    summary.overdue.to_string(),
+                    format!("{:.0}%", summary.done as f64 / summary.total as f64 * 100.0),
+                ]);
+            }
+            let mut table = builder.build();
+            table.with(Style::modern());
+            output.push_str(&format!("{}\n", table));
+        }
+
+        if untagged > 0 {
+            output.push_str(&format!("\n{} tasks have no tags\n", untagged));
+        }
+        output
+    }
+
+    /// Tasks carrying one tag, with its open/done/overdue counts
+    pub fn format_tag_tasks(tag: &str, tasks: &[Task]) -> String {
+        let Some(summary) = TagSummary::compute(tasks)
+            .into_iter()
+            .find(|summary| summary.tag.eq_ignore_ascii_case(tag))
+        else {
+            return format!("No tasks tagged '{}'.", tag);
+        };
+
+        let rows = tasks
+            .iter()
+            .map(|task| TaskTableRow::from(task.clone()))
+            .collect();
+        format!(
+            "\n🏷️  Tasks tagged #{} ({} total: {} open, {} done, {} overdue)\n{}\n{}",
+            summary.tag,
+            summary.total,
+            summary.open,
+            summary.done,
+            summary.overdue,
+            "=".repeat(80),
+            styled_table(rows, &TaskColumn::ALL)
+        )
+    }
+
+    pub fn format_single_task(task: &Task, heading: &str) -> String {
+        let table = styled_table(vec![TaskTableRow::from(task.clone())], &TaskColumn::ALL);
+
+        format!("\n{}\n{}\n{}", heading, "=".repeat(80), table)
+    }
+
+    pub fn format_history(summaries: &[HistorySummary]) -> String {
+        if summaries.is_empty() {
+            return "No stored analyses yet. Run `analyze` or `analyze-with-tools` first."
+                .to_string();
+        }
+
+        let rows = summaries
+            .iter()
+            .map(HistoryTableRow::from)
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+
+        format!(
+            "\n🗂️  Analysis History ({} total)\n{}\n{}",
+            summaries.len(),
+            "=".repeat(80),
+            table
+        )
+    }
+
+    pub fn format_usage(summaries: &[ModelUsage]) -> String {
+        if summaries.is_empty() {
+            return "No stored analyses yet. Run `analyze` or `analyze-with-tools` first."
+                .to_string();
+        }
+
+        let rows = summaries
+            .iter()
+            .map(UsageTableRow::from)
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+
+        let runs = summaries.iter().map(|s| s.runs).sum::<usize>();
+        let tokens = summaries
+            .iter()
+            .map(|s| s.usage.total_tokens())
+            .sum::<u64>();
+        let cost = summaries.iter().map(|s| s.cost_usd).sum::<f64>();
+        let untracked = summaries
+            .iter()
+            .map(|s| s.runs_without_usage)
+            .sum::<usize>();
+
+        let mut output = format!(
+            "\n💰 Token Usage\n{}\n{}\n\nTotal: {} tokens over {} runs ≈ ${:.4}",
+            "=".repeat(80),
+            table,
+            tokens,
+            runs,
+            cost
+        );
+        if untracked > 0 {
+            output.push_str(&format!(
+                "\n({} older runs have no usage data and are not included)",
+                untracked
+            ));
+        }
+        output
+    }
+
+    pub fn format_resources(resources: &[ResourceInfo], server: &str) -> String {
+        if resources.is_empty() {
+            return format!("No resources available on MCP server '{}'", server);
+        }
+
+        let rows = resources
+            .iter()
+            .map(ResourceTableRow::from)
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+
+        format!(
+            "\n📚 Resources on MCP server '{}' ({} total)\n{}\n{}",
+            server,
+            resources.len(),
+            "=".repeat(80),
+            table
+        )
+    }
+
+    /// `doctor` results: each server's connection and which task operations its
+    /// tools support, followed by the errors of servers that could not be reached
+    pub fn format_capability_matrix(checks: &[ServerCheck]) -> String {
+        let mut output = format!(
+            "\n🩺 MCP servers ({} configured)\n{}\n",
+            checks.len(),
+            "=".repeat(80)
+        );
+
+        let mut header = vec!["Server", "Connection"];
+        header.extend(Capability::ALL.map(Capability::as_str));
+        header.push("resources");
+
+        let mut builder = Builder::default();
+        builder.push_record(header);
+        for check in checks {
+            let mut row = vec![
+                check.server.clone(),
+                if check.connected {
+                    "✅ ok"
+                } else {
+                    "❌ failed"
+                }
+                .to_string(),
+            ];
+            let mark = |supported: bool| if supported { "✅" } else { "—" }.to_string();
+            match &check.capabilities {
+                Some(capabilities) => {
+                    row.extend(Capability::ALL.map(|capability| {
+                        if capabilities.known() {
+                            mark(capabilities.supports(capability))
+                        } else {
+                            "?".to_string()
+                        }
+                    }));
+                    row.push(mark(capabilities.resources));
+                }
+                None => row.extend(std::iter::repeat_n(
+                    String::new(),
+                    Capability::ALL.len() + 1,
+                )),
+            }
+            builder.push_record(row);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        for check in checks {
+            if let Some(error) = &check.error {
+                output.push_str(&format!("\n❌ {}: {}", check.server, error));
+            } else if check
+                .capabilities
+                .as_ref()
+                .is_some_and(|capabilities| !capabilities.known())
+            {
+                output.push_str(&format!(
+                    "\n⚠️  {}: the tool list could not be read (see --verbose)",
+                    check.server
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Show an AI breakdown of a task: summary, effort, proposed subtasks and blockers
+    pub fn format_breakdown(task: &Task, breakdown: &TaskBreakdown) -> String {
+        let mut output = format!(
+            "\n🔍 Deep-dive: {} ({})\n{}\n",
+            task.title,
+            task.task_ref(),
+            "=".repeat(80)
+        );
+
+        if !breakdown.summary.is_empty() {
+            output.push_str(&format!("{}\n", breakdown.summary));
+        }
+        if let Some(effort) = &breakdown.effort {
+            output.push_str(&format!("\n⏱️  Estimated effort: {}\n", effort));
+        }
+
+        if breakdown.subtasks.is_empty() {
+            output.push_str("\n🧩 No subtasks proposed\n");
+        } else {
+            let rows = breakdown
+                .subtasks
+                .iter()
+                .enumerate()
+                .map(|(idx, subtask)| SubtaskTableRow::new(idx + 1, subtask))
+                .collect::<Vec<_>>();
+            let mut table = Table::new(rows);
+            table.with(Style::modern());
+            output.push_str(&format!(
+                "\n🧩 Proposed subtasks ({})\n{}\n",
+                breakdown.subtasks.len(),
+                table
+            ));
+        }
+
+        if !breakdown.blockers.is_empty() {
+            output.push_str("\n🚧 Blockers:\n");
+            for blocker in &breakdown.blockers {
+                output.push_str(&format!("  - {}\n", blocker));
+            }
+        }
+
+        output
+    }
+
+    /// Every field of one task as a card: the full description wrapped to the
+    /// card's width, tags, timestamps and how far off the due date is. Fields
+    /// the CLI does not know are listed at the end.
+    pub fn format_task_card(task: &Task, now: DateTime<Utc>) -> String {
+        let mut output = format!(
+            "\n📌 {} ({})\n{}\n",
+            task.title,
+            task.task_ref(),
+            "=".repeat(80)
+        );
+
+        let or_na = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+        let due = match TaskDate::parse_opt(task.due_date.as_deref()) {
+            Some(due) if task.is_unfinished() => {
+                format!("{} ({})", due.format(), agenda::describe_due(due, now))
+            }
+            Some(due) => due.format(),
+            None => or_na(task.due_date.clone()),
+        };
+        let fields = [
+            ("Status", format_status(&task.status)),
+            ("Priority", or_na(task.priority.clone())),
+            ("Assignee", or_na(task.assignee.clone())),
+            ("Due", due),
+            (
+                "Tags",
+                or_na(
+                    task.tags
+                        .as_ref()
+                        .filter(|tags| !tags.is_empty())
+                        .map(|tags| tags.join(", ")),
+                ),
+            ),
+            (
+                "Created",
+                or_na(
+                    Some(task.created_at.as_str())
+                        .filter(|created| !created.is_empty())
+                        .map(format_date_time_string),
+                ),
+            ),
+            (
+                "Updated",
+                or_na(task.updated_at.as_deref().map(format_date_time_string)),
+            ),
+            (
+                "Completed",
+                or_na(task.completed_at.as_deref().map(format_date_time_string)),
+            ),
+        ];
+        for (label, value) in fields {
+            output.push_str(&format!("{:<11}{}\n", format!("{}:", label), value));
+        }
+
+        output.push_str("\n📝 Description:\n");
+        match task
+            .description
+            .as_deref()
+            .filter(|description| !description.trim().is_empty())
+        {
+            Some(description) => {
+                for paragraph in description.trim().lines() {
+                    if paragraph.trim().is_empty() {
+                        output.push('\n');
+                    }
+                    for line in output::wrap(paragraph, 76) {
+                        output.push_str(&format!("  {}\n", line));
+                    }
+                }
+            }
+            None => output.push_str("  (none)\n"),
+        }
+
+        if !task.extra.is_empty() {
+            output.push_str("\n🧾 Other fields:\n");
+            for (key, value) in &task.extra {
+                match value.as_str() {
+                    Some(text) => output.push_str(&format!("  {}: {}\n", key, text)),
+                    None => output.push_str(&format!("  {}: {}\n", key, value)),
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Show a structured analysis as a table in the recommended order
+    pub fn format_structured_analysis(analysis: &StructuredAnalysis) -> String {
+        let mut output = format!("\n🧠 Structured Analysis\n{}\n", "=".repeat(80));
+
+        if !analysis.summary.is_empty() {
+            output.push_str(&format!("{}\n", analysis.summary));
+        }
+
+        let ordered = analysis.ordered();
+        if ordered.is_empty() {
+            output.push_str("\n📭 No tasks were assessed\n");
+            return output;
+        }
+
+        let rows = ordered
+            .iter()
+            .enumerate()
+            .map(|(idx, task)| AssessmentTableRow::new(idx + 1, task))
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+        output.push_str(&format!("\n📋 Recommended order\n{}\n", table));
+
+        let rationales = ordered
+            .iter()
+            .filter_map(|task| task.rationale.as_ref().map(|rationale| (task, rationale)))
+            .collect::<Vec<_>>();
+        if !rationales.is_empty() {
+            output.push_str("\n💬 Rationale:\n");
+            for (task, rationale) in rationales {
+                output.push_str(&format!("  - {}: {}\n", task.id, rationale));
+            }
+        }
+
+        output
+    }
+
+    /// Clusters of duplicate tasks with the task kept and the ones `--merge` would close
+    pub fn format_duplicate_clusters(clusters: &[DuplicateCluster], tasks: &[Task]) -> String {
+        let mut output = format!(
+            "\n🔁 Duplicate candidates ({} clusters)\n{}\n",
+            clusters.len(),
+            "=".repeat(80)
+        );
+
+        if clusters.is_empty() {
+            output.push_str("✨ No duplicate tasks found\n");
+            return output;
+        }
+
+        let title = |id: &str| {
+            tasks
+                .iter()
+                .find(|task| task.id == id)
+                .map(|task| output::truncate(&task.title, 50))
+                .unwrap_or_default()
+        };
+
+        let mut builder = Builder::default();
+        builder.push_record(["#", "ID", "Title", "Action"]);
+        for (idx, cluster) in clusters.iter().enumerate() {
+            builder.push_record([
+                (idx + 1).to_string(),
+                cluster.keep.clone(),
+                title(&cluster.keep),
+                "keep".to_string(),
+            ]);
+            for id in cluster.duplicates() {
+                builder.push_record([String::new(), id.clone(), title(id), "close".to_string()]);
+            }
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output.push_str("\n💬 Why:\n");
+        for (idx, cluster) in clusters.iter().enumerate() {
+            let similarity = cluster
+                .similarity
+                .map(|similarity| format!(" ({}% similar)", similarity))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "  {}.{} {}\n",
+                idx + 1,
+                similarity,
+                cluster.rationale
+            ));
+        }
+
+        output
+    }
+
+    /// Semantic search results, best match first
+    pub fn format_semantic_matches(query: &str, matches: &[SemanticMatch]) -> String {
+        let mut output = format!("\n🧭 Tasks closest to \"{}\"\n{}\n", query, "=".repeat(80));
+
+        let mut builder = Builder::default();
+        builder.push_record(["#", "Score", "ID", "Title", "Status"]);
+        for (idx, semantic_match) in matches.iter().enumerate() {
+            builder.push_record([
+                (idx + 1).to_string(),
+                format!("{:.2}", semantic_match.score),
+                semantic_match.task.id.clone(),
+                output::truncate(&semantic_match.task.title, 50),
+                semantic_match.task.status.clone(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// Themes of the backlog with their share and member tasks
+    pub fn format_themes(report: &ThemeReport) -> String {
+        let mut output = format!(
+            "\n🏷️  Backlog themes ({} tasks, {} themes)\n{}\n",
+            report.task_count,
+            report.themes.len(),
+            "=".repeat(80)
+        );
+
+        for (idx, theme) in report.themes.iter().enumerate() {
+            output.push_str(&format!(
+                "\n{}. {} — {:.0}% ({} tasks) {}\n",
+                idx + 1,
+                theme.label,
+                theme.share,
+                theme.tasks.len(),
+                "█".repeat((theme.share / 5.0).round() as usize)
+            ));
+            if !theme.summary.is_empty() {
+                output.push_str(&format!("   {}\n", theme.summary));
+            }
+            for task in &theme.tasks {
+                output.push_str(&format!(
+                    "   • {} ({})\n",
+                    output::truncate(&task.title, 60),
+                    task.id
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Tasks about to be imported from `source`
+    pub fn format_import_preview(source: &str, tasks: &[ImportedTask]) -> String {
+        let mut output = format!(
+            "\n📥 {} tasks to import from {}\n{}\n",
+            tasks.len(),
+            source,
+            "=".repeat(80)
+        );
+
+        let rows = tasks
+            .iter()
+            .enumerate()
+            .map(|(idx, task)| ImportTableRow::new(idx + 1, task))
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// Current vs suggested priority per task; unchanged priorities are marked with `=`
+    pub fn format_priority_changes(changes: &[PriorityChange]) -> String {
+        let changed = changes.iter().filter(|change| change.is_change()).count();
+        let mut output = format!(
+            "\n🎯 Suggested priorities ({} of {} would change)\n{}\n",
+            changed,
+            changes.len(),
+            "=".repeat(80)
+        );
+
+        if changes.is_empty() {
+            output.push_str("📭 No priority suggestions were returned\n");
+            return output;
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record(["ID", "Title", "Current", "Suggested", "Justification"]);
+        for change in changes {
+            builder.push_record([
+                change.id.clone(),
+                output::truncate(&change.title, 40),
+                change.current.clone().unwrap_or_else(|| "N/A".to_string()),
+                if change.is_change() {
+                    format!("→ {}", change.suggested)
+                } else {
+                    "=".to_string()
+                },
+                output::truncate(&change.justification, 60),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// AI effort estimates per task followed by the backlog totals
+    pub fn format_estimates(estimates: &[TaskEstimate], summary: &EstimateSummary) -> String {
+        let unit = summary.unit;
+        let mut output = format!(
+            "\n⏱️  Effort estimates in {} ({} tasks)\n{}\n",
+            unit.label(),
+            estimates.len(),
+            "=".repeat(80)
+        );
+
+        if estimates.is_empty() {
+            output.push_str("📭 No estimates were returned\n");
+            return output;
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            "ID",
+            "Title",
+            "Priority",
+            "Current",
+            "Estimate",
+            "Rationale",
+        ]);
+        for estimate in estimates {
+            builder.push_record([
+                estimate.id.clone(),
+                output::truncate(&estimate.title, 40),
+                estimate
+                    .priority
+                    .clone()
+                    .unwrap_or_else(|| "N/A".to_string()),
+                estimate.current.clone().unwrap_or_else(|| "-".to_string()),
+                unit.format(estimate.estimate),
+                output::truncate(&estimate.rationale, 50),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output.push_str(&format!(
+            "\n📊 Total backlog effort: {} across {} tasks\n",
+            unit.format(summary.total),
+            summary.tasks
+        ));
+        for level in &summary.by_priority {
+            output.push_str(&format!(
+                "   {} {} ({} tasks)\n",
+                output::pad(&format!("{}:", level.priority), 7),
+                unit.format(level.total),
+                level.tasks
+            ));
+        }
+
+        output
+    }
+
+    /// A capacity plan: one row per planned task, grouped by period, then the
+    /// tasks that do not fit
+    pub fn format_plan(plan: &Plan) -> String {
+        let mut output = format!(
+            "\n🗓️  Plan at {} per {} until {}\n{}\n",
+            format_hours(plan.capacity),
+            plan.period.as_str(),
+            plan.until,
+            "=".repeat(80)
+        );
+
+        let mut builder = Builder::default();
+        builder.push_record(["Period", "Load", "ID", "Task", "Hours", "Due"]);
+        for period in &plan.periods {
+            let load = format!(
+                "{}/{}{}",
+                format_hours(period.hours),
+                format_hours(plan.capacity),
+                if period.over_capacity { " ⚠️" } else { "" }
+            );
+            if period.items.is_empty() {
+                builder.push_record([
+                    plan.period_label(period.start),
+                    load,
+                    "-".to_string(),
+                    "Nothing planned".to_string(),
+                    String::new(),
+                    String::new(),
+                ]);
+                continue;
+            }
+            for (idx, item) in period.items.iter().enumerate() {
+                let (label, load) = if idx == 0 {
+                    (plan.period_label(period.start), load.clone())
+                } else {
+                    (String::new(), String::new())
+                };
+                let due = TaskDate::parse_opt(item.due_date.as_deref())
+                    .map(|due| due.format_date())
+                    .unwrap_or_else(|| "-".to_string());
+                builder.push_record([
+                    label,
+                    load,
+                    item.id.clone(),
+                    output::truncate(&item.title, 40),
+                    format_hours(item.hours),
+                    if item.late {
+                        format!("{} ⚠️ late", due)
+                    } else {
+                        due
+                    },
+                ]);
+            }
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output.push_str(&format!(
+            "\n📊 Planned {} of {} available\n",
+            format_hours(plan.planned_hours()),
+            format_hours(plan.available_hours())
+        ));
+
+        if !plan.unscheduled.is_empty() {
+            output.push_str(&format!(
+                "\n🚫 Won't fit ({} tasks)\n",
+                plan.unscheduled.len()
+            ));
+            let mut builder = Builder::default();
+            builder.push_record(["ID", "Task", "Reason", "Suggestion"]);
+            for deferral in &plan.unscheduled {
+                builder.push_record([
+                    deferral.id.clone(),
+                    output::truncate(&deferral.title, 40),
+                    output::truncate(&deferral.reason, 40),
+                    output::truncate(&deferral.suggestion, 40),
+                ]);
+            }
+            let mut table = builder.build();
+            table.with(Style::modern());
+            output.push_str(&format!("{}\n", table));
+        }
+
+        if !plan.notes.is_empty() {
+            output.push_str(&format!("\n📝 {}\n", plan.notes));
+        }
+
+        output
+    }
+
+    /// The AI's proposed sprint backlog and the notable tasks left out
+    pub fn format_sprint_proposal(proposal: &SprintProposal) -> String {
+        let unit = proposal.capacity.unit;
+        let mut output = format!(
+            "\n🏃 {}: {} to {}, {} of {} committed\n{}\n",
+            proposal.name,
+            proposal.start,
+            proposal.end,
+            unit.format(proposal.committed()),
+            proposal.capacity,
+            "=".repeat(80)
+        );
+        if !proposal.goal.is_empty() {
+            output.push_str(&format!("🎯 Goal: {}\n\n", proposal.goal));
+        }
+
+        if proposal.selected.is_empty() {
+            output.push_str("📭 No tasks were proposed\n");
+        } else {
+            let mut builder = Builder::default();
+            builder.push_record(["#", "ID", "Title", "Priority", "Estimate", "Rationale"]);
+            for (idx, pick) in proposal.selected.iter().enumerate() {
+                builder.push_record([
+                    (idx + 1).to_string(),
+                    pick.id.clone(),
+                    output::truncate(&pick.title, 40),
+                    pick.priority.clone().unwrap_or_else(|| "N/A".to_string()),
+                    unit.format(pick.estimate),
+                    output::truncate(&pick.rationale, 50),
+                ]);
+            }
+            let mut table = builder.build();
+            table.with(Style::modern());
+            output.push_str(&format!("{}\n", table));
+        }
+
+        if !proposal.left_out.is_empty() {
+            output.push_str(&format!(
+                "\n⏸️  Left out ({} tasks)\n",
+                proposal.left_out.len()
+            ));
+            for left_out in &proposal.left_out {
+                output.push_str(&format!(
+                    "   • {} ({}): {}\n",
+                    output::truncate(&left_out.title, 40),
+                    left_out.id,
+                    left_out.reason
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Sprint outcome per task followed by the totals
+    pub fn format_sprint_review(review: &SprintReview) -> String {
+        let mut output = format!("\n🏁 Review of {}", review.name);
+        if let (Some(start), Some(end)) = (review.start, review.end) {
+            output.push_str(&format!(" ({} to {})", start, end));
+        }
+        output.push_str(&format!("\n{}\n", "=".repeat(80)));
+        if !review.goal.is_empty() {
+            output.push_str(&format!("🎯 Goal: {}\n\n", review.goal));
+        }
+
+        if review.items.is_empty() {
+            output.push_str("📭 No tasks belong to this sprint\n");
+            return output;
+        }
+
+        let unit = review.capacity.map(|capacity| capacity.unit);
+        let mut builder = Builder::default();
+        builder.push_record(["ID", "Title", "Status", "Estimate", "Outcome"]);
+        for item in &review.items {
+            builder.push_record([
+                item.id.clone(),
+                output::truncate(&item.title, 40),
+                item.status.clone(),
+                item.estimate
+                    .zip(unit)
+                    .map(|(estimate, unit)| unit.format(estimate))
+                    .unwrap_or_else(|| "-".to_string()),
+                item.outcome.label().to_string(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        let done = review
+            .items
+            .iter()
+            .filter(|item| item.outcome.is_completed())
+            .count();
+        output.push_str(&format!(
+            "\n📊 {} of {} tasks done, {} carried over, {} added during the sprint\n",
+            done,
+            review.items.len(),
+            review.count(SprintOutcome::CarriedOver),
+            review.count(SprintOutcome::Added) + review.count(SprintOutcome::AddedCompleted)
+        ));
+        if let Some(unit) = unit
+            && review.planned > 0.0
+        {
+            output.push_str(&format!(
+                "   Completed {} of {} committed ({:.0}%)\n",
+                unit.format(review.completed),
+                unit.format(review.planned),
+                review.completed / review.planned * 100.0
+            ));
+        }
+
+        output
+    }
+
+    /// Outcome of a bulk change per task, failures with their error
+    pub fn format_bulk_results(action: &BulkAction, results: &[BulkResult]) -> String {
+        Self::format_change_results(&format!("Bulk {}", action.describe()), results)
+    }
+
+    /// Each changed task with ✅ or the error it failed with, under `heading`
+    pub fn format_change_results(heading: &str, results: &[BulkResult]) -> String {
+        let failed = results.iter().filter(|result| !result.ok).count();
+        let mut output = format!(
+            "\n📦 {}: {} succeeded, {} failed\n{}\n",
+            heading,
+            results.len() - failed,
+            failed,
+            "=".repeat(80)
+        );
+
+        let mut builder = Builder::default();
+        builder.push_record(["ID", "Title", "Result"]);
+        for result in results {
+            builder.push_record([
+                result.id.clone(),
+                output::truncate(&result.title, 40),
+                match &result.error {
+                    Some(error) => format!("❌ {}", output::truncate(error, 60)),
+                    None => "✅ done".to_string(),
+                },
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// Templates with where they are defined and the tasks they create
+    pub fn format_templates(templates: &[NamedTemplate]) -> String {
+        let mut output = format!(
+            "\n📋 Task templates ({} total)\n{}\n",
+            templates.len(),
+            "=".repeat(80)
+        );
+
+        let mut builder = Builder::default();
+        builder.push_record(["Name", "Source", "Tasks", "Titles"]);
+        for named in templates {
+            let titles = named
+                .template
+                .tasks
+                .iter()
+                .map(|task| match &task.due {
+                    Some(due) => format!("{} (due {})", task.title, due),
+                    None => task.title.clone(),
+                })
+                .collect::<Vec<_>>();
+            builder.push_record([
+                named.name.clone(),
+                named.source.as_str().to_string(),
+                named.template.tasks.len().to_string(),
+                output::truncate(&titles.join("; "), 70),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// Time tracked per task and per day
+    pub fn format_timesheet(timesheet: &Timesheet) -> String {
+        let mut output = format!(
+            "\n⏱️  Timesheet since {}: {} tracked\n{}\n",
+            timesheet.since,
+            format_hours(timesheet.total_hours),
+            "=".repeat(80)
+        );
+
+        if timesheet.tasks.is_empty() {
+            output.push_str("📭 No time tracked; start a timer with `start <ID>`\n");
+            return output;
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record(["ID", "Title", "Entries", "Hours"]);
+        for task in &timesheet.tasks {
+            builder.push_record([
+                task.task_id.clone(),
+                output::truncate(&task.title, 50),
+                task.entries.to_string(),
+                format_hours(task.hours),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        let mut builder = Builder::default();
+        builder.push_record(["Day", "Hours"]);
+        for day in &timesheet.days {
+            builder.push_record([
+                day.date.format("%a %Y-%m-%d").to_string(),
+                format_hours(day.hours),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::modern());
+        output.push_str(&format!("{}\n", table));
+
+        output
+    }
+
+    /// Show the tool calls the AI made, in the order it made them
+    pub fn format_tool_calls(tool_calls: &[ToolCallRecord]) -> String {
+        if tool_calls.is_empty() {
+            return "\n🔧 No tool calls were made".to_string();
+        }
+
+        let rows = tool_calls
+            .iter()
+            .enumerate()
+            .map(|(idx, call)| ToolCallTableRow::new(idx + 1, call))
+            .collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table.with(Style::modern());
+
+        format!(
+            "\n🔧 Tool calls ({} total)\n{}\n{}",
+            tool_calls.len(),
+            "=".repeat(80),
+            table
+        )
+    }
+
+    /// Changes between two task snapshots, one line per task
+    pub fn format_snapshot_diff(diff: &SnapshotDiff) -> String {
+        let mut output = format!(
+            "\n🗂️  Snapshot {} → {}: {}\n{}\n",
+            dates::format_instant(diff.from, "%Y-%m-%d %H:%M"),
+            dates::format_instant(diff.to, "%Y-%m-%d %H:%M"),
+            diff.counts(),
+            "=".repeat(80)
+        );
+
+        if diff.changes.is_empty() {
+            output.push_str("No tasks changed.\n");
+        }
+        for change in &diff.changes {
+            output.push_str(&format!("{}\n", change.describe()));
+        }
+
+        output
+    }
+
+    /// Show which fields changed between two versions of the same task
+    pub fn format_task_diff(before: &Task, after: &Task) -> String {
+        let mut output = format!(
+            "\n🔄 Changes to task {}\n{}\n",
+            after.task_ref(),
+            "=".repeat(40)
+        );
+        let changes = task_field_changes(before, after);
+
+        for (label, old_value, new_value) in &changes {
+            output.push_str(&format!("{}: {} → {}\n", label, old_value, new_value));
+        }
+
+        if changes.is_empty() {
+            output.push_str("No fields changed.\n");
+        }
+
+        output
+    }
+}
+
+/// Fields that differ between two versions of a task as `(label, old, new)`
+pub fn task_field_changes(before: &Task, after: &Task) -> Vec<(&'static str, String, String)> {
+    let fields = [
+        (
+            "Title",
+            Some(before.title.clone()),
+            Some(after.title.clone()),
+        ),
+        (
+            "Description",
+            before.description.clone(),
+            after.description.clone(),
+        ),
+        (
+            "Status",
+            Some(before.status.clone()),
+            Some(after.status.clone()),
+        ),
+        ("Priority", before.priority.clone(), after.priority.clone()),
+        ("Assignee", before.assignee.clone(), after.assignee.clone()),
+        ("Due Date", before.due_date.clone(), after.due_date.clone()),
+        (
+            "Completed",
+            before.completed_at.clone(),
+            after.completed_at.clone(),
+        ),
+        (
+            "Tags",
+            before.tags.as_ref().map(|tags| tags.join(", ")),
+            after.tags.as_ref().map(|tags| tags.join(", ")),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(label, old_value, new_value)| {
+            (
+                label,
+                old_value.unwrap_or_else(|| "N/A".to_string()),
+                new_value.unwrap_or_else(|| "N/A".to_string()),
+            )
+        })
+        .collect()
 }
 
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+/// Normalize a priority value to "high", "medium", "low" or "none"
+pub fn priority_level(priority: Option<&str>) -> &'static str {
+    match priority.unwrap_or("").to_lowercase().as_str() {
+        "high" | "urgent" | "critical" => "high",
+        "medium" | "normal" => "medium",
+        "low" => "low",
+        _ => "none",
     }
 }
 
+/// Tasks whose due date has already passed
+pub fn overdue_tasks(tasks: &[Task]) -> Vec<&Task> {
+    let now = Utc::now();
+    tasks
+        .iter()
+        .filter(|task| {
+            TaskDate::parse_opt(task.due_date.as_deref()).is_some_and(|due| due.is_overdue(now))
+        })
+        .collect()
+}
+
+/// Vertical bar chart of open tasks per week
+fn burndown_chart(weeks: &[WeekVelocity]) -> String {
+    const HEIGHT: usize = 8;
+    const COLUMN: usize = 7;
+
+    let max = weeks.iter().map(|week| week.open).max().unwrap_or(0).max(1);
+    let axis_width = max.to_string().len();
+    let heights = weeks
+        .iter()
+        .map(|week| (week.open * HEIGHT).div_ceil(max))
+        .collect::<Vec<_>>();
+
+    let mut chart = String::new();
+    for row in (1..=HEIGHT).rev() {
+        let label = if row == HEIGHT {
+            max.to_string()
+        } else {
+            String::new()
+        };
+        chart.push_str(&format!("{:>width$} │", label, width = axis_width));
+        for height in &heights {
+            let cell = if *height >= row { "██" } else { "" };
+            chart.push_str(&format!("{:^width$}", cell, width = COLUMN));
+        }
+        chart.push('\n');
+    }
+    chart.push_str(&format!(
+        "{:>width$} └{}\n",
+        0,
+        "─".repeat(COLUMN * weeks.len()),
+        width = axis_width
+    ));
+    chart.push_str(&" ".repeat(axis_width + 2));
+    for week in weeks {
+        chart.push_str(&format!(
+            "{:^width$}",
+            week.week_start.format("%m-%d").to_string(),
+            width = COLUMN
+        ));
+    }
+    chart.push('\n');
+
+    chart
+}
+
+fn styled_table(rows: Vec<TaskTableRow>, columns: &[TaskColumn]) -> Table {
+    let mut builder = Builder::default();
+    builder.push_record(columns.iter().map(|column| column.header()));
+    for row in &rows {
+        builder.push_record(columns.iter().map(|column| column.value(row)));
+    }
+
+    // Widest cell of each column, header included
+    let natural = columns
+        .iter()
+        .map(|column| {
+            rows.iter()
+                .map(|row| get_text_width(column.value(row)))
+                .chain([get_text_width(column.header())])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let mut table = builder.build();
+
+    // Apply styling: ID, status and priority columns are centered
+    table.with(Style::modern());
+    for (idx, column) in columns.iter().enumerate() {
+        if column.centered() {
+            table.with(Modify::new(Column::from(idx)).with(Alignment::center()));
+        }
+    }
+
+    fit_to_terminal(&mut table, columns, &natural);
+    if colors_enabled() {
+        color_by_urgency(&mut table, &rows, columns);
+    }
+    table
+}
+
+/// Tables are colored on a terminal unless `--no-color` or `NO_COLOR` is set
+fn colors_enabled() -> bool {
+    output::style().color && std::io::stdout().is_terminal()
+}
+
+/// Completed tasks are dimmed; for the others overdue due dates are red, due
+/// dates of today yellow and high priorities bold red
+fn color_by_urgency(table: &mut Table, rows: &[TaskTableRow], columns: &[TaskColumn]) {
+    let due_column = columns.iter().position(|column| *column == TaskColumn::Due);
+    let priority_column = columns
+        .iter()
+        .position(|column| *column == TaskColumn::Priority);
+
+    // The header is row 0
+    for (idx, row) in rows.iter().enumerate().map(|(idx, row)| (idx + 1, row)) {
+        let urgency = row.urgency;
+        if urgency.completed {
+            table.with(Modify::new(Row::from(idx)).with(Color::new("\u{1b}[2m", "\u{1b}[22m")));
+            continue;
+        }
+
+        if let (Some(column), Some(due)) = (due_column, urgency.due) {
+            let color = match due {
+                DueUrgency::Overdue => Color::FG_RED,
+                DueUrgency::Today => Color::FG_YELLOW,
+            };
+            table.with(Modify::new(Cell::new(idx, column)).with(color));
+        }
+        if let Some(column) = priority_column.filter(|_| urgency.high_priority) {
+            table.with(Modify::new(Cell::new(idx, column)).with(Color::BOLD | Color::FG_RED));
+        }
+    }
+}
+
+/// Narrow the title, assignee and tags columns until the table fits the
+/// terminal. The other columns keep their width; the room left over is shared
+/// in proportion to each flexible column's widest cell, with at least
+/// `MIN_FLEXIBLE_WIDTH` each. Cells over their share are wrapped, or cut off
+/// with `--truncate`.
+fn fit_to_terminal(table: &mut Table, columns: &[TaskColumn], natural: &[usize]) {
+    let mode = table_width();
+    let available = terminal_columns();
+    if mode == TableWidth::Wide || table.total_width() <= available {
+        return;
+    }
+
+    let (flexible, fixed): (Vec<_>, Vec<_>) = columns
+        .iter()
+        .zip(natural)
+        .partition(|(column, _)| column.flexible());
+    let flexible_width = flexible.iter().map(|(_, width)| **width).sum::<usize>();
+    if flexible_width == 0 {
+        return;
+    }
+
+    // Each column has a border on its left and a space of padding on both sides
+    let chrome = columns.len() * 3 + 1;
+    let fixed_width = fixed.iter().map(|(_, width)| **width).sum::<usize>();
+    let room = available.saturating_sub(chrome + fixed_width);
+
+    for (idx, (column, &width)) in columns.iter().zip(natural).enumerate() {
+        if !column.flexible() {
+            continue;
+        }
+        let share = (room * width / flexible_width).max(MIN_FLEXIBLE_WIDTH.min(width));
+        if share >= width {
+            continue;
+        }
+        match mode {
+            TableWidth::Truncate => table
+                .with(Modify::new(Column::from(idx)).with(Width::truncate(share).suffix("..."))),
+            _ => {
+                table.with(Modify::new(Column::from(idx)).with(Width::wrap(share).keep_words(true)))
+            }
+        };
+    }
+}
+
+/// Columns tables may use: `COLUMNS`, the terminal's width, or
+/// `DEFAULT_TABLE_COLUMNS` when output is not a terminal
+fn terminal_columns() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .or_else(|| {
+            std::io::stdout()
+                .is_terminal()
+                .then(terminal::size)
+                .and_then(Result::ok)
+                .map(|(columns, _)| usize::from(columns))
+        })
+        .unwrap_or(DEFAULT_TABLE_COLUMNS)
+}
+
 fn format_date_string(date_str: Option<&str>) -> String {
     match date_str {
-        Some(date) => {
-            // Try to parse and format the date nicely
-            if let Ok(parsed_date) = DateTime::parse_from_rfc3339(date) {
-                parsed_date.format("%Y-%m-%d").to_string()
-            } else if let Ok(parsed_date) = DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S") {
-                parsed_date.format("%Y-%m-%d").to_string()
-            } else {
-                // If parsing fails, just truncate and return as-is
-                truncate_string(date, 10)
+        // Shown as the day in the display time zone, or relative to today with
+        // --relative-dates; unknown formats are truncated as-is
+        Some(date) => match TaskDate::parse(date) {
+            Some(parsed_date) if dates::relative_dates() => {
+                dates::humanize(parsed_date, Utc::now())
             }
-        }
+            Some(parsed_date) => parsed_date.format_date(),
+            None => output::truncate(date, 10),
+        },
         None => "N/A".to_string(),
     }
 }
 
+fn format_date_time_string(date_str: &str) -> String {
+    match TaskDate::parse(date_str) {
+        Some(parsed_date) => parsed_date.format(),
+        None => date_str.to_string(),
+    }
+}
+
+/// Long IDs are shortened unless tables are `--wide`, but the server alias of a
+/// namespaced task is kept whole so `alias:id` stays unambiguous
+fn format_task_id(task: &Task) -> String {
+    let id = match table_width() {
+        TableWidth::Wide => task.id.clone(),
+        _ => output::truncate(&task.id, 8),
+    };
+    TaskRef {
+        server: task.server.clone(),
+        id,
+    }
+    .to_string()
+}
+
 fn format_tags(tags: Option<&[String]>) -> String {
     match tags {
-        Some(tag_slice) if !tag_slice.is_empty() => {
-            let tags_str = tag_slice.join(", ");
-
-            truncate_string(&tags_str, 30)
-        }
+        Some(tag_slice) if !tag_slice.is_empty() => tag_slice.join(", "),
         _ => "N/A".to_string(),
     }
 }
 
 fn format_status(status: &str) -> String {
-    match status.to_lowercase().as_str() {
-        "todo" | "pending" => "To Do".to_string(),
-        "in_progress" => "In Progress".to_string(),
-        "done" | "completed" => "Done".to_string(),
-        "cancelled" => "Cancelled".to_string(),
-        _ => status.to_string(),
+    match TaskStatus::parse(status) {
+        Some(status) => status.label().to_string(),
+        None => status.to_string(),
     }
 }
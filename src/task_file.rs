@@ -0,0 +1,369 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::dates::TaskDate;
+use crate::error::McpTasksError;
+use crate::import::{ImportedTask, tag_name};
+use crate::mcp_client::NewTask;
+use crate::table_formatter::priority_level;
+
+/// Syntax of a task list file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskFileFormat {
+    /// GitHub-style checkboxes under `#` headings
+    Markdown,
+    /// Emacs org-mode: `TODO`/`DONE` headings and checkboxes under `*` headings
+    Org,
+}
+
+impl TaskFileFormat {
+    /// `.org` files are org-mode, anything else Markdown
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("org") => Self::Org,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// Read the tasks of a Markdown or org-mode file
+pub fn read_task_file(path: &Path) -> Result<Vec<ImportedTask>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| McpTasksError::Import(format!("Failed to read {}", path.display())))?;
+    parse_task_file(&content, TaskFileFormat::from_path(path))
+        .with_context(|| format!("Failed to import {}", path.display()))
+}
+
+/// Parse checkbox items (`- [ ]`, `- [x]`) and, in org-mode, `TODO`/`DONE` headings.
+/// The enclosing headings become tags, and `due:<date>` and `prio:<level>` in an
+/// item set its due date and priority.
+pub fn parse_task_file(content: &str, format: TaskFileFormat) -> Result<Vec<ImportedTask>> {
+    // Headings enclosing the current line, with their level
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut tasks: Vec<ImportedTask> = Vec::new();
+    // Org headings only take a DEADLINE from the line right below them
+    let mut last_org_task: Option<usize> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_number = idx + 1;
+        let org_task = last_org_task.take();
+
+        if let Some((level, text)) = heading(line, format) {
+            headings.retain(|(outer, _)| *outer < level);
+
+            if format == TaskFileFormat::Org
+                && let Some((done, text)) = org_keyword(text)
+            {
+                let (text, priority, own_tags) = org_heading_parts(text);
+                let mut task = parse_item(text, done, &headings, line_number)?;
+                if task.task.priority.is_none() {
+                    task.task.priority = priority;
+                }
+                add_tags(&mut task.task, own_tags);
+                tasks.push(task);
+                last_org_task = Some(tasks.len() - 1);
+            } else {
+                let (text, _, _) = org_heading_parts(text);
+                headings.push((level, text.to_string()));
+            }
+            continue;
+        }
+
+        if let Some(task_idx) = org_task
+            && let Some(deadline) = org_deadline(line)
+        {
+            let task = &mut tasks[task_idx].task;
+            if task.due_date.is_none() {
+                task.due_date = Some(parse_due(deadline, line_number)?);
+            }
+            continue;
+        }
+
+        if let Some((done, text)) = checkbox(line) {
+            tasks.push(parse_item(text, done, &headings, line_number)?);
+        }
+    }
+
+    Ok(tasks)
+}
+
+fn heading(line: &str, format: TaskFileFormat) -> Option<(usize, &str)> {
+    let marker = match format {
+        TaskFileFormat::Markdown => '#',
+        TaskFileFormat::Org => '*',
+    };
+    let level = line.chars().take_while(|c| *c == marker).count();
+    let text = line[level..].strip_prefix(' ')?;
+    (level > 0).then(|| (level, text.trim()))
+}
+
+/// `- [ ] text`, `* [x] text` or `1. [ ] text`, at any indentation
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            (digits > 0)
+                .then(|| line[digits..].strip_prefix(". "))
+                .flatten()
+        })?
+        .trim_start();
+
+    let done = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((done, rest[3..].trim()))
+}
+
+fn org_keyword(text: &str) -> Option<(bool, &str)> {
+    if let Some(rest) = text.strip_prefix("TODO ") {
+        Some((false, rest))
+    } else {
+        text.strip_prefix("DONE ").map(|rest| (true, rest))
+    }
+}
+
+/// Split an org heading into its text, `[#A]`-style priority and trailing `:tag:` list
+fn org_heading_parts(text: &str) -> (&str, Option<String>, Vec<String>) {
+    let mut text = text.trim();
+
+    let priority = ["A", "B", "C"]
+        .iter()
+        .zip(["high", "medium", "low"])
+        .find_map(|(cookie, level)| {
+            let rest = text.strip_prefix(&format!("[#{}]", cookie))?;
+            text = rest.trim_start();
+            Some(level.to_string())
+        });
+
+    let mut tags = Vec::new();
+    if let Some((head, last)) = text.rsplit_once(char::is_whitespace)
+        && last.len() > 2
+        && last.starts_with(':')
+        && last.ends_with(':')
+    {
+        tags = last
+            .trim_matches(':')
+            .split(':')
+            .filter(|tag| !tag.is_empty())
+            .map(tag_name)
+            .collect();
+        text = head.trim_end();
+    }
+
+    (text, priority, tags)
+}
+
+/// Date of a `DEADLINE: <2025-01-10 Fri>` planning line
+fn org_deadline(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("DEADLINE:")?;
+    let rest = rest.trim_start().strip_prefix('<')?;
+    let (stamp, _) = rest.split_once('>')?;
+    stamp.split_whitespace().next()
+}
+
+/// Build a task from an item's text, taking `due:` and `prio:` annotations out of the title
+fn parse_item(
+    text: &str,
+    done: bool,
+    headings: &[(usize, String)],
+    line_number: usize,
+) -> Result<ImportedTask> {
+    let mut title = Vec::new();
+    let mut due_date = None;
+    let mut priority = None;
+
+    for word in text.split_whitespace() {
+        if let Some(due) = word.strip_prefix("due:") {
+            due_date = Some(parse_due(due, line_number)?);
+        } else if let Some(prio) = word.strip_prefix("prio:") {
+            let level = priority_level(Some(prio));
+            if level == "none" {
+                anyhow::bail!(McpTasksError::Import(format!(
+                    "Line {}: unknown priority '{}' (expected high, medium or low)",
+                    line_number, prio
+                )));
+            }
+            priority = Some(level.to_string());
+        } else {
+            title.push(word);
+        }
+    }
+
+    if title.is_empty() {
+        anyhow::bail!(McpTasksError::Import(format!(
+            "Line {}: task has no title",
+            line_number
+        )));
+    }
+
+    let mut task = NewTask {
+        title: title.join(" "),
+        description: None,
+        priority,
+        assignee: None,
+        due_date,
+        tags: None,
+    };
+    add_tags(
+        &mut task,
+        headings.iter().map(|(_, heading)| tag_name(heading)),
+    );
+
+    Ok(ImportedTask { task, done })
+}
+
+fn parse_due(value: &str, line_number: usize) -> Result<String> {
+    match TaskDate::parse(value) {
+        Some(_) => Ok(value.to_string()),
+        None => anyhow::bail!(McpTasksError::Import(format!(
+            "Line {}: invalid due date '{}' (expected YYYY-MM-DD or RFC3339)",
+            line_number, value
+        ))),
+    }
+}
+
+fn add_tags(task: &mut NewTask, tags: impl IntoIterator<Item = String>) {
+    for tag in tags {
+        if tag.is_empty() {
+            continue;
+        }
+        let existing = task.tags.get_or_insert_with(Vec::new);
+        if !existing.contains(&tag) {
+            existing.push(tag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(tasks: &[ImportedTask]) -> Vec<&str> {
+        tasks.iter().map(|task| task.task.title.as_str()).collect()
+    }
+
+    fn tags(task: &ImportedTask) -> Vec<&str> {
+        task.task
+            .tags
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn markdown_checkboxes_become_tasks() {
+        let content = "\
+- [ ] Write docs
+- [x] Ship release
+  * [X] Nested item
+1. [ ] Numbered item
+- plain bullet
+";
+        let tasks = parse_task_file(content, TaskFileFormat::Markdown).unwrap();
+        assert_eq!(
+            titles(&tasks),
+            ["Write docs", "Ship release", "Nested item", "Numbered item"]
+        );
+        let done = tasks.iter().map(|task| task.done).collect::<Vec<_>>();
+        assert_eq!(done, [false, true, true, false]);
+    }
+
+    #[test]
+    fn nested_headings_become_tags() {
+        let content = "\
+# Website
+- [ ] Fix login bug
+## Blog Posts
+- [ ] Write launch post
+# Ops
+- [ ] Rotate keys
+";
+        let tasks = parse_task_file(content, TaskFileFormat::Markdown).unwrap();
+        assert_eq!(tags(&tasks[0]), ["website"]);
+        assert_eq!(tags(&tasks[1]), ["website", "blog-posts"]);
+        // A heading of the same level closes the previous section
+        assert_eq!(tags(&tasks[2]), ["ops"]);
+    }
+
+    #[test]
+    fn item_annotations_set_due_date_and_priority() {
+        let tasks = parse_task_file(
+            "- [ ] Fix login bug due:2025-07-01 prio:high",
+            TaskFileFormat::Markdown,
+        )
+        .unwrap();
+        assert_eq!(tasks[0].task.title, "Fix login bug");
+        assert_eq!(tasks[0].task.due_date.as_deref(), Some("2025-07-01"));
+        assert_eq!(tasks[0].task.priority.as_deref(), Some("high"));
+    }
+
+    #[test]
+    fn org_todo_and_done_headings_become_tasks() {
+        let content = "\
+* Project
+** TODO [#A] Plan launch :work:q3:
+DEADLINE: <2025-01-10 Fri>
+** DONE Book venue
+** Notes
+- [ ] Call caterer
+";
+        let tasks = parse_task_file(content, TaskFileFormat::Org).unwrap();
+        assert_eq!(
+            titles(&tasks),
+            ["Plan launch", "Book venue", "Call caterer"]
+        );
+
+        assert!(!tasks[0].done);
+        assert_eq!(tasks[0].task.priority.as_deref(), Some("high"));
+        assert_eq!(tasks[0].task.due_date.as_deref(), Some("2025-01-10"));
+        assert_eq!(tags(&tasks[0]), ["project", "work", "q3"]);
+
+        assert!(tasks[1].done);
+        assert_eq!(tasks[1].task.due_date, None);
+        assert_eq!(tags(&tasks[2]), ["project", "notes"]);
+    }
+
+    #[test]
+    fn malformed_checkboxes_are_not_tasks() {
+        let content = "\
+- [] Missing space
+-[ ] Missing space after dash
+- [y] Unknown mark
+#Not a heading
+- [ ] Real task
+";
+        let tasks = parse_task_file(content, TaskFileFormat::Markdown).unwrap();
+        assert_eq!(titles(&tasks), ["Real task"]);
+        assert_eq!(tags(&tasks[0]), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn invalid_items_fail_with_their_line_number() {
+        for (content, message) in [
+            (
+                "- [ ] ok\n- [ ] due:2025-07-01",
+                "Line 2: task has no title",
+            ),
+            (
+                "- [ ] Fix bug due:someday",
+                "Line 1: invalid due date 'someday'",
+            ),
+            ("- [ ] Fix bug prio:asap", "Line 1: unknown priority 'asap'"),
+        ] {
+            let error = parse_task_file(content, TaskFileFormat::Markdown).unwrap_err();
+            assert!(
+                error.to_string().contains(message),
+                "{}: {}",
+                content,
+                error
+            );
+        }
+    }
+}
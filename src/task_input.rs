@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::error::McpTasksError;
+use crate::fields;
+use crate::mcp_client::Task;
+
+/// Where `analyze` reads its tasks from instead of the MCP server
+#[derive(Debug, Clone)]
+pub enum TaskInput {
+    Stdin,
+    File(PathBuf),
+}
+
+impl TaskInput {
+    /// `--from-stdin` or `--from-file`, if either was given
+    pub fn from_args(from_stdin: bool, from_file: Option<PathBuf>) -> Option<Self> {
+        match from_file {
+            Some(path) => Some(Self::File(path)),
+            None if from_stdin => Some(Self::Stdin),
+            None => None,
+        }
+    }
+
+    pub fn read(&self) -> Result<Vec<Task>> {
+        let mut content = String::new();
+        match self {
+            Self::Stdin => std::io::stdin().read_to_string(&mut content).map(|_| ()),
+            Self::File(path) => std::fs::read_to_string(path).map(|file| content = file),
+        }
+        .with_context(|| McpTasksError::Import(format!("Failed to read {}", self)))?;
+
+        parse_tasks(&content).with_context(|| format!("Failed to read tasks from {}", self))
+    }
+}
+
+impl std::fmt::Display for TaskInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stdin => write!(f, "stdin"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Parse a JSON task array (or `{"tasks": [...]}` as `list_tasks` returns it)
+/// or a plain text list with one task per line
+pub fn parse_tasks(content: &str) -> Result<Vec<Task>> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        parse_json_tasks(trimmed)
+    } else {
+        Ok(parse_text_tasks(content))
+    }
+}
+
+/// Tasks may use any field names the server mapping knows and may be bare
+/// titles; tasks without an ID are numbered and tasks without a status are
+/// pending
+fn parse_json_tasks(content: &str) -> Result<Vec<Task>> {
+    let value: Value = serde_json::from_str(content)
+        .context(McpTasksError::Import("Input is not valid JSON".to_string()))?;
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(mut object) => match object.remove("tasks") {
+            Some(Value::Array(items)) => items,
+            _ => anyhow::bail!(McpTasksError::Import(
+                "Expected a JSON array of tasks or an object with a \"tasks\" array".to_string()
+            )),
+        },
+        _ => anyhow::bail!(McpTasksError::Import(
+            "Expected a JSON array of tasks".to_string()
+        )),
+    };
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let mut task = match item {
+                Value::String(title) => {
+                    let mut task = Map::new();
+                    task.insert("title".to_string(), Value::String(title));
+                    task
+                }
+                Value::Object(task) => task,
+                _ => anyhow::bail!(McpTasksError::Import(format!(
+                    "Task {} is neither an object nor a title",
+                    idx + 1
+                ))),
+            };
+            fields::normalize_task(&mut task);
+            task.entry("id")
+                .or_insert_with(|| Value::String((idx + 1).to_string()));
+            task.entry("status")
+                .or_insert_with(|| Value::String("pending".to_string()));
+
+            serde_json::from_value(Value::Object(task)).with_context(|| {
+                McpTasksError::Import(format!("Task {} is not a valid task", idx + 1))
+            })
+        })
+        .collect()
+}
+
+/// One task per non-empty line; list bullets, numbers and checkboxes are
+/// dropped, and checked items count as completed. Markdown headings are
+/// skipped.
+fn parse_text_tasks(content: &str) -> Vec<Task> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .enumerate()
+        .map(|(idx, line)| {
+            let line = strip_bullet(line);
+            let (title, done) = match line.get(..3) {
+                Some("[ ]") => (&line[3..], false),
+                Some("[x]" | "[X]") => (&line[3..], true),
+                _ => (line, false),
+            };
+
+            Task {
+                id: (idx + 1).to_string(),
+                title: title.trim().to_string(),
+                description: None,
+                status: if done { "completed" } else { "pending" }.to_string(),
+                priority: None,
+                assignee: None,
+                due_date: None,
+                created_at: String::new(),
+                updated_at: None,
+                completed_at: None,
+                tags: None,
+                server: None,
+                extra: Map::new(),
+            }
+        })
+        .filter(|task| !task.title.is_empty())
+        .collect()
+}
+
+/// `line` without a leading `-`, `*`, `+`, `1.` or `1)`
+fn strip_bullet(line: &str) -> &str {
+    if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        return rest.trim_start();
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0
+        && let Some(rest) = line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+    {
+        return rest.trim_start();
+    }
+
+    line
+}
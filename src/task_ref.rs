@@ -0,0 +1,77 @@
+use std::fmt;
+
+/// A task on a specific MCP server. IDs are only unique per server, so
+/// commands and tool calls that work across servers address tasks as
+/// `alias:id`; a bare ID means the default (or `--server` selected) server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskRef {
+    /// Alias of the server, `None` for the default one
+    pub server: Option<String>,
+    pub id: String,
+}
+
+impl TaskRef {
+    /// A task on the default server
+    pub fn local(id: impl Into<String>) -> Self {
+        Self {
+            server: None,
+            id: id.into(),
+        }
+    }
+
+    /// Parse `alias:id` or a bare ID. The prefix only counts as a server when
+    /// it is one of `aliases`, so IDs that contain a colon themselves still
+    /// work on the default server.
+    pub fn parse(value: &str, aliases: &[String]) -> Self {
+        match value.split_once(':') {
+            Some((alias, id)) if !id.is_empty() && aliases.iter().any(|a| a == alias) => Self {
+                server: Some(alias.to_string()),
+                id: id.to_string(),
+            },
+            _ => Self::local(value),
+        }
+    }
+}
+
+impl fmt::Display for TaskRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.server {
+            Some(server) => write!(f, "{}:{}", server, self.id),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases() -> Vec<String> {
+        vec!["work".to_string(), "home".to_string()]
+    }
+
+    #[test]
+    fn parse_splits_known_alias() {
+        let task = TaskRef::parse("work:42", &aliases());
+        assert_eq!(task.server.as_deref(), Some("work"));
+        assert_eq!(task.id, "42");
+        assert_eq!(task.to_string(), "work:42");
+    }
+
+    #[test]
+    fn parse_keeps_bare_and_unknown_prefixed_ids_local() {
+        assert_eq!(TaskRef::parse("42", &aliases()), TaskRef::local("42"));
+        assert_eq!(
+            TaskRef::parse("urn:task:7", &aliases()),
+            TaskRef::local("urn:task:7")
+        );
+        assert_eq!(TaskRef::parse("work:", &aliases()), TaskRef::local("work:"));
+    }
+
+    #[test]
+    fn parse_keeps_colons_in_the_id() {
+        let task = TaskRef::parse("home:a:b", &aliases());
+        assert_eq!(task.server.as_deref(), Some("home"));
+        assert_eq!(task.id, "a:b");
+    }
+}
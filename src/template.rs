@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::dates;
+use crate::error::McpTasksError;
+use crate::import::ImportedTask;
+use crate::mcp_client::NewTask;
+
+const TEMPLATE_FILE: &str = "templates.toml";
+
+/// One task a template creates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateTask {
+    /// Title, with placeholders such as `{month_name}` filled in when applied
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Due date relative to the day the template is applied, e.g. `+3d`, `+2w` or `+1m`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+}
+
+/// A named set of tasks for recurring work, from `[templates.<name>]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+}
+
+impl TaskTemplate {
+    /// The template's tasks as they would be created on `day`
+    pub fn instantiate(&self, day: NaiveDate) -> Result<Vec<ImportedTask>> {
+        self.tasks
+            .iter()
+            .map(|task| {
+                let due_date = task
+                    .due
+                    .as_deref()
+                    .map(|due| {
+                        relative_due(due, day)
+                            .map(|date| dates::end_of_day(date).to_rfc3339())
+                            .map_err(McpTasksError::Config)
+                    })
+                    .transpose()?;
+                Ok(ImportedTask::pending(NewTask {
+                    title: fill(&task.title, day),
+                    description: task.description.as_deref().map(|text| fill(text, day)),
+                    priority: task.priority.clone(),
+                    assignee: None,
+                    due_date,
+                    tags: (!task.tags.is_empty()).then(|| task.tags.clone()),
+                }))
+            })
+            .collect()
+    }
+}
+
+/// Check a relative due date such as `+3d`, `2w` or `+1m`
+pub fn parse_due(input: &str) -> Result<String, String> {
+    relative_due(input, NaiveDate::MIN).map(|_| input.trim().to_string())
+}
+
+/// `input` counted from `day`: days (`d`), weeks (`w`) or calendar months (`m`)
+pub fn relative_due(input: &str, day: NaiveDate) -> Result<NaiveDate, String> {
+    let trimmed = input.trim();
+    let amount = trimmed.strip_prefix('+').unwrap_or(trimmed);
+    let invalid = || {
+        format!(
+            "invalid relative due date '{}': expected e.g. +3d, +2w or +1m",
+            trimmed
+        )
+    };
+    let (count, unit) = amount.split_at(amount.len().saturating_sub(1));
+    let count = count.parse::<u32>().map_err(|_| invalid())?;
+    let date = match unit {
+        "d" => day.checked_add_days(Days::new(u64::from(count))),
+        "w" => day.checked_add_days(Days::new(7 * u64::from(count))),
+        "m" => day.checked_add_months(Months::new(count)),
+        _ => return Err(invalid()),
+    };
+    // Validation runs against the earliest date, where nothing overflows
+    Ok(date.unwrap_or(day))
+}
+
+/// Replace the placeholders in `pattern` with values for `day`
+pub fn fill(pattern: &str, day: NaiveDate) -> String {
+    pattern
+        .replace("{date}", &day.to_string())
+        .replace("{year}", &day.year().to_string())
+        .replace("{month}", &day.format("%Y-%m").to_string())
+        .replace("{month_name}", &day.format("%B").to_string())
+        .replace("{week}", &format!("{:02}", day.iso_week().week()))
+}
+
+/// Layout of the local template file, the same as the config file's sections
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFile {
+    #[serde(default)]
+    templates: BTreeMap<String, TaskTemplate>,
+}
+
+/// Templates kept in `templates.toml` in the data directory, written by `template add`
+pub struct TemplateStore {
+    path: PathBuf,
+}
+
+impl TemplateStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(TEMPLATE_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<BTreeMap<String, TaskTemplate>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let content = std::fs::read_to_string(&self.path).with_context(|| {
+            McpTasksError::Config(format!(
+                "Failed to read template file {}",
+                self.path.display()
+            ))
+        })?;
+        let file: TemplateFile = toml::from_str(&content).with_context(|| {
+            McpTasksError::Config(format!(
+                "Failed to parse template file {}",
+                self.path.display()
+            ))
+        })?;
+        Ok(file.templates)
+    }
+
+    /// Append `task` to the template `name`, creating the template if needed
+    pub fn add_task(&self, name: &str, task: TemplateTask) -> Result<()> {
+        let mut templates = self.load()?;
+        templates
+            .entry(name.to_string())
+            .or_default()
+            .tasks
+            .push(task);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Config(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+        let content = toml::to_string_pretty(&TemplateFile { templates })
+            .context("Failed to serialize templates")?;
+        std::fs::write(&self.path, content).with_context(|| {
+            McpTasksError::Config(format!(
+                "Failed to write template file {}",
+                self.path.display()
+            ))
+        })?;
+
+        info!(
+            "Added a task to template {} in {}",
+            name,
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Where a template is defined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Config,
+    File,
+}
+
+impl TemplateSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::File => "file",
+        }
+    }
+}
+
+/// A template with its name and where it comes from
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedTemplate {
+    pub name: String,
+    pub source: TemplateSource,
+    #[serde(flatten)]
+    pub template: TaskTemplate,
+}
+
+/// Templates from the config file and the local template file, by name; the
+/// local file wins when both define the same name
+pub fn all_templates(
+    configured: &BTreeMap<String, TaskTemplate>,
+    store: &TemplateStore,
+) -> Result<Vec<NamedTemplate>> {
+    let mut templates = configured
+        .iter()
+        .map(|(name, template)| {
+            (
+                name.clone(),
+                NamedTemplate {
+                    name: name.clone(),
+                    source: TemplateSource::Config,
+                    template: template.clone(),
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (name, template) in store.load()? {
+        templates.insert(
+            name.clone(),
+            NamedTemplate {
+                name,
+                source: TemplateSource::File,
+                template,
+            },
+        );
+    }
+    Ok(templates.into_values().collect())
+}
@@ -0,0 +1,253 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::embeddings::cosine_similarity;
+use crate::error::McpTasksError;
+use crate::mcp_client::Task;
+use crate::structured;
+
+/// Marker the themes prompt contains; the mock provider recognizes it
+pub const THEMES_PROMPT_MARKER: &str = "\"themes\"";
+
+pub const THEMES_SYSTEM_PROMPT: &str = "You are a product manager naming the themes of a backlog. You answer with a single JSON object that follows the requested shape exactly.";
+
+/// Most themes picked when `--count` is not given
+const MAX_DEFAULT_THEMES: usize = 8;
+
+const MAX_ITERATIONS: usize = 50;
+
+/// Words too common to say anything about a theme
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "that", "this", "add", "fix", "update", "make",
+    "use", "new", "all", "our", "are", "not", "when", "of", "to", "in", "on", "a", "an", "is",
+];
+
+/// Tasks that belong together, with their share of the backlog
+#[derive(Debug, Serialize)]
+pub struct Theme {
+    pub label: String,
+    pub summary: String,
+    /// Percentage of the analyzed tasks in this theme
+    pub share: f64,
+    pub tasks: Vec<Task>,
+}
+
+/// The backlog split into themes, largest first
+#[derive(Debug, Serialize)]
+pub struct ThemeReport {
+    pub task_count: usize,
+    pub themes: Vec<Theme>,
+}
+
+/// Name and one-sentence description of a theme
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThemeLabel {
+    pub group: usize,
+    pub label: String,
+    #[serde(default)]
+    pub summary: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThemeLabelList {
+    themes: Vec<ThemeLabel>,
+}
+
+/// About √(n/2) themes, between 1 and 8
+pub fn default_theme_count(task_count: usize) -> usize {
+    ((task_count as f64 / 2.0).sqrt().round() as usize)
+        .clamp(1, MAX_DEFAULT_THEMES)
+        .min(task_count.max(1))
+}
+
+/// Group `embeddings` into `count` clusters with k-means on cosine similarity,
+/// returning the cluster of each vector. Centers start from the first vector
+/// and then the vector least similar to all centers so far, so the result is
+/// the same on every run.
+pub fn cluster(embeddings: &[Vec<f32>], count: usize) -> Vec<usize> {
+    let count = count.min(embeddings.len());
+    if count <= 1 {
+        return vec![0; embeddings.len()];
+    }
+
+    let mut centers = vec![embeddings[0].clone()];
+    while centers.len() < count {
+        let farthest = embeddings
+            .iter()
+            .map(|embedding| {
+                centers
+                    .iter()
+                    .map(|center| cosine_similarity(embedding, center))
+                    .fold(f32::MIN, f32::max)
+            })
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx)
+            .unwrap_or(0);
+        centers.push(embeddings[farthest].clone());
+    }
+
+    let mut assignments = vec![usize::MAX; embeddings.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let next = embeddings
+            .iter()
+            .map(|embedding| nearest(embedding, &centers))
+            .collect::<Vec<_>>();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+
+        for (cluster, center) in centers.iter_mut().enumerate() {
+            let members = embeddings
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, assigned)| **assigned == cluster)
+                .map(|(embedding, _)| embedding)
+                .collect::<Vec<_>>();
+            // An emptied cluster keeps its old center
+            if members.is_empty() {
+                continue;
+            }
+            for (dimension, value) in center.iter_mut().enumerate() {
+                *value = members.iter().map(|member| member[dimension]).sum::<f32>()
+                    / members.len() as f32;
+            }
+        }
+    }
+
+    assignments
+}
+
+fn nearest(embedding: &[f32], centers: &[Vec<f32>]) -> usize {
+    centers
+        .iter()
+        .map(|center| cosine_similarity(embedding, center))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
+/// Tasks grouped by cluster, largest group first; empty clusters are dropped
+pub fn group_tasks(tasks: Vec<Task>, assignments: &[usize]) -> Vec<Vec<Task>> {
+    let mut groups: BTreeMap<usize, Vec<Task>> = BTreeMap::new();
+    for (task, cluster) in tasks.into_iter().zip(assignments) {
+        groups.entry(*cluster).or_default().push(task);
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+    groups
+}
+
+/// Prompt asking for a label per group of tasks
+pub fn themes_prompt(groups: &[Vec<Task>]) -> String {
+    let mut listing = String::new();
+    for (idx, group) in groups.iter().enumerate() {
+        listing.push_str(&format!("Group {} ({} tasks):\n", idx + 1, group.len()));
+        for task in group {
+            listing.push_str(&format!("- {}\n", task.title));
+        }
+        listing.push('\n');
+    }
+
+    format!(
+        r#"These tasks were grouped by similarity into {count} groups. Name the theme each group has in common.
+
+{listing}Answer with a single JSON object and nothing else, in this shape:
+{{
+  "themes": [
+    {{
+      "group": group number,
+      "label": "short theme name, 2-4 words (e.g. infra debt, onboarding)",
+      "summary": "one sentence on what the work in this group is about"
+    }}
+  ]
+}}
+
+Give exactly one entry per group."#,
+        count = groups.len()
+    )
+}
+
+/// Parse the model's answer into one label per group; groups the model
+/// skipped get a label from their most frequent words
+pub fn parse_labels(response: &str, groups: &[Vec<Task>]) -> Result<Vec<ThemeLabel>> {
+    let json = structured::json_object(response).ok_or_else(|| {
+        McpTasksError::DeepSeekApi("AI response did not contain JSON themes".to_string())
+    })?;
+
+    let list: ThemeLabelList = serde_json::from_str(json).context(McpTasksError::DeepSeekApi(
+        "Failed to parse the AI theme labels".to_string(),
+    ))?;
+
+    Ok(groups
+        .iter()
+        .enumerate()
+        .map(|(idx, group)| {
+            list.themes
+                .iter()
+                .find(|label| label.group == idx + 1 && !label.label.trim().is_empty())
+                .cloned()
+                .unwrap_or_else(|| keyword_label(idx + 1, group))
+        })
+        .collect())
+}
+
+/// Label from the words the group's titles share most, for runs without AI
+pub fn keyword_label(group: usize, tasks: &[Task]) -> ThemeLabel {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for task in tasks {
+        let mut seen = Vec::new();
+        for word in task
+            .title
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|word| word.chars().count() > 2 && !STOP_WORDS.contains(&word.as_str()))
+        {
+            if !seen.contains(&word) {
+                *counts.entry(word.clone()).or_default() += 1;
+                seen.push(word);
+            }
+        }
+    }
+
+    let mut words = counts.into_iter().collect::<Vec<_>>();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let label = words
+        .iter()
+        .take(2)
+        .map(|(word, _)| word.as_str())
+        .collect::<Vec<_>>()
+        .join(" / ");
+
+    ThemeLabel {
+        group,
+        label: if label.is_empty() {
+            format!("Theme {}", group)
+        } else {
+            label
+        },
+        summary: String::new(),
+    }
+}
+
+impl ThemeReport {
+    pub fn new(groups: Vec<Vec<Task>>, labels: Vec<ThemeLabel>) -> Self {
+        let task_count = groups.iter().map(Vec::len).sum::<usize>();
+        let themes = groups
+            .into_iter()
+            .zip(labels)
+            .map(|(tasks, label)| Theme {
+                label: label.label.trim().to_string(),
+                summary: label.summary.trim().to_string(),
+                share: tasks.len() as f64 * 100.0 / task_count.max(1) as f64,
+                tasks,
+            })
+            .collect();
+
+        Self { task_count, themes }
+    }
+}
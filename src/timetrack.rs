@@ -0,0 +1,294 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::dates;
+use crate::error::McpTasksError;
+
+const TIMER_FILE: &str = "timer.json";
+const ENTRY_FILE: &str = "time_entries.jsonl";
+
+/// Appended to the task summary of analyses when time was tracked
+pub const ANALYSIS_NOTE: &str = "\"Time Tracked\" is the effort actually logged on a task so far. Compare it with the task's estimate and account for overruns and the real pace of work in your recommendations.\n";
+
+/// The task being worked on right now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningTimer {
+    pub task_id: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+}
+
+impl RunningTimer {
+    /// The finished entry when the timer is stopped at `end`
+    pub fn finish(self, end: DateTime<Utc>) -> TimeEntry {
+        TimeEntry {
+            task_id: self.task_id,
+            title: self.title,
+            start: self.start,
+            end: end.max(self.start),
+        }
+    }
+}
+
+/// A stretch of time spent on one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub task_id: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeEntry {
+    pub fn hours(&self) -> f64 {
+        (self.end - self.start).num_seconds() as f64 / 3600.0
+    }
+}
+
+/// `1h 25m`, `40m` or `15s`
+pub fn format_elapsed(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    let seconds = (end - start).num_seconds().max(0);
+    match (seconds / 3600, seconds % 3600 / 60) {
+        (0, 0) => format!("{}s", seconds),
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// Time entries in `time_entries.jsonl` and the running timer in `timer.json`,
+/// both in the data directory
+pub struct TimeLog {
+    timer_path: PathBuf,
+    entry_path: PathBuf,
+}
+
+impl TimeLog {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            timer_path: data_dir.join(TIMER_FILE),
+            entry_path: data_dir.join(ENTRY_FILE),
+        }
+    }
+
+    pub fn running(&self) -> Result<Option<RunningTimer>> {
+        if !self.timer_path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&self.timer_path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to read timer {}",
+                self.timer_path.display()
+            ))
+        })?;
+        let timer = serde_json::from_str(&content).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to parse timer {}; delete it to reset the timer",
+                self.timer_path.display()
+            ))
+        })?;
+        Ok(Some(timer))
+    }
+
+    /// Start `timer`, stopping and returning the entry of the one running before
+    pub fn start(&self, timer: &RunningTimer) -> Result<Option<TimeEntry>> {
+        let previous = self.stop(timer.start)?;
+
+        self.ensure_dir()?;
+        let content = serde_json::to_string_pretty(timer).context("Failed to serialize timer")?;
+        std::fs::write(&self.timer_path, content).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write timer {}",
+                self.timer_path.display()
+            ))
+        })?;
+
+        info!("Started timer for task {}", timer.task_id);
+        Ok(previous)
+    }
+
+    /// Stop the running timer at `end` and record its entry; `None` when no timer runs
+    pub fn stop(&self, end: DateTime<Utc>) -> Result<Option<TimeEntry>> {
+        let Some(timer) = self.running()? else {
+            return Ok(None);
+        };
+
+        let entry = timer.finish(end);
+        self.append(&entry)?;
+        std::fs::remove_file(&self.timer_path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to remove timer {}",
+                self.timer_path.display()
+            ))
+        })?;
+
+        info!("Stopped timer for task {}", entry.task_id);
+        Ok(Some(entry))
+    }
+
+    fn append(&self, entry: &TimeEntry) -> Result<()> {
+        self.ensure_dir()?;
+        let line = serde_json::to_string(entry).context("Failed to serialize time entry")?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.entry_path)
+            .with_context(|| {
+                McpTasksError::Report(format!(
+                    "Failed to open time entries {}",
+                    self.entry_path.display()
+                ))
+            })?;
+        writeln!(file, "{}", line).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write time entries {}",
+                self.entry_path.display()
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn ensure_dir(&self) -> Result<()> {
+        if let Some(parent) = self.entry_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Recorded entries, oldest first; unreadable lines are skipped
+    pub fn entries(&self) -> Result<Vec<TimeEntry>> {
+        if !self.entry_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&self.entry_path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to read time entries {}",
+                self.entry_path.display()
+            ))
+        })?;
+
+        let mut entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(
+                |(line_no, line)| match serde_json::from_str::<TimeEntry>(line) {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable time entry at {}:{}: {}",
+                            self.entry_path.display(),
+                            line_no + 1,
+                            e
+                        );
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.start);
+        Ok(entries)
+    }
+
+    /// Recorded entries plus the running timer up to `now`
+    pub fn entries_until(&self, now: DateTime<Utc>) -> Result<Vec<TimeEntry>> {
+        let mut entries = self.entries()?;
+        if let Some(timer) = self.running()? {
+            entries.push(timer.finish(now));
+        }
+        Ok(entries)
+    }
+
+    /// Hours tracked per task ID, including the running timer
+    pub fn hours_by_task(&self) -> Result<BTreeMap<String, f64>> {
+        let mut hours = BTreeMap::new();
+        for entry in self.entries_until(Utc::now())? {
+            *hours.entry(entry.task_id.clone()).or_insert(0.0) += entry.hours();
+        }
+        Ok(hours)
+    }
+}
+
+/// Time tracked on one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTime {
+    pub task_id: String,
+    pub title: String,
+    pub entries: usize,
+    pub hours: f64,
+}
+
+/// Time tracked on one day
+#[derive(Debug, Clone, Serialize)]
+pub struct DayTime {
+    pub date: NaiveDate,
+    pub hours: f64,
+}
+
+/// Per-task and per-day totals of the entries since a day
+#[derive(Debug, Clone, Serialize)]
+pub struct Timesheet {
+    pub since: NaiveDate,
+    /// Most time first
+    pub tasks: Vec<TaskTime>,
+    /// Oldest day first
+    pub days: Vec<DayTime>,
+    pub total_hours: f64,
+    pub entries: Vec<TimeEntry>,
+}
+
+impl Timesheet {
+    /// Totals of `entries` started on or after `since` (local time), optionally
+    /// of one task; entries count towards the day they started on
+    pub fn build(entries: Vec<TimeEntry>, since: NaiveDate, task_id: Option<&str>) -> Self {
+        let entries = entries
+            .into_iter()
+            .filter(|entry| dates::local_date(entry.start) >= since)
+            .filter(|entry| task_id.is_none_or(|id| entry.task_id == id))
+            .collect::<Vec<_>>();
+
+        let mut tasks: Vec<TaskTime> = Vec::new();
+        let mut days = BTreeMap::new();
+        for entry in &entries {
+            match tasks.iter_mut().find(|task| task.task_id == entry.task_id) {
+                Some(task) => {
+                    task.entries += 1;
+                    task.hours += entry.hours();
+                    // The latest title wins when a task was renamed
+                    task.title = entry.title.clone();
+                }
+                None => tasks.push(TaskTime {
+                    task_id: entry.task_id.clone(),
+                    title: entry.title.clone(),
+                    entries: 1,
+                    hours: entry.hours(),
+                }),
+            }
+            *days.entry(dates::local_date(entry.start)).or_insert(0.0) += entry.hours();
+        }
+        tasks.sort_by(|a, b| b.hours.total_cmp(&a.hours));
+
+        Self {
+            since,
+            total_hours: entries
+                .iter()
+                .fold(0.0, |hours, entry| hours + entry.hours()),
+            tasks,
+            days: days
+                .into_iter()
+                .map(|(date, hours)| DayTime { date, hours })
+                .collect(),
+            entries,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::error::McpTasksError;
+use crate::import::{ImportedTask, tag_name};
+use crate::mcp_client::NewTask;
+
+pub const TODOIST_API_URL: &str = "https://api.todoist.com/rest/v2";
+
+/// Environment variable read when no `--token` is given
+pub const TODOIST_TOKEN_ENV: &str = "TODOIST_API_TOKEN";
+
+/// An active task as returned by Todoist's REST API
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistTask {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// 1 (normal) to 4 (urgent)
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub due: Option<TodoistDue>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistDue {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    /// Set when the task is due at a specific time
+    #[serde(default)]
+    pub datetime: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistProject {
+    pub id: String,
+    pub name: String,
+}
+
+/// Read-only client for the Todoist REST API
+pub struct TodoistClient {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl TodoistClient {
+    pub fn new(base_url: &str, token: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    pub async fn tasks(&self) -> Result<Vec<TodoistTask>> {
+        self.get("tasks").await
+    }
+
+    pub async fn projects(&self) -> Result<Vec<TodoistProject>> {
+        self.get("projects").await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/{}", self.base_url, path);
+        debug!("Fetching {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .with_context(|| {
+                McpTasksError::Import(format!("Failed to reach Todoist at {}", url))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(McpTasksError::Import(format!(
+                "Todoist API error {}: {}",
+                status, text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| McpTasksError::Import(format!("Failed to parse Todoist {}", path)))
+    }
+}
+
+/// Convert Todoist tasks into tasks for the MCP server. The project becomes a tag
+/// (lowercase, spaces as dashes), labels are kept as tags, and Todoist's
+/// priorities 4/3/2 map to high/medium/low.
+pub fn to_imported_tasks(tasks: &[TodoistTask], projects: &[TodoistProject]) -> Vec<ImportedTask> {
+    let project_names = projects
+        .iter()
+        .map(|project| (project.id.as_str(), project.name.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    tasks
+        .iter()
+        .map(|task| {
+            let mut tags = task
+                .project_id
+                .as_deref()
+                .and_then(|id| project_names.get(id))
+                .map(|name| vec![tag_name(name)])
+                .unwrap_or_default();
+            for label in &task.labels {
+                let label = tag_name(label);
+                if !tags.contains(&label) {
+                    tags.push(label);
+                }
+            }
+
+            ImportedTask::pending(NewTask {
+                title: task.content.clone(),
+                description: (!task.description.is_empty()).then(|| task.description.clone()),
+                priority: match task.priority {
+                    4 => Some("high".to_string()),
+                    3 => Some("medium".to_string()),
+                    2 => Some("low".to_string()),
+                    _ => None,
+                },
+                assignee: None,
+                due_date: task
+                    .due
+                    .as_ref()
+                    .map(|due| due.datetime.clone().unwrap_or_else(|| due.date.clone())),
+                tags: (!tags.is_empty()).then_some(tags),
+            })
+        })
+        .collect()
+}
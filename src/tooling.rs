@@ -1,11 +1,19 @@
 use anyhow::{Context, Result};
+use futures::future::BoxFuture;
 use reqwest::Client;
+use rmcp::model::{JsonObject, Tool, ToolAnnotations};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::collections::HashMap;
-use tracing::{debug, info};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
-use crate::mcp_client::McpClient;
+use crate::error::McpTasksError;
+use crate::llm::{LlmProvider, TokenSink};
+use crate::mcp_client::{McpClient, McpClientPool};
+use crate::output::{self, ResourceInfo};
+use crate::progress;
+use crate::usage::TokenUsage;
 
 /// DeepSeek API tool definitions
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,25 +41,144 @@ pub struct ChatRequest {
     pub tool_choice: Option<String>,
     pub temperature: f32,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// Rough characters per token for estimating a request before it is sent
+const CHARS_PER_TOKEN: usize = 4;
+
+impl ChatRequest {
+    /// Prompt tokens estimated from the size of the messages and tool definitions
+    pub fn estimated_prompt_tokens(&self) -> u64 {
+        let chars = serde_json::to_string(&self.messages)
+            .map(|json| json.len())
+            .unwrap_or_default()
+            + serde_json::to_string(&self.tools)
+                .map(|json| json.len())
+                .unwrap_or_default();
+        (chars / CHARS_PER_TOKEN) as u64
+    }
+}
+
+/// Output format the model is held to; `json_object` is DeepSeek's JSON mode
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseFormat {
+    #[serde(rename = "type")]
+    pub format_type: String,
+}
+
+impl ResponseFormat {
+    pub fn json_object() -> Self {
+        Self {
+            format_type: "json_object".to_string(),
+        }
+    }
+}
+
+/// Streaming options; `include_usage` asks for a final chunk with token usage
+#[derive(Debug, Serialize)]
+pub struct StreamOptions {
+    pub include_usage: bool,
 }
 
 /// DeepSeek Chat Response structure
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Token usage reported by the DeepSeek API for one request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Prompt tokens served from DeepSeek's context cache
+    #[serde(default)]
+    pub prompt_cache_hit_tokens: u64,
+    /// Cached prompt tokens as reported by OpenAI-compatible servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: u64,
+}
+
+impl From<&Usage> for TokenUsage {
+    fn from(usage: &Usage) -> Self {
+        let cached_tokens = usage
+            .prompt_tokens_details
+            .as_ref()
+            .map_or(0, |details| details.cached_tokens);
+
+        Self {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            cached_prompt_tokens: usage.prompt_cache_hit_tokens.max(cached_tokens),
+            requests: 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Choice {
     pub message: ResponseMessage,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMessage {
     pub content: Option<String>,
+    /// Chain of thought returned by `deepseek-reasoner`
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
     pub tool_calls: Option<Vec<ToolCall>>,
 }
 
+/// DeepSeek streaming chunk structure (one SSE `data:` payload)
+#[derive(Debug, Deserialize)]
+pub struct ChatStreamChunk {
+    #[serde(default)]
+    pub choices: Vec<StreamChoice>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamChoice {
+    pub delta: StreamDelta,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamDelta {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// Partial tool call as streamed by the API; fragments share the same `index`
+#[derive(Debug, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: usize,
+    pub id: Option<String>,
+    pub function: Option<ToolCallFunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToolCallFunctionDelta {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
     pub id: String,
@@ -74,50 +201,73 @@ pub struct Message {
     pub tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCall>>,
+    /// `deepseek-reasoner` expects its reasoning back on assistant messages that
+    /// requested tool calls, until the turn is answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
 }
 
-/// DeepSeek API client for tool-enabled interactions
-pub struct DeepSeekApiClient {
+/// A text fragment of a streamed response
+pub enum StreamToken<'a> {
+    Content(&'a str),
+    /// Part of the chain of thought of `deepseek-reasoner`
+    Reasoning(&'a str),
+}
+
+/// Client for OpenAI-compatible chat completion APIs (DeepSeek, OpenAI, vLLM, ...)
+pub struct OpenAiCompatibleClient {
     client: Client,
-    api_key: String,
-    base_url: String,
+    /// Provider name used in log and error messages
+    name: &'static str,
+    api_key: Option<String>,
+    url: String,
 }
 
-impl DeepSeekApiClient {
-    pub fn new(api_key: String) -> Self {
+impl OpenAiCompatibleClient {
+    /// `base_url` is the API root; requests go to `<base_url>/chat/completions`
+    pub fn new(name: &'static str, base_url: &str, api_key: Option<String>) -> Self {
         Self {
             client: Client::new(),
+            name,
             api_key,
-            base_url: "https://api.deepseek.com/chat/completions".to_string(),
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+        }
+    }
+
+    fn post(&self) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        match &self.api_key {
+            Some(api_key) => request.header("Authorization", format!("Bearer {}", api_key)),
+            None => request,
         }
     }
 
     pub async fn chat_with_tools(&self, request: ChatRequest) -> Result<ChatResponse> {
         debug!(
-            "Sending chat request to DeepSeek API with {} tools",
+            "Sending chat request to {} API with {} tools",
+            self.name,
             request.tools.as_ref().map_or(0, |t| t.len())
         );
 
-        let response = self
-            .client
-            .post(&self.base_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to DeepSeek API")?;
+        let response = self.post().json(&request).send().await.with_context(|| {
+            McpTasksError::DeepSeekApi(format!("Failed to send request to {} API", self.name))
+        })?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("DeepSeek API error {}: {}", status, text);
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "{} API error {}: {}",
+                self.name, status, text
+            )));
         }
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse DeepSeek API response")?;
+        let chat_response: ChatResponse = response.json().await.with_context(|| {
+            McpTasksError::DeepSeekApi(format!("Failed to parse {} API response", self.name))
+        })?;
 
         debug!(
             "Received response with {} choices",
@@ -125,17 +275,222 @@ impl DeepSeekApiClient {
         );
         Ok(chat_response)
     }
+
+    /// Send a chat request with SSE streaming enabled, invoking `on_token` for every
+    /// content or reasoning fragment as it arrives. The streamed deltas are assembled into a regular
+    /// `ChatResponse` so callers can handle tool calls the same way as the buffered API.
+    pub async fn chat_with_tools_streaming<F>(
+        &self,
+        mut request: ChatRequest,
+        mut on_token: F,
+    ) -> Result<ChatResponse>
+    where
+        F: FnMut(StreamToken<'_>),
+    {
+        debug!(
+            "Sending streaming chat request to {} API with {} tools",
+            self.name,
+            request.tools.as_ref().map_or(0, |t| t.len())
+        );
+
+        request.stream = Some(true);
+        request.stream_options = Some(StreamOptions {
+            include_usage: true,
+        });
+
+        let mut response = self
+            .post()
+            .header("Accept", "text/event-stream")
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| {
+                McpTasksError::DeepSeekApi(format!("Failed to send request to {} API", self.name))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!(McpTasksError::DeepSeekApi(format!(
+                "{} API error {}: {}",
+                self.name, status, text
+            )));
+        }
+
+        let mut buffer = LineBuffer::default();
+        let mut content = String::new();
+        let mut reasoning_content = String::new();
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+        let mut usage = None;
+        let mut done = false;
+
+        while !done {
+            let Some(chunk) = response.chunk().await.with_context(|| {
+                McpTasksError::DeepSeekApi(format!("Failed to read {} API stream", self.name))
+            })?
+            else {
+                break;
+            };
+            buffer.push(&chunk);
+
+            // SSE events are newline-delimited; a trailing partial line stays in the buffer
+            while let Some(line) = buffer.next_line().with_context(|| {
+                McpTasksError::DeepSeekApi(format!("{} API stream is not valid UTF-8", self.name))
+            })? {
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    done = true;
+                    break;
+                }
+
+                let stream_chunk: ChatStreamChunk =
+                    serde_json::from_str(data).with_context(|| {
+                        McpTasksError::DeepSeekApi(format!(
+                            "Failed to parse {} API stream chunk",
+                            self.name
+                        ))
+                    })?;
+
+                if stream_chunk.usage.is_some() {
+                    usage = stream_chunk.usage;
+                }
+
+                for choice in stream_chunk.choices {
+                    if let Some(token) = choice.delta.content
+                        && !token.is_empty()
+                    {
+                        on_token(StreamToken::Content(&token));
+                        content.push_str(&token);
+                    }
+                    if let Some(reasoning) = choice.delta.reasoning_content
+                        && !reasoning.is_empty()
+                    {
+                        on_token(StreamToken::Reasoning(&reasoning));
+                        reasoning_content.push_str(&reasoning);
+                    }
+
+                    for delta in choice.delta.tool_calls.unwrap_or_default() {
+                        merge_tool_call_delta(&mut tool_calls, delta);
+                    }
+                }
+            }
+        }
+
+        debug!(
+            "Stream finished with {} content chars and {} tool calls",
+            content.len(),
+            tool_calls.len()
+        );
+
+        Ok(ChatResponse {
+            choices: vec![Choice {
+                message: ResponseMessage {
+                    content: if content.is_empty() {
+                        None
+                    } else {
+                        Some(content)
+                    },
+                    reasoning_content: if reasoning_content.is_empty() {
+                        None
+                    } else {
+                        Some(reasoning_content)
+                    },
+                    tool_calls: if tool_calls.is_empty() {
+                        None
+                    } else {
+                        Some(tool_calls)
+                    },
+                },
+            }],
+            usage,
+        })
+    }
+}
+
+impl LlmProvider for OpenAiCompatibleClient {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(self.chat_with_tools(request))
+    }
+
+    fn chat_streaming<'a>(
+        &'a self,
+        request: ChatRequest,
+        on_token: TokenSink<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse>> {
+        Box::pin(self.chat_with_tools_streaming(request, on_token))
+    }
+}
+
+/// Merge a streamed tool call fragment into the accumulated tool calls
+fn merge_tool_call_delta(tool_calls: &mut Vec<ToolCall>, delta: ToolCallDelta) {
+    while tool_calls.len() <= delta.index {
+        tool_calls.push(ToolCall {
+            id: String::new(),
+            call_type: Some("function".to_string()),
+            function: ToolCallFunction {
+                name: String::new(),
+                arguments: String::new(),
+            },
+        });
+    }
+
+    let tool_call = &mut tool_calls[delta.index];
+    if let Some(id) = delta.id {
+        tool_call.id = id;
+    }
+    if let Some(function) = delta.function {
+        if let Some(name) = function.name {
+            tool_call.function.name.push_str(&name);
+        }
+        if let Some(arguments) = function.arguments {
+            tool_call.function.arguments.push_str(&arguments);
+        }
+    }
+}
+
+/// Bytes of a streamed response split into lines. A network chunk can end in the
+/// middle of a multi-byte character, so only complete lines are decoded.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+    }
+
+    /// The next complete line, trimmed, or `None` until its newline has arrived
+    pub fn next_line(&mut self) -> Result<Option<String>> {
+        let Some(newline_pos) = self.pending.iter().position(|&byte| byte == b'\n') else {
+            return Ok(None);
+        };
+        let line = self.pending.drain(..=newline_pos).collect::<Vec<_>>();
+        let line = std::str::from_utf8(&line[..newline_pos])?;
+        Ok(Some(line.trim().to_string()))
+    }
 }
 
 /// Creates a DeepSeek-compatible tool definition for invoking MCP tools
-pub fn mcp_invoke_tool() -> ToolObject {
+pub fn mcp_invoke_tool(server_aliases: &[String]) -> ToolObject {
     let parameters = json!({
         "type": "object",
         "required": ["server", "tool", "arguments"],
         "properties": {
             "server": {
                 "type": "string",
-                "description": "MCP server alias (e.g., 'todo', 'weather', etc.)"
+                "enum": server_aliases,
+                "description": format!(
+                    "MCP server alias. Available servers: {}",
+                    server_aliases.join(", ")
+                )
             },
             "tool": {
                 "type": "string",
@@ -159,10 +514,17 @@ pub fn mcp_invoke_tool() -> ToolObject {
 }
 
 /// Creates DeepSeek-compatible tool definitions for specific MCP tools
-pub async fn create_mcp_tool_definitions(mcp_client: &McpClient) -> Result<Vec<ToolObject>> {
+///
+/// The tools come from the default server's catalog: its task tools under their
+/// own name (see [`create_task_tools`]) and every other tool with an `mcp_`
+/// prefix, so each is offered once. Other servers are reachable through the
+/// generic `mcp_invoke` tool.
+pub async fn create_mcp_tool_definitions(pool: &McpClientPool) -> Result<Vec<ToolObject>> {
     info!("Creating DeepSeek tool definitions from MCP server tools");
 
-    let mcp_tools = mcp_client
+    let mcp_tools = pool
+        .default_client()
+        .await?
         .get_tools_list()
         .await
         .context("Failed to get MCP tools list")?;
@@ -170,10 +532,20 @@ pub async fn create_mcp_tool_definitions(mcp_client: &McpClient) -> Result<Vec<T
     let mut deepseek_tools = Vec::new();
 
     // Add the generic mcp_invoke tool
-    deepseek_tools.push(mcp_invoke_tool());
+    deepseek_tools.push(mcp_invoke_tool(&pool.aliases()));
+
+    // Expose resources (project documents, notes, ...) when the server has any
+    if pool.default_client().await?.supports_resources() {
+        deepseek_tools.extend(create_resource_tools(&pool.aliases()));
+    }
 
-    // Create specific tool definitions for each MCP tool
-    for mcp_tool in mcp_tools {
+    deepseek_tools.extend(create_task_tools(&mcp_tools));
+
+    // Create specific tool definitions for the remaining MCP tools
+    for mcp_tool in mcp_tools
+        .iter()
+        .filter(|tool| !TASK_TOOLS.contains(&tool.name.as_ref()))
+    {
         let tool_name = format!("mcp_{}", mcp_tool.name);
         let description = mcp_tool
             .description
@@ -216,9 +588,84 @@ pub async fn create_mcp_tool_definitions(mcp_client: &McpClient) -> Result<Vec<T
     Ok(deepseek_tools)
 }
 
+/// Creates DeepSeek-compatible tool definitions for listing and reading MCP resources
+pub fn create_resource_tools(server_aliases: &[String]) -> Vec<ToolObject> {
+    let server_property = json!({
+        "type": "string",
+        "enum": server_aliases,
+        "description": "MCP server alias (defaults to the current server)"
+    });
+
+    vec![
+        ToolObject {
+            tool_type: "function".to_string(),
+            function: Function {
+                name: "list_resources".to_string(),
+                description:
+                    "List resources (project documents, notes, ...) attached to the MCP server"
+                        .to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "server": server_property
+                    }
+                }),
+            },
+        },
+        ToolObject {
+            tool_type: "function".to_string(),
+            function: Function {
+                name: "read_resource".to_string(),
+                description: "Read the contents of an MCP resource by URI".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "required": ["uri"],
+                    "properties": {
+                        "uri": {
+                            "type": "string",
+                            "description": "URI of the resource, as returned by list_resources"
+                        },
+                        "server": server_property
+                    }
+                }),
+            },
+        },
+    ]
+}
+
+/// Executes the resource tools against the requested (or default) MCP server
+async fn execute_resource_tool(
+    pool: &McpClientPool,
+    tool_name: &str,
+    arguments: &Value,
+) -> Result<Value> {
+    let mcp_client = match arguments.get("server").and_then(|v| v.as_str()) {
+        Some(server) => pool.get(server).await?,
+        None => pool.default_client().await?,
+    };
+
+    match tool_name {
+        "list_resources" => {
+            let resources = mcp_client.list_resources().await?;
+            let resources = resources.iter().map(ResourceInfo::from).collect::<Vec<_>>();
+            Ok(json!({ "resources": resources, "count": resources.len() }))
+        }
+        "read_resource" => {
+            let uri = arguments
+                .get("uri")
+                .and_then(|v| v.as_str())
+                .context("Missing 'uri' argument for read_resource")?;
+
+            let contents = mcp_client.read_resource(uri).await?;
+            Ok(json!({ "uri": uri, "contents": contents }))
+        }
+        _ => anyhow::bail!("Unknown resource tool: {}", tool_name),
+    }
+}
+
 /// Handles tool call execution by routing to the appropriate MCP server
 pub async fn execute_mcp_tool_call(
-    mcp_client: &McpClient,
+    pool: &McpClientPool,
     tool_name: &str,
     arguments: &Value,
 ) -> Result<Value> {
@@ -228,15 +675,18 @@ pub async fn execute_mcp_tool_call(
     );
 
     match tool_name {
-        "mcp_invoke" => execute_generic_mcp_invoke(mcp_client, arguments).await,
+        "mcp_invoke" => execute_generic_mcp_invoke(pool, arguments).await,
+        "list_resources" | "read_resource" => {
+            execute_resource_tool(pool, tool_name, arguments).await
+        }
         // Handle specific task tools
-        "list_tasks" | "get_task" | "task_stats" => {
-            execute_task_tool(mcp_client, tool_name, arguments).await
+        tool_name if TASK_TOOLS.contains(&tool_name) => {
+            execute_task_tool(pool, tool_name, arguments).await
         }
         tool_name if tool_name.starts_with("mcp_") => {
             // Extract the actual MCP tool name by removing the "mcp_" prefix
             let mcp_tool_name = tool_name.strip_prefix("mcp_").unwrap();
-            execute_specific_mcp_tool(mcp_client, mcp_tool_name, arguments).await
+            execute_specific_mcp_tool(pool.default_client().await?, mcp_tool_name, arguments).await
         }
         _ => {
             anyhow::bail!("Unknown tool: {}", tool_name);
@@ -244,8 +694,8 @@ pub async fn execute_mcp_tool_call(
     }
 }
 
-/// Executes the generic mcp_invoke tool
-async fn execute_generic_mcp_invoke(mcp_client: &McpClient, arguments: &Value) -> Result<Value> {
+/// Executes the generic mcp_invoke tool on the server selected by alias
+async fn execute_generic_mcp_invoke(pool: &McpClientPool, arguments: &Value) -> Result<Value> {
     let server = arguments
         .get("server")
         .and_then(|v| v.as_str())
@@ -263,7 +713,7 @@ async fn execute_generic_mcp_invoke(mcp_client: &McpClient, arguments: &Value) -
         tool, server, tool_args
     );
 
-    // For now, we assume single MCP server. In the future, this could route to different servers
+    let mcp_client = pool.get(server).await?;
     execute_specific_mcp_tool(mcp_client, tool, &tool_args).await
 }
 
@@ -273,19 +723,31 @@ async fn execute_specific_mcp_tool(
     tool_name: &str,
     arguments: &Value,
 ) -> Result<Value> {
-    use rmcp::model::CallToolRequestParam;
-    use std::borrow::Cow;
-
     debug!(
         "Executing specific MCP tool: {} with arguments: {}",
         tool_name, arguments
     );
 
-    // Get the peer for making requests
-    let peer = {
-        let client = mcp_client.client.lock().await;
-        client.clone()
-    };
+    // Let the model correct its arguments instead of passing them on to fail on the server
+    if let Some(schema) = mcp_client.tool_schema(tool_name).await
+        && let Err(problems) = validate_arguments(&schema, arguments)
+    {
+        info!(
+            "Rejected arguments for MCP tool '{}': {}",
+            tool_name,
+            problems.join("; ")
+        );
+        return Ok(json!({
+            "tool_name": tool_name,
+            "success": false,
+            "error": format!(
+                "Invalid arguments for '{}'. Fix them according to input_schema and call it again.",
+                tool_name
+            ),
+            "validation_errors": problems,
+            "input_schema": schema,
+        }));
+    }
 
     // Convert arguments to the format expected by rmcp
     let args = if arguments.is_object() && !arguments.as_object().unwrap().is_empty() {
@@ -298,15 +760,7 @@ async fn execute_specific_mcp_tool(
         None
     };
 
-    let params = CallToolRequestParam {
-        name: Cow::Owned(tool_name.to_string()),
-        arguments: args,
-    };
-
-    let result = peer
-        .call_tool(params)
-        .await
-        .context(format!("Failed to call MCP tool '{}'", tool_name))?;
+    let result = mcp_client.call_tool(tool_name, args).await?;
 
     // Convert the result to a JSON value for DeepSeek
     let mut response = HashMap::new();
@@ -365,6 +819,11 @@ async fn execute_specific_mcp_tool(
     response.insert("tool_name".to_string(), json!(tool_name));
     response.insert("success".to_string(), json!(true));
 
+    // Tools with an output schema (MCP 2025 revisions) also return structured content
+    if let Some(structured) = result.structured_content {
+        response.insert("structured_content".to_string(), structured);
+    }
+
     if result.is_error.unwrap_or(false) {
         response.insert("success".to_string(), json!(false));
         response.insert(
@@ -382,77 +841,270 @@ async fn execute_specific_mcp_tool(
     Ok(response_json)
 }
 
-/// Creates task-specific tools for the available MCP server commands
-pub fn create_task_tools() -> Vec<ToolObject> {
-    vec![
-        // list_tasks tool
-        ToolObject {
-            tool_type: "function".to_string(),
-            function: Function {
-                name: "list_tasks".to_string(),
-                description:
-                    "List all tasks, optionally filtered by status, priority, assignee, or tag"
-                        .to_string(),
-                parameters: json!({
-                    "type": "object",
-                    "properties": {
-                        "assignee": {
-                            "type": "string",
-                            "description": "Filter tasks by assignee"
-                        },
-                        "priority": {
-                            "type": "string",
-                            "description": "Filter tasks by priority"
-                        },
-                        "status": {
-                            "type": "string",
-                            "description": "Filter tasks by status"
-                        },
-                        "tag": {
-                            "type": "string",
-                            "description": "Filter tasks by tag"
-                        }
-                    }
-                }),
-            },
-        },
-        // get_task tool
-        ToolObject {
-            tool_type: "function".to_string(),
-            function: Function {
-                name: "get_task".to_string(),
-                description: "Get detailed information about a specific task by ID".to_string(),
-                parameters: json!({
-                    "type": "object",
-                    "required": ["id"],
-                    "properties": {
-                        "id": {
-                            "type": "string",
-                            "description": "The ID of the task to retrieve"
-                        }
-                    }
-                }),
-            },
-        },
-        // task_stats tool
-        ToolObject {
-            tool_type: "function".to_string(),
-            function: Function {
-                name: "task_stats".to_string(),
-                description: "Get statistics about tasks (counts by status, priority, etc.)"
-                    .to_string(),
-                parameters: json!({
-                    "type": "object",
-                    "properties": {}
-                }),
-            },
-        },
-    ]
+/// Check tool arguments against the tool's JSON Schema. Returns one message per
+/// problem, each naming the offending argument. Schemas the validator cannot
+/// compile are not enforced.
+fn validate_arguments(schema: &JsonObject, arguments: &Value) -> Result<(), Vec<String>> {
+    let schema = Value::Object(schema.clone());
+    let validator = match jsonschema::validator_for(&schema) {
+        Ok(validator) => validator,
+        Err(e) => {
+            warn!("Skipping argument validation, unusable tool schema: {}", e);
+            return Ok(());
+        }
+    };
+
+    let problems = validator
+        .iter_errors(arguments)
+        .map(|error| match error.instance_path.to_string() {
+            path if path.is_empty() => error.to_string(),
+            path => format!("{}: {}", path.trim_start_matches('/'), error),
+        })
+        .collect::<Vec<_>>();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Task tools of the server offered to the model under their own name rather
+/// than as `mcp_<name>`
+pub const TASK_TOOLS: &[&str] = &[
+    "list_tasks",
+    "get_task",
+    "task_stats",
+    "create_task",
+    "update_task",
+    "complete_task",
+];
+
+/// Description for a task tool the server did not describe
+fn task_tool_description(name: &str) -> &'static str {
+    match name {
+        "list_tasks" => "List all tasks, optionally filtered by status, priority, assignee, or tag",
+        "get_task" => "Get detailed information about a specific task by ID",
+        "task_stats" => "Get statistics about tasks (counts by status, priority, etc.)",
+        "create_task" => "Create a new task",
+        "update_task" => "Update fields of an existing task",
+        "complete_task" => "Mark a task as completed",
+        _ => "Task tool from the MCP server",
+    }
+}
+
+/// Creates the task tools the server's `catalog` has, with the server's input
+/// schema; tools the server lacks are left out. Tools that change tasks are
+/// gated through `MutationPolicy` when called.
+pub fn create_task_tools(catalog: &[Tool]) -> Vec<ToolObject> {
+    TASK_TOOLS
+        .iter()
+        .filter_map(|name| {
+            let Some(tool) = catalog.iter().find(|tool| tool.name == *name) else {
+                debug!("MCP server has no '{}' tool, not offering it", name);
+                return None;
+            };
+
+            let mut parameters = tool.schema_as_json_value();
+            if !parameters.is_object() {
+                parameters = json!({ "type": "object", "properties": {} });
+            }
+
+            Some(ToolObject {
+                tool_type: "function".to_string(),
+                function: Function {
+                    name: name.to_string(),
+                    description: tool
+                        .description
+                        .as_ref()
+                        .map(|description| description.to_string())
+                        .unwrap_or_else(|| task_tool_description(name).to_string()),
+                    parameters,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Tool name prefixes that indicate a tool changes data on the server
+const MUTATING_TOOL_PREFIXES: &[&str] = &[
+    "create_",
+    "update_",
+    "complete_",
+    "delete_",
+    "remove_",
+    "add_",
+    "set_",
+];
+
+/// How tool calls that change data on the MCP server are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MutationPolicy {
+    /// Run them like any other tool call
+    Allow,
+    /// Ask on the terminal before each one
+    Confirm,
+    /// Refuse them; the model is told the change was not made
+    Deny,
+}
+
+/// Which tool calls the AI may run. Names in `allow`/`deny` are MCP tool names
+/// (`create_task`, not `mcp_create_task`); a trailing `*` matches a prefix.
+#[derive(Debug, Clone)]
+pub struct ToolPolicy {
+    pub mutations: MutationPolicy,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+/// Outcome of checking a tool call against a `ToolPolicy`; carries the MCP tool name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolDecision {
+    Run,
+    Confirm(String),
+    Refuse(String),
+}
+
+/// Name of the MCP tool behind an AI tool call (`create_task` for `mcp_create_task`,
+/// the `tool` argument for `mcp_invoke`)
+pub fn mcp_tool_name<'a>(tool_name: &'a str, arguments: &'a Value) -> &'a str {
+    match tool_name {
+        "mcp_invoke" => arguments
+            .get("tool")
+            .and_then(|v| v.as_str())
+            .unwrap_or(tool_name),
+        tool_name => tool_name.strip_prefix("mcp_").unwrap_or(tool_name),
+    }
+}
+
+/// Annotations the MCP server declared for the tool behind an AI tool call, if any
+pub async fn tool_annotations(
+    pool: &McpClientPool,
+    tool_name: &str,
+    arguments: &Value,
+) -> Option<ToolAnnotations> {
+    let mcp_client = match (tool_name, arguments.get("server").and_then(|v| v.as_str())) {
+        ("mcp_invoke", Some(server)) => pool.get(server).await.ok()?,
+        _ => pool.default_client().await.ok()?,
+    };
+    mcp_client
+        .tool(mcp_tool_name(tool_name, arguments))
+        .await?
+        .annotations
+}
+
+impl ToolPolicy {
+    /// The deny list wins over the allow list; read-only tools run unless denied,
+    /// data-changing tools not on the allow list follow `mutations`. A tool's
+    /// `readOnlyHint` annotation decides whether it changes data; tools without one
+    /// are judged by their name.
+    pub fn decide(
+        &self,
+        tool_name: &str,
+        arguments: &Value,
+        annotations: Option<&ToolAnnotations>,
+    ) -> ToolDecision {
+        let mcp_tool_name = mcp_tool_name(tool_name, arguments).to_string();
+
+        if matches_any(&self.deny, &mcp_tool_name) {
+            return ToolDecision::Refuse(mcp_tool_name);
+        }
+
+        let mutating = match annotations.and_then(|annotations| annotations.read_only_hint) {
+            Some(read_only) => !read_only,
+            None => MUTATING_TOOL_PREFIXES
+                .iter()
+                .any(|prefix| mcp_tool_name.starts_with(prefix)),
+        };
+        if !mutating || matches_any(&self.allow, &mcp_tool_name) {
+            return ToolDecision::Run;
+        }
+
+        match self.mutations {
+            MutationPolicy::Allow => ToolDecision::Run,
+            MutationPolicy::Confirm => ToolDecision::Confirm(mcp_tool_name),
+            MutationPolicy::Deny => ToolDecision::Refuse(mcp_tool_name),
+        }
+    }
+}
+
+/// How long AI-triggered MCP tool calls may take. Names in `per_tool` are MCP
+/// tool names; a trailing `*` matches a prefix, and the longest match wins.
+#[derive(Debug, Clone, Default)]
+pub struct ToolTimeouts {
+    /// Seconds for tools without their own timeout; 0 waits as long as it takes
+    pub default: u64,
+    pub per_tool: BTreeMap<String, u64>,
+}
+
+impl ToolTimeouts {
+    /// Time the MCP tool behind an AI tool call gets before it is cancelled;
+    /// `None` waits as long as it takes
+    pub fn for_tool(&self, tool_name: &str, arguments: &Value) -> Option<Duration> {
+        let mcp_tool_name = mcp_tool_name(tool_name, arguments);
+        let seconds = match self.per_tool.get(mcp_tool_name) {
+            Some(seconds) => *seconds,
+            None => self
+                .per_tool
+                .iter()
+                .filter_map(|(pattern, seconds)| {
+                    let prefix = pattern.strip_suffix('*')?;
+                    mcp_tool_name
+                        .starts_with(prefix)
+                        .then_some((prefix.len(), *seconds))
+                })
+                .max_by_key(|(len, _)| *len)
+                .map_or(self.default, |(_, seconds)| seconds),
+        };
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+}
+
+/// Result handed to the model for a tool call that was cancelled after `timeout`
+pub fn tool_timeout_result(tool_name: &str, arguments: &Value, timeout: Duration) -> Value {
+    json!({
+        "tool_name": mcp_tool_name(tool_name, arguments),
+        "success": false,
+        "timed_out": true,
+        "error": format!(
+            "The tool did not finish within {}s and was cancelled. Go on without its result, or try a narrower call once.",
+            timeout.as_secs()
+        ),
+    })
 }
 
-/// Executes task-specific tool calls using the actual MCP server commands
+fn matches_any(patterns: &[String], tool_name: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => tool_name.starts_with(prefix),
+            None => pattern == tool_name,
+        })
+}
+
+/// Ask a yes/no question on the terminal; anything but `y`/`yes` (or end of input) is a no.
+/// The question goes to stderr so piped output stays clean.
+pub fn confirm(question: &str) -> Result<bool> {
+    progress::suspend(|| {
+        eprint!("{} [y/N] ", output::decorate(question));
+        std::io::Write::flush(&mut std::io::stderr())?;
+
+        let mut answer = String::new();
+        std::io::stdin()
+            .read_line(&mut answer)
+            .context("Failed to read confirmation")?;
+
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    })
+}
+
+/// Executes task-specific tool calls using the actual MCP server commands.
+/// Arguments follow the server's schema and pass straight through, except that
+/// an `alias:id` task ID runs the call on that server with the bare ID; a tool
+/// the server lacks is reported to the model instead of failing the loop.
 pub async fn execute_task_tool(
-    mcp_client: &McpClient,
+    pool: &McpClientPool,
     tool_name: &str,
     arguments: &Value,
 ) -> Result<Value> {
@@ -461,47 +1113,215 @@ pub async fn execute_task_tool(
         tool_name, arguments
     );
 
-    match tool_name {
-        "list_tasks" => {
-            // Extract filter parameters and build arguments for the MCP tool
-            let mut mcp_args = serde_json::Map::new();
-
-            if let Some(assignee) = arguments.get("assignee").and_then(|v| v.as_str()) {
-                mcp_args.insert("assignee".to_string(), json!(assignee));
-            }
-            if let Some(priority) = arguments.get("priority").and_then(|v| v.as_str()) {
-                mcp_args.insert("priority".to_string(), json!(priority));
-            }
-            if let Some(status) = arguments.get("status").and_then(|v| v.as_str()) {
-                mcp_args.insert("status".to_string(), json!(status));
-            }
-            if let Some(tag) = arguments.get("tag").and_then(|v| v.as_str()) {
-                mcp_args.insert("tag".to_string(), json!(tag));
-            }
+    if !TASK_TOOLS.contains(&tool_name) {
+        anyhow::bail!("Unknown task tool: {}", tool_name);
+    }
 
-            execute_specific_mcp_tool(mcp_client, "list_tasks", &json!(mcp_args)).await
+    let mut arguments = arguments.clone();
+    let task = arguments
+        .get("id")
+        .and_then(Value::as_str)
+        .map(|id| pool.task_ref(id));
+    let mcp_client = match task {
+        Some(task) if task.server.is_some() => {
+            let mcp_client = pool.client_for(&task).await?;
+            arguments["id"] = Value::String(task.id);
+            mcp_client
         }
+        _ => pool.default_client().await?,
+    };
 
-        "get_task" => {
-            let id = arguments
-                .get("id")
-                .and_then(|v| v.as_str())
-                .context("Missing 'id' argument for get_task")?;
+    if mcp_client.tool(tool_name).await.is_none() {
+        info!(
+            "MCP server '{}' has no '{}' tool",
+            mcp_client.alias, tool_name
+        );
+        return Ok(json!({
+            "tool_name": tool_name,
+            "success": false,
+            "error": format!(
+                "The MCP server has no '{}' tool. Use the other available tools instead.",
+                tool_name
+            ),
+        }));
+    }
 
-            let mcp_args = json!({
-                "id": id
-            });
+    execute_specific_mcp_tool(mcp_client, tool_name, &arguments).await
+}
 
-            execute_specific_mcp_tool(mcp_client, "get_task", &mcp_args).await
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mutations: MutationPolicy, allow: &[&str], deny: &[&str]) -> ToolPolicy {
+        ToolPolicy {
+            mutations,
+            allow: allow.iter().map(|name| name.to_string()).collect(),
+            deny: deny.iter().map(|name| name.to_string()).collect(),
         }
+    }
 
-        "task_stats" => {
-            // task_stats takes no parameters
-            execute_specific_mcp_tool(mcp_client, "task_stats", &json!({})).await
+    #[test]
+    fn tool_policy_runs_read_only_tools_and_applies_mutation_policy() {
+        let no_args = json!({});
+        let confirm = policy(MutationPolicy::Confirm, &[], &[]);
+        assert_eq!(
+            confirm.decide("mcp_list_tasks", &no_args, None),
+            ToolDecision::Run
+        );
+        assert_eq!(
+            confirm.decide("mcp_create_task", &no_args, None),
+            ToolDecision::Confirm("create_task".to_string())
+        );
+        assert_eq!(
+            policy(MutationPolicy::Deny, &[], &[]).decide("mcp_delete_task", &no_args, None),
+            ToolDecision::Refuse("delete_task".to_string())
+        );
+        assert_eq!(
+            policy(MutationPolicy::Allow, &[], &[]).decide("mcp_update_task", &no_args, None),
+            ToolDecision::Run
+        );
+    }
+
+    #[test]
+    fn tool_policy_deny_wins_over_allow_and_patterns_match_prefixes() {
+        let no_args = json!({});
+        let policy = policy(
+            MutationPolicy::Deny,
+            &["update_*", "delete_task"],
+            &["delete_*"],
+        );
+        assert_eq!(
+            policy.decide("mcp_update_task", &no_args, None),
+            ToolDecision::Run
+        );
+        assert_eq!(
+            policy.decide("mcp_delete_task", &no_args, None),
+            ToolDecision::Refuse("delete_task".to_string())
+        );
+        assert_eq!(
+            policy.decide("mcp_invoke", &json!({"tool": "delete_all"}), None),
+            ToolDecision::Refuse("delete_all".to_string())
+        );
+    }
+
+    #[test]
+    fn tool_policy_prefers_read_only_annotation_over_name() {
+        let no_args = json!({});
+        let policy = policy(MutationPolicy::Deny, &[], &[]);
+        let read_only = ToolAnnotations::new().read_only(true);
+        let writes = ToolAnnotations::new().read_only(false);
+        assert_eq!(
+            policy.decide("mcp_create_report", &no_args, Some(&read_only)),
+            ToolDecision::Run
+        );
+        assert_eq!(
+            policy.decide("mcp_archive_tasks", &no_args, Some(&writes)),
+            ToolDecision::Refuse("archive_tasks".to_string())
+        );
+    }
+
+    #[test]
+    fn tool_timeouts_prefer_exact_names_then_longest_prefix() {
+        let timeouts = ToolTimeouts {
+            default: 30,
+            per_tool: BTreeMap::from([
+                ("list_tasks".to_string(), 5),
+                ("list_*".to_string(), 10),
+                ("l*".to_string(), 20),
+                ("export_*".to_string(), 0),
+            ]),
+        };
+        let no_args = json!({});
+
+        assert_eq!(
+            timeouts.for_tool("mcp_list_tasks", &no_args),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            timeouts.for_tool("mcp_list_projects", &no_args),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            timeouts.for_tool("mcp_invoke", &json!({"tool": "lookup"})),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            timeouts.for_tool("mcp_get_task", &no_args),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(timeouts.for_tool("mcp_export_all", &no_args), None);
+        assert_eq!(
+            ToolTimeouts::default().for_tool("mcp_get_task", &no_args),
+            None
+        );
+    }
+
+    #[test]
+    fn line_buffer_keeps_characters_split_across_chunks() {
+        let line = "data: {\"content\":\"Привет 你好 🎉\"}\n".as_bytes();
+        let mut buffer = LineBuffer::default();
+
+        // Cut inside 'П', '你' and the emoji
+        let cuts = [19, 32, 40];
+        let mut start = 0;
+        for cut in cuts {
+            buffer.push(&line[start..cut]);
+            assert_eq!(buffer.next_line().unwrap(), None);
+            start = cut;
         }
+        buffer.push(&line[start..]);
 
-        _ => {
-            anyhow::bail!("Unknown task tool: {}", tool_name);
+        assert_eq!(
+            buffer.next_line().unwrap().as_deref(),
+            Some("data: {\"content\":\"Привет 你好 🎉\"}")
+        );
+        assert_eq!(buffer.next_line().unwrap(), None);
+    }
+
+    #[test]
+    fn line_buffer_rejects_invalid_utf8() {
+        let mut buffer = LineBuffer::default();
+        buffer.push(b"data: \xff\n");
+        assert!(buffer.next_line().is_err());
+    }
+
+    /// Deltas as the API streams them, parsed from JSON
+    fn delta(value: Value) -> ToolCallDelta {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn merge_tool_call_delta_joins_fragments_by_index() {
+        let mut tool_calls = Vec::new();
+        for fragment in [
+            json!({"index": 0, "id": "call_1", "function": {"name": "mcp_get_", "arguments": ""}}),
+            json!({"index": 0, "function": {"name": "task", "arguments": "{\"id\":"}}),
+            json!({"index": 1, "id": "call_2", "function": {"name": "mcp_task_stats"}}),
+            json!({"index": 0, "function": {"arguments": "\"task-1\"}"}}),
+        ] {
+            merge_tool_call_delta(&mut tool_calls, delta(fragment));
         }
+
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].id, "call_1");
+        assert_eq!(tool_calls[0].function.name, "mcp_get_task");
+        assert_eq!(tool_calls[0].function.arguments, "{\"id\":\"task-1\"}");
+        assert_eq!(tool_calls[1].id, "call_2");
+        assert_eq!(tool_calls[1].function.name, "mcp_task_stats");
+        assert_eq!(tool_calls[1].function.arguments, "");
+    }
+
+    #[test]
+    fn merge_tool_call_delta_fills_skipped_indexes() {
+        let mut tool_calls = Vec::new();
+        merge_tool_call_delta(
+            &mut tool_calls,
+            delta(json!({"index": 2, "id": "call_3", "function": {"name": "mcp_list_tasks"}})),
+        );
+
+        assert_eq!(tool_calls.len(), 3);
+        assert!(tool_calls[0].id.is_empty());
+        assert_eq!(tool_calls[2].function.name, "mcp_list_tasks");
     }
 }
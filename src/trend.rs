@@ -0,0 +1,244 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::dates;
+use crate::error::McpTasksError;
+use crate::output::{PriorityCounts, StatusCounts, TaskStats};
+
+const SNAPSHOT_FILE: &str = "stats.jsonl";
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Task counts at one point in time, recorded by every `stats` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub total: usize,
+    pub unfinished: usize,
+    pub completed: usize,
+    pub overdue: usize,
+    pub status: StatusCounts,
+    pub priority: PriorityCounts,
+}
+
+impl StatsSnapshot {
+    pub fn new(stats: &TaskStats, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            total: stats.total,
+            unfinished: stats.unfinished,
+            completed: stats.completed,
+            overdue: stats.overdue,
+            status: stats.status.clone(),
+            priority: stats.priority.clone(),
+        }
+    }
+
+    /// Completed share of all tasks in percent
+    pub fn completion_rate(&self) -> f64 {
+        if self.total > 0 {
+            (self.completed as f64 / self.total as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Append-only JSON-lines store of stats snapshots
+pub struct SnapshotStore {
+    path: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(SNAPSHOT_FILE),
+        }
+    }
+
+    pub fn append(&self, snapshot: &StatsSnapshot) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                McpTasksError::Report(format!("Failed to create directory {}", parent.display()))
+            })?;
+        }
+
+        let line = serde_json::to_string(snapshot).context("Failed to serialize stats snapshot")?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| {
+                McpTasksError::Report(format!(
+                    "Failed to open stats history {}",
+                    self.path.display()
+                ))
+            })?;
+        writeln!(file, "{}", line).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to write stats history {}",
+                self.path.display()
+            ))
+        })?;
+
+        info!("Stored stats snapshot in {}", self.path.display());
+        Ok(())
+    }
+
+    /// Snapshots taken at or after `since`, oldest first; unreadable lines are skipped
+    pub fn since(&self, since: DateTime<Utc>) -> Result<Vec<StatsSnapshot>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&self.path).with_context(|| {
+            McpTasksError::Report(format!(
+                "Failed to read stats history {}",
+                self.path.display()
+            ))
+        })?;
+
+        let mut snapshots = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(
+                |(line_no, line)| match serde_json::from_str::<StatsSnapshot>(line) {
+                    Ok(snapshot) => Some(snapshot),
+                    Err(e) => {
+                        warn!(
+                            "Skipping unreadable stats snapshot on line {}: {}",
+                            line_no + 1,
+                            e
+                        );
+                        None
+                    }
+                },
+            )
+            .filter(|snapshot| snapshot.timestamp >= since)
+            .collect::<Vec<_>>();
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+
+        Ok(snapshots)
+    }
+}
+
+/// Counts at the end of one day
+#[derive(Debug, Serialize)]
+pub struct TrendPoint {
+    pub date: NaiveDate,
+    pub total: usize,
+    pub unfinished: usize,
+    pub completed: usize,
+    pub completion_rate: f64,
+    pub overdue: usize,
+}
+
+/// Daily completion rate and backlog over a period
+#[derive(Debug, Serialize)]
+pub struct Trend {
+    pub period_days: u64,
+    pub snapshots: usize,
+    pub points: Vec<TrendPoint>,
+}
+
+impl Trend {
+    /// One point per day in the display time zone, from the last snapshot of that day
+    pub fn build(snapshots: &[StatsSnapshot], period: Duration) -> Self {
+        let mut points: Vec<TrendPoint> = Vec::new();
+        for snapshot in snapshots {
+            let point = TrendPoint {
+                date: dates::local_date(snapshot.timestamp),
+                total: snapshot.total,
+                unfinished: snapshot.unfinished,
+                completed: snapshot.completed,
+                completion_rate: snapshot.completion_rate(),
+                overdue: snapshot.overdue,
+            };
+            match points.last_mut() {
+                Some(last) if last.date == point.date => *last = point,
+                _ => points.push(point),
+            }
+        }
+
+        Self {
+            period_days: period.as_secs().div_ceil(86_400),
+            snapshots: snapshots.len(),
+            points,
+        }
+    }
+
+    pub fn completion_rates(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .map(|point| point.completion_rate)
+            .collect()
+    }
+
+    pub fn backlog(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .map(|point| point.unfinished as f64)
+            .collect()
+    }
+
+    pub fn overdue(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .map(|point| point.overdue as f64)
+            .collect()
+    }
+
+    /// Render as one row per day with fields separated by `separator`
+    pub fn to_delimited(&self, separator: char) -> String {
+        let mut output = [
+            "date",
+            "total",
+            "unfinished",
+            "completed",
+            "completion_rate",
+            "overdue",
+        ]
+        .join(&separator.to_string());
+        output.push('\n');
+        for point in &self.points {
+            let fields = [
+                point.date.to_string(),
+                point.total.to_string(),
+                point.unfinished.to_string(),
+                point.completed.to_string(),
+                format!("{:.1}", point.completion_rate),
+                point.overdue.to_string(),
+            ];
+            output.push_str(&fields.join(&separator.to_string()));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// One block character per value, scaled between the series' minimum and maximum
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = SPARK_LEVELS.len() - 1;
+
+    values
+        .iter()
+        .map(|value| {
+            let level = if max > min {
+                (((value - min) / (max - min)) * top as f64).round() as usize
+            } else {
+                top / 2
+            };
+            SPARK_LEVELS[level.min(top)]
+        })
+        .collect()
+}
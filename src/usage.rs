@@ -0,0 +1,145 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::history::HistoryEntry;
+
+/// Token counts reported by the DeepSeek API, summed over the requests of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Prompt tokens served from DeepSeek's context cache, billed at a lower rate
+    #[serde(default)]
+    pub cached_prompt_tokens: u64,
+    /// Number of API requests the counts cover
+    #[serde(default)]
+    pub requests: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cached_prompt_tokens += other.cached_prompt_tokens;
+        self.requests += other.requests;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// Share of prompt tokens served from the context cache, in percent
+    pub fn cache_hit_rate(&self) -> f64 {
+        if self.prompt_tokens == 0 {
+            return 0.0;
+        }
+        self.cached_prompt_tokens.min(self.prompt_tokens) as f64 * 100.0 / self.prompt_tokens as f64
+    }
+
+    /// One-line summary such as `1200 prompt (800 cached, 67%) + 350 completion tokens ≈ $0.0004`
+    pub fn describe(&self, cost_usd: f64) -> String {
+        let cached = if self.cached_prompt_tokens > 0 {
+            format!(
+                " ({} cached, {:.0}%)",
+                self.cached_prompt_tokens,
+                self.cache_hit_rate()
+            )
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{} prompt{} + {} completion tokens ≈ ${:.4}",
+            self.prompt_tokens, cached, self.completion_tokens, cost_usd
+        )
+    }
+}
+
+/// DeepSeek prices in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+    pub input: f64,
+    pub cached_input: f64,
+    pub output: f64,
+}
+
+impl Default for Pricing {
+    /// DeepSeek's list prices for `deepseek-chat` and `deepseek-reasoner` (V3.2)
+    fn default() -> Self {
+        Self {
+            input: 0.28,
+            cached_input: 0.028,
+            output: 0.42,
+        }
+    }
+}
+
+impl Pricing {
+    /// Estimated cost of `usage` in USD
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cached = usage.cached_prompt_tokens.min(usage.prompt_tokens);
+        let uncached = usage.prompt_tokens - cached;
+
+        (uncached as f64 * self.input
+            + cached as f64 * self.cached_input
+            + usage.completion_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+
+    /// USD saved by the cached prompt tokens of `usage` compared to paying the
+    /// full input price for them
+    pub fn cache_savings(&self, usage: &TokenUsage) -> f64 {
+        let cached = usage.cached_prompt_tokens.min(usage.prompt_tokens);
+        cached as f64 * (self.input - self.cached_input).max(0.0) / 1_000_000.0
+    }
+}
+
+/// Cumulative usage of stored analyses for one model
+#[derive(Debug, Default, Serialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub runs: usize,
+    /// Runs stored before usage was tracked; they are not part of the totals
+    pub runs_without_usage: usize,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// Sum token usage and cost per model over history entries on or after `since`.
+/// Stored cost estimates are used as-is; entries with tokens but no stored cost
+/// are priced with `pricing`.
+pub fn summarize(
+    entries: &[HistoryEntry],
+    since: Option<NaiveDate>,
+    pricing: &Pricing,
+) -> Vec<ModelUsage> {
+    let mut by_model = BTreeMap::<String, ModelUsage>::new();
+
+    for entry in entries {
+        if since.is_some_and(|since| entry.report.timestamp.date_naive() < since) {
+            continue;
+        }
+
+        let summary = by_model
+            .entry(entry.report.model.clone())
+            .or_insert_with(|| ModelUsage {
+                model: entry.report.model.clone(),
+                ..Default::default()
+            });
+        summary.runs += 1;
+
+        let metadata = &entry.report.metadata;
+        match &metadata.token_usage {
+            Some(usage) => {
+                summary.usage.add(usage);
+                summary.cost_usd += metadata
+                    .estimated_cost_usd
+                    .unwrap_or_else(|| pricing.cost(usage));
+            }
+            None => summary.runs_without_usage += 1,
+        }
+    }
+
+    by_model.into_values().collect()
+}
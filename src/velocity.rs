@@ -0,0 +1,145 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::dates::{self, TaskDate};
+use crate::mcp_client::Task;
+
+/// Marker the prompt starts with; the mock provider recognizes it
+pub const VELOCITY_PROMPT_HEADER: &str = "Weekly velocity report";
+
+/// Counts for one Monday-to-Sunday week in the display time zone
+#[derive(Debug, Serialize)]
+pub struct WeekVelocity {
+    pub week_start: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+    /// Tasks still open at the end of the week (or now, for the current week)
+    pub open: usize,
+}
+
+/// Throughput, cycle time and burndown over the last few weeks
+#[derive(Debug, Serialize)]
+pub struct VelocityReport {
+    pub weeks: Vec<WeekVelocity>,
+    /// Mean tasks completed per week
+    pub average_completed: f64,
+    /// Mean days from creation to completion of the tasks completed in the period
+    pub average_cycle_days: Option<f64>,
+    /// Tasks the cycle time is based on
+    pub cycle_samples: usize,
+    /// Finished tasks without a `completed_at` timestamp, left out of the counts
+    pub missing_completed_at: usize,
+}
+
+impl VelocityReport {
+    /// Velocity of the last `weeks` weeks, including the current one
+    pub fn compute(tasks: &[Task], now: DateTime<Utc>, weeks: u32) -> Self {
+        let today = dates::local_date(now);
+        let current_week = week_start(today);
+        let first_week = current_week - Days::new(7 * u64::from(weeks.saturating_sub(1)));
+
+        let created = |task: &Task| TaskDate::parse(&task.created_at).map(TaskDate::date);
+        let completed =
+            |task: &Task| TaskDate::parse_opt(task.completed_at.as_deref()).map(TaskDate::date);
+
+        let weeks = (0..weeks)
+            .map(|idx| {
+                let start = first_week + Days::new(7 * u64::from(idx));
+                let end = (start + Days::new(6)).min(today);
+                let in_week =
+                    |date: Option<NaiveDate>| date.is_some_and(|date| date >= start && date <= end);
+                let open = tasks
+                    .iter()
+                    .filter(|task| created(task).is_none_or(|date| date <= end))
+                    .filter(|task| match completed(task) {
+                        Some(date) => date > end,
+                        None => task.is_unfinished(),
+                    })
+                    .count();
+
+                WeekVelocity {
+                    week_start: start,
+                    created: tasks.iter().filter(|task| in_week(created(task))).count(),
+                    completed: tasks.iter().filter(|task| in_week(completed(task))).count(),
+                    open,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let cycle_days = tasks
+            .iter()
+            .filter_map(|task| {
+                let created = TaskDate::parse(&task.created_at)?;
+                let completed = TaskDate::parse_opt(task.completed_at.as_deref())?;
+                (completed.date() >= first_week).then(|| {
+                    (completed.sort_key() - created.sort_key()).num_minutes() as f64 / 1440.0
+                })
+            })
+            .filter(|days| *days >= 0.0)
+            .collect::<Vec<_>>();
+
+        let average_completed = if weeks.is_empty() {
+            0.0
+        } else {
+            weeks.iter().map(|week| week.completed).sum::<usize>() as f64 / weeks.len() as f64
+        };
+
+        Self {
+            average_completed,
+            average_cycle_days: (!cycle_days.is_empty())
+                .then(|| cycle_days.iter().sum::<f64>() / cycle_days.len() as f64),
+            cycle_samples: cycle_days.len(),
+            missing_completed_at: tasks
+                .iter()
+                .filter(|task| !task.is_unfinished() && task.completed_at.is_none())
+                .count(),
+            weeks,
+        }
+    }
+
+    /// Prompt asking the model to comment on the numbers
+    pub fn summary_prompt(&self) -> String {
+        let mut prompt = format!(
+            "{} for the last {} weeks.\n\n",
+            VELOCITY_PROMPT_HEADER,
+            self.weeks.len()
+        );
+        for week in &self.weeks {
+            prompt.push_str(&format!(
+                "- Week of {}: {} created, {} completed, {} open at the end\n",
+                week.week_start, week.created, week.completed, week.open
+            ));
+        }
+        prompt.push_str(&format!(
+            "\nAverage completed per week: {:.1}\n",
+            self.average_completed
+        ));
+        if let Some(cycle) = self.average_cycle_days {
+            prompt.push_str(&format!(
+                "Average cycle time (created to completed): {:.1} days over {} tasks\n",
+                cycle, self.cycle_samples
+            ));
+        }
+        prompt.push_str(
+            "\nIn three to five sentences, describe the trend: is the team keeping up with incoming work, \
+             is the backlog burning down, and what one change would help most?",
+        );
+        prompt
+    }
+
+    /// Render as CSV with one row per week
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("week_start,created,completed,open\n");
+        for week in &self.weeks {
+            output.push_str(&format!(
+                "{},{},{},{}\n",
+                week.week_start, week.created, week.completed, week.open
+            ));
+        }
+        output
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::mcp_client::{McpClient, Task};
+use crate::notify::DesktopNotifier;
+use crate::output::DisplayFormat;
+use crate::table_formatter::task_field_changes;
+
+/// A change between two consecutive task snapshots
+#[derive(Debug, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum TaskChange {
+    Created { task: Task },
+    Updated { before: Box<Task>, after: Box<Task> },
+    Completed { task: Task },
+    Deleted { task: Task },
+}
+
+impl TaskChange {
+    pub fn describe(&self) -> String {
+        match self {
+            TaskChange::Created { task } => format!("➕ Created: {} ({})", task.title, task.id),
+            TaskChange::Completed { task } => {
+                format!("✅ Completed: {} ({})", task.title, task.id)
+            }
+            TaskChange::Deleted { task } => format!("🗑️  Deleted: {} ({})", task.title, task.id),
+            TaskChange::Updated { before, after } => {
+                let changes = task_field_changes(before, after)
+                    .into_iter()
+                    .map(|(label, old_value, new_value)| {
+                        format!("{} {} → {}", label.to_lowercase(), old_value, new_value)
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "✏️  Updated: {} ({}): {}",
+                    after.title,
+                    after.id,
+                    changes.join(", ")
+                )
+            }
+        }
+    }
+
+    /// `created`, `updated`, `completed` or `deleted`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TaskChange::Created { .. } => "created",
+            TaskChange::Updated { .. } => "updated",
+            TaskChange::Completed { .. } => "completed",
+            TaskChange::Deleted { .. } => "deleted",
+        }
+    }
+
+    /// The task as it is after the change, or as it was before a deletion
+    pub fn task(&self) -> &Task {
+        match self {
+            TaskChange::Created { task }
+            | TaskChange::Completed { task }
+            | TaskChange::Deleted { task } => task,
+            TaskChange::Updated { after, .. } => after,
+        }
+    }
+
+    fn notification(&self) -> (&'static str, String) {
+        match self {
+            TaskChange::Created { task } => ("Task created", task.title.clone()),
+            TaskChange::Updated { after, .. } => ("Task updated", after.title.clone()),
+            TaskChange::Completed { task } => ("Task completed", task.title.clone()),
+            TaskChange::Deleted { task } => ("Task deleted", task.title.clone()),
+        }
+    }
+}
+
+/// Compare two snapshots, keeping the order of `new` (deletions come last)
+pub fn diff_snapshots(old: &[Task], new: &[Task]) -> Vec<TaskChange> {
+    let old_by_id = old
+        .iter()
+        .map(|task| (task.id.as_str(), task))
+        .collect::<HashMap<_, _>>();
+    let new_ids = new
+        .iter()
+        .map(|task| task.id.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut changes = Vec::new();
+
+    for task in new {
+        match old_by_id.get(task.id.as_str()) {
+            None => changes.push(TaskChange::Created { task: task.clone() }),
+            Some(before) if before.is_unfinished() && !task.is_unfinished() => {
+                changes.push(TaskChange::Completed { task: task.clone() })
+            }
+            Some(before) if !task_field_changes(before, task).is_empty() => {
+                changes.push(TaskChange::Updated {
+                    before: Box::new((*before).clone()),
+                    after: Box::new(task.clone()),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes.extend(
+        old.iter()
+            .filter(|task| !new_ids.contains(task.id.as_str()))
+            .map(|task| TaskChange::Deleted { task: task.clone() }),
+    );
+
+    changes
+}
+
+/// Parse an interval such as `30s`, `5m`, `1h`, `500ms` or a bare number of seconds
+pub fn parse_interval(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value = number
+        .parse::<u64>()
+        .map_err(|_| format!("invalid interval '{}': expected e.g. 30s, 5m, 1h", input))?;
+
+    let duration = match unit.trim() {
+        "" | "s" | "sec" | "secs" => Duration::from_secs(value),
+        "ms" => Duration::from_millis(value),
+        "m" | "min" | "mins" => Duration::from_secs(value * 60),
+        "h" => Duration::from_secs(value * 3600),
+        "d" => Duration::from_secs(value * 86_400),
+        "w" => Duration::from_secs(value * 7 * 86_400),
+        other => {
+            return Err(format!(
+                "unknown interval unit '{}': use ms, s, m, h, d or w",
+                other
+            ));
+        }
+    };
+
+    if duration.is_zero() {
+        return Err("interval must be greater than zero".to_string());
+    }
+
+    Ok(duration)
+}
+
+/// Poll the server until interrupted, printing task changes as they happen.
+/// A resource change notification from the server triggers an immediate poll.
+pub async fn run_watch(
+    mcp_client: &McpClient,
+    interval: Duration,
+    notify: bool,
+    format: DisplayFormat,
+) -> Result<()> {
+    let mut snapshot = mcp_client.get_all_tasks().await?;
+
+    if format == DisplayFormat::Table {
+        crate::notice!(
+            "👀 Watching {} tasks on MCP server '{}' every {:?} (Ctrl-C to stop)",
+            snapshot.len(),
+            mcp_client.alias,
+            interval
+        );
+    }
+
+    let mut notifier = DesktopNotifier::new(notify);
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = mcp_client.changed() => debug!("Server reported a change, polling now"),
+        }
+
+        mcp_client.invalidate_cache();
+        let current = match mcp_client.get_all_tasks().await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                warn!("Failed to poll tasks, retrying next interval: {}", e);
+                continue;
+            }
+        };
+
+        for change in diff_snapshots(&snapshot, &current) {
+            match format {
+                DisplayFormat::Json => println!("{}", serde_json::to_string(&change)?),
+                _ => crate::say!(
+                    "[{}] {}",
+                    Local::now().format("%H:%M:%S"),
+                    change.describe()
+                ),
+            }
+
+            let (summary, body) = change.notification();
+            notifier.send(summary, &body);
+        }
+
+        snapshot = current;
+    }
+}
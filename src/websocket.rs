@@ -0,0 +1,551 @@
+use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use rmcp::{
+    service::{RoleClient, RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+};
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use url::Url;
+
+use crate::error::McpTasksError;
+
+/// A ping is sent this often; a server that has not answered the previous one by
+/// then counts as gone
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reconnect attempts after the connection drops, waiting 1, 2, 4, ... seconds
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Messages above this size are refused instead of buffered
+const MAX_MESSAGE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Header the server names its session in; sent back on reconnect to resume it
+const SESSION_HEADER: &str = "mcp-session-id";
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+trait AsyncStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> AsyncStream for T {}
+
+type Stream = BufReader<Box<dyn AsyncStream>>;
+
+/// A received frame; fragmented messages are already joined
+enum Frame {
+    Message(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong,
+    Close,
+}
+
+/// One open WebSocket connection
+struct Socket {
+    writer: tokio::io::WriteHalf<Stream>,
+    frames: mpsc::Receiver<io::Result<Frame>>,
+    reader: JoinHandle<()>,
+    session: Option<String>,
+}
+
+impl Socket {
+    /// Open a connection to `url`, asking to resume `session`
+    async fn open(url: &Url, session: Option<&str>) -> Result<Self> {
+        tokio::time::timeout(HANDSHAKE_TIMEOUT, Self::handshake(url, session))
+            .await
+            .with_context(|| {
+                McpTasksError::McpTransport(format!("Timed out connecting to {}", url))
+            })?
+    }
+
+    async fn handshake(url: &Url, session: Option<&str>) -> Result<Self> {
+        let transport_error =
+            || McpTasksError::McpTransport(format!("Failed to connect to {}", url));
+        let host = url.host_str().with_context(transport_error)?;
+        let port = url.port_or_known_default().with_context(transport_error)?;
+
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .with_context(transport_error)?;
+        tcp.set_nodelay(true).with_context(transport_error)?;
+        let stream: Box<dyn AsyncStream> = if url.scheme() == "wss" {
+            let connector =
+                tokio_native_tls::native_tls::TlsConnector::new().with_context(transport_error)?;
+            Box::new(
+                tokio_native_tls::TlsConnector::from(connector)
+                    .connect(host, tcp)
+                    .await
+                    .with_context(transport_error)?,
+            )
+        } else {
+            Box::new(tcp)
+        };
+        let mut stream = BufReader::new(stream);
+
+        let key = BASE64.encode([random_u64().to_be_bytes(), random_u64().to_be_bytes()].concat());
+        let mut target = url.path().to_string();
+        if let Some(query) = url.query() {
+            target.push('?');
+            target.push_str(query);
+        }
+        let host_header = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let mut request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Protocol: mcp\r\n",
+            target, host_header, key
+        );
+        if let Some(session) = session {
+            request.push_str(&format!("Mcp-Session-Id: {}\r\n", session));
+        }
+        request.push_str("\r\n");
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .with_context(transport_error)?;
+        stream.flush().await.with_context(transport_error)?;
+
+        let mut status_line = String::new();
+        stream
+            .read_line(&mut status_line)
+            .await
+            .with_context(transport_error)?;
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if stream
+                .read_line(&mut line)
+                .await
+                .with_context(transport_error)?
+                == 0
+                || line.trim().is_empty()
+            {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        if status_line.split_whitespace().nth(1) != Some("101") {
+            anyhow::bail!(McpTasksError::McpTransport(format!(
+                "WebSocket upgrade to {} refused: {}",
+                url,
+                status_line.trim()
+            )));
+        }
+        if headers.get("sec-websocket-accept") != Some(&accept_key(&key)) {
+            anyhow::bail!(McpTasksError::McpTransport(format!(
+                "WebSocket upgrade to {} returned a wrong Sec-WebSocket-Accept",
+                url
+            )));
+        }
+
+        let (reader, writer) = tokio::io::split(stream);
+        let (frames_tx, frames) = mpsc::channel(16);
+        Ok(Self {
+            writer,
+            frames,
+            reader: tokio::spawn(read_frames(reader, frames_tx)),
+            session: headers.remove(SESSION_HEADER),
+        })
+    }
+
+    async fn send(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 14);
+        frame.push(0x80 | opcode);
+        // Client frames are always masked
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(0x80 | 126);
+                frame.extend((len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend((len as u64).to_be_bytes());
+            }
+        }
+        let mask = (random_u64() as u32).to_be_bytes();
+        frame.extend(mask);
+        frame.extend(
+            payload
+                .iter()
+                .enumerate()
+                .map(|(idx, byte)| byte ^ mask[idx % 4]),
+        );
+        self.writer.write_all(&frame).await?;
+        self.writer.flush().await
+    }
+
+    async fn close(mut self) {
+        let _ = self.send(OP_CLOSE, &1000u16.to_be_bytes()).await;
+        let _ = self.writer.shutdown().await;
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Read frames until the connection fails or the server closes it
+async fn read_frames(
+    mut reader: tokio::io::ReadHalf<Stream>,
+    frames: mpsc::Sender<io::Result<Frame>>,
+) {
+    // Opcode and data of a message whose continuation frames are still coming
+    let mut partial: Option<(u8, Vec<u8>)> = None;
+    loop {
+        let frame = match read_frame(&mut reader).await {
+            Ok((fin, OP_CONTINUATION, payload)) => match partial.take() {
+                Some((opcode, mut data)) => {
+                    data.extend(payload);
+                    if !fin {
+                        partial = Some((opcode, data));
+                        continue;
+                    }
+                    Ok(Frame::Message(data))
+                }
+                None => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "continuation frame without a message",
+                )),
+            },
+            Ok((false, opcode @ (OP_TEXT | OP_BINARY), payload)) => {
+                partial = Some((opcode, payload));
+                continue;
+            }
+            Ok((true, OP_TEXT | OP_BINARY, payload)) => Ok(Frame::Message(payload)),
+            Ok((_, OP_PING, payload)) => Ok(Frame::Ping(payload)),
+            Ok((_, OP_PONG, _)) => Ok(Frame::Pong),
+            Ok((_, OP_CLOSE, _)) => Ok(Frame::Close),
+            Ok((_, opcode, _)) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown WebSocket opcode {:#x}", opcode),
+            )),
+            Err(e) => Err(e),
+        };
+
+        let last = !matches!(frame, Ok(Frame::Message(_) | Frame::Ping(_) | Frame::Pong));
+        if frames.send(frame).await.is_err() || last {
+            return;
+        }
+    }
+}
+
+/// FIN flag, opcode and unmasked payload of the next frame
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<(bool, u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    reader.read_exact(&mut head).await?;
+    let fin = head[0] & 0x80 != 0;
+    let opcode = head[0] & 0x0f;
+    let len = match head[1] & 0x7f {
+        126 => reader.read_u16().await? as u64,
+        127 => reader.read_u64().await?,
+        len => len as u64,
+    };
+    if len > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("WebSocket frame of {} bytes is too large", len),
+        ));
+    }
+
+    let mask = if head[1] & 0x80 != 0 {
+        let mut mask = [0u8; 4];
+        reader.read_exact(&mut mask).await?;
+        Some(mask)
+    } else {
+        None
+    };
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload).await?;
+    if let Some(mask) = mask {
+        for (idx, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[idx % 4];
+        }
+    }
+    Ok((fin, opcode, payload))
+}
+
+/// Why [`serve`] stopped
+enum Ended {
+    /// The client side went away; nothing to reconnect for
+    Closed,
+    /// The connection broke; `unsent` is the message that could not be written
+    Lost {
+        reason: String,
+        unsent: Option<String>,
+    },
+}
+
+/// MCP transport over a WebSocket connection. The connection is kept alive with
+/// pings, and when it drops it is opened again, resuming the server's session.
+/// When that fails, or the server starts a new session, the transport closes so
+/// the client can start over.
+pub struct WsTransport {
+    outgoing: Option<mpsc::UnboundedSender<String>>,
+    incoming: mpsc::UnboundedReceiver<RxJsonRpcMessage<RoleClient>>,
+}
+
+impl WsTransport {
+    /// Connect to a `ws://` or `wss://` URL
+    pub async fn connect(url: &str) -> Result<Self> {
+        let url = Url::parse(url)
+            .with_context(|| McpTasksError::Config(format!("Invalid WebSocket URL '{}'", url)))?;
+        let socket = Socket::open(&url, None).await?;
+        debug!(
+            "Connected to {} (session {})",
+            url,
+            socket.session.as_deref().unwrap_or("none")
+        );
+
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        tokio::spawn(run(url, socket, outgoing_rx, incoming_tx));
+        Ok(Self {
+            outgoing: Some(outgoing),
+            incoming,
+        })
+    }
+}
+
+impl Transport<RoleClient> for WsTransport {
+    type Error = io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let result = serde_json::to_string(&item)
+            .map_err(io::Error::other)
+            .and_then(|text| {
+                self.outgoing
+                    .as_ref()
+                    .and_then(|outgoing| outgoing.send(text).ok())
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::BrokenPipe, "WebSocket connection closed")
+                    })
+            });
+        std::future::ready(result)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        self.incoming.recv()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        // The connection task sends a close frame once the sender is gone
+        self.outgoing = None;
+        std::future::ready(Ok(()))
+    }
+}
+
+/// Move messages between the transport and the socket, reconnecting when it breaks
+async fn run(
+    url: Url,
+    mut socket: Socket,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+    incoming: mpsc::UnboundedSender<RxJsonRpcMessage<RoleClient>>,
+) {
+    let mut unsent = None;
+    loop {
+        match serve(&mut socket, &mut outgoing, &incoming, unsent.take()).await {
+            Ended::Closed => {
+                socket.close().await;
+                debug!("Closed WebSocket connection to {}", url);
+                return;
+            }
+            Ended::Lost {
+                reason,
+                unsent: message,
+            } => {
+                warn!("WebSocket connection to {} lost: {}", url, reason);
+                unsent = message;
+                let session = socket.session.clone();
+                match reconnect(&url, session.as_deref()).await {
+                    Some(new_socket) if new_socket.session == session => {
+                        info!("Reconnected to {}", url);
+                        socket = new_socket;
+                    }
+                    Some(new_socket) => {
+                        // The server no longer knows the session, so requests sent
+                        // in it would fail; end the transport so the client starts over
+                        warn!("{} did not resume the MCP session", url);
+                        new_socket.close().await;
+                        return;
+                    }
+                    None => {
+                        error!(
+                            "Giving up on {} after {} reconnect attempts",
+                            url, RECONNECT_ATTEMPTS
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn reconnect(url: &Url, session: Option<&str>) -> Option<Socket> {
+    for attempt in 0..RECONNECT_ATTEMPTS {
+        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+        match Socket::open(url, session).await {
+            Ok(socket) => return Some(socket),
+            Err(e) => debug!(
+                "Reconnect attempt {}/{} failed: {:#}",
+                attempt + 1,
+                RECONNECT_ATTEMPTS,
+                e
+            ),
+        }
+    }
+    None
+}
+
+async fn serve(
+    socket: &mut Socket,
+    outgoing: &mut mpsc::UnboundedReceiver<String>,
+    incoming: &mpsc::UnboundedSender<RxJsonRpcMessage<RoleClient>>,
+    unsent: Option<String>,
+) -> Ended {
+    if let Some(text) = unsent
+        && let Err(e) = socket.send(OP_TEXT, text.as_bytes()).await
+    {
+        return Ended::Lost {
+            reason: e.to_string(),
+            unsent: Some(text),
+        };
+    }
+
+    let mut ping =
+        tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut awaiting_pong = false;
+
+    loop {
+        tokio::select! {
+            message = outgoing.recv() => {
+                let Some(text) = message else {
+                    return Ended::Closed;
+                };
+                if let Err(e) = socket.send(OP_TEXT, text.as_bytes()).await {
+                    return Ended::Lost { reason: e.to_string(), unsent: Some(text) };
+                }
+            }
+            frame = socket.frames.recv() => match frame {
+                Some(Ok(Frame::Message(data))) => {
+                    match serde_json::from_slice(&data) {
+                        Ok(message) => {
+                            if incoming.send(message).is_err() {
+                                return Ended::Closed;
+                            }
+                        }
+                        Err(e) => warn!("Ignoring malformed MCP message: {}", e),
+                    }
+                }
+                Some(Ok(Frame::Ping(payload))) => {
+                    if let Err(e) = socket.send(OP_PONG, &payload).await {
+                        return Ended::Lost { reason: e.to_string(), unsent: None };
+                    }
+                }
+                Some(Ok(Frame::Pong)) => awaiting_pong = false,
+                Some(Ok(Frame::Close)) => {
+                    return Ended::Lost { reason: "closed by the server".to_string(), unsent: None };
+                }
+                Some(Err(e)) => return Ended::Lost { reason: e.to_string(), unsent: None },
+                None => return Ended::Lost { reason: "connection closed".to_string(), unsent: None },
+            },
+            _ = ping.tick() => {
+                if awaiting_pong {
+                    return Ended::Lost { reason: "no answer to ping".to_string(), unsent: None };
+                }
+                if let Err(e) = socket.send(OP_PING, &[]).await {
+                    return Ended::Lost { reason: e.to_string(), unsent: None };
+                }
+                awaiting_pong = true;
+            }
+        }
+    }
+}
+
+/// Unpredictable enough for handshake keys and frame masks, which only have to
+/// keep proxies from caching or misreading the traffic
+fn random_u64() -> u64 {
+    use std::hash::BuildHasher;
+    std::collections::hash_map::RandomState::new().hash_one(std::time::SystemTime::now())
+}
+
+/// The `Sec-WebSocket-Accept` value a server must answer `key` with
+fn accept_key(key: &str) -> String {
+    BASE64.encode(sha1(
+        format!("{}258EAFA5-E914-47DA-95CA-C5AB0DC85B11", key).as_bytes(),
+    ))
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut words = [0u32; 80];
+        for (idx, word) in block.chunks(4).enumerate() {
+            words[idx] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for idx in 16..80 {
+            words[idx] = (words[idx - 3] ^ words[idx - 8] ^ words[idx - 14] ^ words[idx - 16])
+                .rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (idx, word) in words.iter().enumerate() {
+            let (f, k) = match idx {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
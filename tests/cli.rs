@@ -0,0 +1,250 @@
+//! End-to-end runs of the CLI against `mock-mcp-server` and the mock LLM
+//! provider. The mock server starts from its fixture tasks (`task-1` to
+//! `task-4`) on every run, so each command sees the same data.
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// A scratch home directory, so no user config, `.env` or data leaks into a run
+struct Cli {
+    home: PathBuf,
+}
+
+impl Cli {
+    fn new(name: &str) -> Self {
+        let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).unwrap();
+        Self { home }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_deepseek_mcp_tasks"))
+            .args(args)
+            .current_dir(&self.home)
+            .env_clear()
+            .env("HOME", &self.home)
+            .env("XDG_CONFIG_HOME", self.home.join("config"))
+            .env("XDG_DATA_HOME", self.home.join("data"))
+            .env("MCP_SERVER_COMMAND", env!("CARGO_BIN_EXE_mock-mcp-server"))
+            .env("RUST_LOG", "off")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    }
+
+    /// Run a command that has to succeed and parse its JSON output
+    fn json(&self, args: &[&str]) -> Value {
+        let output = self.run(&[&["--format", "json"], args].concat());
+        assert!(
+            output.status.success(),
+            "{:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+            panic!(
+                "{:?} printed invalid JSON ({}): {}",
+                args,
+                e,
+                String::from_utf8_lossy(&output.stdout)
+            )
+        })
+    }
+}
+
+fn ids(tasks: &Value) -> Vec<&str> {
+    tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["id"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn list_prints_the_server_tasks() {
+    let cli = Cli::new("list");
+    let tasks = cli.json(&["list"]);
+    assert_eq!(ids(&tasks), ["task-1", "task-2", "task-3", "task-4"]);
+
+    let pending = cli.json(&["list", "--status", "pending"]);
+    assert_eq!(ids(&pending), ["task-1", "task-3"]);
+}
+
+#[test]
+fn add_creates_a_task() {
+    let cli = Cli::new("add");
+    let task = cli.json(&["add", "--title", "Write tests", "--priority", "high"]);
+    assert_eq!(task["id"], "task-5");
+    assert_eq!(task["title"], "Write tests");
+    assert_eq!(task["priority"], "high");
+    assert_eq!(task["status"], "pending");
+}
+
+#[test]
+fn update_shows_the_task_before_and_after() {
+    let cli = Cli::new("update");
+    let change = cli.json(&[
+        "update",
+        "task-1",
+        "--status",
+        "in_progress",
+        "--assignee",
+        "carol",
+    ]);
+    assert_eq!(change["before"]["status"], "pending");
+    assert_eq!(change["after"]["status"], "in_progress");
+    assert_eq!(change["before"]["assignee"], "alice");
+    assert_eq!(change["after"]["assignee"], "carol");
+}
+
+#[test]
+fn update_of_an_unknown_task_fails_as_a_protocol_error() {
+    let cli = Cli::new("update-unknown");
+    let output = cli.run(&["update", "task-99", "--status", "completed"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn bulk_completes_every_matching_task() {
+    let cli = Cli::new("bulk");
+    let results = cli.json(&["bulk", "--filter", "status=pending", "--complete", "-y"]);
+    assert_eq!(ids(&results), ["task-1", "task-3"]);
+    for result in results.as_array().unwrap() {
+        assert_eq!(result["ok"], true);
+        assert_eq!(result["task"]["status"], "completed");
+    }
+}
+
+#[test]
+fn delete_removes_listed_and_matching_tasks() {
+    let cli = Cli::new("delete");
+    let results = cli.json(&["delete", "task-2", "--filter", "status=pending", "--force"]);
+    assert_eq!(ids(&results), ["task-2", "task-1", "task-3"]);
+    assert!(
+        results
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|result| result["ok"] == true)
+    );
+}
+
+#[test]
+fn declined_delete_keeps_json_output_valid() {
+    // stdin is closed, so the confirmation is answered with no
+    let cli = Cli::new("delete-declined");
+    let results = cli.json(&["delete", "task-1"]);
+    assert_eq!(results, serde_json::json!([]));
+}
+
+#[test]
+fn delete_of_an_unknown_task_deletes_nothing() {
+    let cli = Cli::new("delete-unknown");
+    let output = cli.run(&["--format", "json", "delete", "task-1", "task-99", "--force"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn import_file_reports_each_created_task() {
+    let cli = Cli::new("import-file");
+    let path = cli.home.join("TODO.md");
+    std::fs::write(
+        &path,
+        "# Website\n- [ ] Fix login bug\n- [x] Update footer\n",
+    )
+    .unwrap();
+
+    let results = cli.json(&["import", "file", path.to_str().unwrap(), "--yes"]);
+    assert_eq!(ids(&results), ["task-5", "task-6"]);
+    assert_eq!(results[0]["ok"], true);
+    assert_eq!(results[0]["task"]["tags"], serde_json::json!(["website"]));
+    assert_eq!(results[1]["ok"], true);
+    assert_eq!(results[1]["task"]["status"], "completed");
+}
+
+#[test]
+fn template_apply_reports_each_created_task() {
+    let cli = Cli::new("template-apply");
+    for title in ["Review {month}", "Send report"] {
+        let output = cli.run(&["template", "add", "monthly", "--title", title]);
+        assert!(output.status.success());
+    }
+
+    let results = cli.json(&[
+        "template",
+        "apply",
+        "monthly",
+        "--on",
+        "2025-07-01",
+        "--yes",
+    ]);
+    assert_eq!(ids(&results), ["task-5", "task-6"]);
+    assert_eq!(results[0]["title"], "Review 2025-07");
+    assert!(
+        results
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|result| result["ok"] == true)
+    );
+}
+
+#[test]
+fn analyze_task_apply_reports_each_created_subtask() {
+    let cli = Cli::new("analyze-task-apply");
+    let report = cli.json(&["--mock-llm", "analyze-task", "task-1", "--apply"]);
+    assert_eq!(report["task"]["id"], "task-1");
+
+    let subtasks = report["breakdown"]["subtasks"].as_array().unwrap();
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), subtasks.len());
+    assert!(results.iter().all(|result| result["ok"] == true));
+}
+
+#[test]
+fn expand_reports_each_created_subtask() {
+    let cli = Cli::new("expand");
+    let results = cli.json(&["--mock-llm", "expand", "task-1", "--yes"]);
+    let results = results.as_array().unwrap();
+    assert!(!results.is_empty());
+    for result in results {
+        assert_eq!(result["ok"], true);
+        assert!(
+            result["task"]["tags"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!("parent:task-1"))
+        );
+    }
+}
+
+#[test]
+fn declined_expand_keeps_json_output_valid() {
+    let cli = Cli::new("expand-declined");
+    let results = cli.json(&["--mock-llm", "expand", "task-1"]);
+    assert_eq!(results, serde_json::json!([]));
+}
+
+#[test]
+fn analyze_with_tools_runs_on_the_mock_llm() {
+    let cli = Cli::new("analyze-with-tools");
+    let output = cli.run(&["--mock-llm", "analyze-with-tools"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Mock analysis of 2 tasks"), "{}", stdout);
+    assert!(stdout.contains("Prepare release notes"), "{}", stdout);
+
+    // The analysis is kept in the history
+    let history = cli.home.join("data/mcp-tasks/analyses.jsonl");
+    let history = std::fs::read_to_string(&history).unwrap();
+    assert_eq!(history.lines().count(), 1);
+}